@@ -7,6 +7,8 @@ pub use executor::*;
 mod async_drop;
 pub(crate) mod async_lock;
 pub use async_drop::*;
+mod timeout;
+pub(crate) use timeout::timeout;
 
 // Not unix-specific itself but only used on unix.
 #[cfg(target_family = "unix")]
@@ -0,0 +1,24 @@
+use std::{future::Future, time::Duration};
+
+/// Run `future` to completion, unless `duration` elapses first.
+///
+/// Returns `None` if the timeout elapsed.
+pub(crate) async fn timeout<F>(duration: Duration, future: F) -> Option<F::Output>
+where
+    F: Future,
+{
+    #[cfg(not(feature = "tokio"))]
+    {
+        use futures_util::future::{select, Either};
+
+        match select(std::pin::pin!(future), async_io::Timer::after(duration)).await {
+            Either::Left((output, _)) => Some(output),
+            Either::Right(_) => None,
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    {
+        tokio::time::timeout(duration, future).await.ok()
+    }
+}
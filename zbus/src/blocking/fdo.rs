@@ -15,5 +15,5 @@ pub use crate::fdo::{
     },
     peer::PeerProxyBlocking as PeerProxy,
     properties::{PropertiesChangedIterator, PropertiesProxyBlocking as PropertiesProxy},
-    stats::StatsProxyBlocking as StatsProxy,
+    stats::{DebugStatsProxyBlocking as DebugStatsProxy, StatsProxyBlocking as StatsProxy},
 };
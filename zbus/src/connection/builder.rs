@@ -21,6 +21,7 @@ use uds_windows::UnixStream;
 #[cfg(all(feature = "vsock", not(feature = "tokio")))]
 use vsock::VsockStream;
 
+use tracing::instrument;
 use zvariant::ObjectPath;
 
 use crate::{
@@ -32,6 +33,7 @@ use crate::{
 
 use super::{
     handshake::{AuthMechanism, Authenticated},
+    limits::Limits,
     socket::{BoxedSplit, ReadHalf, Split, WriteHalf},
 };
 
@@ -65,6 +67,8 @@ pub struct Builder<'a> {
     #[cfg(feature = "p2p")]
     p2p: bool,
     internal_executor: bool,
+    strict_validation: bool,
+    limits: Limits,
     interfaces: Interfaces<'a>,
     names: HashSet<WellKnownName<'a>>,
     auth_mechanism: Option<AuthMechanism>,
@@ -253,6 +257,66 @@ impl<'a> Builder<'a> {
         self
     }
 
+    /// Set the maximum number of file descriptors a single message can carry.
+    ///
+    /// Sending a message with more file descriptors fails with
+    /// [`LimitExceeded::FdsPerMessage`]. Receiving one is a violation of the limit by the peer, so
+    /// the connection is terminated and all message streams receive the same error.
+    ///
+    /// By default, there is no limit.
+    ///
+    /// [`LimitExceeded::FdsPerMessage`]: crate::LimitExceeded::FdsPerMessage
+    #[cfg(unix)]
+    pub fn max_fds_per_message(mut self, max: usize) -> Self {
+        self.limits.max_fds_per_message = Some(max);
+
+        self
+    }
+
+    /// Set the maximum number of method calls that can await a reply at the same time.
+    ///
+    /// Further method calls fail with [`LimitExceeded::PendingReplies`] until replies to the
+    /// previous ones are received (or the calls are dropped).
+    ///
+    /// By default, there is no limit.
+    ///
+    /// [`LimitExceeded::PendingReplies`]: crate::LimitExceeded::PendingReplies
+    pub fn max_pending_replies(mut self, max: usize) -> Self {
+        self.limits.max_pending_replies = Some(max);
+
+        self
+    }
+
+    /// Set the maximum number of distinct match rules the connection can subscribe to.
+    ///
+    /// Subscribing to a new match rule (e.g through a signal stream) fails with
+    /// [`LimitExceeded::MatchRules`] once the limit is reached. Subscribing again to an existing
+    /// rule doesn't count against the limit.
+    ///
+    /// By default, there is no limit.
+    ///
+    /// [`LimitExceeded::MatchRules`]: crate::LimitExceeded::MatchRules
+    pub fn max_match_rules(mut self, max: usize) -> Self {
+        self.limits.max_match_rules = Some(max);
+
+        self
+    }
+
+    /// Set the maximum number of bytes of outgoing messages waiting to be written to the socket.
+    ///
+    /// Sending a message that would take the amount over the limit fails with
+    /// [`LimitExceeded::QueuedOutgoingBytes`]. This bounds the memory used by messages to a peer
+    /// that isn't reading them fast enough.
+    ///
+    /// By default, there is no limit.
+    ///
+    /// [`LimitExceeded::QueuedOutgoingBytes`]: crate::LimitExceeded::QueuedOutgoingBytes
+    pub fn max_queued_outgoing_bytes(mut self, max: usize) -> Self {
+        self.limits.max_queued_outgoing_bytes = Some(max);
+
+        self
+    }
+
     /// Enable or disable the internal executor thread.
     ///
     /// The thread is enabled by default.
@@ -264,6 +328,25 @@ impl<'a> Builder<'a> {
         self
     }
 
+    /// Enable or disable strict validation of incoming messages.
+    ///
+    /// Even when disabled, messages with an invalid encoding or invalid names in their header are
+    /// rejected. When enabled, each incoming message is additionally checked for the header fields
+    /// required by its type, the use of the reserved `org.freedesktop.DBus.Local` path or
+    /// interface and the consistency of its body with its signature.
+    ///
+    /// A peer sending a message violating any of these rules is not speaking the D-Bus protocol
+    /// correctly, so just like for messages that can't be parsed, the connection is terminated and
+    /// all message streams receive the corresponding error.
+    ///
+    /// This is meant for message brokers and security-sensitive services, where the cost of
+    /// validating every message is worth paying. It is disabled by default.
+    pub fn strict_validation(mut self, enabled: bool) -> Self {
+        self.strict_validation = enabled;
+
+        self
+    }
+
     /// Register a D-Bus [`Interface`] to be served at a given path.
     ///
     /// This is similar to [`zbus::ObjectServer::at`], except that it allows you to have your
@@ -345,6 +428,7 @@ impl<'a> Builder<'a> {
         Ok(conn)
     }
 
+    #[instrument(name = "connection setup", skip_all)]
     async fn build_(mut self, executor: Executor<'static>) -> Result<Connection> {
         #[cfg(feature = "p2p")]
         let is_bus_conn = !self.p2p;
@@ -359,7 +443,7 @@ impl<'a> Builder<'a> {
         #[cfg(unix)]
         let already_received_fds = auth.already_received_fds.drain(..).collect();
 
-        let mut conn = Connection::new(auth, is_bus_conn, executor).await?;
+        let mut conn = Connection::new(auth, is_bus_conn, executor, self.limits).await?;
         conn.set_max_queued(self.max_queued.unwrap_or(DEFAULT_MAX_QUEUED));
 
         if !self.interfaces.is_empty() {
@@ -388,6 +472,7 @@ impl<'a> Builder<'a> {
             already_received_bytes,
             #[cfg(unix)]
             already_received_fds,
+            self.strict_validation,
         );
 
         for name in self.names {
@@ -405,6 +490,8 @@ impl<'a> Builder<'a> {
             max_queued: None,
             guid: None,
             internal_executor: true,
+            strict_validation: false,
+            limits: Limits::default(),
             interfaces: HashMap::new(),
             names: HashSet::new(),
             auth_mechanism: None,
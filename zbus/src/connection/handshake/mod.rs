@@ -242,6 +242,17 @@ mod tests {
         crate::utils::block_on(server.perform()).unwrap();
     }
 
+    #[test]
+    #[timeout(15000)]
+    fn missing_external_data_without_credentials() {
+        let (mut p0, p1) = create_async_socket_pair();
+        let server = Server::new(p1.into(), Guid::generate().into(), None, None, None).unwrap();
+
+        crate::utils::block_on(p0.write_all(b"\0AUTH EXTERNAL\r\nDATA\r\nBEGIN\r\n")).unwrap();
+        drop(p0);
+        crate::utils::block_on(server.perform()).unwrap_err();
+    }
+
     #[test]
     #[timeout(15000)]
     fn anonymous_handshake() {
@@ -169,7 +169,20 @@ impl Server {
         trace!("Waiting for authentication data");
         let reply = self.common.read_command().await?;
         match (mech, reply) {
-            (AuthMechanism::External, Command::Data(None)) => self.auth_ok().await?,
+            // An empty response means the client wants to be authenticated as whoever the
+            // socket credentials say it is, so they must be known.
+            (AuthMechanism::External, Command::Data(None)) => {
+                #[cfg(unix)]
+                let known = self.client_uid.is_some();
+                #[cfg(windows)]
+                let known = self.client_sid.is_some();
+
+                if known {
+                    self.auth_ok().await?
+                } else {
+                    self.rejected_error().await?
+                }
+            }
             (AuthMechanism::External, Command::Data(Some(data))) => {
                 self.check_external_auth(&data).await?;
             }
@@ -0,0 +1,94 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use crate::{Error, LimitExceeded, Result};
+
+/// The resource limits of a connection.
+///
+/// `None` means no limit.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Limits {
+    #[cfg(unix)]
+    pub max_fds_per_message: Option<usize>,
+    pub max_pending_replies: Option<usize>,
+    pub max_match_rules: Option<usize>,
+    pub max_queued_outgoing_bytes: Option<usize>,
+}
+
+impl Limits {
+    /// Check the number of file descriptors of a message against the limit.
+    #[cfg(unix)]
+    pub fn check_fds_per_message(&self, num_fds: usize) -> Result<()> {
+        check(
+            num_fds,
+            self.max_fds_per_message,
+            LimitExceeded::FdsPerMessage,
+        )
+    }
+
+    /// Check the number of match rules, including a new one, against the limit.
+    pub fn check_match_rules(&self, num_rules: usize) -> Result<()> {
+        check(num_rules, self.max_match_rules, LimitExceeded::MatchRules)
+    }
+}
+
+fn check(value: usize, max: Option<usize>, limit: LimitExceeded) -> Result<()> {
+    match max {
+        Some(max) if value > max => Err(Error::LimitExceeded(limit)),
+        _ => Ok(()),
+    }
+}
+
+/// The usage of a resource that is released over time, bounded by a limit.
+#[derive(Debug)]
+pub(crate) struct Usage {
+    used: AtomicUsize,
+    max: Option<usize>,
+    limit: LimitExceeded,
+}
+
+impl Usage {
+    pub fn new(max: Option<usize>, limit: LimitExceeded) -> Arc<Self> {
+        Arc::new(Self {
+            used: AtomicUsize::new(0),
+            max,
+            limit,
+        })
+    }
+
+    /// Use `amount` more of the resource, until the returned guard is dropped.
+    ///
+    /// Fails if that would exceed the limit.
+    pub fn acquire(self: &Arc<Self>, amount: usize) -> Result<UsageGuard> {
+        self.used
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                let used = used.saturating_add(amount);
+
+                match self.max {
+                    Some(max) if used > max => None,
+                    _ => Some(used),
+                }
+            })
+            .map_err(|_| Error::LimitExceeded(self.limit))?;
+
+        Ok(UsageGuard {
+            usage: self.clone(),
+            amount,
+        })
+    }
+}
+
+/// Releases the resource it was acquired for, when dropped.
+#[derive(Debug)]
+pub(crate) struct UsageGuard {
+    usage: Arc<Usage>,
+    amount: usize,
+}
+
+impl Drop for UsageGuard {
+    fn drop(&mut self) {
+        self.usage.used.fetch_sub(self.amount, Ordering::AcqRel);
+    }
+}
@@ -24,8 +24,8 @@ use crate::{
     fdo::{ConnectionCredentials, ReleaseNameReply, RequestNameFlags, RequestNameReply},
     is_flatpak,
     message::{Flags, Message, Type},
-    DBusError, Error, Executor, MatchRule, MessageStream, ObjectServer, OwnedGuid, OwnedMatchRule,
-    Result, Task,
+    DBusError, Error, Executor, LimitExceeded, MatchRule, MessageStream, ObjectServer, OwnedGuid,
+    OwnedMatchRule, Result, Task,
 };
 
 mod builder;
@@ -37,6 +37,15 @@ pub use socket::Socket;
 mod socket_reader;
 use socket_reader::SocketReader;
 
+mod msg_senders;
+use msg_senders::MsgSenders;
+
+mod limits;
+use limits::{Limits, Usage, UsageGuard};
+
+mod name_handle;
+pub use name_handle::NameHandle;
+
 pub(crate) mod handshake;
 pub use handshake::AuthMechanism;
 use handshake::Authenticated;
@@ -67,10 +76,14 @@ pub(crate) struct ConnectionInner {
 
     pub(crate) msg_receiver: InactiveReceiver<Result<Message>>,
     pub(crate) method_return_receiver: InactiveReceiver<Result<Message>>,
-    msg_senders: Arc<Mutex<HashMap<Option<OwnedMatchRule>, MsgBroadcaster>>>,
+    msg_senders: Arc<Mutex<MsgSenders>>,
 
     subscriptions: Mutex<Subscriptions>,
 
+    limits: Limits,
+    pending_replies: Arc<Usage>,
+    queued_outgoing_bytes: Arc<Usage>,
+
     object_server: OnceLock<ObjectServer>,
     object_server_dispatch_task: OnceLock<Task<()>>,
 
@@ -221,6 +234,8 @@ assert_impl_all!(Connection: Send, Sync, Unpin);
 pub(crate) struct PendingMethodCall {
     stream: Option<MessageStream>,
     serial: NonZeroU32,
+    // Accounts for this call in the pending replies of the connection, until the reply is received.
+    pending_reply: Option<UsageGuard>,
 }
 
 impl Future for PendingMethodCall {
@@ -263,6 +278,7 @@ impl OrderedFuture for PendingMethodCall {
                             _ => continue,
                         };
                         this.stream = None;
+                        this.pending_reply = None;
                         return Poll::Ready(Some((ordering, res)));
                     }
                     Poll::Ready(PollResult::Item {
@@ -288,12 +304,28 @@ impl OrderedFuture for PendingMethodCall {
 
 impl Connection {
     /// Send `msg` to the peer.
+    #[instrument(
+        level = "trace",
+        skip_all,
+        fields(
+            msg.type = ?msg.message_type(),
+            msg.serial = %msg.primary_header().serial_num(),
+            msg.interface = ?msg.header().interface(),
+            msg.member = ?msg.header().member(),
+        )
+    )]
     pub async fn send(&self, msg: &Message) -> Result<()> {
         #[cfg(unix)]
-        if !msg.data().fds().is_empty() && !self.inner.cap_unix_fd {
-            return Err(Error::Unsupported);
+        if !msg.data().fds().is_empty() {
+            if !self.inner.cap_unix_fd {
+                return Err(Error::Unsupported);
+            }
+            self.inner
+                .limits
+                .check_fds_per_message(msg.data().fds().len())?;
         }
 
+        let _queued = self.inner.queued_outgoing_bytes.acquire(msg.data().len())?;
         self.inner.activity_event.notify(usize::MAX);
         let mut write = self.inner.socket_write.lock().await;
 
@@ -393,11 +425,20 @@ impl Connection {
             self,
         ));
         let serial = msg.primary_header().serial_num();
+        let pending_reply = if flags.contains(Flags::NoReplyExpected) {
+            None
+        } else {
+            Some(self.inner.pending_replies.acquire(1)?)
+        };
         self.send(&msg).await?;
         if flags.contains(Flags::NoReplyExpected) {
             Ok(None)
         } else {
-            Ok(Some(PendingMethodCall { stream, serial }))
+            Ok(Some(PendingMethodCall {
+                stream,
+                serial,
+                pending_reply,
+            }))
         }
     }
 
@@ -764,6 +805,53 @@ impl Connection {
         Ok(reply)
     }
 
+    /// Register a well-known name for this connection, returning a handle to track its ownership.
+    ///
+    /// This is the same as [`Connection::request_name_with_flags`] but returns a [`NameHandle`],
+    /// which provides the streams of `NameAcquired` and `NameLost` signals for the name. Since the
+    /// streams are created before the name is requested, no signals are missed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # zbus::block_on(async {
+    /// use zbus::{Connection, fdo::{RequestNameFlags, RequestNameReply}};
+    /// use futures_util::stream::StreamExt;
+    ///
+    /// let name = "org.freedesktop.zbus.NameHandleTest";
+    /// let conn1 = Connection::session().await?;
+    /// let mut handle = conn1
+    ///     .request_name_with_handle(name, RequestNameFlags::AllowReplacement.into())
+    ///     .await?;
+    /// assert_eq!(handle.reply(), RequestNameReply::PrimaryOwner);
+    ///
+    /// // Another connection replaces us as the owner of the name.
+    /// let conn2 = Connection::session().await?;
+    /// conn2.request_name(name).await?;
+    /// let lost = handle.receive_name_lost().next().await.unwrap();
+    /// assert_eq!(lost.args()?.name, name);
+    ///
+    /// # Ok::<(), zbus::Error>(())
+    /// # }).unwrap();
+    /// ```
+    ///
+    /// # Caveats
+    ///
+    /// Same as that of [`Connection::request_name`].
+    pub async fn request_name_with_handle<'w, W>(
+        &self,
+        well_known_name: W,
+        flags: BitFlags<RequestNameFlags>,
+    ) -> Result<NameHandle>
+    where
+        W: TryInto<WellKnownName<'w>>,
+        W::Error: Into<Error>,
+    {
+        let well_known_name = well_known_name.try_into().map_err(Into::into)?;
+
+        NameHandle::request(self, well_known_name, flags).await
+    }
+
     /// Deregister a previously registered well-known name for this service on the bus.
     ///
     /// Use this method to deregister a well-known name, registered through
@@ -1044,9 +1132,11 @@ impl Connection {
         }
 
         let mut subscriptions = self.inner.subscriptions.lock().await;
+        let num_rules = subscriptions.len();
         let msg_type = rule.msg_type().unwrap_or(Type::Signal);
         match subscriptions.entry(rule.clone()) {
             Entry::Vacant(e) => {
+                self.inner.limits.check_match_rules(num_rules + 1)?;
                 let max_queued = max_queued.unwrap_or(DEFAULT_MAX_QUEUED);
                 let (sender, mut receiver) = broadcast(max_queued);
                 receiver.set_await_active(false);
@@ -1106,11 +1196,7 @@ impl Connection {
                         .await?;
                     }
                     e.remove();
-                    self.inner
-                        .msg_senders
-                        .lock()
-                        .await
-                        .remove(&Some(rule.into()));
+                    self.inner.msg_senders.lock().await.remove(&rule.into());
                 }
                 Ok(true)
             }
@@ -1129,6 +1215,7 @@ impl Connection {
         auth: Authenticated,
         #[allow(unused)] bus_connection: bool,
         executor: Executor<'static>,
+        limits: Limits,
     ) -> Result<Self> {
         #[cfg(unix)]
         let cap_unix_fd = auth.cap_unix_fd;
@@ -1144,7 +1231,7 @@ impl Connection {
         }
         // The unfiltered message channel.
         let (msg_sender, msg_receiver) = create_msg_broadcast_channel!(DEFAULT_MAX_QUEUED);
-        let mut msg_senders = HashMap::new();
+        let mut msg_senders = MsgSenders::default();
         msg_senders.insert(None, msg_sender);
 
         // The special method return & error channel.
@@ -1171,6 +1258,15 @@ impl Connection {
                 bus_conn: bus_connection,
                 unique_name: OnceLock::new(),
                 subscriptions,
+                limits,
+                pending_replies: Usage::new(
+                    limits.max_pending_replies,
+                    LimitExceeded::PendingReplies,
+                ),
+                queued_outgoing_bytes: Usage::new(
+                    limits.max_queued_outgoing_bytes,
+                    LimitExceeded::QueuedOutgoingBytes,
+                ),
                 object_server: OnceLock::new(),
                 object_server_dispatch_task: OnceLock::new(),
                 executor,
@@ -1214,7 +1310,8 @@ impl Connection {
     ///
     /// # Caveats
     ///
-    /// Currently `unix_group_ids` and `linux_security_label` fields are not populated.
+    /// Currently `unix_group_ids` and `linux_security_label` fields are not populated. On FreeBSD
+    /// before version 13, DragonFly BSD, OpenBSD and NetBSD, `process_id` isn't populated either.
     pub async fn peer_credentials(&self) -> io::Result<ConnectionCredentials> {
         self.inner
             .socket_write
@@ -1292,6 +1389,7 @@ impl Connection {
         socket_read: Box<dyn socket::ReadHalf>,
         already_read: Vec<u8>,
         #[cfg(unix)] already_received_fds: Vec<std::os::fd::OwnedFd>,
+        strict_validation: bool,
     ) {
         let inner = &self.inner;
         inner
@@ -1304,6 +1402,9 @@ impl Connection {
                     #[cfg(unix)]
                     already_received_fds,
                     inner.activity_event.clone(),
+                    #[cfg(unix)]
+                    inner.limits,
+                    strict_validation,
                 )
                 .spawn(&inner.executor),
             )
@@ -1541,12 +1642,15 @@ mod tests {
 #[cfg(feature = "p2p")]
 #[cfg(test)]
 mod p2p_tests {
-    use futures_util::stream::TryStreamExt;
+    use futures_util::{
+        sink::SinkExt,
+        stream::{self, StreamExt, TryStreamExt},
+    };
     use ntest::timeout;
     use test_log::test;
     use zvariant::{Endian, NATIVE_ENDIAN};
 
-    use crate::{conn::AuthMechanism, Guid};
+    use crate::{conn::AuthMechanism, Guid, MessageSink};
 
     use super::*;
 
@@ -1706,6 +1810,162 @@ mod p2p_tests {
         test_p2p(server1, client1, server2, client2).await
     }
 
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[test]
+    #[timeout(15000)]
+    fn unix_p2p_peer_credentials() {
+        crate::utils::block_on(async {
+            let (server, client) = unix_p2p_pipe().await?;
+            for conn in [server, client] {
+                let creds = conn.peer_credentials().await?;
+                assert_eq!(creds.process_id(), Some(std::process::id()));
+                assert_eq!(creds.unix_user_id(), Some(nix::unistd::getuid().as_raw()));
+                // pidfds are only supported since Linux 6.5.
+                if let Some(pidfd) = creds.process_fd() {
+                    use std::os::fd::AsRawFd;
+
+                    let fdinfo = std::fs::read_to_string(format!(
+                        "/proc/self/fdinfo/{}",
+                        pidfd.as_raw_fd()
+                    ))?;
+                    let pid = format!("Pid:\t{}", std::process::id());
+                    assert!(fdinfo.lines().any(|line| line == pid));
+                }
+            }
+
+            Ok::<_, Error>(())
+        })
+        .unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[timeout(15000)]
+    fn unix_p2p_limits() {
+        #[cfg(not(feature = "tokio"))]
+        use std::os::unix::net::UnixStream;
+        #[cfg(feature = "tokio")]
+        use tokio::net::UnixStream;
+        use zvariant::Fd;
+
+        crate::utils::block_on(async {
+            let (p0, p1) = UnixStream::pair().unwrap();
+            let (client, server) = futures_util::try_join!(
+                Builder::unix_stream(p1)
+                    .p2p()
+                    .max_fds_per_message(1)
+                    .max_pending_replies(1)
+                    .max_match_rules(1)
+                    .max_queued_outgoing_bytes(1024)
+                    .build(),
+                Builder::unix_stream(p0)
+                    .server(Guid::generate())
+                    .unwrap()
+                    .p2p()
+                    .build(),
+            )?;
+            fn limit_exceeded<T>(res: Result<T>, limit: LimitExceeded) {
+                match res {
+                    Err(e) => assert_eq!(e, Error::LimitExceeded(limit)),
+                    Ok(_) => panic!("limit `{limit:?}` not enforced"),
+                }
+            }
+
+            // The server has no object server so it never replies.
+            let call = || {
+                client.call_method_raw(None::<()>, "/", None::<()>, "Test", BitFlags::empty(), &())
+            };
+            let pending = call().await?;
+            limit_exceeded(call().await, LimitExceeded::PendingReplies);
+            drop(pending);
+            call().await?;
+
+            let rule = |member| -> OwnedMatchRule {
+                MatchRule::builder()
+                    .msg_type(Type::Signal)
+                    .member(member)
+                    .unwrap()
+                    .build()
+                    .into()
+            };
+            let _first = client.add_match(rule("First"), None).await?;
+            let _first_again = client.add_match(rule("First"), None).await?;
+            limit_exceeded(
+                client.add_match(rule("Second"), None).await,
+                LimitExceeded::MatchRules,
+            );
+
+            let big = Message::signal("/", "org.zbus.Limits", "Big")?.build(&vec![0u8; 1024])?;
+            limit_exceeded(client.send(&big).await, LimitExceeded::QueuedOutgoingBytes);
+
+            let stdout = std::io::stdout();
+            let fds = Message::signal("/", "org.zbus.Limits", "Fds")?
+                .build(&(Fd::from(&stdout), Fd::from(&stdout)))?;
+            limit_exceeded(client.send(&fds).await, LimitExceeded::FdsPerMessage);
+            // Receiving too many FDs terminates the connection.
+            let mut stream = MessageStream::from(&client);
+            server.send(&fds).await?;
+            limit_exceeded(
+                stream.try_next().await.transpose().unwrap(),
+                LimitExceeded::FdsPerMessage,
+            );
+
+            Ok::<_, Error>(())
+        })
+        .unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[timeout(15000)]
+    fn unix_p2p_fds_in_a_row() {
+        use std::{
+            io::{Read, Seek, Write},
+            os::fd::AsFd,
+        };
+        use zvariant::Fd;
+
+        crate::utils::block_on(async {
+            let (server, client) = unix_p2p_pipe().await?;
+            let mut stream = MessageStream::from(&server);
+            let mut file = tempfile::tempfile()?;
+            file.write_all(b"zbus")?;
+            file.rewind()?;
+
+            // Send messages with and without FDs in a row, so they're likely to be read together.
+            for i in 0..4u32 {
+                let msg = Message::signal("/org/zbus/Fds", "org.zbus.Fds", "Fd")?;
+                let msg = if i % 2 == 1 {
+                    msg.build(&(i, Fd::from(file.as_fd())))?
+                } else {
+                    msg.build(&(i,))?
+                };
+                client.send(&msg).await?;
+            }
+
+            for i in 0..4u32 {
+                let msg = stream.try_next().await?.unwrap();
+                let body = msg.body();
+                if i % 2 == 1 {
+                    let (n, fd): (u32, Fd<'_>) = body.deserialize()?;
+                    assert_eq!(n, i);
+                    let mut received = std::fs::File::from(fd.as_fd().try_clone_to_owned()?);
+                    let mut contents = String::new();
+                    received.rewind()?;
+                    received.read_to_string(&mut contents)?;
+                    assert_eq!(contents, "zbus");
+                } else {
+                    let (n,): (u32,) = body.deserialize()?;
+                    assert_eq!(n, i);
+                    assert!(msg.data().fds().is_empty());
+                }
+            }
+
+            Ok::<_, Error>(())
+        })
+        .unwrap();
+    }
+
     #[cfg(unix)]
     async fn unix_p2p_pipe() -> Result<(Connection, Connection)> {
         #[cfg(not(feature = "tokio"))]
@@ -1847,6 +2107,30 @@ mod p2p_tests {
         test_p2p(server1, client1, server2, client2).await
     }
 
+    #[test]
+    #[timeout(15000)]
+    fn message_sink() {
+        crate::utils::block_on(test_message_sink()).unwrap();
+    }
+
+    async fn test_message_sink() -> Result<()> {
+        let (server, client) = create_channel_pair().await;
+        let mut stream = MessageStream::from(&server);
+        let mut sink = MessageSink::from(&client);
+
+        let signals = (0..3u32)
+            .map(|i| Message::signal("/", "org.zbus.p2p", "Count")?.build(&i))
+            .collect::<Result<Vec<_>>>()?;
+        sink.send_all(&mut stream::iter(signals).map(Ok)).await?;
+        sink.close().await?;
+        for i in 0..3u32 {
+            let m = stream.try_next().await?.unwrap();
+            assert_eq!(m.body().deserialize::<u32>()?, i);
+        }
+
+        Ok(())
+    }
+
     async fn create_channel_pair() -> (Connection, Connection) {
         let (a, b) = socket::Channel::pair();
 
@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use zbus_names::BusName;
+
+use crate::{match_rule::PathSpec, Message, OwnedMatchRule};
+
+use super::MsgBroadcaster;
+
+type Entries = Vec<(Option<OwnedMatchRule>, MsgBroadcaster)>;
+
+/// The message broadcasters of a connection's streams, keyed by their match rule.
+///
+/// `None` is the key of the unfiltered stream's broadcaster.
+///
+/// To avoid matching each incoming message against every single rule, the rules are indexed by the
+/// most selective field they require an exact value for, so only the rules that could possibly
+/// match a message are checked against it.
+#[derive(Debug, Default)]
+pub(crate) struct MsgSenders {
+    by_path: HashMap<String, Entries>,
+    by_member: HashMap<String, Entries>,
+    by_interface: HashMap<String, Entries>,
+    by_sender: HashMap<String, Entries>,
+    // Rules (and the unfiltered stream) that can't be indexed by any of the above.
+    unindexed: Entries,
+}
+
+impl MsgSenders {
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn insert(&mut self, rule: Option<OwnedMatchRule>, sender: MsgBroadcaster) {
+        let entries = self.entries_mut(rule.as_ref());
+        match entries.iter_mut().find(|(r, _)| *r == rule) {
+            Some(entry) => entry.1 = sender,
+            None => entries.push((rule, sender)),
+        }
+    }
+
+    pub fn remove(&mut self, rule: &OwnedMatchRule) {
+        let Some((index, key)) = Index::of(rule) else {
+            self.unindexed.retain(|(r, _)| r.as_ref() != Some(rule));
+
+            return;
+        };
+        let index = self.index_mut(index);
+        if let Some(entries) = index.get_mut(&key) {
+            entries.retain(|(r, _)| r.as_ref() != Some(rule));
+            if entries.is_empty() {
+                index.remove(&key);
+            }
+        }
+    }
+
+    /// All the broadcasters, along with their rules.
+    pub fn iter(&self) -> impl Iterator<Item = &(Option<OwnedMatchRule>, MsgBroadcaster)> {
+        [
+            &self.by_path,
+            &self.by_member,
+            &self.by_interface,
+            &self.by_sender,
+        ]
+        .into_iter()
+        .flat_map(HashMap::values)
+        .chain(std::iter::once(&self.unindexed))
+        .flatten()
+    }
+
+    /// The broadcasters whose rules could match `msg`, along with their rules.
+    ///
+    /// The rules still need to be matched against `msg` by the caller.
+    pub fn candidates(&self, msg: &Message) -> Vec<&(Option<OwnedMatchRule>, MsgBroadcaster)> {
+        let hdr = msg.header();
+
+        [
+            (&self.by_path, hdr.path().map(|p| p.as_str())),
+            (&self.by_member, hdr.member().map(|m| m.as_str())),
+            (&self.by_interface, hdr.interface().map(|i| i.as_str())),
+            (&self.by_sender, hdr.sender().map(|s| s.as_str())),
+        ]
+        .into_iter()
+        .filter_map(|(index, key)| key.and_then(|key| index.get(key)))
+        .chain(std::iter::once(&self.unindexed))
+        .flatten()
+        .collect()
+    }
+
+    fn entries_mut(&mut self, rule: Option<&OwnedMatchRule>) -> &mut Entries {
+        match rule.and_then(Index::of) {
+            Some((index, key)) => self.index_mut(index).entry(key).or_default(),
+            None => &mut self.unindexed,
+        }
+    }
+
+    fn index_mut(&mut self, index: Index) -> &mut HashMap<String, Entries> {
+        match index {
+            Index::Path => &mut self.by_path,
+            Index::Member => &mut self.by_member,
+            Index::Interface => &mut self.by_interface,
+            Index::Sender => &mut self.by_sender,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Index {
+    Path,
+    Member,
+    Interface,
+    Sender,
+}
+
+impl Index {
+    // The index `rule` belongs to and its key in there. We go from the most selective to the least
+    // selective field.
+    fn of(rule: &OwnedMatchRule) -> Option<(Self, String)> {
+        if let Some(PathSpec::Path(path)) = rule.path_spec() {
+            Some((Self::Path, path.to_string()))
+        } else if let Some(member) = rule.member() {
+            Some((Self::Member, member.to_string()))
+        } else if let Some(interface) = rule.interface() {
+            Some((Self::Interface, interface.to_string()))
+        } else if let Some(BusName::Unique(sender)) = rule.sender() {
+            Some((Self::Sender, sender.to_string()))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_broadcast::broadcast;
+    use test_log::test;
+
+    use super::MsgSenders;
+    use crate::{message::Message, MatchRule, OwnedMatchRule};
+
+    #[test]
+    fn candidates() {
+        let rule = |rule: &str| Some(OwnedMatchRule::from(MatchRule::try_from(rule).unwrap()));
+        let by_path = rule("type='signal',path='/org/zbus/a',member='Changed'");
+        let by_member = rule("type='signal',member='Changed'");
+        let by_interface = rule("type='signal',interface='org.zbus.A'");
+        let by_sender = rule("type='signal',sender=':1.42'");
+        let unindexed = rule("type='signal',path_namespace='/org/zbus'");
+        let mut senders = MsgSenders::default();
+        for rule in [
+            None,
+            by_path.clone(),
+            by_member.clone(),
+            by_interface.clone(),
+            by_sender.clone(),
+            unindexed.clone(),
+        ] {
+            senders.insert(rule, broadcast(1).0);
+        }
+        let candidates = |senders: &MsgSenders, msg: &Message| {
+            senders
+                .candidates(msg)
+                .into_iter()
+                .map(|(rule, _)| rule.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let msg = Message::signal("/org/zbus/a", "org.zbus.B", "Changed")
+            .unwrap()
+            .build(&())
+            .unwrap();
+        let found = candidates(&senders, &msg);
+        assert_eq!(found.len(), 4);
+        for rule in [&None, &by_path, &by_member, &unindexed] {
+            assert!(found.contains(rule));
+        }
+
+        let msg = Message::signal("/org/zbus/b", "org.zbus.A", "Other")
+            .unwrap()
+            .sender(":1.42")
+            .unwrap()
+            .build(&())
+            .unwrap();
+        let found = candidates(&senders, &msg);
+        assert_eq!(found.len(), 4);
+        for rule in [&None, &by_interface, &by_sender, &unindexed] {
+            assert!(found.contains(rule));
+        }
+
+        senders.remove(by_path.as_ref().unwrap());
+        senders.remove(unindexed.as_ref().unwrap());
+        assert_eq!(senders.iter().count(), 4);
+        assert!(senders.by_path.is_empty());
+        assert!(!senders.is_empty());
+        senders.clear();
+        assert!(senders.is_empty());
+    }
+}
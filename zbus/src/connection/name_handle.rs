@@ -0,0 +1,97 @@
+use enumflags2::BitFlags;
+use static_assertions::assert_impl_all;
+use zbus_names::WellKnownName;
+
+use crate::{
+    fdo::{DBusProxy, NameAcquiredStream, NameLostStream, RequestNameFlags, RequestNameReply},
+    Connection, Result,
+};
+
+/// A handle to a well-known name requested through [`Connection::request_name_with_handle`].
+///
+/// Besides the reply of the bus to the request, the handle provides streams of the `NameAcquired`
+/// and `NameLost` signals for the name. This allows services to react to changes in the ownership
+/// of their name, e.g. when they get out of the queue or when another peer replaces them as the
+/// owner, instead of discovering it from failing calls.
+///
+/// Dropping the handle doesn't release the name. Use [`NameHandle::release`] or
+/// [`Connection::release_name`] for that.
+#[derive(Debug)]
+pub struct NameHandle {
+    conn: Connection,
+    name: WellKnownName<'static>,
+    reply: RequestNameReply,
+    acquired: NameAcquiredStream,
+    lost: NameLostStream,
+}
+
+assert_impl_all!(NameHandle: Send, Sync, Unpin);
+
+impl NameHandle {
+    pub(crate) async fn request(
+        conn: &Connection,
+        name: WellKnownName<'_>,
+        flags: BitFlags<RequestNameFlags>,
+    ) -> Result<Self> {
+        // Create the streams before the request so we don't miss any signals emitted in response.
+        let proxy = DBusProxy::new(conn).await?;
+        let acquired = proxy
+            .receive_name_acquired_for(name.as_ref().into())
+            .await?;
+        let lost = proxy.receive_name_lost_for(name.as_ref().into()).await?;
+        let reply = conn.request_name_with_flags(name.as_ref(), flags).await?;
+
+        Ok(Self {
+            conn: conn.clone(),
+            name: name.into_owned(),
+            reply,
+            acquired,
+            lost,
+        })
+    }
+
+    /// The requested name.
+    pub fn name(&self) -> &WellKnownName<'static> {
+        &self.name
+    }
+
+    /// The reply of the bus to the name request.
+    ///
+    /// This is never [`RequestNameReply::Exists`] since [`crate::Error::NameTaken`] is returned in
+    /// that case.
+    pub fn reply(&self) -> RequestNameReply {
+        self.reply
+    }
+
+    /// Whether the request put us in the queue of the name, rather than making us its owner.
+    ///
+    /// Use [`NameHandle::receive_name_acquired`] to get notified when we become the owner.
+    pub fn is_queued(&self) -> bool {
+        self.reply == RequestNameReply::InQueue
+    }
+
+    /// The stream of `NameAcquired` signals for the name.
+    pub fn receive_name_acquired(&mut self) -> &mut NameAcquiredStream {
+        &mut self.acquired
+    }
+
+    /// The stream of `NameLost` signals for the name.
+    ///
+    /// The name can only be lost if it was requested with [`RequestNameFlags::AllowReplacement`],
+    /// or if it's released.
+    pub fn receive_name_lost(&mut self) -> &mut NameLostStream {
+        &mut self.lost
+    }
+
+    /// Consume `self`, returning the `NameAcquired` and `NameLost` signal streams.
+    pub fn into_streams(self) -> (NameAcquiredStream, NameLostStream) {
+        (self.acquired, self.lost)
+    }
+
+    /// Release the name.
+    ///
+    /// Same as [`Connection::release_name`], but consumes `self`.
+    pub async fn release(self) -> Result<bool> {
+        self.conn.release_name(self.name).await
+    }
+}
@@ -43,6 +43,9 @@ type RecvmsgResult = io::Result<(usize, Vec<OwnedFd>)>;
 #[cfg(not(unix))]
 type RecvmsgResult = io::Result<usize>;
 
+/// The amount of bytes we try to read from the socket at once, when starting to read a message.
+const READ_BUFFER_SIZE: usize = 4096;
+
 /// Trait representing some transport layer over which the DBus protocol can be used.
 ///
 /// In order to allow simultaneous reading and writing, this trait requires you to split the socket
@@ -85,10 +88,10 @@ pub trait ReadHalf: std::fmt::Debug + Send + Sync + 'static {
     ///
     /// - `seq`: The sequence number of the message. The returned message should have this sequence.
     /// - `already_received_bytes`: Sometimes, zbus already received some bytes from the socket
-    ///   belonging to the first message(s) (as part of the connection handshake process). This is
-    ///   the buffer containing those bytes (if any). If you're implementing this method, most
-    ///   likely you can safely ignore this parameter.
-    /// - `already_received_fds`: Same goes for file descriptors belonging to first messages.
+    ///   belonging to the next message(s) (as part of the connection handshake process or of the
+    ///   previous message). This is the buffer containing those bytes (if any). If you're
+    ///   implementing this method, most likely you can safely ignore this parameter.
+    /// - `already_received_fds`: Same goes for file descriptors belonging to the next messages.
     async fn receive_message(
         &mut self,
         seq: u64,
@@ -97,46 +100,37 @@ pub trait ReadHalf: std::fmt::Debug + Send + Sync + 'static {
     ) -> crate::Result<Message> {
         #[cfg(unix)]
         let mut fds = vec![];
-        let mut bytes = if already_received_bytes.len() < MIN_MESSAGE_SIZE {
-            let mut bytes = vec![];
-            if !already_received_bytes.is_empty() {
-                mem::swap(already_received_bytes, &mut bytes);
-            }
-            let mut pos = bytes.len();
-            bytes.resize(MIN_MESSAGE_SIZE, 0);
-            // We don't have enough data to make a proper message header yet.
-            // Some partial read may be in raw_in_buffer, so we try to complete it
-            // until we have MIN_MESSAGE_SIZE bytes
-            //
-            // Given that MIN_MESSAGE_SIZE is 16, this codepath is actually extremely unlikely
-            // to be taken more than once
-            while pos < MIN_MESSAGE_SIZE {
-                let res = self.recvmsg(&mut bytes[pos..]).await?;
-                let len = {
-                    #[cfg(unix)]
-                    {
-                        fds.extend(res.1);
-                        res.0
-                    }
-                    #[cfg(not(unix))]
-                    {
-                        res
-                    }
-                };
-                pos += len;
-                if len == 0 {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::UnexpectedEof,
-                        "failed to receive message",
-                    )
-                    .into());
+        // We don't have enough data to make a proper message header yet. Read as much as we can
+        // in one go, so that small messages are received with a single call and messages sent in
+        // a row are buffered for the next calls.
+        while already_received_bytes.len() < MIN_MESSAGE_SIZE {
+            let pos = already_received_bytes.len();
+            already_received_bytes.resize(READ_BUFFER_SIZE, 0);
+            let res = self.recvmsg(&mut already_received_bytes[pos..]).await;
+            let len = match res {
+                #[cfg(unix)]
+                Ok((len, received_fds)) => {
+                    fds.extend(received_fds);
+                    len
                 }
-            }
+                #[cfg(not(unix))]
+                Ok(len) => len,
+                Err(e) => {
+                    already_received_bytes.truncate(pos);
 
-            bytes
-        } else {
-            already_received_bytes.drain(..MIN_MESSAGE_SIZE).collect()
-        };
+                    return Err(e.into());
+                }
+            };
+            already_received_bytes.truncate(pos + len);
+            if len == 0 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "failed to receive message",
+                )
+                .into());
+            }
+        }
+        let mut bytes: Vec<u8> = already_received_bytes.drain(..MIN_MESSAGE_SIZE).collect();
 
         let (primary_header, fields_len) = PrimaryHeader::read(&bytes)?;
         let header_len = MIN_MESSAGE_SIZE + fields_len as usize;
@@ -187,8 +181,12 @@ pub trait ReadHalf: std::fmt::Debug + Send + Sync + 'static {
         // If we reach here, the message is complete; return it
         let endian = Endian::from(primary_header.endian_sig());
 
+        // The FDs we received might belong to the messages we buffered and the FDs received before
+        // to this message, so we need to assign them to the message according to its header.
         #[cfg(unix)]
-        if !already_received_fds.is_empty() {
+        if !already_received_fds.is_empty()
+            || (!fds.is_empty() && !already_received_bytes.is_empty())
+        {
             use crate::message::header::PRIMARY_HEADER_SIZE;
 
             let ctxt = Context::new_dbus(endian, PRIMARY_HEADER_SIZE);
@@ -199,17 +197,18 @@ pub trait ReadHalf: std::fmt::Debug + Send + Sync + 'static {
                 Some(num_fds) => num_fds as usize,
                 _ => 0,
             };
-            let num_pending = num_required_fds
-                .checked_sub(fds.len())
-                .ok_or_else(|| crate::Error::ExcessData)?;
-            // If we had previously received FDs, `num_pending` has to be > 0
-            if num_pending == 0 {
+            // All previously received FDs must go first in the list.
+            let mut all_fds = mem::take(already_received_fds);
+            all_fds.extend(fds);
+            if all_fds.len() < num_required_fds {
                 return Err(crate::Error::MissingParameter("Missing file descriptors"));
             }
-            // All previously received FDs must go first in the list.
-            let mut already_received: Vec<_> = already_received_fds.drain(..num_pending).collect();
-            mem::swap(&mut already_received, &mut fds);
-            fds.extend(already_received);
+            *already_received_fds = all_fds.split_off(num_required_fds);
+            // Left over FDs are only fine if they can belong to the next messages.
+            if !already_received_fds.is_empty() && already_received_bytes.is_empty() {
+                return Err(crate::Error::ExcessData);
+            }
+            fds = all_fds;
         }
 
         let ctxt = Context::new_dbus(endian, 0);
@@ -335,28 +335,161 @@ fn get_unix_peer_creds_blocking(fd: RawFd) -> io::Result<crate::fdo::ConnectionC
     {
         use nix::sys::socket::{getsockopt, sockopt::PeerCredentials};
 
-        getsockopt(&fd, PeerCredentials)
-            .map(|creds| {
-                crate::fdo::ConnectionCredentials::default()
-                    .set_process_id(creds.pid() as _)
-                    .set_unix_user_id(creds.uid())
-            })
-            .map_err(|e| e.into())
+        let creds = getsockopt(&fd, PeerCredentials).map(|creds| {
+            crate::fdo::ConnectionCredentials::default()
+                .set_process_id(creds.pid() as _)
+                .set_unix_user_id(creds.uid())
+        })?;
+
+        // Older kernels don't support pidfds, so that's not an error.
+        Ok(match get_peer_pidfd(fd) {
+            Some(pidfd) => creds.set_process_fd(pidfd.into()),
+            None => creds,
+        })
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    {
+        use nix::sys::socket::{getsockopt, sockopt::LocalPeerPid};
+
+        let uid = nix::unistd::getpeereid(fd).map(|(uid, _)| uid.into())?;
+        let pid = getsockopt(&fd, LocalPeerPid)?;
+
+        Ok(crate::fdo::ConnectionCredentials::default()
+            .set_process_id(pid as _)
+            .set_unix_user_id(uid))
+    }
+
+    #[cfg(target_os = "freebsd")]
+    {
+        use nix::libc;
+
+        // `LOCAL_PEERCRED` rather than `getpeereid`, since the former also gives us the PID.
+        let mut xucred = std::mem::MaybeUninit::<libc::xucred>::zeroed();
+        let mut len = std::mem::size_of::<libc::xucred>() as libc::socklen_t;
+        // SAFETY: `xucred` and `len` are valid for writes and `len` is the size of `xucred`.
+        let res = unsafe {
+            libc::getsockopt(
+                fd.as_raw_fd(),
+                // `SOL_LOCAL`, which isn't in the libc crate.
+                0,
+                libc::LOCAL_PEERCRED,
+                xucred.as_mut_ptr() as *mut libc::c_void,
+                &mut len,
+            )
+        };
+        if res != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: The kernel filled in the credentials on success and they were zeroed before.
+        let xucred = unsafe { xucred.assume_init() };
+        if xucred.cr_version != libc::XUCRED_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unexpected `xucred` version",
+            ));
+        }
+        // SAFETY: The PID shares its storage with an unused pointer, so it's always initialized.
+        let pid = unsafe { xucred.cr_pid__c_anonymous_union.cr_pid };
+
+        let creds = crate::fdo::ConnectionCredentials::default().set_unix_user_id(xucred.cr_uid);
+        // FreeBSD only fills in the PID since version 13, it's left zeroed before that.
+        Ok(if pid > 0 {
+            creds.set_process_id(pid as _)
+        } else {
+            creds
+        })
     }
 
-    #[cfg(any(
-        target_os = "macos",
-        target_os = "ios",
-        target_os = "freebsd",
-        target_os = "dragonfly",
-        target_os = "openbsd",
-        target_os = "netbsd"
-    ))]
+    #[cfg(any(target_os = "dragonfly", target_os = "openbsd", target_os = "netbsd"))]
     {
         let uid = nix::unistd::getpeereid(fd).map(|(uid, _)| uid.into())?;
         // FIXME: Handle pid fetching too.
         Ok(crate::fdo::ConnectionCredentials::default().set_unix_user_id(uid))
     }
+
+    #[cfg(any(target_os = "illumos", target_os = "solaris"))]
+    {
+        use nix::libc;
+
+        let mut ucred = std::ptr::null_mut();
+        // SAFETY: `fd` is a valid socket and `ucred` a valid location for the allocated credentials.
+        if unsafe { libc::getpeerucred(fd.as_raw_fd(), &mut ucred) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: `ucred` was allocated by the successful `getpeerucred` call above, and isn't used
+        // after being freed.
+        let (uid, pid) = unsafe {
+            let ids = (libc::ucred_geteuid(ucred), libc::ucred_getpid(ucred));
+            libc::ucred_free(ucred);
+
+            ids
+        };
+
+        let creds = crate::fdo::ConnectionCredentials::default().set_unix_user_id(uid);
+        // `-1` means the PID isn't available, e.g for a peer in another zone.
+        Ok(if pid >= 0 {
+            creds.set_process_id(pid as _)
+        } else {
+            creds
+        })
+    }
+}
+
+/// The pidfd of the peer process, if the kernel supports `SO_PEERPIDFD` (Linux 6.5+).
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn get_peer_pidfd(fd: BorrowedFd<'_>) -> Option<OwnedFd> {
+    use nix::libc;
+
+    // Not in the libc crate yet. The value is architecture-specific, so only use it on the
+    // architectures we know it for.
+    const SO_PEERPIDFD: Option<libc::c_int> =
+        if cfg!(any(target_arch = "sparc", target_arch = "sparc64")) {
+            Some(0x0056)
+        } else if cfg!(any(
+            target_arch = "x86",
+            target_arch = "x86_64",
+            target_arch = "arm",
+            target_arch = "aarch64",
+            target_arch = "riscv32",
+            target_arch = "riscv64",
+            target_arch = "loongarch64",
+            target_arch = "powerpc",
+            target_arch = "powerpc64",
+            target_arch = "s390x",
+            target_arch = "mips",
+            target_arch = "mips64",
+            target_arch = "mips32r6",
+            target_arch = "mips64r6",
+            target_arch = "m68k",
+            target_arch = "csky",
+            target_arch = "hexagon",
+        )) {
+            Some(77)
+        } else {
+            // e.g parisc, where it's 0x404B.
+            None
+        };
+    let so_peerpidfd = SO_PEERPIDFD?;
+
+    let mut pidfd: libc::c_int = -1;
+    let mut len = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+    // SAFETY: `pidfd` and `len` are valid for writes and `len` is the size of `pidfd`.
+    let res = unsafe {
+        libc::getsockopt(
+            fd.as_raw_fd(),
+            libc::SOL_SOCKET,
+            so_peerpidfd,
+            &mut pidfd as *mut _ as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if res != 0 || pidfd < 0 {
+        return None;
+    }
+
+    // SAFETY: On success, the kernel gave us ownership of a new pidfd.
+    Some(unsafe { OwnedFd::from_raw_fd(pidfd) })
 }
 
 // Send 0 byte as a separate SCM_CREDS message.
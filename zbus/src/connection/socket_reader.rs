@@ -1,32 +1,37 @@
-use std::{collections::HashMap, sync::Arc};
+use std::sync::Arc;
 
 use event_listener::Event;
 use tracing::{debug, instrument, trace};
 
-use crate::{
-    async_lock::Mutex, connection::MsgBroadcaster, Executor, Message, OwnedMatchRule, Task,
-};
+use crate::{async_lock::Mutex, Executor, Message, Task};
 
-use super::socket::ReadHalf;
+#[cfg(unix)]
+use super::Limits;
+use super::{socket::ReadHalf, MsgSenders};
 
 #[derive(Debug)]
 pub(crate) struct SocketReader {
     socket: Box<dyn ReadHalf>,
-    senders: Arc<Mutex<HashMap<Option<OwnedMatchRule>, MsgBroadcaster>>>,
+    senders: Arc<Mutex<MsgSenders>>,
     already_received_bytes: Vec<u8>,
     #[cfg(unix)]
     already_received_fds: Vec<std::os::fd::OwnedFd>,
     prev_seq: u64,
     activity_event: Arc<Event>,
+    #[cfg(unix)]
+    limits: Limits,
+    strict_validation: bool,
 }
 
 impl SocketReader {
     pub fn new(
         socket: Box<dyn ReadHalf>,
-        senders: Arc<Mutex<HashMap<Option<OwnedMatchRule>, MsgBroadcaster>>>,
+        senders: Arc<Mutex<MsgSenders>>,
         already_received_bytes: Vec<u8>,
         #[cfg(unix)] already_received_fds: Vec<std::os::fd::OwnedFd>,
         activity_event: Arc<Event>,
+        #[cfg(unix)] limits: Limits,
+        strict_validation: bool,
     ) -> Self {
         Self {
             socket,
@@ -36,6 +41,9 @@ impl SocketReader {
             already_received_fds,
             prev_seq: 0,
             activity_event,
+            #[cfg(unix)]
+            limits,
+            strict_validation,
         }
     }
 
@@ -50,12 +58,23 @@ impl SocketReader {
             trace!("Waiting for message on the socket..");
             let msg = self.read_socket().await;
             match &msg {
-                Ok(msg) => trace!("Message received on the socket: {:?}", msg),
+                Ok(msg) => trace!(
+                    msg.type = ?msg.message_type(),
+                    msg.serial = %msg.primary_header().serial_num(),
+                    msg.interface = ?msg.header().interface(),
+                    msg.member = ?msg.header().member(),
+                    "Message received on the socket: {:?}",
+                    msg
+                ),
                 Err(e) => trace!("Error reading from the socket: {:?}", e),
             };
 
             let mut senders = self.senders.lock().await;
-            for (rule, sender) in &*senders {
+            let candidates = match &msg {
+                Ok(msg) => senders.candidates(msg),
+                Err(_) => senders.iter().collect(),
+            };
+            for (rule, sender) in candidates {
                 if let Ok(msg) = &msg {
                     if let Some(rule) = rule.as_ref() {
                         match rule.matches(msg) {
@@ -111,6 +130,11 @@ impl SocketReader {
                 &mut self.already_received_fds,
             )
             .await?;
+        #[cfg(unix)]
+        self.limits.check_fds_per_message(msg.data().fds().len())?;
+        if self.strict_validation {
+            msg.validate()?;
+        }
         self.prev_seq = seq;
 
         Ok(msg)
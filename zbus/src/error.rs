@@ -8,6 +8,42 @@ use crate::{
     message::{Message, Type},
 };
 
+/// The connection resource limit that was exceeded.
+///
+/// See [`connection::Builder`](crate::connection::Builder) for how to set these limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LimitExceeded {
+    /// The maximum number of file descriptors in a single message was exceeded.
+    FdsPerMessage,
+    /// The maximum number of method calls awaiting a reply was exceeded.
+    PendingReplies,
+    /// The maximum number of match rules was exceeded.
+    MatchRules,
+    /// The maximum number of bytes of outgoing messages waiting to be written was exceeded.
+    QueuedOutgoingBytes,
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FdsPerMessage => write!(
+                f,
+                "Maximum allowed number of file descriptors in a message was exceeded"
+            ),
+            Self::PendingReplies => write!(
+                f,
+                "Maximum allowed number of method calls awaiting a reply was exceeded"
+            ),
+            Self::MatchRules => write!(f, "Maximum allowed number of match rules was exceeded"),
+            Self::QueuedOutgoingBytes => write!(
+                f,
+                "Maximum allowed number of bytes of queued outgoing messages was exceeded"
+            ),
+        }
+    }
+}
+
 /// The error type for `zbus`.
 ///
 /// The various errors that can be reported by this crate.
@@ -61,6 +97,8 @@ pub enum Error {
     InvalidSerial,
     /// The given interface already exists at the given path.
     InterfaceExists(InterfaceName<'static>, ObjectPath<'static>),
+    /// A resource limit of the connection was exceeded.
+    LimitExceeded(LimitExceeded),
 }
 
 assert_impl_all!(Error: Send, Sync, Unpin);
@@ -88,6 +126,7 @@ impl PartialEq for Error {
             (Error::InputOutput(_), Self::InputOutput(_)) => false,
             (Self::Failure(s1), Self::Failure(s2)) => s1 == s2,
             (Self::InterfaceExists(s1, s2), Self::InterfaceExists(o1, o2)) => s1 == o1 && s2 == o2,
+            (Self::LimitExceeded(s), Self::LimitExceeded(o)) => s == o,
             (_, _) => false,
         }
     }
@@ -117,6 +156,7 @@ impl error::Error for Error {
             Error::MissingParameter(_) => None,
             Error::InvalidSerial => None,
             Error::InterfaceExists(_, _) => None,
+            Error::LimitExceeded(_) => None,
         }
     }
 }
@@ -152,6 +192,7 @@ impl fmt::Display for Error {
             }
             Error::InvalidSerial => write!(f, "Serial number in the message header is 0"),
             Error::InterfaceExists(i, p) => write!(f, "Interface `{i}` already exists at `{p}`"),
+            Error::LimitExceeded(limit) => write!(f, "{limit}"),
         }
     }
 }
@@ -182,6 +223,7 @@ impl Clone for Error {
             Error::MissingParameter(p) => Error::MissingParameter(p),
             Error::InvalidSerial => Error::InvalidSerial,
             Error::InterfaceExists(i, p) => Error::InterfaceExists(i.clone(), p.clone()),
+            Error::LimitExceeded(limit) => Error::LimitExceeded(*limit),
         }
     }
 }
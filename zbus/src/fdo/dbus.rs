@@ -4,17 +4,19 @@
 //! be useful across various D-Bus applications. This module provides their proxy.
 
 use enumflags2::{bitflags, BitFlags};
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use static_assertions::assert_impl_all;
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
+use tracing::{trace_span, warn, Instrument};
 use zbus_names::{
     BusName, OwnedBusName, OwnedInterfaceName, OwnedUniqueName, UniqueName, WellKnownName,
 };
 use zvariant::{DeserializeDict, Optional, SerializeDict, Type};
 
-use super::Result;
-use crate::{proxy, OwnedGuid};
+use super::{Error, Result};
+use crate::{proxy, AsyncDrop, Connection, OwnedGuid, OwnedMatchRule};
 
 /// The flags used by the bus [`request_name`] method.
 ///
@@ -57,7 +59,7 @@ assert_impl_all!(RequestNameFlags: Send, Sync, Unpin);
 ///
 /// [`request_name`]: struct.DBusProxy.html#method.request_name
 #[repr(u32)]
-#[derive(Deserialize_repr, Serialize_repr, Type, Debug, PartialEq, Eq)]
+#[derive(Deserialize_repr, Serialize_repr, Type, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RequestNameReply {
     /// The caller is now the primary owner of the name, replacing any previous owner. Either the
     /// name had no owner before, or the caller specified [`ReplaceExisting`] and the current owner
@@ -108,6 +110,20 @@ pub enum ReleaseNameReply {
 
 assert_impl_all!(ReleaseNameReply: Send, Sync, Unpin);
 
+/// The return code of the [`start_service`] method.
+///
+/// [`start_service`]: struct.DBusProxy.html#method.start_service
+#[repr(u32)]
+#[derive(Deserialize_repr, Serialize_repr, Type, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartServiceReply {
+    /// The service was successfully started.
+    Success = 0x01,
+    /// A connection already owns the given name.
+    AlreadyRunning = 0x02,
+}
+
+assert_impl_all!(StartServiceReply: Send, Sync, Unpin);
+
 /// Credentials of a process connected to a bus server.
 ///
 /// If unable to determine certain credentials (for instance, because the process is not on the same
@@ -117,7 +133,7 @@ assert_impl_all!(ReleaseNameReply: Send, Sync, Unpin);
 ///
 /// **Note**: unknown keys, in particular those with "." that are not from the specification, will
 /// be ignored. Use your own implementation or contribute your keys here, or in the specification.
-#[derive(Debug, Default, DeserializeDict, PartialEq, Eq, SerializeDict, Type)]
+#[derive(Debug, Default, DeserializeDict, SerializeDict, Type)]
 #[zvariant(signature = "a{sv}")]
 pub struct ConnectionCredentials {
     #[zvariant(rename = "UnixUserID")]
@@ -129,6 +145,10 @@ pub struct ConnectionCredentials {
     #[zvariant(rename = "ProcessID")]
     pub(crate) process_id: Option<u32>,
 
+    #[cfg(unix)]
+    #[zvariant(rename = "ProcessFD")]
+    pub(crate) process_fd: Option<zvariant::OwnedFd>,
+
     #[zvariant(rename = "WindowsSID")]
     pub(crate) windows_sid: Option<String>,
 
@@ -163,6 +183,22 @@ impl ConnectionCredentials {
         self.process_id
     }
 
+    /// A pidfd referring to the process, on Linux.
+    ///
+    /// Unlike the process ID, which can be reused once the process exits, a pidfd always refers to
+    /// the same process. Use it instead of [`ConnectionCredentials::process_id`] to identify the
+    /// peer for authorization, to avoid races with PID reuse.
+    #[cfg(unix)]
+    pub fn process_fd(&self) -> Option<&zvariant::OwnedFd> {
+        self.process_fd.as_ref()
+    }
+
+    /// Same as [`ConnectionCredentials::process_fd`], but consumes `self` and returns the pidfd.
+    #[cfg(unix)]
+    pub fn into_process_fd(self) -> Option<zvariant::OwnedFd> {
+        self.process_fd
+    }
+
     /// The Windows security identifier in its string form, e.g.
     /// `S-1-5-21-3623811015-3361044348-30300820-1013` for a domain or local computer user or
     /// "S-1-5-18` for the LOCAL_SYSTEM user.
@@ -231,6 +267,16 @@ impl ConnectionCredentials {
         self
     }
 
+    /// Set the pidfd referring to the process.
+    ///
+    /// See [`ConnectionCredentials::process_fd`] for more information.
+    #[cfg(unix)]
+    pub fn set_process_fd(mut self, process_fd: zvariant::OwnedFd) -> Self {
+        self.process_fd = Some(process_fd);
+
+        self
+    }
+
     /// Set the Windows security identifier in its string form.
     pub fn set_windows_sid(mut self, windows_sid: String) -> Self {
         self.windows_sid = Some(windows_sid);
@@ -248,6 +294,19 @@ impl ConnectionCredentials {
     }
 }
 
+// The pidfd is left out, since two descriptors referring to the same process needn't be equal.
+impl PartialEq for ConnectionCredentials {
+    fn eq(&self, other: &Self) -> bool {
+        self.unix_user_id == other.unix_user_id
+            && self.unix_group_ids == other.unix_group_ids
+            && self.process_id == other.process_id
+            && self.windows_sid == other.windows_sid
+            && self.linux_security_label == other.linux_security_label
+    }
+}
+
+impl Eq for ConnectionCredentials {}
+
 /// Proxy for the `org.freedesktop.DBus` interface.
 #[proxy(
     default_service = "org.freedesktop.DBus",
@@ -319,6 +378,9 @@ pub trait DBus {
     fn start_service_by_name(&self, name: WellKnownName<'_>, flags: u32) -> Result<u32>;
 
     /// This method adds to or modifies that environment when activating services.
+    ///
+    /// An environment of owned strings, e.g. collected from [`std::env::vars`], can be passed by
+    /// borrowing its entries: `env.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect()`.
     fn update_activation_environment(&self, environment: HashMap<&str, &str>) -> Result<()>;
 
     /// This signal indicates that the owner of a name has
@@ -361,6 +423,231 @@ pub trait DBus {
     fn interfaces(&self) -> Result<Vec<OwnedInterfaceName>>;
 }
 
+impl DBusProxy<'_> {
+    /// Adds a match rule to match messages going through the message bus, returning a guard that
+    /// removes it when dropped.
+    ///
+    /// This is the same as [`DBusProxy::add_match_rule`], except that the match rule can't be
+    /// leaked by forgetting to call [`DBusProxy::remove_match_rule`], e.g. on early returns.
+    ///
+    /// Unless [`AsyncDrop::async_drop`] is called on the guard, the match rule is removed by a
+    /// task spawned when the guard is dropped.
+    pub async fn add_match_rule_with_guard(
+        &self,
+        rule: crate::MatchRule<'_>,
+    ) -> Result<MatchRuleGuard> {
+        let rule = OwnedMatchRule::from(rule);
+        self.add_match_rule((&rule).into()).await?;
+
+        Ok(MatchRuleGuard {
+            conn: self.inner().connection().clone(),
+            rule: Some(rule),
+        })
+    }
+
+    /// Tries to launch the executable associated with a name (service activation), as an explicit
+    /// request.
+    ///
+    /// This is the same as [`DBusProxy::start_service_by_name`] but with a typed reply and without
+    /// the flags argument, which is currently unused by the specification.
+    pub async fn start_service(&self, name: WellKnownName<'_>) -> Result<StartServiceReply> {
+        self.inner()
+            .call("StartServiceByName", &(name, 0u32))
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Create a stream for the [`NameOwnerChanged`] signals of the given name.
+    ///
+    /// This is a shorthand for [`DBusProxy::receive_name_owner_changed_with_args`] with an `arg0`
+    /// match on `name`, so only the changes of ownership of `name` are received.
+    pub async fn receive_name_owner_changed_for(
+        &self,
+        name: BusName<'_>,
+    ) -> crate::Result<NameOwnerChangedStream> {
+        self.receive_name_owner_changed_with_args(&[(0, name.as_str())])
+            .await
+    }
+
+    /// Create a stream for the [`NameAcquired`] signals of the given name.
+    ///
+    /// This is a shorthand for [`DBusProxy::receive_name_acquired_with_args`] with an `arg0` match
+    /// on `name`.
+    pub async fn receive_name_acquired_for(
+        &self,
+        name: BusName<'_>,
+    ) -> crate::Result<NameAcquiredStream> {
+        self.receive_name_acquired_with_args(&[(0, name.as_str())])
+            .await
+    }
+
+    /// Create a stream for the [`NameLost`] signals of the given name.
+    ///
+    /// This is a shorthand for [`DBusProxy::receive_name_lost_with_args`] with an `arg0` match on
+    /// `name`.
+    pub async fn receive_name_lost_for(&self, name: BusName<'_>) -> crate::Result<NameLostStream> {
+        self.receive_name_lost_with_args(&[(0, name.as_str())])
+            .await
+    }
+
+    /// The position of this connection in the ownership queue of `name`.
+    ///
+    /// Position `0` means this connection is the primary owner of `name`, while any other position
+    /// means it's waiting in the queue behind as many other connections, e.g. as a standby in a
+    /// failover setup. Returns `None` if this connection neither owns nor is queued for `name`.
+    ///
+    /// This is based on [`DBusProxy::list_queued_owners`].
+    pub async fn name_queue_position(&self, name: WellKnownName<'_>) -> Result<Option<usize>> {
+        let unique_name = self
+            .inner()
+            .connection()
+            .unique_name()
+            .ok_or_else(|| Error::Failed("Connection has no unique name".into()))?;
+        let owners = match self.list_queued_owners(name).await {
+            Ok(owners) => owners,
+            Err(Error::NameHasNoOwner(_)) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        Ok(owners.iter().position(|owner| owner == unique_name))
+    }
+
+    /// Ensure the service owning `name` is running, activating it if needed.
+    ///
+    /// If `name` has no owner, the service is started through [`DBusProxy::start_service`]. This
+    /// method then waits for the name to appear on the bus, for at most `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::ServiceUnknown`] if `name` is not activatable and has no owner, and
+    /// [`Error::TimedOut`] if the name didn't appear on the bus in time.
+    pub async fn ensure_activated(&self, name: WellKnownName<'_>, timeout: Duration) -> Result<()> {
+        // Create the stream first so we don't miss the name appearing.
+        let mut owner_changed = self
+            .receive_name_owner_changed_for(name.as_ref().into())
+            .await?;
+        let wait = async {
+            if self.name_has_owner(name.as_ref().into()).await? {
+                return Ok(());
+            }
+            self.start_service(name.as_ref()).await?;
+            if self.name_has_owner(name.as_ref().into()).await? {
+                return Ok(());
+            }
+
+            while let Some(signal) = owner_changed.next().await {
+                if signal.args()?.new_owner().is_some() {
+                    return Ok(());
+                }
+            }
+
+            Err(Error::Failed(format!(
+                "`NameOwnerChanged` stream ended while waiting for `{name}`"
+            )))
+        };
+
+        crate::abstractions::timeout(timeout, wait)
+            .await
+            .unwrap_or_else(|| {
+                Err(Error::TimedOut(format!(
+                    "Timed out waiting for `{name}` to be activated"
+                )))
+            })
+    }
+}
+
+#[cfg(feature = "blocking-api")]
+impl DBusProxyBlocking<'_> {
+    /// Create an iterator for the [`NameOwnerChanged`] signals of the given name.
+    ///
+    /// See [`DBusProxy::receive_name_owner_changed_for`] for details.
+    pub fn receive_name_owner_changed_for(
+        &self,
+        name: BusName<'_>,
+    ) -> crate::Result<NameOwnerChangedIterator> {
+        self.receive_name_owner_changed_with_args(&[(0, name.as_str())])
+    }
+
+    /// Create an iterator for the [`NameAcquired`] signals of the given name.
+    ///
+    /// See [`DBusProxy::receive_name_acquired_for`] for details.
+    pub fn receive_name_acquired_for(
+        &self,
+        name: BusName<'_>,
+    ) -> crate::Result<NameAcquiredIterator> {
+        self.receive_name_acquired_with_args(&[(0, name.as_str())])
+    }
+
+    /// Create an iterator for the [`NameLost`] signals of the given name.
+    ///
+    /// See [`DBusProxy::receive_name_lost_for`] for details.
+    pub fn receive_name_lost_for(&self, name: BusName<'_>) -> crate::Result<NameLostIterator> {
+        self.receive_name_lost_with_args(&[(0, name.as_str())])
+    }
+}
+
 assert_impl_all!(DBusProxy<'_>: Send, Sync, Unpin);
 #[cfg(feature = "blocking-api")]
 assert_impl_all!(DBusProxyBlocking<'_>: Send, Sync, Unpin);
+
+/// A match rule added through [`DBusProxy::add_match_rule_with_guard`].
+///
+/// The match rule is removed from the bus when the guard is dropped.
+#[derive(Debug)]
+#[must_use = "the match rule is removed immediately if the guard is dropped"]
+pub struct MatchRuleGuard {
+    conn: Connection,
+    rule: Option<OwnedMatchRule>,
+}
+
+assert_impl_all!(MatchRuleGuard: Send, Sync, Unpin);
+
+impl MatchRuleGuard {
+    /// The match rule.
+    pub fn rule(&self) -> &OwnedMatchRule {
+        // The rule is only taken on drop.
+        self.rule.as_ref().expect("match rule already removed")
+    }
+}
+
+async fn remove_match_rule(conn: &Connection, rule: OwnedMatchRule) -> crate::Result<()> {
+    conn.call_method(
+        Some("org.freedesktop.DBus"),
+        "/org/freedesktop/DBus",
+        Some("org.freedesktop.DBus"),
+        "RemoveMatch",
+        &rule,
+    )
+    .await
+    .map(|_| ())
+}
+
+impl Drop for MatchRuleGuard {
+    fn drop(&mut self) {
+        if let Some(rule) = self.rule.take() {
+            let conn = self.conn.clone();
+            let task_name = format!("Remove match `{}`", *rule);
+            let remove_match = async move {
+                if let Err(e) = remove_match_rule(&conn, rule).await {
+                    warn!("Failed to remove match rule: {}", e);
+                }
+            }
+            .instrument(trace_span!("{}", task_name));
+            self.conn
+                .executor()
+                .spawn(remove_match, &task_name)
+                .detach();
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AsyncDrop for MatchRuleGuard {
+    async fn async_drop(mut self) {
+        if let Some(rule) = self.rule.take() {
+            if let Err(e) = remove_match_rule(&self.conn, rule).await {
+                warn!("Failed to remove match rule: {}", e);
+            }
+        }
+    }
+}
@@ -3,6 +3,22 @@ use static_assertions::assert_impl_all;
 use crate::DBusError;
 
 /// Errors from <https://gitlab.freedesktop.org/dbus/dbus/-/blob/master/dbus/dbus-protocol.h>
+///
+/// All the standard `org.freedesktop.DBus.Error.*` errors are covered, so you can match on the
+/// specific error returned by a bus or a peer. A [`zbus::Error::MethodError`] with a standard
+/// error name converts to the corresponding variant, keeping the error description. Converting
+/// an `Error` into [`zbus::Error`] and back gives the original error.
+///
+/// ```
+/// use zbus::fdo;
+///
+/// let e = fdo::Error::AccessDenied("not allowed".to_string());
+/// let e = zbus::Error::from(e);
+/// assert_eq!(
+///     fdo::Error::from(e),
+///     fdo::Error::AccessDenied("not allowed".to_string()),
+/// );
+/// ```
 #[derive(Clone, Debug, DBusError, PartialEq)]
 #[zbus(prefix = "org.freedesktop.DBus.Error", impl_display = true)]
 #[allow(clippy::upper_case_acronyms)]
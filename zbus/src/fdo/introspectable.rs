@@ -4,8 +4,16 @@
 //! be useful across various D-Bus applications. This module provides their proxy.
 
 use static_assertions::assert_impl_all;
+#[cfg(feature = "xml")]
+use std::{collections::HashMap, sync::Arc};
+#[cfg(feature = "xml")]
+use zbus_names::{BusName, OwnedBusName};
+#[cfg(feature = "xml")]
+use zvariant::{ObjectPath, OwnedObjectPath};
 
 use super::{Error, Result};
+#[cfg(feature = "xml")]
+use crate::{async_lock::Mutex, proxy::CacheProperties, Connection};
 use crate::{interface, message::Header, ObjectServer};
 
 /// Service-side implementation for the `org.freedesktop.DBus.Introspectable` interface.
@@ -36,6 +44,97 @@ impl Introspectable {
     }
 }
 
+#[cfg(feature = "xml")]
+impl IntrospectableProxy<'_> {
+    /// Same as [`IntrospectableProxy::introspect`], but returns the parsed introspection data.
+    pub async fn introspect_parsed(&self) -> Result<crate::xml::Node<'static>> {
+        let xml = self.introspect().await?;
+
+        parse_introspection(&xml)
+    }
+}
+
+#[cfg(feature = "xml")]
+fn parse_introspection(xml: &str) -> Result<crate::xml::Node<'static>> {
+    crate::xml::Node::from_reader(xml.as_bytes())
+        .map_err(|e| Error::Failed(format!("Failed to parse introspection XML: {e}")))
+}
+
+/// A cache of parsed introspection data.
+///
+/// Clients walking object trees typically need the introspection data of the same objects over and
+/// over. This cache introspects each object only once, sharing the parsed [`crate::xml::Node`]
+/// between all the users.
+///
+/// The cache is never invalidated automatically. Use [`IntrospectionCache::invalidate`] or
+/// [`IntrospectionCache::clear`] when the introspection data of objects is known to have changed,
+/// e.g. on `InterfacesAdded` or `InterfacesRemoved` signals.
+#[cfg(feature = "xml")]
+#[derive(Debug)]
+pub struct IntrospectionCache {
+    conn: Connection,
+    nodes: Mutex<HashMap<(OwnedBusName, OwnedObjectPath), Arc<crate::xml::Node<'static>>>>,
+}
+
+#[cfg(feature = "xml")]
+assert_impl_all!(IntrospectionCache: Send, Sync, Unpin);
+
+#[cfg(feature = "xml")]
+impl IntrospectionCache {
+    /// Create a new cache for introspecting objects over `conn`.
+    pub fn new(conn: &Connection) -> Self {
+        Self {
+            conn: conn.clone(),
+            nodes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Get the parsed introspection data of the object at `path` on `destination`.
+    ///
+    /// The object is only introspected if its data isn't already in the cache.
+    pub async fn get<'d, 'p, D, P>(
+        &self,
+        destination: D,
+        path: P,
+    ) -> Result<Arc<crate::xml::Node<'static>>>
+    where
+        D: TryInto<BusName<'d>>,
+        P: TryInto<ObjectPath<'p>>,
+        D::Error: Into<crate::Error>,
+        P::Error: Into<crate::Error>,
+    {
+        let destination = destination.try_into().map_err(Into::into)?;
+        let path = path.try_into().map_err(Into::into)?;
+        let key = (destination.into(), path.into());
+        if let Some(node) = self.nodes.lock().await.get(&key) {
+            return Ok(node.clone());
+        }
+
+        let (destination, path) = &key;
+        let proxy = IntrospectableProxy::builder(&self.conn)
+            .destination(destination.as_ref())?
+            .path(path.as_ref())?
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await?;
+        let node = Arc::new(proxy.introspect_parsed().await?);
+        self.nodes.lock().await.insert(key, node.clone());
+
+        Ok(node)
+    }
+
+    /// Remove the introspection data of the object at `path` on `destination` from the cache.
+    pub async fn invalidate(&self, destination: &BusName<'_>, path: &ObjectPath<'_>) {
+        let key = (destination.to_owned().into(), path.to_owned().into());
+        self.nodes.lock().await.remove(&key);
+    }
+
+    /// Remove all the introspection data from the cache.
+    pub async fn clear(&self) {
+        self.nodes.lock().await.clear();
+    }
+}
+
 assert_impl_all!(IntrospectableProxy<'_>: Send, Sync, Unpin);
 #[cfg(feature = "blocking-api")]
 assert_impl_all!(IntrospectableProxyBlocking<'_>: Send, Sync, Unpin);
@@ -3,23 +3,26 @@ pub use error::{Error, Result};
 
 pub(crate) mod dbus;
 pub use dbus::{
-    ConnectionCredentials, DBusProxy, NameAcquired, NameAcquiredArgs, NameAcquiredStream, NameLost,
-    NameLostArgs, NameLostStream, NameOwnerChanged, NameOwnerChangedArgs, NameOwnerChangedStream,
-    ReleaseNameReply, RequestNameFlags, RequestNameReply,
+    ConnectionCredentials, DBusProxy, MatchRuleGuard, NameAcquired, NameAcquiredArgs,
+    NameAcquiredStream, NameLost, NameLostArgs, NameLostStream, NameOwnerChanged,
+    NameOwnerChangedArgs, NameOwnerChangedStream, ReleaseNameReply, RequestNameFlags,
+    RequestNameReply, StartServiceReply,
 };
 
 pub(crate) mod introspectable;
 pub(crate) use introspectable::Introspectable;
 pub use introspectable::IntrospectableProxy;
+#[cfg(feature = "xml")]
+pub use introspectable::IntrospectionCache;
 
 pub(crate) mod monitoring;
 pub use monitoring::MonitoringProxy;
 
 pub(crate) mod object_manager;
 pub use object_manager::{
-    InterfacesAdded, InterfacesAddedArgs, InterfacesAddedStream, InterfacesRemoved,
-    InterfacesRemovedArgs, InterfacesRemovedStream, ManagedObjects, ObjectManager,
-    ObjectManagerProxy,
+    InterfacesAdded, InterfacesAddedArgs, InterfacesAddedStream, InterfacesAndProperties,
+    InterfacesRemoved, InterfacesRemovedArgs, InterfacesRemovedStream, ManagedObjects,
+    ManagedObjectsCache, ObjectChange, ObjectChangeStream, ObjectManager, ObjectManagerProxy,
 };
 
 pub(crate) mod peer;
@@ -32,7 +35,7 @@ pub use properties::{
 };
 
 pub(crate) mod stats;
-pub use stats::StatsProxy;
+pub use stats::{BusStats, ConnectionStats, DebugStatsProxy, StatsProxy};
 
 #[cfg(test)]
 mod tests {
@@ -62,6 +65,36 @@ mod tests {
         assert_eq!(e.description(), Some("so long"));
     }
 
+    #[test]
+    fn error_conversions() {
+        let m = Message::method_call("/", "foo")
+            .unwrap()
+            .destination(":1.2")
+            .unwrap()
+            .build(&())
+            .unwrap();
+        let m = Message::error(&m.header(), "org.freedesktop.DBus.Error.LimitsExceeded")
+            .unwrap()
+            .build(&())
+            .unwrap();
+        let e: fdo::Error = Error::from(m).into();
+        assert_eq!(e, fdo::Error::LimitsExceeded(String::new()));
+
+        for e in [
+            fdo::Error::AddressInUse("in use".to_string()),
+            fdo::Error::Disconnected("bye".to_string()),
+            fdo::Error::SpawnChildExited("exited".to_string()),
+            fdo::Error::NotContainer("no container".to_string()),
+        ] {
+            let converted: fdo::Error = Error::from(e.clone()).into();
+            assert_eq!(converted, e);
+        }
+
+        // Non-fdo errors are kept as is.
+        let e: fdo::Error = Error::InvalidReply.into();
+        assert_eq!(e, fdo::Error::ZBus(Error::InvalidReply));
+    }
+
     #[test]
     #[timeout(15000)]
     fn signal() {
@@ -132,6 +165,131 @@ mod tests {
             });
     }
 
+    #[test]
+    #[timeout(15000)]
+    fn name_signals_for() {
+        crate::block_on(async {
+            let conn = crate::Connection::session().await.unwrap();
+            let proxy = fdo::DBusProxy::new(&conn).await.unwrap();
+            let unique_name = conn.unique_name().unwrap();
+            let well_known: WellKnownName<'_> = "org.freedesktop.zbus.FdoNameSignalsForTest"
+                .try_into()
+                .unwrap();
+            // Signals for other names aren't received.
+            let other: WellKnownName<'_> = "org.freedesktop.zbus.FdoNameSignalsForTest.Other"
+                .try_into()
+                .unwrap();
+
+            let mut owner_changed = proxy
+                .receive_name_owner_changed_for(well_known.as_ref().into())
+                .await
+                .unwrap();
+            let mut acquired = proxy
+                .receive_name_acquired_for(well_known.as_ref().into())
+                .await
+                .unwrap();
+            let mut lost = proxy
+                .receive_name_lost_for(well_known.as_ref().into())
+                .await
+                .unwrap();
+
+            for name in [&other, &well_known] {
+                proxy
+                    .request_name(name.as_ref(), Default::default())
+                    .await
+                    .unwrap();
+            }
+            let signal = owner_changed.next().await.unwrap();
+            let args = signal.args().unwrap();
+            assert_eq!(args.name(), &well_known);
+            assert_eq!(args.new_owner().as_ref().unwrap(), unique_name);
+            let signal = acquired.next().await.unwrap();
+            assert_eq!(signal.args().unwrap().name(), &well_known);
+
+            for name in [&other, &well_known] {
+                proxy.release_name(name.as_ref()).await.unwrap();
+            }
+            let signal = owner_changed.next().await.unwrap();
+            let args = signal.args().unwrap();
+            assert_eq!(args.name(), &well_known);
+            assert!(args.new_owner().is_none());
+            let signal = lost.next().await.unwrap();
+            assert_eq!(signal.args().unwrap().name(), &well_known);
+        });
+    }
+
+    #[test]
+    #[timeout(15000)]
+    #[cfg(feature = "blocking-api")]
+    fn name_signals_for_blocking() {
+        let conn = crate::blocking::Connection::session().unwrap();
+        let proxy = crate::blocking::fdo::DBusProxy::new(&conn).unwrap();
+        let well_known: WellKnownName<'_> = "org.freedesktop.zbus.FdoNameSignalsForBlockingTest"
+            .try_into()
+            .unwrap();
+
+        let mut owner_changed = proxy
+            .receive_name_owner_changed_for(well_known.as_ref().into())
+            .unwrap();
+        let mut acquired = proxy
+            .receive_name_acquired_for(well_known.as_ref().into())
+            .unwrap();
+        let mut lost = proxy
+            .receive_name_lost_for(well_known.as_ref().into())
+            .unwrap();
+
+        proxy
+            .request_name(well_known.as_ref(), Default::default())
+            .unwrap();
+        let signal = owner_changed.next().unwrap();
+        assert_eq!(signal.args().unwrap().name(), &well_known);
+        let signal = acquired.next().unwrap();
+        assert_eq!(signal.args().unwrap().name(), &well_known);
+
+        proxy.release_name(well_known.as_ref()).unwrap();
+        let signal = owner_changed.next().unwrap();
+        assert!(signal.args().unwrap().new_owner().is_none());
+        let signal = lost.next().unwrap();
+        assert_eq!(signal.args().unwrap().name(), &well_known);
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn connection_credentials() {
+        crate::block_on(async {
+            let conn = crate::Connection::session().await?;
+            let proxy = fdo::DBusProxy::new(&conn).await?;
+            let creds = proxy
+                .get_connection_credentials(conn.unique_name().unwrap().as_str().try_into()?)
+                .await?;
+            assert_eq!(creds.process_id(), Some(std::process::id()));
+            // Only recent bus implementations pass a pidfd.
+            #[cfg(unix)]
+            if let Some(pidfd) = creds.process_fd() {
+                use std::os::fd::AsRawFd;
+
+                assert!(pidfd.as_raw_fd() >= 0);
+            }
+
+            Ok::<_, crate::Error>(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn connection_credentials_eq() {
+        use std::{fs::File, os::fd::OwnedFd};
+
+        let creds = || fdo::ConnectionCredentials::default().set_process_id(std::process::id());
+        let fd = || zvariant::OwnedFd::from(OwnedFd::from(File::open("/dev/null").unwrap()));
+
+        // Different descriptors don't make the credentials differ.
+        assert_eq!(creds().set_process_fd(fd()), creds().set_process_fd(fd()));
+        assert_eq!(creds().set_process_fd(fd()), creds());
+        assert_ne!(creds(), creds().set_process_id(0));
+    }
+
     #[test]
     #[timeout(15000)]
     fn no_object_manager_signals_before_hello() {
@@ -198,4 +356,390 @@ mod tests {
             .collect()
         );
     }
+
+    #[test]
+    #[timeout(15000)]
+    fn debug_stats() {
+        crate::block_on(debug_stats_async());
+    }
+
+    async fn debug_stats_async() {
+        let conn = crate::Connection::session().await.unwrap();
+        let proxy = fdo::DebugStatsProxy::new(&conn).await.unwrap();
+
+        let stats = match proxy.get_stats().await {
+            Ok(stats) => stats,
+            // Not all buses provide statistics.
+            Err(fdo::Error::UnknownInterface(_) | fdo::Error::UnknownMethod(_)) => return,
+            Err(e) => panic!("{e}"),
+        };
+        assert!(stats.active_connections().unwrap() >= 1);
+        assert!(stats.bus_names().is_some());
+
+        let unique_name = conn.unique_name().unwrap();
+        let stats = proxy
+            .get_connection_stats(unique_name.into())
+            .await
+            .unwrap();
+        assert_eq!(stats.unique_name(), Some(&unique_name.to_owned()));
+        assert!(stats.incoming_messages().is_some());
+
+        let rules = proxy.get_all_match_rules().await.unwrap();
+        assert!(rules.contains_key(unique_name));
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn service_activation() {
+        crate::block_on(service_activation_async());
+    }
+
+    async fn service_activation_async() {
+        let conn = crate::Connection::session().await.unwrap();
+        let proxy = fdo::DBusProxy::new(&conn).await.unwrap();
+        let timeout = std::time::Duration::from_secs(5);
+
+        let activatable = proxy.list_activatable_names().await.unwrap();
+        assert!(activatable
+            .iter()
+            .any(|name| name.as_str() == "org.freedesktop.DBus"));
+
+        // Already running.
+        let name = WellKnownName::from_static_str("org.freedesktop.DBus").unwrap();
+        proxy.ensure_activated(name, timeout).await.unwrap();
+
+        let name = WellKnownName::from_static_str("org.freedesktop.zbus.NotActivatable").unwrap();
+        assert!(matches!(
+            proxy.ensure_activated(name, timeout).await,
+            Err(fdo::Error::ServiceUnknown(_))
+        ));
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn match_rule_guard() {
+        crate::block_on(match_rule_guard_async());
+    }
+
+    async fn match_rule_guard_async() {
+        use crate::AsyncDrop;
+
+        let conn = crate::Connection::session().await.unwrap();
+        let proxy = fdo::DBusProxy::new(&conn).await.unwrap();
+        let stats = fdo::DebugStatsProxy::new(&conn).await.unwrap();
+        let unique_name = conn.unique_name().unwrap();
+        let rule = crate::MatchRule::builder()
+            .msg_type(crate::message::Type::Signal)
+            .interface("org.freedesktop.zbus.MatchRuleGuard")
+            .unwrap()
+            .build();
+
+        let guard = proxy.add_match_rule_with_guard(rule.clone()).await.unwrap();
+        assert_eq!(*guard.rule(), rule);
+        let rules = match stats.get_all_match_rules().await {
+            Ok(rules) => rules,
+            // Not all buses provide statistics.
+            Err(fdo::Error::UnknownInterface(_) | fdo::Error::UnknownMethod(_)) => return,
+            Err(e) => panic!("{e}"),
+        };
+        assert!(rules[unique_name].iter().any(|r| *r == rule));
+
+        guard.async_drop().await;
+        let rules = stats.get_all_match_rules().await.unwrap();
+        assert!(!rules[unique_name].iter().any(|r| *r == rule));
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn properties_changed_with_invalidated() {
+        crate::block_on(properties_changed_with_invalidated_async());
+    }
+
+    async fn properties_changed_with_invalidated_async() {
+        let service = crate::Connection::session().await.unwrap();
+        let client = crate::Connection::session().await.unwrap();
+        let path = "/org/zbus/PropertiesChangedWithInvalidated";
+        let proxy = fdo::PropertiesProxy::builder(&client)
+            .destination(service.unique_name().unwrap())
+            .unwrap()
+            .path(path)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let mut stream = proxy.receive_properties_changed().await.unwrap();
+
+        let emitter = crate::object_server::SignalEmitter::new(&service, path).unwrap();
+        let changed: std::collections::HashMap<_, _> = [("Count", zvariant::Value::from(42u32))]
+            .into_iter()
+            .collect();
+        emitter
+            .properties_changed("org.zbus.Test", changed.clone(), &["Blob"])
+            .await
+            .unwrap();
+
+        let signal = stream.next().await.unwrap();
+        let args = signal.args().unwrap();
+        assert_eq!(args.interface_name(), "org.zbus.Test");
+        assert_eq!(args.changed_properties(), &changed);
+        assert_eq!(args.invalidated_properties().as_ref(), ["Blob"]);
+    }
+
+    #[cfg(feature = "xml")]
+    #[test]
+    #[timeout(15000)]
+    fn introspect_parsed() {
+        crate::block_on(introspect_parsed_async());
+    }
+
+    #[cfg(feature = "xml")]
+    async fn introspect_parsed_async() {
+        struct TestObj;
+        #[interface(name = "org.zbus.IntrospectParsed")]
+        impl TestObj {
+            fn test(&self) {}
+        }
+        let service = zbus::conn::Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/IntrospectParsed/Obj", TestObj)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let destination = service.unique_name().unwrap();
+        let client = crate::Connection::session().await.unwrap();
+
+        let proxy = fdo::IntrospectableProxy::builder(&client)
+            .destination(destination)
+            .unwrap()
+            .path("/org/zbus/IntrospectParsed")
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let node = proxy.introspect_parsed().await.unwrap();
+        assert_eq!(node.nodes()[0].name(), Some("Obj"));
+
+        let cache = fdo::IntrospectionCache::new(&client);
+        let node = cache
+            .get(destination, "/org/zbus/IntrospectParsed/Obj")
+            .await
+            .unwrap();
+        assert!(node
+            .interfaces()
+            .iter()
+            .any(|iface| iface.name() == "org.zbus.IntrospectParsed"));
+        let cached = cache
+            .get(destination, "/org/zbus/IntrospectParsed/Obj")
+            .await
+            .unwrap();
+        assert!(std::sync::Arc::ptr_eq(&node, &cached));
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn introspection_invalidation() {
+        crate::block_on(introspection_invalidation_async());
+    }
+
+    async fn introspection_invalidation_async() {
+        struct TestObj;
+        #[interface(name = "org.zbus.IntrospectionInvalidation")]
+        impl TestObj {
+            fn test(&self) {}
+        }
+        let service = zbus::conn::Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/IntrospectionInvalidation/A", TestObj)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let client = crate::Connection::session().await.unwrap();
+        let proxy = fdo::IntrospectableProxy::builder(&client)
+            .destination(service.unique_name().unwrap())
+            .unwrap()
+            .path("/org/zbus/IntrospectionInvalidation")
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        let xml = proxy.introspect().await.unwrap();
+        assert!(xml.contains(r#"<node name="A">"#));
+        assert!(!xml.contains(r#"<node name="B">"#));
+        // Served from the cache.
+        assert_eq!(proxy.introspect().await.unwrap(), xml);
+
+        let server = service.object_server();
+        assert!(server
+            .at("/org/zbus/IntrospectionInvalidation/B", TestObj)
+            .await
+            .unwrap());
+        let xml = proxy.introspect().await.unwrap();
+        assert!(xml.contains(r#"<node name="A">"#));
+        assert!(xml.contains(r#"<node name="B">"#));
+
+        assert!(server
+            .remove::<TestObj, _>("/org/zbus/IntrospectionInvalidation/A")
+            .await
+            .unwrap());
+        let xml = proxy.introspect().await.unwrap();
+        assert!(!xml.contains(r#"<node name="A">"#));
+        assert!(xml.contains(r#"<node name="B">"#));
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn name_queue_position() {
+        crate::block_on(name_queue_position_async());
+    }
+
+    async fn name_queue_position_async() {
+        let name = "org.freedesktop.zbus.NameQueuePositionTest";
+        let well_known = WellKnownName::from_static_str(name).unwrap();
+        let primary = crate::Connection::session().await.unwrap();
+        let standby = crate::Connection::session().await.unwrap();
+        let primary_proxy = fdo::DBusProxy::new(&primary).await.unwrap();
+        let standby_proxy = fdo::DBusProxy::new(&standby).await.unwrap();
+
+        assert_eq!(
+            primary_proxy
+                .name_queue_position(well_known.clone())
+                .await
+                .unwrap(),
+            None
+        );
+        primary.request_name(name).await.unwrap();
+        let reply = standby
+            .request_name_with_flags(name, Default::default())
+            .await
+            .unwrap();
+        assert_eq!(reply, fdo::RequestNameReply::InQueue);
+
+        let owners = primary_proxy
+            .list_queued_owners(well_known.clone())
+            .await
+            .unwrap();
+        assert_eq!(owners.len(), 2);
+        assert_eq!(
+            primary_proxy
+                .name_queue_position(well_known.clone())
+                .await
+                .unwrap(),
+            Some(0)
+        );
+        assert_eq!(
+            standby_proxy
+                .name_queue_position(well_known.clone())
+                .await
+                .unwrap(),
+            Some(1)
+        );
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn managed_objects_cache() {
+        crate::block_on(managed_objects_cache_async());
+    }
+
+    async fn managed_objects_cache_async() {
+        use zvariant::{ObjectPath, OwnedValue};
+
+        struct Thing {
+            count: u32,
+        }
+        #[interface(name = "org.zbus.ManagedObjectsCache.Thing")]
+        impl Thing {
+            #[zbus(property)]
+            fn count(&self) -> u32 {
+                self.count
+            }
+        }
+
+        let root = "/org/zbus/ManagedObjectsCache";
+        let first = ObjectPath::from_static_str("/org/zbus/ManagedObjectsCache/First").unwrap();
+        let second = ObjectPath::from_static_str("/org/zbus/ManagedObjectsCache/Second").unwrap();
+        let service = zbus::conn::Builder::session()
+            .unwrap()
+            .serve_at(&first, Thing { count: 1 })
+            .unwrap()
+            .serve_at(root, super::ObjectManager)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let client = crate::Connection::session().await.unwrap();
+        let proxy = fdo::ObjectManagerProxy::builder(&client)
+            .destination(service.unique_name().unwrap())
+            .unwrap()
+            .path(root)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        let cache = fdo::ManagedObjectsCache::new(&proxy).await.unwrap();
+        let iface = "org.zbus.ManagedObjectsCache.Thing";
+        assert_eq!(cache.paths_with_interface(iface), [first.clone().into()]);
+        assert_eq!(
+            cache.property(&first, iface, "Count"),
+            Some(OwnedValue::from(1u32))
+        );
+        let mut changes = cache.receive_changes();
+
+        // Property changes.
+        let thing = service
+            .object_server()
+            .interface::<_, Thing>(&first)
+            .await
+            .unwrap();
+        thing.get_mut().await.count = 2;
+        thing
+            .get()
+            .await
+            .count_changed(thing.signal_emitter())
+            .await
+            .unwrap();
+        assert_eq!(
+            changes.next().await.unwrap(),
+            fdo::ObjectChange::PropertiesChanged {
+                path: first.clone().into(),
+                interface: iface.try_into().unwrap(),
+                changed: vec!["Count".to_string()],
+                invalidated: vec![],
+            }
+        );
+        assert_eq!(
+            cache.property(&first, iface, "Count"),
+            Some(OwnedValue::from(2u32))
+        );
+
+        // New and removed objects.
+        service
+            .object_server()
+            .at(&second, Thing { count: 3 })
+            .await
+            .unwrap();
+        assert!(matches!(
+            changes.next().await.unwrap(),
+            fdo::ObjectChange::InterfacesAdded { path, .. } if *path == second
+        ));
+        assert_eq!(
+            cache.property(&second, iface, "Count"),
+            Some(OwnedValue::from(3u32))
+        );
+        service
+            .object_server()
+            .remove::<Thing, _>(&first)
+            .await
+            .unwrap();
+        assert!(matches!(
+            changes.next().await.unwrap(),
+            fdo::ObjectChange::InterfacesRemoved { path, .. } if *path == first
+        ));
+        assert!(cache.interfaces(&first).is_none());
+        assert_eq!(cache.paths(), [second.into()]);
+    }
 }
@@ -3,17 +3,36 @@
 //! The D-Bus specification defines the message bus messages and some standard interfaces that may
 //! be useful across various D-Bus applications. This module provides their proxy.
 
+use async_broadcast::{broadcast, InactiveReceiver, Receiver, Sender as Broadcaster};
+use futures_core::Stream;
 use static_assertions::assert_impl_all;
-use std::{borrow::Cow, collections::HashMap};
-use zbus_names::{InterfaceName, OwnedInterfaceName};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    pin::Pin,
+    sync::{Arc, RwLock},
+    task::{Context, Poll},
+};
+use tracing::{debug, info_span, trace, Instrument};
+use zbus_names::{BusName, InterfaceName, OwnedInterfaceName};
 use zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
 
-use super::{Error, Result};
-use crate::{interface, message::Header, object_server::SignalEmitter, Connection, ObjectServer};
+use super::{DBusProxy, Error, Result};
+use crate::{
+    interface,
+    message::{self, Header, Message, Sequence},
+    object_server::SignalEmitter,
+    Connection, MatchRule, MessageStream, ObjectServer, Task,
+};
 
 /// The type returned by the [`ObjectManagerProxy::get_managed_objects`] method.
-pub type ManagedObjects =
-    HashMap<OwnedObjectPath, HashMap<OwnedInterfaceName, HashMap<String, OwnedValue>>>;
+pub type ManagedObjects = HashMap<OwnedObjectPath, InterfacesAndProperties>;
+
+/// The interfaces of an object, and their properties.
+///
+/// This is the value type of [`ManagedObjects`] and the owned form of the
+/// `interfaces_and_properties` argument of the [`InterfacesAdded`] signal.
+pub type InterfacesAndProperties = HashMap<OwnedInterfaceName, HashMap<String, OwnedValue>>;
 
 /// Service-side [Object Manager][om] interface implementation.
 ///
@@ -81,6 +100,316 @@ impl ObjectManager {
     ) -> zbus::Result<()>;
 }
 
+impl InterfacesAdded {
+    /// The owned arguments of the signal.
+    ///
+    /// Unlike [`InterfacesAdded::args`], the returned value doesn't borrow from the message and
+    /// uses the same types as [`ManagedObjects`], so it can be readily merged into the result of
+    /// [`ObjectManagerProxy::get_managed_objects`].
+    pub fn owned_args(&self) -> zbus::Result<(OwnedObjectPath, InterfacesAndProperties)> {
+        self.message().body().deserialize()
+    }
+}
+
+impl InterfacesRemoved {
+    /// The owned arguments of the signal.
+    ///
+    /// Unlike [`InterfacesRemoved::args`], the returned value doesn't borrow from the message.
+    pub fn owned_args(&self) -> zbus::Result<(OwnedObjectPath, Vec<OwnedInterfaceName>)> {
+        self.message().body().deserialize()
+    }
+}
+
 assert_impl_all!(ObjectManagerProxy<'_>: Send, Sync, Unpin);
 #[cfg(feature = "blocking-api")]
 assert_impl_all!(ObjectManagerProxyBlocking<'_>: Send, Sync, Unpin);
+
+/// A client-side cache of the objects managed by an [Object Manager][om].
+///
+/// The cache is populated through [`ObjectManagerProxy::get_managed_objects`] and then kept up to
+/// date by applying the `InterfacesAdded`, `InterfacesRemoved` and `PropertiesChanged` signals
+/// emitted by the service, so you can query the objects, their interfaces and properties at any
+/// time without any round-trips. Use [`ManagedObjectsCache::receive_changes`] to get notified of
+/// the changes applied to the cache.
+///
+/// The cache tracks the owner of the service name at the time of creation. If the service is
+/// restarted, a new cache must be created.
+///
+/// # Example
+///
+/// ```no_run
+/// # zbus::block_on(async {
+/// use zbus::{fdo::{ManagedObjectsCache, ObjectManagerProxy}, Connection};
+///
+/// let conn = Connection::system().await?;
+/// let proxy = ObjectManagerProxy::builder(&conn)
+///     .destination("org.bluez")?
+///     .path("/")?
+///     .build()
+///     .await?;
+/// let cache = ManagedObjectsCache::new(&proxy).await?;
+/// for path in cache.paths_with_interface("org.bluez.Device1") {
+///     let address = cache.property(&path, "org.bluez.Device1", "Address");
+///     println!("{path}: {address:?}");
+/// }
+/// # Ok::<(), zbus::Error>(())
+/// # }).unwrap();
+/// ```
+///
+/// [om]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces-objectmanager
+#[derive(Debug)]
+pub struct ManagedObjectsCache {
+    objects: Arc<RwLock<ManagedObjects>>,
+    changes: InactiveReceiver<ObjectChange>,
+    _task: Task<()>,
+}
+
+assert_impl_all!(ManagedObjectsCache: Send, Sync, Unpin);
+
+/// A change applied to a [`ManagedObjectsCache`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObjectChange {
+    /// Interfaces were added to an object, which may be a new object.
+    InterfacesAdded {
+        /// The path of the object.
+        path: OwnedObjectPath,
+        /// The names of the interfaces added.
+        interfaces: Vec<OwnedInterfaceName>,
+    },
+    /// Interfaces were removed from an object. If all of its interfaces are removed, the object is
+    /// removed from the cache.
+    InterfacesRemoved {
+        /// The path of the object.
+        path: OwnedObjectPath,
+        /// The names of the interfaces removed.
+        interfaces: Vec<OwnedInterfaceName>,
+    },
+    /// Properties of an interface of an object changed.
+    PropertiesChanged {
+        /// The path of the object.
+        path: OwnedObjectPath,
+        /// The name of the interface.
+        interface: OwnedInterfaceName,
+        /// The names of the properties with new values.
+        changed: Vec<String>,
+        /// The names of the properties invalidated, which are removed from the cache.
+        invalidated: Vec<String>,
+    },
+}
+
+impl ManagedObjectsCache {
+    /// Create a cache of the objects managed by the object manager `proxy` is for.
+    ///
+    /// This method returns once the cache is populated.
+    pub async fn new(proxy: &ObjectManagerProxy<'_>) -> zbus::Result<Self> {
+        let conn = proxy.inner().connection();
+        let destination = proxy.inner().destination();
+        let sender = match destination {
+            BusName::Unique(name) => name.to_owned(),
+            BusName::WellKnown(_) => DBusProxy::new(conn)
+                .await?
+                .get_name_owner(destination.as_ref())
+                .await?
+                .into_inner(),
+        };
+        // Subscribe to the signals first so we don't miss any changes.
+        let rule = MatchRule::builder()
+            .msg_type(message::Type::Signal)
+            .sender(sender)?
+            .path_namespace(proxy.inner().path().to_owned())?
+            .build();
+        let stream = MessageStream::for_match_rule(rule, conn, None).await?;
+
+        let reply = proxy.inner().call_method("GetManagedObjects", &()).await?;
+        let objects = Arc::new(RwLock::new(reply.body().deserialize::<ManagedObjects>()?));
+
+        let (mut sender, changes) = broadcast(CHANGES_QUEUE_SIZE);
+        sender.set_overflow(true);
+        sender.set_await_active(false);
+        let task_name = format!("`{}` managed objects cache", proxy.inner().path());
+        let task = conn.executor().spawn(
+            keep_updated(objects.clone(), stream, reply.recv_position(), sender)
+                .instrument(info_span!("{}", task_name)),
+            &task_name,
+        );
+
+        Ok(Self {
+            objects,
+            changes: changes.deactivate(),
+            _task: task,
+        })
+    }
+
+    /// A snapshot of all the cached objects.
+    pub fn objects(&self) -> ManagedObjects {
+        self.read().clone()
+    }
+
+    /// The paths of all the cached objects.
+    pub fn paths(&self) -> Vec<OwnedObjectPath> {
+        self.read().keys().cloned().collect()
+    }
+
+    /// The paths of all the cached objects implementing `interface`.
+    pub fn paths_with_interface(&self, interface: &str) -> Vec<OwnedObjectPath> {
+        self.read()
+            .iter()
+            .filter(|(_, interfaces)| interfaces.keys().any(|i| i.as_str() == interface))
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    /// The interfaces, and their properties, of the object at `path`.
+    pub fn interfaces(&self, path: &ObjectPath<'_>) -> Option<InterfacesAndProperties> {
+        self.read()
+            .get(&OwnedObjectPath::from(path.to_owned()))
+            .cloned()
+    }
+
+    /// The cached value of the `name` property of `interface` of the object at `path`.
+    ///
+    /// Returns `None` if the object doesn't exist, doesn't implement `interface`, or the property
+    /// isn't known, e.g. because it was invalidated.
+    pub fn property(
+        &self,
+        path: &ObjectPath<'_>,
+        interface: &str,
+        name: &str,
+    ) -> Option<OwnedValue> {
+        self.read()
+            .get(&OwnedObjectPath::from(path.to_owned()))
+            .and_then(|interfaces| interfaces.iter().find(|(i, _)| i.as_str() == interface))
+            .and_then(|(_, properties)| properties.get(name).cloned())
+    }
+
+    /// Create a stream of the changes applied to the cache.
+    ///
+    /// Only the changes applied after this call are received. If the stream isn't consumed fast
+    /// enough, the oldest changes are dropped.
+    pub fn receive_changes(&self) -> ObjectChangeStream {
+        ObjectChangeStream(self.changes.activate_cloned())
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, ManagedObjects> {
+        self.objects.read().expect("lock poisoned")
+    }
+}
+
+/// A [`Stream`] of the changes applied to a [`ManagedObjectsCache`].
+///
+/// Use [`ManagedObjectsCache::receive_changes`] to create an instance of this type.
+#[derive(Debug)]
+pub struct ObjectChangeStream(Receiver<ObjectChange>);
+
+assert_impl_all!(ObjectChangeStream: Send, Sync, Unpin);
+
+impl Stream for ObjectChangeStream {
+    type Item = ObjectChange;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.0).poll_next(cx)
+    }
+}
+
+const CHANGES_QUEUE_SIZE: usize = 64;
+
+async fn keep_updated(
+    objects: Arc<RwLock<ManagedObjects>>,
+    mut stream: MessageStream,
+    populated_at: Sequence,
+    changes: Broadcaster<ObjectChange>,
+) {
+    use futures_util::StreamExt;
+
+    while let Some(msg) = stream.next().await {
+        let msg = match msg {
+            // Changes before the population are already reflected in the cache.
+            Ok(msg) if msg.recv_position() < populated_at => continue,
+            Ok(msg) => msg,
+            Err(e) => {
+                debug!("Error receiving signal for managed objects cache: {e}");
+                continue;
+            }
+        };
+        let change = match apply_change(&objects, &msg) {
+            Ok(Some(change)) => change,
+            Ok(None) => continue,
+            Err(e) => {
+                debug!("Failed to apply signal to managed objects cache: {e}");
+                continue;
+            }
+        };
+        if let Err(e) = changes.broadcast_direct(change).await {
+            trace!("No receivers for managed objects changes: {e}");
+        }
+    }
+}
+
+fn apply_change(
+    objects: &RwLock<ManagedObjects>,
+    msg: &Message,
+) -> zbus::Result<Option<ObjectChange>> {
+    let header = msg.header();
+    let (Some(interface), Some(member), Some(path)) =
+        (header.interface(), header.member(), header.path())
+    else {
+        return Ok(None);
+    };
+    let path = OwnedObjectPath::from(path.to_owned());
+    let mut objects = objects.write().expect("lock poisoned");
+
+    let change = match (interface.as_str(), member.as_str()) {
+        ("org.freedesktop.DBus.ObjectManager", "InterfacesAdded") => {
+            let (path, added) = msg
+                .body()
+                .deserialize::<(OwnedObjectPath, InterfacesAndProperties)>()?;
+            let interfaces = added.keys().cloned().collect();
+            objects.entry(path.clone()).or_default().extend(added);
+
+            ObjectChange::InterfacesAdded { path, interfaces }
+        }
+        ("org.freedesktop.DBus.ObjectManager", "InterfacesRemoved") => {
+            let (path, interfaces) = msg
+                .body()
+                .deserialize::<(OwnedObjectPath, Vec<OwnedInterfaceName>)>()?;
+            if let Some(object) = objects.get_mut(&path) {
+                for interface in &interfaces {
+                    object.remove(interface);
+                }
+                if object.is_empty() {
+                    objects.remove(&path);
+                }
+            }
+
+            ObjectChange::InterfacesRemoved { path, interfaces }
+        }
+        ("org.freedesktop.DBus.Properties", "PropertiesChanged") => {
+            let (interface, changed, invalidated) =
+                msg.body()
+                    .deserialize::<(OwnedInterfaceName, HashMap<String, OwnedValue>, Vec<String>)>(
+                    )?;
+            let Some(properties) = objects
+                .get_mut(&path)
+                .and_then(|object| object.get_mut(&interface))
+            else {
+                // Not a managed object or interface.
+                return Ok(None);
+            };
+            for name in &invalidated {
+                properties.remove(name);
+            }
+            let changed_names = changed.keys().cloned().collect();
+            properties.extend(changed);
+
+            ObjectChange::PropertiesChanged {
+                path,
+                interface,
+                changed: changed_names,
+                invalidated,
+            }
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(change))
+}
@@ -10,8 +10,8 @@ use super::{Error, Result};
 pub(crate) struct Peer;
 
 /// Service-side implementation for the `org.freedesktop.DBus.Peer` interface.
-/// This interface is implemented automatically for any object registered to the
-/// [ObjectServer](crate::ObjectServer).
+/// This interface is implemented automatically by the [ObjectServer](crate::ObjectServer) on all
+/// paths, including the ones no object is registered at.
 #[crate::interface(
     name = "org.freedesktop.DBus.Peer",
     introspection_docs = false,
@@ -5,13 +5,36 @@
 
 use static_assertions::assert_impl_all;
 use std::collections::HashMap;
-use zbus_names::BusName;
-use zvariant::OwnedValue;
+use zbus_names::{BusName, OwnedUniqueName};
+use zvariant::{DeserializeDict, OwnedValue, SerializeDict, Type};
 
 use super::Result;
-use crate::proxy;
+use crate::{proxy, OwnedMatchRule};
 
 /// Proxy for the `org.freedesktop.DBus.Debug.Stats` interface.
+///
+/// This interface is only available on message buses built with statistics support, and is usually
+/// restricted to privileged clients. It's mostly useful for monitoring the health of a bus and for
+/// debugging, e.g. finding connections leaking match rules.
+#[proxy(
+    interface = "org.freedesktop.DBus.Debug.Stats",
+    default_service = "org.freedesktop.DBus",
+    default_path = "/org/freedesktop/DBus"
+)]
+pub trait DebugStats {
+    /// Get statistics about the message bus itself.
+    fn get_stats(&self) -> Result<BusStats>;
+
+    /// Get statistics about the connection owning `name`.
+    fn get_connection_stats(&self, name: BusName<'_>) -> Result<ConnectionStats>;
+
+    /// Get the match rules registered by each connection on the bus.
+    fn get_all_match_rules(&self) -> Result<HashMap<OwnedUniqueName, Vec<OwnedMatchRule>>>;
+}
+
+/// Proxy for the `org.freedesktop.DBus.Debug.Stats` interface.
+///
+/// Prefer [`DebugStatsProxy`], which returns the statistics as typed values.
 #[proxy(
     interface = "org.freedesktop.DBus.Debug.Stats",
     default_service = "org.freedesktop.DBus",
@@ -31,5 +54,253 @@ pub trait Stats {
 }
 
 assert_impl_all!(StatsProxy<'_>: Send, Sync, Unpin);
+
+/// The statistics of a message bus, as returned by [`DebugStatsProxy::get_stats`].
+///
+/// All statistics are optional since buses are free to only provide some of them.
+///
+/// **Note**: unknown keys will be ignored.
+#[derive(Debug, Default, Clone, DeserializeDict, PartialEq, Eq, SerializeDict, Type)]
+#[zvariant(signature = "a{sv}")]
+pub struct BusStats {
+    #[zvariant(rename = "Serial")]
+    pub(crate) serial: Option<u32>,
+
+    #[zvariant(rename = "ActiveConnections")]
+    pub(crate) active_connections: Option<u32>,
+
+    #[zvariant(rename = "IncompleteConnections")]
+    pub(crate) incomplete_connections: Option<u32>,
+
+    #[zvariant(rename = "MatchRules")]
+    pub(crate) match_rules: Option<u32>,
+
+    #[zvariant(rename = "PeakMatchRules")]
+    pub(crate) peak_match_rules: Option<u32>,
+
+    #[zvariant(rename = "PeakMatchRulesPerConnection")]
+    pub(crate) peak_match_rules_per_connection: Option<u32>,
+
+    #[zvariant(rename = "BusNames")]
+    pub(crate) bus_names: Option<u32>,
+
+    #[zvariant(rename = "PeakBusNames")]
+    pub(crate) peak_bus_names: Option<u32>,
+
+    #[zvariant(rename = "PeakBusNamesPerConnection")]
+    pub(crate) peak_bus_names_per_connection: Option<u32>,
+
+    #[zvariant(rename = "ListMemPoolUsedBytes")]
+    pub(crate) list_mem_pool_used_bytes: Option<u32>,
+
+    #[zvariant(rename = "ListMemPoolCachedBytes")]
+    pub(crate) list_mem_pool_cached_bytes: Option<u32>,
+
+    #[zvariant(rename = "ListMemPoolAllocatedBytes")]
+    pub(crate) list_mem_pool_allocated_bytes: Option<u32>,
+}
+
+impl BusStats {
+    /// The serial number of the statistics, incremented every time they are requested.
+    pub fn serial(&self) -> Option<u32> {
+        self.serial
+    }
+
+    /// The number of connections that have completed authentication.
+    pub fn active_connections(&self) -> Option<u32> {
+        self.active_connections
+    }
+
+    /// The number of connections that are still authenticating.
+    pub fn incomplete_connections(&self) -> Option<u32> {
+        self.incomplete_connections
+    }
+
+    /// The number of match rules currently registered on the bus.
+    pub fn match_rules(&self) -> Option<u32> {
+        self.match_rules
+    }
+
+    /// The highest number of match rules registered at the same time.
+    pub fn peak_match_rules(&self) -> Option<u32> {
+        self.peak_match_rules
+    }
+
+    /// The highest number of match rules registered by a single connection at the same time.
+    pub fn peak_match_rules_per_connection(&self) -> Option<u32> {
+        self.peak_match_rules_per_connection
+    }
+
+    /// The number of names currently owned on the bus.
+    pub fn bus_names(&self) -> Option<u32> {
+        self.bus_names
+    }
+
+    /// The highest number of names owned at the same time.
+    pub fn peak_bus_names(&self) -> Option<u32> {
+        self.peak_bus_names
+    }
+
+    /// The highest number of names owned by a single connection at the same time.
+    pub fn peak_bus_names_per_connection(&self) -> Option<u32> {
+        self.peak_bus_names_per_connection
+    }
+
+    /// The number of bytes in use by the bus' internal list memory pool.
+    pub fn list_mem_pool_used_bytes(&self) -> Option<u32> {
+        self.list_mem_pool_used_bytes
+    }
+
+    /// The number of bytes cached by the bus' internal list memory pool.
+    pub fn list_mem_pool_cached_bytes(&self) -> Option<u32> {
+        self.list_mem_pool_cached_bytes
+    }
+
+    /// The number of bytes allocated by the bus' internal list memory pool.
+    pub fn list_mem_pool_allocated_bytes(&self) -> Option<u32> {
+        self.list_mem_pool_allocated_bytes
+    }
+}
+
+/// The statistics of a connection, as returned by [`DebugStatsProxy::get_connection_stats`].
+///
+/// All statistics are optional since buses are free to only provide some of them.
+///
+/// **Note**: unknown keys will be ignored.
+#[derive(Debug, Default, Clone, DeserializeDict, PartialEq, Eq, SerializeDict, Type)]
+#[zvariant(signature = "a{sv}")]
+pub struct ConnectionStats {
+    #[zvariant(rename = "UniqueName")]
+    pub(crate) unique_name: Option<OwnedUniqueName>,
+
+    #[zvariant(rename = "Serial")]
+    pub(crate) serial: Option<u32>,
+
+    #[zvariant(rename = "IncomingMessages")]
+    pub(crate) incoming_messages: Option<u32>,
+
+    #[zvariant(rename = "IncomingBytes")]
+    pub(crate) incoming_bytes: Option<u32>,
+
+    #[zvariant(rename = "IncomingFDs")]
+    pub(crate) incoming_fds: Option<u32>,
+
+    #[zvariant(rename = "PeakIncomingBytes")]
+    pub(crate) peak_incoming_bytes: Option<u32>,
+
+    #[zvariant(rename = "PeakIncomingFDs")]
+    pub(crate) peak_incoming_fds: Option<u32>,
+
+    #[zvariant(rename = "OutgoingMessages")]
+    pub(crate) outgoing_messages: Option<u32>,
+
+    #[zvariant(rename = "OutgoingBytes")]
+    pub(crate) outgoing_bytes: Option<u32>,
+
+    #[zvariant(rename = "OutgoingFDs")]
+    pub(crate) outgoing_fds: Option<u32>,
+
+    #[zvariant(rename = "PeakOutgoingBytes")]
+    pub(crate) peak_outgoing_bytes: Option<u32>,
+
+    #[zvariant(rename = "PeakOutgoingFDs")]
+    pub(crate) peak_outgoing_fds: Option<u32>,
+
+    #[zvariant(rename = "MatchRules")]
+    pub(crate) match_rules: Option<u32>,
+
+    #[zvariant(rename = "PeakMatchRules")]
+    pub(crate) peak_match_rules: Option<u32>,
+
+    #[zvariant(rename = "BusNames")]
+    pub(crate) bus_names: Option<u32>,
+
+    #[zvariant(rename = "PeakBusNames")]
+    pub(crate) peak_bus_names: Option<u32>,
+}
+
+impl ConnectionStats {
+    /// The unique name of the connection.
+    pub fn unique_name(&self) -> Option<&OwnedUniqueName> {
+        self.unique_name.as_ref()
+    }
+
+    /// The serial number of the statistics, incremented every time they are requested.
+    pub fn serial(&self) -> Option<u32> {
+        self.serial
+    }
+
+    /// The number of messages queued for the bus to receive from the connection.
+    pub fn incoming_messages(&self) -> Option<u32> {
+        self.incoming_messages
+    }
+
+    /// The number of bytes queued for the bus to receive from the connection.
+    pub fn incoming_bytes(&self) -> Option<u32> {
+        self.incoming_bytes
+    }
+
+    /// The number of file descriptors queued for the bus to receive from the connection.
+    pub fn incoming_fds(&self) -> Option<u32> {
+        self.incoming_fds
+    }
+
+    /// The highest number of incoming bytes queued at the same time.
+    pub fn peak_incoming_bytes(&self) -> Option<u32> {
+        self.peak_incoming_bytes
+    }
+
+    /// The highest number of incoming file descriptors queued at the same time.
+    pub fn peak_incoming_fds(&self) -> Option<u32> {
+        self.peak_incoming_fds
+    }
+
+    /// The number of messages queued for the bus to send to the connection.
+    pub fn outgoing_messages(&self) -> Option<u32> {
+        self.outgoing_messages
+    }
+
+    /// The number of bytes queued for the bus to send to the connection.
+    pub fn outgoing_bytes(&self) -> Option<u32> {
+        self.outgoing_bytes
+    }
+
+    /// The number of file descriptors queued for the bus to send to the connection.
+    pub fn outgoing_fds(&self) -> Option<u32> {
+        self.outgoing_fds
+    }
+
+    /// The highest number of outgoing bytes queued at the same time.
+    pub fn peak_outgoing_bytes(&self) -> Option<u32> {
+        self.peak_outgoing_bytes
+    }
+
+    /// The highest number of outgoing file descriptors queued at the same time.
+    pub fn peak_outgoing_fds(&self) -> Option<u32> {
+        self.peak_outgoing_fds
+    }
+
+    /// The number of match rules registered by the connection.
+    pub fn match_rules(&self) -> Option<u32> {
+        self.match_rules
+    }
+
+    /// The highest number of match rules registered by the connection at the same time.
+    pub fn peak_match_rules(&self) -> Option<u32> {
+        self.peak_match_rules
+    }
+
+    /// The number of names owned by the connection.
+    pub fn bus_names(&self) -> Option<u32> {
+        self.bus_names
+    }
+
+    /// The highest number of names owned by the connection at the same time.
+    pub fn peak_bus_names(&self) -> Option<u32> {
+        self.peak_bus_names
+    }
+}
+
+assert_impl_all!(DebugStatsProxy<'_>: Send, Sync, Unpin);
 #[cfg(feature = "blocking-api")]
-assert_impl_all!(StatsProxyBlocking<'_>: Send, Sync, Unpin);
+assert_impl_all!(DebugStatsProxyBlocking<'_>: Send, Sync, Unpin);
@@ -68,6 +68,8 @@ pub use connection::Connection;
 
 mod message_stream;
 pub use message_stream::*;
+mod message_sink;
+pub use message_sink::*;
 mod abstractions;
 pub use abstractions::*;
 
@@ -89,7 +91,7 @@ pub mod fdo;
 #[cfg(feature = "blocking-api")]
 pub mod blocking;
 
-pub use zbus_macros::{interface, proxy, DBusError};
+pub use zbus_macros::{interface, proxy, DBusError, DBusProperties};
 
 // Required for the macros to function within this crate.
 extern crate self as zbus;
@@ -106,4 +108,6 @@ pub mod export {
 }
 
 pub use zbus_names as names;
+#[cfg(feature = "xml")]
+pub use zbus_xml as xml;
 pub use zvariant;
@@ -1,9 +1,9 @@
 //! D-Bus Message.
-use std::{fmt, sync::Arc};
+use std::{borrow::Cow, fmt, sync::Arc};
 
 use static_assertions::assert_impl_all;
 use zbus_names::{ErrorName, InterfaceName, MemberName};
-use zvariant::{serialized, Endian};
+use zvariant::{serialized, Endian, Signature};
 
 use crate::{utils::padding_for_8_bytes, zvariant::ObjectPath, Error, Result};
 
@@ -24,6 +24,11 @@ pub(crate) mod header;
 pub use header::{EndianSig, Flags, Header, PrimaryHeader, Type, NATIVE_ENDIAN_SIG};
 use header::{MIN_MESSAGE_SIZE, PRIMARY_HEADER_SIZE};
 
+// The path and interface reserved for messages generated locally by implementations, which must
+// never be received from a peer.
+const LOCAL_PATH: &str = "/org/freedesktop/DBus/Local";
+const LOCAL_INTERFACE: &str = "org.freedesktop.DBus.Local";
+
 /// A position in the stream of [`Message`] objects received by a single [`zbus::Connection`].
 ///
 /// Note: the relative ordering of values obtained from distinct [`zbus::Connection`] objects is
@@ -176,7 +181,7 @@ impl Message {
             reply_serial: quick_fields.reply_serial(),
             destination: quick_fields.destination(self),
             sender: quick_fields.sender(self),
-            signature: quick_fields.signature().clone(),
+            signature: Cow::Borrowed(quick_fields.signature()),
             unix_fds: quick_fields.unix_fds(),
         };
 
@@ -238,6 +243,46 @@ impl Message {
         self.inner.recv_seq
     }
 
+    /// Check the message against the rules of the D-Bus specification that aren't already enforced
+    /// when it's parsed.
+    ///
+    /// These are the header fields required by the type of the message, the path and interface
+    /// reserved for local use by implementations, and the consistency of the body with its
+    /// signature.
+    pub(crate) fn validate(&self) -> Result<()> {
+        let header = self.header();
+        let has_required_fields = match header.message_type() {
+            Type::MethodCall => header.path().is_some() && header.member().is_some(),
+            Type::MethodReturn => header.reply_serial().is_some(),
+            Type::Error => header.error_name().is_some() && header.reply_serial().is_some(),
+            Type::Signal => {
+                header.path().is_some() && header.interface().is_some() && header.member().is_some()
+            }
+        };
+        if !has_required_fields {
+            return Err(Error::MissingField);
+        }
+        if header.path().map(|p| p.as_str()) == Some(LOCAL_PATH)
+            || header.interface().map(|i| i.as_str()) == Some(LOCAL_INTERFACE)
+        {
+            return Err(Error::InvalidField);
+        }
+
+        let body = self.body();
+        let signature = header.signature();
+        let body_len = if *signature == Signature::Unit {
+            0
+        } else {
+            // This also checks the file descriptor indices against the FDs of the message.
+            body.data().validate(signature)?
+        };
+        if body_len != body.len() {
+            return Err(Error::ExcessData);
+        }
+
+        Ok(())
+    }
+
     fn quick_fields(&self) -> &QuickFields {
         self.inner.quick_fields.get_or_init(|| {
             let bytes = &self.inner.bytes;
@@ -392,4 +437,77 @@ mod tests {
             .unwrap();
         assert_eq!(e.to_string(), "Error org.freedesktop.zbus.Error: kaboom!");
     }
+
+    #[test]
+    fn validate() {
+        let m = Message::method_call("/", "do")
+            .unwrap()
+            .build(&("foo", 42u32))
+            .unwrap();
+        m.validate().unwrap();
+        Message::method_return(&m.header())
+            .unwrap()
+            .build(&())
+            .unwrap()
+            .validate()
+            .unwrap();
+
+        let local = Message::signal(
+            "/org/freedesktop/DBus/Local",
+            "org.freedesktop.DBus.Local",
+            "Disconnected",
+        )
+        .unwrap()
+        .build(&())
+        .unwrap();
+        assert_eq!(local.validate().unwrap_err(), Error::InvalidField);
+
+        // SAFETY: We're building invalid messages on purpose.
+        let excess = unsafe {
+            Message::method_call("/", "do")
+                .unwrap()
+                .build_raw_body(
+                    &[42, 0, 0, 0, 0, 0, 0, 0],
+                    Signature::U32,
+                    #[cfg(unix)]
+                    vec![],
+                )
+                .unwrap()
+        };
+        assert_eq!(excess.validate().unwrap_err(), Error::ExcessData);
+        let garbage = unsafe {
+            Message::method_call("/", "do")
+                .unwrap()
+                .build_raw_body(
+                    &[42, 0, 0, 0, 0xff, 0xff, 0],
+                    Signature::Str,
+                    #[cfg(unix)]
+                    vec![],
+                )
+                .unwrap()
+        };
+        assert!(matches!(garbage.validate().unwrap_err(), Error::Variant(_)));
+
+        // FD index out of range.
+        #[cfg(unix)]
+        {
+            let stdout = std::io::stdout();
+            // SAFETY: We're building invalid messages on purpose.
+            let fd_body = |fds| unsafe {
+                Message::method_call("/", "do")
+                    .unwrap()
+                    .build_raw_body(&[1, 0, 0, 0], Signature::Fd, fds)
+                    .unwrap()
+            };
+            assert!(matches!(
+                fd_body(vec![]).validate().unwrap_err(),
+                Error::Variant(_)
+            ));
+            let fds = vec![
+                stdout.as_fd().try_clone_to_owned().unwrap().into(),
+                stdout.as_fd().try_clone_to_owned().unwrap().into(),
+            ];
+            fd_body(fds).validate().unwrap();
+        }
+    }
 }
@@ -0,0 +1,138 @@
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_sink::Sink;
+use static_assertions::assert_impl_all;
+
+use crate::{message::Message, Connection, Error, Result};
+
+type SendFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+/// A [`Sink`] implementation that sends [`Message`]s over a [`Connection`].
+///
+/// This is the counterpart of [`crate::MessageStream`], allowing a connection to be used with the
+/// combinators of the futures ecosystem, e.g to forward all messages received on one connection to
+/// another one.
+///
+/// Messages are sent one at a time: [`Sink::poll_ready`] only returns `Ready` once the previous
+/// message has been completely written to the socket. Sending errors are returned from the next
+/// call to [`Sink::poll_ready`], [`Sink::poll_flush`] or [`Sink::poll_close`].
+///
+/// You can convert a [`Connection`] to this type and back to [`Connection`].
+///
+/// # Example
+///
+/// ```
+/// use futures_util::{SinkExt, TryStreamExt};
+/// use zbus::{Connection, Message, MessageSink, MessageStream};
+///
+/// # zbus::block_on(async {
+/// let conn = Connection::session().await?;
+/// let mut sink = MessageSink::from(&conn);
+/// let mut stream = MessageStream::from(&conn);
+///
+/// let call = Message::method_call("/org/freedesktop/DBus", "GetId")?
+///     .destination("org.freedesktop.DBus")?
+///     .interface("org.freedesktop.DBus")?
+///     .build(&())?;
+/// let serial = call.primary_header().serial_num();
+/// sink.send(call).await?;
+///
+/// while let Some(msg) = stream.try_next().await? {
+///     if msg.header().reply_serial() == Some(serial) {
+///         let body = msg.body();
+///         let id: &str = body.deserialize()?;
+///         assert_eq!(id.len(), 32);
+///         break;
+///     }
+/// }
+/// # Ok::<(), zbus::Error>(())
+/// # }).unwrap();
+/// ```
+#[must_use = "sinks do nothing unless polled"]
+pub struct MessageSink {
+    conn: Connection,
+    pending: Option<SendFuture>,
+}
+
+assert_impl_all!(MessageSink: Send, Unpin);
+
+impl MessageSink {
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let Some(pending) = self.pending.as_mut() else {
+            return Poll::Ready(Ok(()));
+        };
+        let res = futures_core::ready!(pending.as_mut().poll(cx));
+        self.pending = None;
+
+        Poll::Ready(res)
+    }
+}
+
+impl Sink<Message> for MessageSink {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.get_mut().poll_pending(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, msg: Message) -> Result<()> {
+        let this = self.get_mut();
+        assert!(
+            this.pending.is_none(),
+            "`start_send` called without `poll_ready` returning `Ready` first"
+        );
+        let conn = this.conn.clone();
+        this.pending = Some(Box::pin(async move { conn.send(&msg).await }));
+
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.get_mut().poll_pending(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.get_mut().poll_pending(cx)
+    }
+}
+
+impl fmt::Debug for MessageSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MessageSink")
+            .field("conn", &self.conn)
+            .field("pending", &self.pending.is_some())
+            .finish()
+    }
+}
+
+impl From<Connection> for MessageSink {
+    fn from(conn: Connection) -> Self {
+        Self {
+            conn,
+            pending: None,
+        }
+    }
+}
+
+impl From<&Connection> for MessageSink {
+    fn from(conn: &Connection) -> Self {
+        Self::from(conn.clone())
+    }
+}
+
+impl From<MessageSink> for Connection {
+    fn from(sink: MessageSink) -> Connection {
+        sink.conn
+    }
+}
+
+impl From<&MessageSink> for Connection {
+    fn from(sink: &MessageSink) -> Connection {
+        sink.conn.clone()
+    }
+}
@@ -19,7 +19,8 @@ use crate::{
 
 /// A [`stream::Stream`] implementation that yields [`Message`] items.
 ///
-/// You can convert a [`Connection`] to this type and back to [`Connection`].
+/// You can convert a [`Connection`] to this type and back to [`Connection`]. For the other
+/// direction, see [`crate::MessageSink`].
 ///
 /// **NOTE**: You must ensure a `MessageStream` is continuously polled or you will experience hangs.
 /// If you don't need to continuously poll the `MessageStream` but need to keep it around for later
@@ -137,6 +137,11 @@ pub trait Interface: Any + Send + Sync {
     ) -> DispatchResult<'call>;
 
     /// Write introspection XML to the writer, with the given indentation level.
+    ///
+    /// The [`ObjectServer`] caches the resulting XML of each object, and only regenerates it when
+    /// interfaces or objects are added to or removed from the object or its descendants. Hence
+    /// the output must not change for as long as the interface is served. If it needs to change,
+    /// remove the interface from the server and add it back.
     fn introspect_to_writer(&self, writer: &mut dyn Write, level: usize);
 }
 
@@ -25,11 +25,15 @@ pub use signal_emitter::SignalEmitter;
 #[deprecated(since = "5.0.0", note = "Please use `SignalEmitter` instead.")]
 pub type SignalContext<'s> = SignalEmitter<'s>;
 
+mod properties;
+pub use properties::DBusProperties;
+
 mod dispatch_notifier;
 pub use dispatch_notifier::ResponseDispatchNotifier;
 
 mod node;
 pub(crate) use node::Node;
+use node::INTROSPECTION_DOCTYPE;
 
 /// An object server, holding server-side D-Bus objects & interfaces.
 ///
@@ -372,9 +376,17 @@ impl ObjectServer {
         // way, the object server can be mutated during that time.
         let (iface, with_spawn) = {
             let root = self.root.read().await;
-            let node = root
-                .get_child(path)
-                .ok_or_else(|| fdo::Error::UnknownObject(format!("Unknown object '{path}'")))?;
+            let node = match root.get_child(path) {
+                Some(node) => node,
+                // Like other D-Bus implementations, we answer `Peer` calls on all paths, not
+                // just the ones we serve objects at.
+                None if *iface_name == fdo::Peer::name() => &root,
+                None => {
+                    return Err(fdo::Error::UnknownObject(format!(
+                        "Unknown object '{path}'"
+                    )))
+                }
+            };
 
             let iface = node.interface_lock(iface_name.as_ref()).ok_or_else(|| {
                 fdo::Error::UnknownInterface(format!("Unknown interface '{iface_name}'"))
@@ -384,7 +396,9 @@ impl ObjectServer {
 
         if with_spawn {
             let executor = connection.executor().clone();
-            let task_name = format!("`{msg}` method dispatcher");
+            // The span field is only formatted if the span is enabled, unlike a task name, which
+            // we'd have to format for each call.
+            let span = trace_span!("method dispatcher", msg = %msg);
             let connection = connection.clone();
             let msg = msg.clone();
             executor
@@ -406,8 +420,8 @@ impl ObjectServer {
                             }
                         }
                     }
-                    .instrument(trace_span!("{}", task_name)),
-                    &task_name,
+                    .instrument(span),
+                    "method dispatcher",
                 )
                 .detach();
             Ok(())
@@ -449,6 +463,45 @@ impl ObjectServer {
     }
 }
 
+/// Generate the introspection XML document describing the given interfaces.
+///
+/// The result is the same document a peer would receive when introspecting an object serving
+/// `interfaces`, minus the standard interfaces (such as `org.freedesktop.DBus.Properties`) that
+/// the [`ObjectServer`] implements on every object. No connection is needed, which makes this
+/// suitable for publishing the D-Bus API description of a service, e.g from a test or a small
+/// binary that writes the document out and fails when the checked-in copy is out of date.
+///
+/// # Example
+///
+/// ```
+/// use zbus::{interface, object_server::introspect};
+///
+/// struct Greeter;
+///
+/// #[interface(name = "org.zbus.Greeter1")]
+/// impl Greeter {
+///     fn say_hello(&self, name: &str) -> String {
+///         format!("Hello {name}!")
+///     }
+/// }
+///
+/// let xml = introspect(&[&Greeter]);
+/// assert!(xml.contains(r#"<interface name="org.zbus.Greeter1">"#));
+/// assert!(xml.contains(r#"<method name="SayHello">"#));
+/// ```
+pub fn introspect(interfaces: &[&dyn Interface]) -> String {
+    use std::fmt::Write;
+
+    let mut xml = String::with_capacity(1024);
+    writeln!(xml, "{INTROSPECTION_DOCTYPE}\n<node>").unwrap();
+    for iface in interfaces {
+        iface.introspect_to_writer(&mut xml, 2);
+    }
+    writeln!(xml, "</node>").unwrap();
+
+    xml
+}
+
 #[cfg(feature = "blocking-api")]
 impl From<crate::blocking::ObjectServer> for ObjectServer {
     fn from(server: crate::blocking::ObjectServer) -> Self {
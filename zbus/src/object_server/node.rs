@@ -3,6 +3,7 @@
 use std::{
     collections::{hash_map::Entry, HashMap},
     fmt::Write,
+    sync::OnceLock,
 };
 
 use zbus_names::InterfaceName;
@@ -16,11 +17,17 @@ use crate::{
 
 use super::{ArcInterface, Interface};
 
+/// The `DOCTYPE` declaration heading every introspection document.
+pub(crate) const INTROSPECTION_DOCTYPE: &str = r#"<!DOCTYPE node PUBLIC "-//freedesktop//DTD D-BUS Object Introspection 1.0//EN"
+ "http://www.freedesktop.org/standards/dbus/1.0/introspect.dtd">"#;
+
 #[derive(Default, Debug)]
 pub(crate) struct Node {
     path: OwnedObjectPath,
     children: HashMap<String, Node>,
     interfaces: HashMap<InterfaceName<'static>, ArcInterface>,
+    // The cached introspection XML of the node (and all its descendants).
+    introspection: OnceLock<String>,
 }
 
 impl Node {
@@ -57,6 +64,9 @@ impl Node {
     ///
     /// This also returns the path of the parent node that implements ObjectManager (if any). If
     /// multiple parents implement it (they shouldn't), then the closest one is returned.
+    ///
+    /// Since the returned node is expected to be modified, this invalidates the cached
+    /// introspection XML of all the nodes on the way.
     pub(super) fn get_child_mut(
         &mut self,
         path: &ObjectPath<'_>,
@@ -66,6 +76,7 @@ impl Node {
         let mut node_path = String::new();
         let mut obj_manager_path = None;
 
+        node.introspection.take();
         for i in path.split('/').skip(1) {
             if i.is_empty() {
                 continue;
@@ -87,6 +98,7 @@ impl Node {
                 }
                 Entry::Occupied(e) => node = e.into_mut(),
             }
+            node.introspection.take();
         }
 
         (Some(node), obj_manager_path)
@@ -170,14 +182,7 @@ impl Node {
                     }
 
                     if level == 0 {
-                        writeln!(
-                            writer,
-                            r#"
-<!DOCTYPE node PUBLIC "-//freedesktop//DTD D-BUS Object Introspection 1.0//EN"
- "http://www.freedesktop.org/standards/dbus/1.0/introspect.dtd">
-<node>"#
-                        )
-                        .unwrap();
+                        writeln!(writer, "\n{INTROSPECTION_DOCTYPE}\n<node>").unwrap();
                     } else {
                         writeln!(
                             writer,
@@ -205,9 +210,14 @@ impl Node {
     }
 
     pub(crate) async fn introspect(&self) -> String {
-        let mut xml = String::with_capacity(1024);
+        if let Some(xml) = self.introspection.get() {
+            return xml.clone();
+        }
 
+        let mut xml = String::with_capacity(1024);
         self.introspect_to_writer(&mut xml).await;
+        // Another call might have beaten us to it but it'd have generated the same document.
+        let _ = self.introspection.set(xml.clone());
 
         xml
     }
@@ -0,0 +1,38 @@
+use std::{collections::HashMap, fmt::Write};
+
+use async_trait::async_trait;
+use zvariant::{OwnedValue, Value};
+
+use crate::{fdo, object_server::SignalEmitter};
+
+/// D-Bus properties backed by the fields of a type.
+///
+/// This trait is implemented by [`macro@crate::DBusProperties`] and used by the
+/// [`macro@crate::interface`] macro when given the `derived_properties` attribute, to expose the
+/// fields of the interface type as D-Bus properties, alongside the property methods of the
+/// interface. There is usually no need to implement it manually.
+///
+/// This trait should be treated as an unstable API, just like [`super::Interface`].
+#[async_trait]
+pub trait DBusProperties {
+    /// Get the value of the property `name`.
+    ///
+    /// Returns `None` if the property doesn't exist.
+    fn get_property(&self, name: &str) -> Option<fdo::Result<OwnedValue>>;
+
+    /// Add the values of all the readable properties to `props`.
+    fn get_all_properties(&self, props: &mut HashMap<String, OwnedValue>) -> fdo::Result<()>;
+
+    /// Set the property `name` to `value`, and notify about the change through `emitter`.
+    ///
+    /// Returns `None` if the property doesn't exist.
+    async fn set_property(
+        &mut self,
+        name: &str,
+        value: &Value<'_>,
+        emitter: &SignalEmitter<'_>,
+    ) -> Option<fdo::Result<()>>;
+
+    /// Write introspection XML of the properties to the writer, with the given indentation level.
+    fn introspect_properties(writer: &mut dyn Write, level: usize);
+}
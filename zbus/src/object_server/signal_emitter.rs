@@ -1,6 +1,10 @@
+use std::collections::HashMap;
 use zbus_names::{BusName, InterfaceName, MemberName};
 
-use crate::{zvariant::ObjectPath, Connection, Error, Result};
+use crate::{
+    zvariant::{ObjectPath, Value},
+    Connection, Error, Result,
+};
 
 /// A signal emitter.
 ///
@@ -60,6 +64,37 @@ impl<'s> SignalEmitter<'s> {
             .await
     }
 
+    /// Emit the `org.freedesktop.DBus.Properties.PropertiesChanged` signal for the given interface.
+    ///
+    /// The `<property>_changed` and `<property>_invalidate` methods generated by
+    /// [`crate::interface`] emit this signal for a single property. This method gives full control
+    /// over the signal contents instead: the properties in `changed_properties` are sent along
+    /// with their new values, while the ones in `invalidated_properties` are only listed by name.
+    /// Invalidating is useful for properties with large values, so that they're not shipped to
+    /// all the peers listening for the signal. Interested peers can then query the new values.
+    ///
+    /// This is a shorthand for [`crate::fdo::Properties::properties_changed`].
+    pub async fn properties_changed<'i, I>(
+        &self,
+        interface: I,
+        changed_properties: HashMap<&str, Value<'_>>,
+        invalidated_properties: &[&str],
+    ) -> Result<()>
+    where
+        I: TryInto<InterfaceName<'i>>,
+        I::Error: Into<Error>,
+    {
+        let interface = interface.try_into().map_err(Into::into)?;
+
+        crate::fdo::Properties::properties_changed(
+            self,
+            interface,
+            changed_properties,
+            invalidated_properties.into(),
+        )
+        .await
+    }
+
     /// Set the destination for the signal emission.
     ///
     /// Signals are typically broadcasted and thus don't have a destination. However, there are
@@ -7,12 +7,13 @@ use futures_util::{future::Either, stream::Map};
 use ordered_stream::{join as join_streams, FromFuture, Join, OrderedStream, PollResult};
 use static_assertions::assert_impl_all;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{hash_map::RandomState, HashMap, HashSet},
     fmt,
     future::Future,
+    hash::BuildHasher,
     ops::Deref,
     pin::Pin,
-    sync::{Arc, OnceLock, RwLock, RwLockReadGuard},
+    sync::{Arc, OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard},
     task::{Context, Poll},
 };
 use tracing::{debug, info_span, instrument, trace, Instrument};
@@ -157,7 +158,7 @@ impl<'a, T> PropertyChanged<'a, T> {
         }
 
         {
-            let values = self.properties.values.read().expect("lock poisoned");
+            let values = self.properties.values.read(self.name);
             if values
                 .get(self.name)
                 .expect("PropertyStream with no corresponding property")
@@ -180,7 +181,7 @@ impl<'a, T> PropertyChanged<'a, T> {
 
         // Save the new value
         {
-            let mut values = self.properties.values.write().expect("lock poisoned");
+            let mut values = self.properties.values.write(self.name);
 
             values
                 .get_mut(self.name)
@@ -190,7 +191,7 @@ impl<'a, T> PropertyChanged<'a, T> {
 
         Ok(Wrapper {
             name: self.name,
-            values: self.properties.values.read().expect("lock poisoned"),
+            values: self.properties.values.read(self.name),
         })
     }
 }
@@ -240,8 +241,7 @@ where
 
         m.changed_listener = properties
             .values
-            .read()
-            .expect("lock poisoned")
+            .read(m.name)
             .get(m.name)
             .expect("PropertyStream with no corresponding property")
             .event
@@ -258,7 +258,7 @@ where
 
 #[derive(Debug)]
 pub(crate) struct PropertiesCache {
-    values: RwLock<HashMap<String, PropertyValue>>,
+    values: PropertyValues,
     caching_result: RwLock<CachingResult>,
 }
 
@@ -427,45 +427,67 @@ impl PropertiesCache {
         invalidated: &[&str],
         interface: &InterfaceName<'_>,
     ) {
-        let mut values = self.values.write().expect("lock poisoned");
+        let invalidated: Vec<&str> = invalidated
+            .iter()
+            .copied()
+            .filter(|inval| {
+                if uncached_properties.contains(&Str::from(*inval)) {
+                    debug!(
+                        "Ignoring invalidation of uncached property `{}.{}`",
+                        interface, inval
+                    );
+                    return false;
+                }
+                trace!("Property `{interface}.{inval}` invalidated");
 
-        for inval in invalidated {
-            if uncached_properties.contains(&Str::from(*inval)) {
-                debug!(
-                    "Ignoring invalidation of uncached property `{}.{}`",
-                    interface, inval
-                );
-                continue;
-            }
-            trace!("Property `{interface}.{inval}` invalidated");
+                true
+            })
+            .collect();
+
+        let changed: Vec<(&str, OwnedValue)> = changed
+            .iter()
+            .filter_map(|(property_name, value)| {
+                if uncached_properties.contains(&Str::from(*property_name)) {
+                    debug!(
+                        "Ignoring update of uncached property `{}.{}`",
+                        interface, property_name
+                    );
+                    return None;
+                }
+                trace!("Property `{interface}.{property_name}` updated");
+
+                match OwnedValue::try_from(value) {
+                    Ok(value) => Some((*property_name, value)),
+                    Err(e) => {
+                        debug!(
+                            "Failed to convert property `{interface}.{property_name}` to OwnedValue: {e}"
+                        );
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        // Lock all the affected shards first so the signal is applied as a whole.
+        let mut values = self.values.write_all(
+            invalidated
+                .iter()
+                .copied()
+                .chain(changed.iter().map(|(name, _)| *name)),
+        );
 
-            if let Some(entry) = values.get_mut(*inval) {
+        for inval in invalidated {
+            if let Some(entry) = values.get_mut(inval).get_mut(inval) {
                 entry.value = None;
                 entry.event.notify(usize::MAX);
             }
         }
 
         for (property_name, value) in changed {
-            if uncached_properties.contains(&Str::from(*property_name)) {
-                debug!(
-                    "Ignoring update of uncached property `{}.{}`",
-                    interface, property_name
-                );
-                continue;
-            }
-            trace!("Property `{interface}.{property_name}` updated");
-
-            let entry = values.entry(property_name.to_string()).or_default();
-
-            let value = match OwnedValue::try_from(value) {
-                Ok(value) => value,
-                Err(e) => {
-                    debug!(
-                        "Failed to convert property `{interface}.{property_name}` to OwnedValue: {e}"
-                    );
-                    continue;
-                }
-            };
+            let entry = values
+                .get_mut(property_name)
+                .entry(property_name.to_string())
+                .or_default();
             entry.value = Some(value);
             entry.event.notify(usize::MAX);
         }
@@ -741,7 +763,7 @@ impl<'a> Proxy<'a> {
             .property_cache
             .as_ref()
             .and_then(OnceLock::get)
-            .map(|c| c.0.values.read().expect("lock poisoned"))
+            .map(|c| c.0.values.read(property_name))
         {
             // ensure that the property is in the cache.
             values
@@ -975,7 +997,7 @@ impl<'a> Proxy<'a> {
     ) -> PropertyStream<'a, T> {
         let properties = self.get_property_cache();
         let changed_listener = if let Some(properties) = &properties {
-            let mut values = properties.values.write().expect("lock poisoned");
+            let mut values = properties.values.write(name);
             let entry = values
                 .entry(name.to_string())
                 .or_insert_with(PropertyValue::default);
@@ -1030,6 +1052,71 @@ struct PropertyValue {
     event: Event,
 }
 
+const PROPERTY_VALUES_SHARDS: usize = 8;
+
+/// The cached property values, sharded by property name.
+///
+/// This way, updates of a property don't block the readers of other properties.
+#[derive(Debug, Default)]
+struct PropertyValues {
+    hasher: RandomState,
+    shards: [RwLock<HashMap<String, PropertyValue>>; PROPERTY_VALUES_SHARDS],
+}
+
+impl PropertyValues {
+    fn read(&self, name: &str) -> RwLockReadGuard<'_, HashMap<String, PropertyValue>> {
+        self.shard(name).read().expect("lock poisoned")
+    }
+
+    fn write(&self, name: &str) -> RwLockWriteGuard<'_, HashMap<String, PropertyValue>> {
+        self.shard(name).write().expect("lock poisoned")
+    }
+
+    /// Lock the shards of all the given properties for writing.
+    ///
+    /// The shards are always locked in index order, so concurrent callers can't deadlock.
+    fn write_all<'n>(&self, names: impl IntoIterator<Item = &'n str>) -> PropertyValuesGuard<'_> {
+        let mut affected = [false; PROPERTY_VALUES_SHARDS];
+        for name in names {
+            affected[self.shard_index(name)] = true;
+        }
+        let shards = self
+            .shards
+            .iter()
+            .zip(affected)
+            .map(|(shard, affected)| affected.then(|| shard.write().expect("lock poisoned")))
+            .collect();
+
+        PropertyValuesGuard {
+            values: self,
+            shards,
+        }
+    }
+
+    fn shard(&self, name: &str) -> &RwLock<HashMap<String, PropertyValue>> {
+        &self.shards[self.shard_index(name)]
+    }
+
+    fn shard_index(&self, name: &str) -> usize {
+        self.hasher.hash_one(name) as usize % PROPERTY_VALUES_SHARDS
+    }
+}
+
+/// The write guards of a set of [`PropertyValues`] shards.
+struct PropertyValuesGuard<'a> {
+    values: &'a PropertyValues,
+    shards: Vec<Option<RwLockWriteGuard<'a, HashMap<String, PropertyValue>>>>,
+}
+
+impl PropertyValuesGuard<'_> {
+    /// The shard of the given property, which must be one of the properties locked.
+    fn get_mut(&mut self, name: &str) -> &mut HashMap<String, PropertyValue> {
+        self.shards[self.values.shard_index(name)]
+            .as_mut()
+            .expect("shard of the property not locked")
+    }
+}
+
 /// Flags to use with [`Proxy::call_with_flags`].
 #[bitflags]
 #[repr(u8)]
@@ -1545,4 +1632,49 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    #[timeout(15000)]
+    fn atomic_cache_update() {
+        let cache = PropertiesCache {
+            values: Default::default(),
+            caching_result: RwLock::new(CachingResult::Cached { result: Ok(()) }),
+        };
+        // Enough properties to span multiple shards.
+        let names: Vec<_> = (0..32).map(|i| format!("Property{i}")).collect();
+        let interface = InterfaceName::from_static_str_unchecked("org.freedesktop.zbus.Test");
+        let uncached = HashSet::new();
+
+        std::thread::scope(|s| {
+            // Each updater changes all the properties at once, in the order of its own map, so the
+            // shards are requested in different orders.
+            for _ in 0..2 {
+                s.spawn(|| {
+                    for i in 0..1000u32 {
+                        let changed: HashMap<_, _> =
+                            names.iter().map(|n| (n.as_str(), Value::from(i))).collect();
+                        cache.update_cache(&uncached, &changed, &[], &interface);
+                    }
+                });
+            }
+
+            s.spawn(|| {
+                for _ in 0..1000 {
+                    let mut values = cache.values.write_all(names.iter().map(String::as_str));
+                    let mut seen = HashSet::new();
+                    for name in &names {
+                        if let Some(value) = values
+                            .get_mut(name)
+                            .get(name)
+                            .and_then(|e| e.value.as_ref())
+                        {
+                            seen.insert(u32::try_from(&**value).unwrap());
+                        }
+                    }
+                    // Either no update was applied yet, or all properties come from the same one.
+                    assert!(seen.len() <= 1, "partially applied update: {seen:?}");
+                }
+            });
+        });
+    }
 }
@@ -322,3 +322,127 @@ async fn test_freedesktop_api() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn introspect_interfaces() {
+    use zbus::{interface, object_server::introspect};
+
+    struct Greeter;
+
+    #[interface(name = "org.zbus.Greeter1")]
+    impl Greeter {
+        fn say_hello(&self, name: &str) -> String {
+            format!("Hello {name}!")
+        }
+
+        #[zbus(property)]
+        fn greeting_count(&self) -> u32 {
+            0
+        }
+    }
+
+    let xml = introspect(&[&Greeter]);
+    let node = zbus_xml::Node::from_reader(xml.as_bytes()).unwrap();
+    assert!(node.nodes().is_empty());
+    let [iface] = node.interfaces() else {
+        panic!("expected exactly one interface");
+    };
+    assert_eq!(iface.name(), "org.zbus.Greeter1");
+    let method = &iface.methods()[0];
+    assert_eq!(method.name(), "SayHello");
+    let sigs: Vec<_> = method.args().iter().map(|a| a.ty().to_string()).collect();
+    assert_eq!(sigs, ["s", "s"]);
+    assert_eq!(iface.properties()[0].name(), "GreetingCount");
+    assert_eq!(iface.properties()[0].ty().to_string(), "u");
+}
+
+#[test]
+#[timeout(15000)]
+fn derived_properties() {
+    block_on(test_derived_properties()).unwrap();
+}
+
+async fn test_derived_properties() -> Result<()> {
+    use futures_util::StreamExt;
+    use zbus::{
+        fdo::{self, PropertiesProxy},
+        interface,
+        names::InterfaceName,
+        object_server::introspect,
+        DBusProperties,
+    };
+
+    #[derive(DBusProperties)]
+    struct Counter {
+        #[zbus(property(access = "read"))]
+        count: u32,
+        #[zbus(property, name = "StepSize")]
+        step: u32,
+        #[zbus(property(emits_changed_signal = "const"))]
+        label: String,
+        _ticks: u64,
+    }
+
+    #[interface(name = "org.zbus.DerivedProperties", derived_properties)]
+    impl Counter {
+        fn increment(&mut self) {
+            self.count += self.step;
+        }
+
+        #[zbus(property)]
+        fn double_step(&self) -> u32 {
+            self.step * 2
+        }
+    }
+
+    let path = "/org/zbus/DerivedProperties";
+    let service = Connection::session().await?;
+    let counter = Counter {
+        count: 0,
+        step: 1,
+        label: "counter".into(),
+        _ticks: 0,
+    };
+    service.object_server().at(path, counter).await?;
+
+    let client = Connection::session().await?;
+    let proxy = PropertiesProxy::builder(&client)
+        .destination(service.unique_name().unwrap().to_owned())?
+        .path(path)?
+        .build()
+        .await?;
+    let iface = InterfaceName::from_static_str("org.zbus.DerivedProperties")?;
+    let mut changes = proxy.receive_properties_changed().await?;
+
+    proxy.set(iface.clone(), "StepSize", 2u32.into()).await?;
+    let change = changes.next().await.unwrap();
+    assert_eq!(change.args()?.changed_properties()["StepSize"], 2u32.into());
+    assert_eq!(
+        u32::try_from(proxy.get(iface.clone(), "DoubleStep").await?)?,
+        4
+    );
+
+    let err = proxy
+        .set(iface.clone(), "Count", 1u32.into())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, fdo::Error::PropertyReadOnly(_)));
+
+    let all = proxy.get_all(iface).await?;
+    assert_eq!(all.len(), 4);
+    assert_eq!(String::try_from(all["Label"].try_clone()?)?, "counter");
+
+    let iface_ref = service
+        .object_server()
+        .interface::<_, Counter>(path)
+        .await?;
+    let xml = introspect(&[&*iface_ref.get().await]);
+    assert!(xml.contains(r#"<property name="Count" type="u" access="read"/>"#));
+    assert!(xml.contains(r#"<property name="StepSize" type="u" access="readwrite"/>"#));
+    assert!(xml.contains(r#"<property name="DoubleStep" type="u" access="read"/>"#));
+    assert!(xml.contains(
+        r#"<annotation name="org.freedesktop.DBus.Property.EmitsChangedSignal" value="const"/>"#
+    ));
+
+    Ok(())
+}
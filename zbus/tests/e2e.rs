@@ -14,12 +14,14 @@ use tokio::sync::mpsc::{channel, Sender};
 use tracing::{debug, instrument};
 use zbus::{
     block_on,
-    fdo::{ObjectManager, ObjectManagerProxy},
+    fdo::{ObjectManager, ObjectManagerProxy, PeerProxy},
     message,
     object_server::ResponseDispatchNotifier,
     DBusError, Error, Message, MessageStream,
 };
-use zvariant::{DeserializeDict, Optional, OwnedValue, SerializeDict, Str, Type, Value};
+use zvariant::{
+    DeserializeDict, ObjectPath, Optional, OwnedValue, SerializeDict, Str, Type, Value,
+};
 
 use zbus::{
     connection, interface,
@@ -119,10 +121,17 @@ impl MyIface {
     }
 
     #[instrument]
-    fn test_header(&self, #[zbus(header)] header: Header<'_>) {
+    fn test_header(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(message)] msg: &Message,
+        #[zbus(object_path)] path: &ObjectPath<'_>,
+    ) {
         debug!("`TestHeader` called.");
         assert_eq!(header.message_type(), message::Type::MethodCall);
         assert_eq!(header.member().unwrap(), "TestHeader");
+        assert_eq!(msg.header().member().unwrap(), "TestHeader");
+        assert_eq!(path, "/org/freedesktop/MyService");
     }
 
     #[instrument]
@@ -270,11 +279,13 @@ impl MyIface {
         #[zbus(connection)] connection: &Connection,
         #[zbus(object_server)] object_server: &ObjectServer,
         #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+        #[zbus(object_path)] path: &ObjectPath<'_>,
     ) -> bool {
         debug!(
             "`TestHeaderProp` getter called, header: {:?}, connection: {:?}, object_server: {:?}, emitter: {:?}",
             header, connection, object_server, emitter
         );
+        assert_eq!(path, emitter.path());
         header.is_some()
     }
 
@@ -316,6 +327,20 @@ impl MyIface {
         Some(42).into()
     }
 
+    #[instrument]
+    #[zbus(property(optional))]
+    fn absent_property(&self) -> Option<u32> {
+        debug!("`AbsentProperty` getter called.");
+        None
+    }
+
+    #[instrument]
+    #[zbus(property(optional))]
+    fn present_property(&self) -> zbus::fdo::Result<Option<u32>> {
+        debug!("`PresentProperty` getter called.");
+        Ok(Some(42))
+    }
+
     #[instrument]
     #[zbus(property)]
     fn address_data(&self) -> IP4Adress {
@@ -603,7 +628,7 @@ async fn my_iface_test(conn: Connection, event: Event) -> zbus::Result<u32> {
 
     proxy.ping().await?;
     proxy.set_test_header_prop(true).await?;
-    assert_eq!(proxy.test_header_prop().await?, true);
+    assert!(proxy.test_header_prop().await?);
     assert_eq!(proxy.count().await?, 1);
     assert_eq!(proxy.cached_count()?, None);
 
@@ -647,6 +672,22 @@ async fn my_iface_test(conn: Connection, event: Event) -> zbus::Result<u32> {
 
     assert_eq!(proxy.optional_property().await?, Some(42).into());
 
+    // `None` is an unknown property, and hence not part of `GetAll` either.
+    assert_eq!(
+        proxy.absent_property().await.unwrap_err(),
+        zbus::Error::FDO(Box::new(zbus::fdo::Error::UnknownProperty(
+            "Unknown property 'AbsentProperty'".into()
+        ))),
+    );
+    assert_eq!(proxy.present_property().await?, 42);
+    let props = props_proxy
+        .get_all(zbus::names::InterfaceName::from_static_str_unchecked(
+            "org.freedesktop.MyIface",
+        ))
+        .await?;
+    assert!(!props.contains_key("AbsentProperty"));
+    assert_eq!(u32::try_from(&props["PresentProperty"]).unwrap(), 42);
+
     let xml = proxy.inner().introspect().await?;
     debug!("Introspection: {}", xml);
     let node =
@@ -721,6 +762,9 @@ async fn my_iface_test(conn: Connection, event: Event) -> zbus::Result<u32> {
     let ifaces = args.interfaces_and_properties();
     let _ = ifaces.get("org.freedesktop.MyIface").unwrap();
     // TODO: Check if the properties are correct.
+    let (path, ifaces) = ifaces_added.owned_args()?;
+    let managed_objects = obj_manager_proxy.get_managed_objects().await?;
+    assert_eq!(managed_objects.get(&path), Some(&ifaces));
 
     // issue#207: interface panics on incorrect number of args.
     assert!(proxy.inner().call_method("CreateObj", &()).await.is_err());
@@ -762,9 +806,19 @@ async fn my_iface_test(conn: Connection, event: Event) -> zbus::Result<u32> {
     let args = ifaces_removed.args()?;
     assert_eq!(args.object_path(), "/zbus/test/MyObj");
     assert_eq!(args.interfaces().as_ref(), &["org.freedesktop.MyIface"]);
+    let (path, ifaces) = ifaces_removed.owned_args()?;
+    assert_eq!(path.as_str(), "/zbus/test/MyObj");
+    assert_eq!(ifaces, ["org.freedesktop.MyIface"]);
 
     assert!(my_obj_proxy.inner().introspect().await.is_err());
     assert!(my_obj_proxy.ping().await.is_err());
+    // `Peer` is served on all paths, even without an object.
+    let peer_proxy = PeerProxy::builder(&conn)
+        .destination("org.freedesktop.MyService")?
+        .path("/zbus/test/MyObj")?
+        .build()
+        .await?;
+    peer_proxy.ping().await?;
 
     // Make sure methods modifying the ObjectServer can be called without
     // deadlocks.
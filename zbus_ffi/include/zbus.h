@@ -0,0 +1,107 @@
+/*
+ * C API for the client side of zbus.
+ *
+ * See the documentation of the zbus_ffi crate for details on each function.
+ */
+
+#ifndef ZBUS_H
+#define ZBUS_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define ZBUS_OK 0
+#define ZBUS_ERROR -1
+
+typedef struct ZbusConnection ZbusConnection;
+typedef struct ZbusMessage ZbusMessage;
+typedef struct ZbusValue ZbusValue;
+typedef struct ZbusSubscription ZbusSubscription;
+
+typedef enum ZbusMessageType {
+    ZBUS_MESSAGE_TYPE_METHOD_CALL = 1,
+    ZBUS_MESSAGE_TYPE_METHOD_RETURN = 2,
+    ZBUS_MESSAGE_TYPE_ERROR = 3,
+    ZBUS_MESSAGE_TYPE_SIGNAL = 4,
+} ZbusMessageType;
+
+typedef void (*ZbusMessageCallback)(const ZbusMessage *message, void *user_data);
+
+/* Errors & strings */
+const char *zbus_last_error(void);
+void zbus_string_free(char *s);
+
+/* Connections */
+int zbus_connection_new_session(ZbusConnection **out);
+int zbus_connection_new_system(ZbusConnection **out);
+int zbus_connection_new_for_address(const char *address, ZbusConnection **out);
+void zbus_connection_free(ZbusConnection *conn);
+char *zbus_connection_unique_name(const ZbusConnection *conn);
+int zbus_connection_call_method(const ZbusConnection *conn,
+                                const char *destination,
+                                const char *path,
+                                const char *interface,
+                                const char *method,
+                                const ZbusValue *body,
+                                ZbusMessage **reply);
+int zbus_connection_subscribe(const ZbusConnection *conn,
+                              const char *rule,
+                              ZbusMessageCallback callback,
+                              void *user_data,
+                              ZbusSubscription **out);
+void zbus_subscription_free(ZbusSubscription *sub);
+
+/* Messages */
+void zbus_message_free(ZbusMessage *msg);
+ZbusMessage *zbus_message_ref(const ZbusMessage *msg);
+ZbusMessageType zbus_message_type(const ZbusMessage *msg);
+char *zbus_message_sender(const ZbusMessage *msg);
+char *zbus_message_path(const ZbusMessage *msg);
+char *zbus_message_interface(const ZbusMessage *msg);
+char *zbus_message_member(const ZbusMessage *msg);
+int zbus_message_body(const ZbusMessage *msg, ZbusValue **out);
+
+/* Values */
+void zbus_value_free(ZbusValue *value);
+char *zbus_value_signature(const ZbusValue *value);
+
+ZbusValue *zbus_value_new_bool(bool v);
+ZbusValue *zbus_value_new_u8(uint8_t v);
+ZbusValue *zbus_value_new_i16(int16_t v);
+ZbusValue *zbus_value_new_u16(uint16_t v);
+ZbusValue *zbus_value_new_i32(int32_t v);
+ZbusValue *zbus_value_new_u32(uint32_t v);
+ZbusValue *zbus_value_new_i64(int64_t v);
+ZbusValue *zbus_value_new_u64(uint64_t v);
+ZbusValue *zbus_value_new_f64(double v);
+int zbus_value_new_string(const char *s, ZbusValue **out);
+int zbus_value_new_object_path(const char *path, ZbusValue **out);
+int zbus_value_new_struct(const ZbusValue *const *fields, size_t len, ZbusValue **out);
+int zbus_value_new_array(const char *element_signature,
+                         const ZbusValue *const *elements,
+                         size_t len,
+                         ZbusValue **out);
+
+int zbus_value_get_bool(const ZbusValue *value, bool *out);
+int zbus_value_get_u8(const ZbusValue *value, uint8_t *out);
+int zbus_value_get_i16(const ZbusValue *value, int16_t *out);
+int zbus_value_get_u16(const ZbusValue *value, uint16_t *out);
+int zbus_value_get_i32(const ZbusValue *value, int32_t *out);
+int zbus_value_get_u32(const ZbusValue *value, uint32_t *out);
+int zbus_value_get_i64(const ZbusValue *value, int64_t *out);
+int zbus_value_get_u64(const ZbusValue *value, uint64_t *out);
+int zbus_value_get_f64(const ZbusValue *value, double *out);
+int zbus_value_get_string(const ZbusValue *value, char **out);
+size_t zbus_value_len(const ZbusValue *value);
+int zbus_value_get_element(const ZbusValue *value, size_t index, ZbusValue **out);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ZBUS_H */
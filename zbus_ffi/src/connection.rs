@@ -0,0 +1,230 @@
+use std::{
+    ffi::c_void,
+    os::raw::c_char,
+    ptr,
+    thread::{self, JoinHandle},
+};
+
+use futures_util::{
+    future::{AbortHandle, Abortable},
+    StreamExt,
+};
+use zbus::{blocking::Connection, zvariant::Value, MessageStream};
+
+use crate::{
+    error::{set_last_error, store, ZBUS_ERROR},
+    required_str_arg, str_arg, string_ret, ZbusMessage, ZbusValue,
+};
+
+/// A D-Bus connection.
+pub type ZbusConnection = Connection;
+
+/// A callback receiving the messages matching a subscription.
+///
+/// The message is only valid for the duration of the call.
+pub type ZbusMessageCallback = extern "C" fn(message: *const ZbusMessage, user_data: *mut c_void);
+
+/// Connect to the session bus.
+///
+/// On success, `out` is set to the new connection, to be freed with [`zbus_connection_free`].
+///
+/// # Safety
+///
+/// `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_connection_new_session(out: *mut *mut ZbusConnection) -> i32 {
+    store(Connection::session(), out)
+}
+
+/// Connect to the system bus.
+///
+/// On success, `out` is set to the new connection, to be freed with [`zbus_connection_free`].
+///
+/// # Safety
+///
+/// `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_connection_new_system(out: *mut *mut ZbusConnection) -> i32 {
+    store(Connection::system(), out)
+}
+
+/// Connect to the bus at the given D-Bus address.
+///
+/// On success, `out` is set to the new connection, to be freed with [`zbus_connection_free`].
+///
+/// # Safety
+///
+/// `address` must be a valid string and `out` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_connection_new_for_address(
+    address: *const c_char,
+    out: *mut *mut ZbusConnection,
+) -> i32 {
+    let res = required_str_arg(address, "address")
+        .and_then(|address| zbus::blocking::connection::Builder::address(address)?.build());
+
+    store(res, out)
+}
+
+/// Free a connection.
+///
+/// # Safety
+///
+/// `conn` must be NULL or a connection that wasn't freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_connection_free(conn: *mut ZbusConnection) {
+    if !conn.is_null() {
+        drop(Box::from_raw(conn));
+    }
+}
+
+/// The unique bus name of the connection.
+///
+/// Returns NULL if the connection has no unique name. Otherwise, the string is to be freed with
+/// [`zbus_string_free`](crate::zbus_string_free).
+///
+/// # Safety
+///
+/// `conn` must be a valid connection.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_connection_unique_name(conn: *const ZbusConnection) -> *mut c_char {
+    (*conn)
+        .unique_name()
+        .map(|name| string_ret(name.as_str()))
+        .unwrap_or(ptr::null_mut())
+}
+
+/// Call a method and wait for its reply.
+///
+/// `destination` and `interface` can be NULL. `body` can be NULL for methods without arguments,
+/// otherwise it must be a structure whose fields are the arguments.
+///
+/// On success, `reply` is set to the reply message, to be freed with
+/// [`zbus_message_free`](crate::zbus_message_free). An error reply is reported as a failure.
+///
+/// # Safety
+///
+/// `conn` and `body` (if not NULL) must be valid objects, the strings must be NULL or valid
+/// strings, and `reply` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_connection_call_method(
+    conn: *const ZbusConnection,
+    destination: *const c_char,
+    path: *const c_char,
+    interface: *const c_char,
+    method: *const c_char,
+    body: *const ZbusValue,
+    reply: *mut *mut ZbusMessage,
+) -> i32 {
+    let call = || {
+        let destination = str_arg(destination)?;
+        let path = required_str_arg(path, "path")?;
+        let interface = str_arg(interface)?;
+        let method = required_str_arg(method, "method")?;
+        let conn = &*conn;
+
+        match body.as_ref().map(|body| &**body) {
+            None => conn.call_method(destination, path, interface, method, &()),
+            Some(Value::Structure(body)) => {
+                conn.call_method(destination, path, interface, method, body)
+            }
+            Some(_) => Err(zbus::Error::Failure(
+                "the method call body must be a structure".to_string(),
+            )),
+        }
+    };
+
+    store(call(), reply)
+}
+
+/// A subscription to messages, created by [`zbus_connection_subscribe`].
+#[derive(Debug)]
+pub struct ZbusSubscription {
+    abort: AbortHandle,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// Wrapper to send the callback data to the subscription thread.
+struct UserData(*mut c_void);
+
+// SAFETY: The caller of `zbus_connection_subscribe` guarantees that the data can be used from
+// another thread.
+unsafe impl Send for UserData {}
+
+/// Subscribe to the messages matching the given match rule.
+///
+/// `callback` is called with `user_data` for each matching message, from a thread dedicated to
+/// this subscription, until the subscription is freed with [`zbus_subscription_free`].
+///
+/// # Safety
+///
+/// `conn` must be a valid connection, `rule` a valid string and `out` a valid pointer. `user_data`
+/// must be safe to use from another thread for as long as the subscription exists.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_connection_subscribe(
+    conn: *const ZbusConnection,
+    rule: *const c_char,
+    callback: ZbusMessageCallback,
+    user_data: *mut c_void,
+    out: *mut *mut ZbusSubscription,
+) -> i32 {
+    let subscribe = || {
+        let rule = required_str_arg(rule, "rule")?;
+        let stream = zbus::block_on(MessageStream::for_match_rule(rule, (*conn).inner(), None))?;
+        let user_data = UserData(user_data);
+        let (abort, registration) = AbortHandle::new_pair();
+        let forward = async move {
+            // Move the whole wrapper, and not only its (non-`Send`) field, into the future.
+            let user_data = user_data;
+            let mut stream = stream;
+            while let Some(msg) = stream.next().await {
+                // There's no one to report errors to, so we skip the messages we failed to read.
+                if let Ok(msg) = msg {
+                    callback(&msg, user_data.0);
+                }
+            }
+        };
+        let thread = thread::Builder::new()
+            .name("zbus::ffi::subscription".into())
+            .spawn(move || {
+                let _ = zbus::block_on(Abortable::new(forward, registration));
+            })
+            .map_err(|e| zbus::Error::Failure(e.to_string()))?;
+
+        Ok::<_, zbus::Error>(ZbusSubscription {
+            abort,
+            thread: Some(thread),
+        })
+    };
+
+    if conn.is_null() {
+        set_last_error("`conn` must not be NULL");
+
+        return ZBUS_ERROR;
+    }
+
+    store(subscribe(), out)
+}
+
+/// Cancel and free a subscription.
+///
+/// Once this returns, the callback isn't called anymore, unless this is called from the callback
+/// itself.
+///
+/// # Safety
+///
+/// `sub` must be NULL or a subscription that wasn't freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_subscription_free(sub: *mut ZbusSubscription) {
+    if sub.is_null() {
+        return;
+    }
+
+    let mut sub = Box::from_raw(sub);
+    sub.abort.abort();
+    if let Some(thread) = sub.thread.take() {
+        if thread.thread().id() != thread::current().id() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::{cell::RefCell, ffi::CString, fmt::Display, os::raw::c_char, ptr};
+
+/// Return value of fallible functions on success.
+pub const ZBUS_OK: i32 = 0;
+/// Return value of fallible functions on failure.
+///
+/// The error message can then be retrieved with [`zbus_last_error`].
+pub const ZBUS_ERROR: i32 = -1;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Record `error` as the last error of the current thread.
+pub(crate) fn set_last_error(error: impl Display) {
+    // Interior NUL bytes would truncate the message, so we replace them.
+    let msg = error.to_string().replace('\0', "\u{FFFD}");
+    let msg = CString::new(msg).expect("NUL bytes were replaced");
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(msg));
+}
+
+/// Store `res` in `out` if it's a success, or record the error otherwise.
+pub(crate) fn store<T>(res: Result<T, impl Display>, out: *mut *mut T) -> i32 {
+    if out.is_null() {
+        set_last_error("output pointer is NULL");
+
+        return ZBUS_ERROR;
+    }
+
+    match res {
+        Ok(value) => {
+            // SAFETY: Checked for NULL above, the caller guarantees it's otherwise valid.
+            unsafe { *out = Box::into_raw(Box::new(value)) };
+
+            ZBUS_OK
+        }
+        Err(e) => {
+            // SAFETY: Checked for NULL above, the caller guarantees it's otherwise valid.
+            unsafe { *out = ptr::null_mut() };
+            set_last_error(e);
+
+            ZBUS_ERROR
+        }
+    }
+}
+
+/// The message of the last error that occurred on the current thread.
+///
+/// Returns NULL if no error occurred yet. The returned string is owned by the library and remains
+/// valid until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn zbus_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map(|msg| msg.as_ptr())
+            .unwrap_or(ptr::null())
+    })
+}
@@ -0,0 +1,71 @@
+#![deny(rust_2018_idioms)]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/dbus2/zbus/9f7a90d2b594ddc48b7a5f39fda5e00cd56a7dfb/logo.png"
+)]
+#![doc = include_str!("../README.md")]
+
+use std::{
+    ffi::{CStr, CString},
+    os::raw::c_char,
+    ptr,
+};
+
+mod error;
+pub use error::*;
+
+mod connection;
+pub use connection::*;
+
+mod message;
+pub use message::*;
+
+mod value;
+pub use value::*;
+
+/// Free a string returned by the library.
+///
+/// # Safety
+///
+/// `s` must be NULL or a string returned by the library, that wasn't freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Borrow the UTF-8 string behind `s`, `None` if `s` is NULL.
+///
+/// # Safety
+///
+/// `s` must be NULL or a valid NUL-terminated string, that outlives `'a`.
+pub(crate) unsafe fn str_arg<'a>(s: *const c_char) -> Result<Option<&'a str>, zbus::Error> {
+    if s.is_null() {
+        return Ok(None);
+    }
+
+    CStr::from_ptr(s)
+        .to_str()
+        .map(Some)
+        .map_err(|e| zbus::Error::Failure(format!("invalid UTF-8 string argument: {e}")))
+}
+
+/// Same as [`str_arg`], but for mandatory arguments.
+///
+/// # Safety
+///
+/// Same as [`str_arg`].
+pub(crate) unsafe fn required_str_arg<'a>(
+    s: *const c_char,
+    name: &str,
+) -> Result<&'a str, zbus::Error> {
+    str_arg(s)?.ok_or_else(|| zbus::Error::Failure(format!("`{name}` must not be NULL")))
+}
+
+/// Hand over `s` to the caller, who's responsible to free it with [`zbus_string_free`].
+pub(crate) fn string_ret(s: &str) -> *mut c_char {
+    // D-Bus strings can't contain NUL bytes.
+    CString::new(s)
+        .map(CString::into_raw)
+        .unwrap_or(ptr::null_mut())
+}
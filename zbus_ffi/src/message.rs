@@ -0,0 +1,137 @@
+use std::{os::raw::c_char, ptr};
+
+use zbus::{
+    message::Type,
+    zvariant::{OwnedValue, Structure, Value},
+    Message,
+};
+
+use crate::{
+    error::{store, ZBUS_OK},
+    string_ret,
+};
+
+/// A D-Bus message.
+pub type ZbusMessage = Message;
+
+/// The type of a message.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZbusMessageType {
+    /// Method call.
+    MethodCall = 1,
+    /// A reply to a method call.
+    MethodReturn = 2,
+    /// An error in response to a method call.
+    Error = 3,
+    /// Signal emission.
+    Signal = 4,
+}
+
+/// Free a message.
+///
+/// # Safety
+///
+/// `msg` must be NULL or a message that wasn't freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_message_free(msg: *mut ZbusMessage) {
+    if !msg.is_null() {
+        drop(Box::from_raw(msg));
+    }
+}
+
+/// Create a new reference to a message, e.g to keep it after the callback of a subscription.
+///
+/// The returned message is to be freed with [`zbus_message_free`].
+///
+/// # Safety
+///
+/// `msg` must be a valid message.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_message_ref(msg: *const ZbusMessage) -> *mut ZbusMessage {
+    Box::into_raw(Box::new((*msg).clone()))
+}
+
+/// The type of the message.
+///
+/// # Safety
+///
+/// `msg` must be a valid message.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_message_type(msg: *const ZbusMessage) -> ZbusMessageType {
+    match (*msg).message_type() {
+        Type::MethodCall => ZbusMessageType::MethodCall,
+        Type::MethodReturn => ZbusMessageType::MethodReturn,
+        Type::Error => ZbusMessageType::Error,
+        Type::Signal => ZbusMessageType::Signal,
+    }
+}
+
+macro_rules! header_field {
+    ($(#[$attr:meta])* $fn:ident, $field:ident) => {
+        $(#[$attr])*
+        ///
+        /// Returns NULL if the message doesn't have this header field. Otherwise, the string is to
+        /// be freed with [`zbus_string_free`](crate::zbus_string_free).
+        ///
+        /// # Safety
+        ///
+        /// `msg` must be a valid message.
+        #[no_mangle]
+        pub unsafe extern "C" fn $fn(msg: *const ZbusMessage) -> *mut c_char {
+            (*msg)
+                .header()
+                .$field()
+                .map(|field| string_ret(field.as_str()))
+                .unwrap_or(ptr::null_mut())
+        }
+    };
+}
+
+header_field!(
+    /// The sender of the message.
+    zbus_message_sender,
+    sender
+);
+header_field!(
+    /// The object path of the message.
+    zbus_message_path,
+    path
+);
+header_field!(
+    /// The interface of the message.
+    zbus_message_interface,
+    interface
+);
+header_field!(
+    /// The member (method or signal name) of the message.
+    zbus_message_member,
+    member
+);
+
+/// The body of the message, as a structure whose fields are the arguments.
+///
+/// On success, `out` is set to the body, to be freed with
+/// [`zbus_value_free`](crate::zbus_value_free), or to NULL if the message has no arguments.
+///
+/// # Safety
+///
+/// `msg` must be a valid message and `out` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_message_body(
+    msg: *const ZbusMessage,
+    out: *mut *mut OwnedValue,
+) -> i32 {
+    let body = (*msg).body();
+    if body.is_empty() && !out.is_null() {
+        *out = ptr::null_mut();
+
+        return ZBUS_OK;
+    }
+
+    let res = body
+        .deserialize::<Structure<'_>>()
+        .and_then(|s| Value::from(s).try_to_owned().map_err(Into::into));
+
+    store(res, out)
+}
@@ -0,0 +1,287 @@
+use std::{os::raw::c_char, ptr, slice};
+
+use zbus::zvariant::{Array, ObjectPath, OwnedValue, Signature, StructureBuilder, Value};
+
+use crate::{
+    error::{set_last_error, store, ZBUS_ERROR, ZBUS_OK},
+    required_str_arg, string_ret,
+};
+
+/// A D-Bus value.
+pub type ZbusValue = OwnedValue;
+
+/// Free a value.
+///
+/// # Safety
+///
+/// `value` must be NULL or a value that wasn't freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_value_free(value: *mut ZbusValue) {
+    if !value.is_null() {
+        drop(Box::from_raw(value));
+    }
+}
+
+/// The D-Bus signature of the value.
+///
+/// The string is to be freed with [`zbus_string_free`](crate::zbus_string_free).
+///
+/// # Safety
+///
+/// `value` must be a valid value.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_value_signature(value: *const ZbusValue) -> *mut c_char {
+    string_ret(&(*value).value_signature().to_string())
+}
+
+macro_rules! basic_value {
+    ($ty:ty, $new:ident, $get:ident, $variant:ident, $doc:literal) => {
+        #[doc = concat!("Create a new ", $doc, " value.")]
+        ///
+        /// The value is to be freed with [`zbus_value_free`].
+        #[no_mangle]
+        pub extern "C" fn $new(v: $ty) -> *mut ZbusValue {
+            Box::into_raw(Box::new(OwnedValue::from(v)))
+        }
+
+        #[doc = concat!("Get the ", $doc, " `value` holds.")]
+        ///
+        /// Fails if `value` is of another type.
+        ///
+        /// # Safety
+        ///
+        /// `value` must be a valid value and `out` a valid pointer.
+        #[no_mangle]
+        pub unsafe extern "C" fn $get(value: *const ZbusValue, out: *mut $ty) -> i32 {
+            match &**value {
+                Value::$variant(v) if !out.is_null() => {
+                    *out = *v;
+
+                    ZBUS_OK
+                }
+                v => {
+                    set_last_error(format!(
+                        concat!("expected ", $doc, " value, got `{}`"),
+                        v.value_signature()
+                    ));
+
+                    ZBUS_ERROR
+                }
+            }
+        }
+    };
+}
+
+basic_value!(
+    bool,
+    zbus_value_new_bool,
+    zbus_value_get_bool,
+    Bool,
+    "a boolean"
+);
+basic_value!(u8, zbus_value_new_u8, zbus_value_get_u8, U8, "a byte");
+basic_value!(i16, zbus_value_new_i16, zbus_value_get_i16, I16, "an int16");
+basic_value!(u16, zbus_value_new_u16, zbus_value_get_u16, U16, "a uint16");
+basic_value!(i32, zbus_value_new_i32, zbus_value_get_i32, I32, "an int32");
+basic_value!(u32, zbus_value_new_u32, zbus_value_get_u32, U32, "a uint32");
+basic_value!(i64, zbus_value_new_i64, zbus_value_get_i64, I64, "an int64");
+basic_value!(u64, zbus_value_new_u64, zbus_value_get_u64, U64, "a uint64");
+basic_value!(f64, zbus_value_new_f64, zbus_value_get_f64, F64, "a double");
+
+/// Create a new string value.
+///
+/// On success, `out` is set to the value, to be freed with [`zbus_value_free`].
+///
+/// # Safety
+///
+/// `s` must be a valid string and `out` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_value_new_string(s: *const c_char, out: *mut *mut ZbusValue) -> i32 {
+    let res = required_str_arg(s, "s").map(|s| OwnedValue::from(zbus::zvariant::Str::from(s)));
+
+    store(res, out)
+}
+
+/// Create a new object path value.
+///
+/// On success, `out` is set to the value, to be freed with [`zbus_value_free`].
+///
+/// # Safety
+///
+/// `path` must be a valid string and `out` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_value_new_object_path(
+    path: *const c_char,
+    out: *mut *mut ZbusValue,
+) -> i32 {
+    let res = required_str_arg(path, "path").and_then(|path| {
+        ObjectPath::try_from(path)
+            .map(OwnedValue::from)
+            .map_err(Into::into)
+    });
+
+    store(res, out)
+}
+
+/// Get the string `value` holds, if it's a string, an object path or a signature.
+///
+/// On success, `out` is set to the string, to be freed with
+/// [`zbus_string_free`](crate::zbus_string_free).
+///
+/// # Safety
+///
+/// `value` must be a valid value and `out` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_value_get_string(
+    value: *const ZbusValue,
+    out: *mut *mut c_char,
+) -> i32 {
+    let s = match &**value {
+        Value::Str(s) => s.to_string(),
+        Value::ObjectPath(p) => p.to_string(),
+        Value::Signature(s) => s.to_string(),
+        v => {
+            set_last_error(format!(
+                "expected a string value, got `{}`",
+                v.value_signature()
+            ));
+
+            return ZBUS_ERROR;
+        }
+    };
+    if out.is_null() {
+        set_last_error("output pointer is NULL");
+
+        return ZBUS_ERROR;
+    }
+    *out = string_ret(&s);
+
+    ZBUS_OK
+}
+
+/// Collect the values in `values`.
+///
+/// # Safety
+///
+/// `values` must point to `len` valid values.
+unsafe fn values_arg<'a>(
+    values: *const *const ZbusValue,
+    len: usize,
+) -> Result<Vec<Value<'a>>, zbus::Error> {
+    if len == 0 {
+        return Ok(vec![]);
+    }
+
+    slice::from_raw_parts(values, len)
+        .iter()
+        .map(|v| Value::try_clone(&**v).map_err(Into::into))
+        .collect()
+}
+
+/// Create a new structure value out of `len` fields.
+///
+/// The fields are copied, so they still need to be freed. On success, `out` is set to the value, to
+/// be freed with [`zbus_value_free`].
+///
+/// # Safety
+///
+/// `fields` must point to `len` valid values and `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_value_new_struct(
+    fields: *const *const ZbusValue,
+    len: usize,
+    out: *mut *mut ZbusValue,
+) -> i32 {
+    let res = values_arg(fields, len).and_then(|fields| {
+        let s = fields
+            .into_iter()
+            .fold(StructureBuilder::new(), |builder, field| {
+                builder.append_field(field)
+            })
+            .build()?;
+
+        Value::from(s).try_to_owned().map_err(Into::into)
+    });
+
+    store(res, out)
+}
+
+/// Create a new array value out of `len` elements of the given signature.
+///
+/// The elements are copied, so they still need to be freed. On success, `out` is set to the value,
+/// to be freed with [`zbus_value_free`].
+///
+/// # Safety
+///
+/// `element_signature` must be a valid string, `elements` must point to `len` valid values and
+/// `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_value_new_array(
+    element_signature: *const c_char,
+    elements: *const *const ZbusValue,
+    len: usize,
+    out: *mut *mut ZbusValue,
+) -> i32 {
+    let res = required_str_arg(element_signature, "element_signature").and_then(|signature| {
+        let signature = Signature::try_from(signature)?;
+        let mut array = Array::new(&signature);
+        for element in values_arg(elements, len)? {
+            array.append(element)?;
+        }
+
+        Value::from(array).try_to_owned().map_err(Into::into)
+    });
+
+    store(res, out)
+}
+
+/// The number of fields or elements of a structure or array value.
+///
+/// Returns 0 for other values.
+///
+/// # Safety
+///
+/// `value` must be a valid value.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_value_len(value: *const ZbusValue) -> usize {
+    match &**value {
+        Value::Structure(s) => s.fields().len(),
+        Value::Array(a) => a.len(),
+        _ => 0,
+    }
+}
+
+/// Get a copy of the field or element of a structure or array value, at the given index.
+///
+/// On success, `out` is set to the value, to be freed with [`zbus_value_free`].
+///
+/// # Safety
+///
+/// `value` must be a valid value and `out` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn zbus_value_get_element(
+    value: *const ZbusValue,
+    index: usize,
+    out: *mut *mut ZbusValue,
+) -> i32 {
+    let element = match &**value {
+        Value::Structure(s) => s.fields().get(index),
+        Value::Array(a) => a.inner().get(index),
+        v => {
+            set_last_error(format!(
+                "expected a structure or array value, got `{}`",
+                v.value_signature()
+            ));
+            if !out.is_null() {
+                *out = ptr::null_mut();
+            }
+
+            return ZBUS_ERROR;
+        }
+    };
+    let res = element
+        .ok_or_else(|| zbus::Error::Failure(format!("index {index} is out of bounds")))
+        .and_then(|element| element.try_to_owned().map_err(Into::into));
+
+    store(res, out)
+}
@@ -0,0 +1,199 @@
+use std::{
+    ffi::{c_void, CStr, CString},
+    os::raw::c_char,
+    ptr,
+    sync::mpsc::{channel, Sender},
+    time::Duration,
+};
+
+use ntest::timeout;
+use zbus_ffi::*;
+
+fn c(s: &str) -> CString {
+    CString::new(s).unwrap()
+}
+
+unsafe fn take_string(s: *mut c_char) -> String {
+    assert!(!s.is_null());
+    let owned = CStr::from_ptr(s).to_str().unwrap().to_string();
+    zbus_string_free(s);
+
+    owned
+}
+
+#[test]
+fn values() {
+    unsafe {
+        let mut s = ptr::null_mut();
+        assert_eq!(zbus_value_new_string(c("hello").as_ptr(), &mut s), ZBUS_OK);
+        let n = zbus_value_new_u32(42);
+        let mut a = ptr::null_mut();
+        let elements = [n as *const ZbusValue, n];
+        assert_eq!(
+            zbus_value_new_array(c("u").as_ptr(), elements.as_ptr(), 2, &mut a),
+            ZBUS_OK
+        );
+        let mut st = ptr::null_mut();
+        let fields = [s as *const ZbusValue, a];
+        assert_eq!(zbus_value_new_struct(fields.as_ptr(), 2, &mut st), ZBUS_OK);
+        assert_eq!(take_string(zbus_value_signature(st)), "(sau)");
+        assert_eq!(zbus_value_len(st), 2);
+
+        let mut field = ptr::null_mut();
+        assert_eq!(zbus_value_get_element(st, 1, &mut field), ZBUS_OK);
+        assert_eq!(zbus_value_len(field), 2);
+        let mut element = ptr::null_mut();
+        assert_eq!(zbus_value_get_element(field, 0, &mut element), ZBUS_OK);
+        let mut v = 0;
+        assert_eq!(zbus_value_get_u32(element, &mut v), ZBUS_OK);
+        assert_eq!(v, 42);
+
+        // Type mismatches and invalid indices are errors.
+        let mut b = false;
+        assert_eq!(zbus_value_get_bool(element, &mut b), ZBUS_ERROR);
+        assert_eq!(
+            CStr::from_ptr(zbus_last_error()).to_str().unwrap(),
+            "expected a boolean value, got `u`"
+        );
+        let mut none = ptr::null_mut();
+        assert_eq!(zbus_value_get_element(st, 2, &mut none), ZBUS_ERROR);
+        assert!(none.is_null());
+        assert_eq!(
+            zbus_value_new_array(c("s").as_ptr(), elements.as_ptr(), 2, &mut none),
+            ZBUS_ERROR
+        );
+
+        let mut string = ptr::null_mut();
+        zbus_value_free(field);
+        assert_eq!(zbus_value_get_element(st, 0, &mut field), ZBUS_OK);
+        assert_eq!(zbus_value_get_string(field, &mut string), ZBUS_OK);
+        assert_eq!(take_string(string), "hello");
+
+        for value in [s, n, a, st, field, element] {
+            zbus_value_free(value);
+        }
+    }
+}
+
+#[test]
+#[timeout(15000)]
+fn method_call() {
+    unsafe {
+        let mut conn = ptr::null_mut();
+        assert_eq!(zbus_connection_new_session(&mut conn), ZBUS_OK);
+        let unique_name = take_string(zbus_connection_unique_name(conn));
+        assert!(unique_name.starts_with(':'));
+
+        let mut name = ptr::null_mut();
+        assert_eq!(
+            zbus_value_new_string(c("org.freedesktop.DBus").as_ptr(), &mut name),
+            ZBUS_OK
+        );
+        let mut body = ptr::null_mut();
+        let fields = [name as *const ZbusValue];
+        assert_eq!(
+            zbus_value_new_struct(fields.as_ptr(), 1, &mut body),
+            ZBUS_OK
+        );
+        let mut reply = ptr::null_mut();
+        assert_eq!(
+            zbus_connection_call_method(
+                conn,
+                c("org.freedesktop.DBus").as_ptr(),
+                c("/org/freedesktop/DBus").as_ptr(),
+                c("org.freedesktop.DBus").as_ptr(),
+                c("NameHasOwner").as_ptr(),
+                body,
+                &mut reply,
+            ),
+            ZBUS_OK
+        );
+        assert_eq!(zbus_message_type(reply), ZbusMessageType::MethodReturn);
+        let mut reply_body = ptr::null_mut();
+        assert_eq!(zbus_message_body(reply, &mut reply_body), ZBUS_OK);
+        assert_eq!(take_string(zbus_value_signature(reply_body)), "(b)");
+        let mut has_owner = ptr::null_mut();
+        assert_eq!(
+            zbus_value_get_element(reply_body, 0, &mut has_owner),
+            ZBUS_OK
+        );
+        let mut b = false;
+        assert_eq!(zbus_value_get_bool(has_owner, &mut b), ZBUS_OK);
+        assert!(b);
+
+        // Error replies are failures.
+        let mut error_reply = ptr::null_mut();
+        assert_eq!(
+            zbus_connection_call_method(
+                conn,
+                c("org.freedesktop.DBus").as_ptr(),
+                c("/org/freedesktop/DBus").as_ptr(),
+                c("org.freedesktop.DBus").as_ptr(),
+                c("NoSuchMethod").as_ptr(),
+                ptr::null(),
+                &mut error_reply,
+            ),
+            ZBUS_ERROR
+        );
+        assert!(error_reply.is_null());
+        assert!(!zbus_last_error().is_null());
+
+        zbus_value_free(has_owner);
+        zbus_value_free(reply_body);
+        zbus_message_free(reply);
+        zbus_value_free(body);
+        zbus_value_free(name);
+        zbus_connection_free(conn);
+    }
+}
+
+extern "C" fn on_message(msg: *const ZbusMessage, user_data: *mut c_void) {
+    unsafe {
+        let tx = &*(user_data as *const Sender<(String, String)>);
+        let member = take_string(zbus_message_member(msg));
+        let mut body = ptr::null_mut();
+        assert_eq!(zbus_message_body(msg, &mut body), ZBUS_OK);
+        let mut name = ptr::null_mut();
+        assert_eq!(zbus_value_get_element(body, 0, &mut name), ZBUS_OK);
+        let mut s = ptr::null_mut();
+        assert_eq!(zbus_value_get_string(name, &mut s), ZBUS_OK);
+        let _ = tx.send((member, take_string(s)));
+        zbus_value_free(name);
+        zbus_value_free(body);
+    }
+}
+
+#[test]
+#[timeout(15000)]
+fn subscription() {
+    unsafe {
+        let mut conn = ptr::null_mut();
+        assert_eq!(zbus_connection_new_session(&mut conn), ZBUS_OK);
+        let (tx, rx) = channel::<(String, String)>();
+        let tx = Box::new(tx);
+        let mut sub = ptr::null_mut();
+        assert_eq!(
+            zbus_connection_subscribe(
+                conn,
+                c(
+                    "type='signal',interface='org.freedesktop.DBus',member='NameOwnerChanged',\
+                   arg0='org.freedesktop.zbus.FfiTest'"
+                )
+                .as_ptr(),
+                on_message,
+                &*tx as *const Sender<_> as *mut c_void,
+                &mut sub,
+            ),
+            ZBUS_OK
+        );
+
+        let other = zbus::blocking::Connection::session().unwrap();
+        other.request_name("org.freedesktop.zbus.FfiTest").unwrap();
+        let (member, name) = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(member, "NameOwnerChanged");
+        assert_eq!(name, "org.freedesktop.zbus.FfiTest");
+
+        zbus_subscription_free(sub);
+        zbus_connection_free(conn);
+    }
+}
@@ -144,13 +144,22 @@ pub fn expand_derive(input: DeriveInput) -> Result<TokenStream, Error> {
             quote! {
                 impl ::std::convert::From<#zbus::Error> for #name {
                     fn from(value: #zbus::Error) -> #name {
-                        if let #zbus::Error::MethodError(name, desc, _) = &value {
-                            match name.as_str() {
+                        match &value {
+                            #zbus::Error::MethodError(name, desc, _) => match name.as_str() {
                                 #error_converts
                                 _ => Self::#ident(value),
+                            },
+                            // Errors from `fdo` are matched by name, just like method errors.
+                            #zbus::Error::FDO(e) => {
+                                let name = #zbus::DBusError::name(&**e);
+                                let desc = &#zbus::DBusError::description(&**e)
+                                    .map(::std::string::ToString::to_string);
+                                match name.as_str() {
+                                    #error_converts
+                                    _ => Self::#ident(value),
+                                }
                             }
-                        } else {
-                            Self::#ident(value)
+                            _ => Self::#ident(value),
                         }
                     }
                 }
@@ -2,6 +2,7 @@ use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 use std::collections::BTreeMap;
 use syn::{
+    ext::IdentExt,
     parse::{Parse, ParseStream},
     parse_quote, parse_str,
     punctuated::Punctuated,
@@ -25,6 +26,7 @@ def_attrs! {
         name str,
         spawn bool,
         introspection_docs bool,
+        derived_properties none,
         proxy {
             // Keep this in sync with proxy's method attributes.
             // TODO: Find a way to share code with proxy module.
@@ -44,9 +46,12 @@ def_attrs! {
     pub MethodAttributes("method") {
         name str,
         signal none,
+        skip none,
+        no_reply none,
         property {
             pub PropertyAttributes("property") {
-                emits_changed_signal str
+                emits_changed_signal str,
+                optional none
             }
         },
         out_args [str],
@@ -65,29 +70,34 @@ def_attrs! {
     };
 
     pub ArgAttributes("argument") {
+        name str,
         object_server none,
         connection none,
         header none,
         signal_context none,
-        signal_emitter none
+        signal_emitter none,
+        message none,
+        object_path none
     };
 }
 
 #[derive(Debug)]
-struct Property<'a> {
+struct Property {
     read: bool,
     write: bool,
     emits_changed_signal: PropertyEmitsChangedSignal,
-    ty: Option<&'a Type>,
+    optional: bool,
+    ty: Option<Type>,
     doc_comments: TokenStream,
 }
 
-impl Property<'_> {
+impl Property {
     fn new() -> Self {
         Self {
             read: false,
             write: false,
             emits_changed_signal: PropertyEmitsChangedSignal::True,
+            optional: false,
             ty: None,
             doc_comments: quote!(),
         }
@@ -129,6 +139,8 @@ struct MethodInfo {
     intro_args: TokenStream,
     /// Whether the output type is a Result
     is_result_output: bool,
+    /// Whether no reply is sent for calls of the method
+    no_reply: bool,
     /// Code block to deserialize arguments from zbus message
     args_from_msg: TokenStream,
     /// Names of all arguments to the method
@@ -241,6 +253,24 @@ impl MethodInfo {
             None
         };
 
+        let no_reply = attrs.no_reply;
+        if no_reply {
+            if method_type != MethodType::Other {
+                return Err(Error::new_spanned(
+                    ident,
+                    "`no_reply` can only be used on methods",
+                ));
+            }
+            if let ReturnType::Type(_, _) = output {
+                if !matches!(get_return_type(output)?, Type::Tuple(t) if t.elems.is_empty()) {
+                    return Err(Error::new_spanned(
+                        output,
+                        "`no_reply` methods can't return a value",
+                    ));
+                }
+            }
+        }
+
         let mut intro_args = quote!();
         intro_args.extend(introspect_input_args(&typed_inputs, is_signal, cfg_attrs));
         let is_result_output = introspect_add_output_args(
@@ -249,10 +279,26 @@ impl MethodInfo {
             attrs.out_args.as_deref(),
             cfg_attrs,
         )?;
+        if no_reply {
+            intro_args.extend(quote!(
+                ::std::writeln!(
+                    writer,
+                    "{:indent$}<annotation name=\"org.freedesktop.DBus.Method.NoReply\" value=\"true\"/>",
+                    "",
+                    indent = level,
+                ).unwrap();
+            ));
+        }
 
         let (args_from_msg, args_names) = get_args_from_inputs(&typed_inputs, method_type, zbus)?;
 
-        let reply = if is_result_output {
+        let reply = if no_reply {
+            // The outcome of the call, even if it's an error, is not sent back.
+            quote!({
+                let _ = (reply, hdr);
+                ::std::result::Result::Ok(())
+            })
+        } else if is_result_output {
             let ret = quote!(r);
 
             quote!(match reply {
@@ -264,7 +310,7 @@ impl MethodInfo {
         };
 
         let member_name = attrs.name.clone().unwrap_or_else(|| {
-            let mut name = ident.to_string();
+            let mut name = ident.unraw().to_string();
             if is_property && has_inputs {
                 assert!(name.starts_with("set_"));
                 name = name[4..].to_string();
@@ -284,6 +330,7 @@ impl MethodInfo {
             signal_emitter_arg,
             intro_args,
             is_result_output,
+            no_reply,
             args_from_msg,
             args_names,
             reply,
@@ -346,6 +393,7 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
         .proxy
         .map(|p| Proxy::new(ty, &iface_name, p, &zbus));
     let introspect_docs = impl_attrs.introspection_docs.unwrap_or(true);
+    let derived_properties = impl_attrs.derived_properties;
 
     // Store parsed information about each method
     let mut methods = vec![];
@@ -377,6 +425,23 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
 
         method.attrs.retain(|attr| !attr.path().is_ident("zbus"));
 
+        if method_attrs.skip {
+            if method_attrs.signal || method_attrs.property.is_some() {
+                return Err(syn::Error::new_spanned(
+                    item,
+                    "`skip` can't be combined with `signal` or `property`",
+                ));
+            }
+            if is_signal {
+                return Err(syn::Error::new_spanned(
+                    item,
+                    "skipped methods must have a body",
+                ));
+            }
+
+            continue;
+        }
+
         if is_signal && !method_attrs.signal {
             return Err(syn::Error::new_spanned(
                 item,
@@ -405,20 +470,29 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
         )?;
         let attr_property = method_attrs.property;
         if let Some(prop_attrs) = &attr_property {
+            // Setters without a getter are write-only properties.
+            let property = properties
+                .entry(method_info.member_name.to_string())
+                .or_insert_with(Property::new);
             if method_info.method_type == MethodType::Property(PropertyType::Getter) {
                 let emits_changed_signal = if let Some(s) = &prop_attrs.emits_changed_signal {
                     PropertyEmitsChangedSignal::parse(s, method.span())?
                 } else {
                     PropertyEmitsChangedSignal::True
                 };
-                let mut property = Property::new();
                 property.emits_changed_signal = emits_changed_signal;
-                properties.insert(method_info.member_name.to_string(), property);
+                property.optional = prop_attrs.optional;
+                property.read = true;
             } else if prop_attrs.emits_changed_signal.is_some() {
                 return Err(syn::Error::new(
                     method.span(),
                     "`emits_changed_signal` cannot be specified on setters",
                 ));
+            } else if prop_attrs.optional {
+                return Err(syn::Error::new(
+                    method.span(),
+                    "`optional` cannot be specified on setters",
+                ));
             }
         };
         methods.push((method, method_info));
@@ -451,6 +525,7 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
         } = method_info;
 
         let mut method_clone = method.clone();
+        clear_input_arg_attrs(&mut method_clone.sig.inputs);
         let Signature {
             ident,
             inputs,
@@ -507,10 +582,9 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
                 });
             }
             MethodType::Property(_) => {
-                let p = properties.get_mut(&member_name).ok_or(Error::new_spanned(
-                    &member_name,
-                    "Write-only properties aren't supported yet",
-                ))?;
+                let p = properties
+                    .get_mut(&member_name)
+                    .ok_or(Error::new_spanned(&member_name, "Unknown property"))?;
 
                 let sk_member_name = case::snake_or_kebab_case(&member_name, true);
                 let prop_changed_method_name = format_ident!("{sk_member_name}_changed");
@@ -562,6 +636,8 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
                             && !a.header
                             && !a.signal_context
                             && !a.signal_emitter
+                            && !a.message
+                            && !a.object_path
                     });
 
                     let value_arg = match &*value_param
@@ -593,8 +669,19 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
                     _ => value_to_owned,
                 };
 
-                    let value_param_name = &value_param.unwrap().pat;
+                    let value_param = value_param.unwrap();
+                    let value_param_name = &value_param.pat;
+                    if p.ty.is_none() {
+                        // Write-only property, so we don't have a getter to take the type from.
+                        let ty = match &*value_param.ty {
+                            Type::Reference(r) => &*r.elem,
+                            ty => ty,
+                        };
+                        p.ty = Some(ty.clone());
+                    }
                     let prop_changed_method = match p.emits_changed_signal {
+                        // Write-only properties have no value to notify about.
+                        _ if !p.read => quote!({ Ok(()) }),
                         PropertyEmitsChangedSignal::True => {
                             quote!({
                                 self
@@ -664,7 +751,17 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
                 } else {
                     let is_fallible_property = is_result_output;
 
-                    p.ty = Some(get_return_type(output)?);
+                    // An optional property is absent, i.e unknown, when `None`.
+                    let ty = get_return_type(output)?;
+                    let is_optional_property = p.optional;
+                    if is_optional_property {
+                        let inner_ty = get_option_inner_type(ty)?.ok_or_else(|| {
+                            Error::new_spanned(ty, "optional properties must return an `Option`")
+                        })?;
+                        p.ty = Some(inner_ty.clone());
+                    } else {
+                        p.ty = Some(ty.clone());
+                    }
                     p.read = true;
                     let value_convert = quote!(
                         <#zbus::zvariant::OwnedValue as ::std::convert::TryFrom<_>>::try_from(
@@ -674,50 +771,80 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
                         )
                         .map_err(|e| #zbus::fdo::Error::Failed(e.to_string()))
                     );
-                    let inner = if is_fallible_property {
-                        quote!(self.#ident(#args_names) #method_await .and_then(|value| #value_convert))
-                    } else {
-                        quote!({
+                    let get_value = match (is_fallible_property, is_optional_property) {
+                        (true, true) => quote!(
+                            match self.#ident(#args_names)#method_await {
+                                ::std::result::Result::Ok(::std::option::Option::Some(value)) => {
+                                    ::std::option::Option::Some(#value_convert)
+                                }
+                                ::std::result::Result::Ok(::std::option::Option::None) => {
+                                    ::std::option::Option::None
+                                }
+                                ::std::result::Result::Err(e) => {
+                                    ::std::option::Option::Some(::std::result::Result::Err(e))
+                                }
+                            }
+                        ),
+                        (true, false) => quote!(
+                            ::std::option::Option::Some(
+                                self.#ident(#args_names) #method_await .and_then(|value| #value_convert)
+                            )
+                        ),
+                        (false, true) => quote!(
+                            self.#ident(#args_names)#method_await.map(|value| #value_convert)
+                        ),
+                        (false, false) => quote!({
                             let value = self.#ident(#args_names)#method_await;
-                            #value_convert
-                        })
+                            ::std::option::Option::Some(#value_convert)
+                        }),
                     };
 
                     let q = quote!(
                         #(#cfg_attrs)*
                         #member_name => {
                             #args_from_msg
-                            ::std::option::Option::Some(#inner)
+                            #get_value
                         },
                     );
                     get_dispatch.extend(q);
 
-                    let q = if is_fallible_property {
-                        quote!(
+                    let insert_prop = quote!(
+                        props.insert(
+                            ::std::string::ToString::to_string(#member_name),
+                            <#zbus::zvariant::OwnedValue as ::std::convert::TryFrom<_>>::try_from(
+                                <#zbus::zvariant::Value as ::std::convert::From<_>>::from(
+                                    prop,
+                                ),
+                            )
+                            .map_err(|e| #zbus::fdo::Error::Failed(e.to_string()))?,
+                        );
+                    );
+                    let q = match (is_fallible_property, is_optional_property) {
+                        (true, true) => quote!(
                             #args_from_msg
-                            if let Ok(prop) = self.#ident(#args_names)#method_await {
-                            props.insert(
-                                ::std::string::ToString::to_string(#member_name),
-                                <#zbus::zvariant::OwnedValue as ::std::convert::TryFrom<_>>::try_from(
-                                    <#zbus::zvariant::Value as ::std::convert::From<_>>::from(
-                                        prop,
-                                    ),
-                                )
-                                .map_err(|e| #zbus::fdo::Error::Failed(e.to_string()))?,
-                            );
-                        })
-                    } else {
-                        quote!(
+                            if let ::std::result::Result::Ok(::std::option::Option::Some(prop)) =
+                                self.#ident(#args_names)#method_await
+                            {
+                                #insert_prop
+                            }
+                        ),
+                        (true, false) => quote!(
                             #args_from_msg
-                            props.insert(
-                        ::std::string::ToString::to_string(#member_name),
-                        <#zbus::zvariant::OwnedValue as ::std::convert::TryFrom<_>>::try_from(
-                            <#zbus::zvariant::Value as ::std::convert::From<_>>::from(
-                                self.#ident(#args_names)#method_await,
-                            ),
-                        )
-                        .map_err(|e| #zbus::fdo::Error::Failed(e.to_string()))?,
-                    );)
+                            if let ::std::result::Result::Ok(prop) = self.#ident(#args_names)#method_await {
+                                #insert_prop
+                            }
+                        ),
+                        (false, true) => quote!(
+                            #args_from_msg
+                            if let ::std::option::Option::Some(prop) = self.#ident(#args_names)#method_await {
+                                #insert_prop
+                            }
+                        ),
+                        (false, false) => quote!(
+                            #args_from_msg
+                            let prop = self.#ident(#args_names)#method_await;
+                            #insert_prop
+                        ),
                     };
 
                     get_all.extend(q);
@@ -729,6 +856,33 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
                     };
 
                     if p.emits_changed_signal == PropertyEmitsChangedSignal::True {
+                        // An absent property is announced as invalidated.
+                        let (changed, invalidated) = if is_optional_property {
+                            (
+                                quote!(
+                                    let value = #prop_value_handled;
+                                    let invalidated: &[&str] = match value {
+                                        ::std::option::Option::Some(_) => &[],
+                                        ::std::option::Option::None => &[#member_name],
+                                    };
+                                    if let ::std::option::Option::Some(value) = value {
+                                        changed.insert(
+                                            #member_name,
+                                            <#zbus::zvariant::Value as ::std::convert::From<_>>::from(value),
+                                        );
+                                    }
+                                ),
+                                quote!(::std::borrow::Cow::Borrowed(invalidated)),
+                            )
+                        } else {
+                            (
+                                quote!(
+                                    let value = <#zbus::zvariant::Value as ::std::convert::From<_>>::from(#prop_value_handled);
+                                    changed.insert(#member_name, value);
+                                ),
+                                quote!(::std::borrow::Cow::Borrowed(&[])),
+                            )
+                        };
                         let prop_changed_method = quote!(
                             pub async fn #prop_changed_method_name(
                                 &self,
@@ -739,13 +893,12 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
                                 let object_server = connection.object_server();
                                 #args_from_msg
                                 let mut changed = ::std::collections::HashMap::new();
-                                let value = <#zbus::zvariant::Value as ::std::convert::From<_>>::from(#prop_value_handled);
-                                changed.insert(#member_name, value);
+                                #changed
                                 #zbus::fdo::Properties::properties_changed(
                                     signal_emitter,
                                     #zbus::names::InterfaceName::from_static_str_unchecked(#iface_name),
                                     changed,
-                                    ::std::borrow::Cow::Borrowed(&[]),
+                                    #invalidated,
                                 ).await
                             }
                         );
@@ -852,7 +1005,29 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
         }
     };
 
+    // The properties of the fields, if any, complement the ones of the methods.
+    let (get_fallback, get_all_fallback, set_fallback, set_mut_fallback, introspect_fallback) =
+        if derived_properties {
+            let properties = quote!(#zbus::object_server::DBusProperties);
+            (
+                quote!(#properties::get_property(self, property_name)),
+                quote!(#properties::get_all_properties(self, &mut props)?;),
+                quote!(#zbus::object_server::DispatchResult::RequiresMut),
+                quote!(#properties::set_property(self, property_name, value, signal_emitter).await),
+                quote!(<Self as #properties>::introspect_properties(writer, level);),
+            )
+        } else {
+            (
+                quote!(::std::option::Option::None),
+                quote!(),
+                quote!(#zbus::object_server::DispatchResult::NotFound),
+                quote!(::std::option::Option::None),
+                quote!(),
+            )
+        };
+
     let proxy = proxy.map(|proxy| proxy.gen()).transpose()?;
+    let introspection_xml_doc = format!("The introspection XML of the `{iface_name}` interface.");
     let introspect_format_str = format!("{}<interface name=\"{iface_name}\">", "{:indent$}");
 
     Ok(quote! {
@@ -860,6 +1035,38 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
 
         #generated_signals_impl
 
+        impl #generics #self_ty
+        #where_clause
+        {
+            #[doc = #introspection_xml_doc]
+            ///
+            /// Unlike [`Interface::introspect_to_writer`], no instance of the type is needed, e.g. to
+            /// check the API of the interface in tests, or to publish its description.
+            ///
+            /// [`Interface::introspect_to_writer`]: #zbus::object_server::Interface::introspect_to_writer
+            pub fn introspection_xml() -> ::std::string::String {
+                let mut xml = ::std::string::String::new();
+                let writer: &mut dyn ::std::fmt::Write = &mut xml;
+                let level = 0;
+                ::std::writeln!(
+                    writer,
+                    #introspect_format_str,
+                    "",
+                    indent = level
+                ).unwrap();
+                {
+                    use #zbus::zvariant::Type;
+
+                    let level = level + 2;
+                    #introspect
+                    #introspect_fallback
+                }
+                ::std::writeln!(writer, r#"{:indent$}</interface>"#, "", indent = level).unwrap();
+
+                xml
+            }
+        }
+
         #signals_trait_and_impl
 
         #[#zbus::export::async_trait::async_trait]
@@ -884,7 +1091,7 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
             ) -> ::std::option::Option<#zbus::fdo::Result<#zbus::zvariant::OwnedValue>> {
                 match property_name {
                     #get_dispatch
-                    _ => ::std::option::Option::None,
+                    _ => #get_fallback,
                 }
             }
 
@@ -903,6 +1110,7 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
                     #zbus::zvariant::OwnedValue,
                 > = ::std::collections::HashMap::new();
                 #get_all
+                #get_all_fallback
                 Ok(props)
             }
 
@@ -917,7 +1125,7 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
             ) -> #zbus::object_server::DispatchResult<'call> {
                 match property_name {
                     #set_dispatch
-                    _ => #zbus::object_server::DispatchResult::NotFound,
+                    _ => #set_fallback,
                 }
             }
 
@@ -932,7 +1140,7 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
             ) -> ::std::option::Option<#zbus::fdo::Result<()>> {
                 match property_name {
                     #set_mut_dispatch
-                    _ => ::std::option::Option::None,
+                    _ => #set_mut_fallback,
                 }
             }
 
@@ -963,19 +1171,13 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, mut input: ItemImpl) -> syn::Re
             }
 
             fn introspect_to_writer(&self, writer: &mut dyn ::std::fmt::Write, level: usize) {
-                ::std::writeln!(
-                    writer,
-                    #introspect_format_str,
-                    "",
-                    indent = level
-                ).unwrap();
-                {
-                    use #zbus::zvariant::Type;
-
-                    let level = level + 2;
-                    #introspect
+                for line in ::std::primitive::str::lines(&Self::introspection_xml()) {
+                    if line.is_empty() {
+                        ::std::writeln!(writer).unwrap();
+                    } else {
+                        ::std::writeln!(writer, "{:indent$}{}", "", line, indent = level).unwrap();
+                    }
                 }
-                ::std::writeln!(writer, r#"{:indent$}</interface>"#, "", indent = level).unwrap();
             }
         }
 
@@ -995,6 +1197,8 @@ fn get_args_from_inputs(
         let mut conn_arg_decl = None;
         let mut header_arg_decl = None;
         let mut signal_emitter_arg_decl = None;
+        let mut message_arg_decl = None;
+        let mut object_path_arg_decl = None;
         let mut args_names = Vec::new();
         let mut tys = Vec::new();
 
@@ -1005,6 +1209,9 @@ fn get_args_from_inputs(
                 header,
                 signal_emitter,
                 signal_context,
+                message,
+                object_path,
+                ..
             } = ArgAttributes::parse(&input.attrs)?;
 
             if object_server {
@@ -1069,6 +1276,46 @@ fn get_args_from_inputs(
                         };
                     }),
                 };
+            } else if message {
+                if message_arg_decl.is_some() {
+                    return Err(Error::new_spanned(
+                        input,
+                        "There can only be one message argument",
+                    ));
+                }
+                if let MethodType::Property(_) = method_type {
+                    return Err(Error::new_spanned(
+                        input,
+                        "`message` argument is not supported on properties",
+                    ));
+                }
+
+                let message_arg = &input.pat;
+                message_arg_decl = Some(quote! { let #message_arg = message; });
+            } else if object_path {
+                if object_path_arg_decl.is_some() {
+                    return Err(Error::new_spanned(
+                        input,
+                        "There can only be one object_path argument",
+                    ));
+                }
+
+                let object_path_arg = &input.pat;
+
+                object_path_arg_decl = match method_type {
+                    MethodType::Property(_) => Some(quote! {
+                        let #object_path_arg = signal_emitter.path();
+                    }),
+                    _ => Some(quote! {
+                        let #object_path_arg = match hdr.path() {
+                            ::std::option::Option::Some(p) => p,
+                            ::std::option::Option::None => {
+                                let err = #zbus::fdo::Error::UnknownObject("Path Required".into());
+                                return connection.reply_dbus_error(&hdr, err).await;
+                            }
+                        };
+                    }),
+                };
             } else {
                 args_names.push(pat_ident(input).unwrap());
                 tys.push(&input.ty);
@@ -1111,6 +1358,10 @@ fn get_args_from_inputs(
 
             #signal_emitter_arg_decl
 
+            #message_arg_decl
+
+            #object_path_arg_decl
+
             #args_decl
         };
 
@@ -1166,8 +1417,10 @@ fn introspect_input_args<'i>(
                 return None;
             }
 
-            let ident = pat_ident(pat_type).unwrap();
-            let arg_name = quote!(#ident).to_string();
+            let arg_name = ArgAttributes::parse(attrs)
+                .ok()
+                .and_then(|attrs| attrs.name)
+                .unwrap_or_else(|| pat_ident(pat_type).unwrap().unraw().to_string());
             let dir = if is_signal { "" } else { " direction=\"in\"" };
             let format_str = format!(
                 "{}<arg name=\"{arg_name}\" type=\"{}\"{dir}/>",
@@ -1209,7 +1462,9 @@ fn is_special_arg(attrs: &[Attribute]) -> bool {
                     path.is_ident("connection") ||
                     path.is_ident("header") ||
                     path.is_ident("signal_context") ||
-                    path.is_ident("signal_emitter")
+                    path.is_ident("signal_emitter") ||
+                    path.is_ident("message") ||
+                    path.is_ident("object_path")
             )
         });
 
@@ -1280,8 +1535,10 @@ fn introspect_add_output_args(
         if let Type::Tuple(t) = ty {
             if let Some(arg_names) = arg_names {
                 if t.elems.len() != arg_names.len() {
-                    // Turn into error
-                    panic!("Number of out arg names different from out args specified")
+                    return Err(Error::new_spanned(
+                        output,
+                        "number of `out_args` names different from the number of out arguments",
+                    ));
                 }
             }
             for i in 0..t.elems.len() {
@@ -1289,13 +1546,38 @@ fn introspect_add_output_args(
                 args.extend(introspect_output_arg(&t.elems[i], name, cfg_attrs));
             }
         } else {
-            args.extend(introspect_output_arg(ty, None, cfg_attrs));
+            let name = match arg_names {
+                Some([name]) => Some(name),
+                Some(_) => {
+                    return Err(Error::new_spanned(
+                        output,
+                        "a single out argument requires exactly one `out_args` name",
+                    ))
+                }
+                None => None,
+            };
+            args.extend(introspect_output_arg(ty, name, cfg_attrs));
         }
     }
 
     Ok(is_result_output)
 }
 
+/// The `T` of an `Option<T>` type, if it's one.
+fn get_option_inner_type(ty: &Type) -> syn::Result<Option<&Type>> {
+    match ty {
+        Type::Path(p)
+            if p.path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Option") =>
+        {
+            get_result_inner_type(p).map(Some)
+        }
+        _ => Ok(None),
+    }
+}
+
 fn get_return_type(output: &ReturnType) -> syn::Result<&Type> {
     if let ReturnType::Type(_, ty) = output {
         let ty = ty.as_ref();
@@ -1321,7 +1603,7 @@ fn get_return_type(output: &ReturnType) -> syn::Result<&Type> {
 
 fn introspect_properties(
     introspection: &mut TokenStream,
-    properties: BTreeMap<String, Property<'_>>,
+    properties: BTreeMap<String, Property>,
 ) -> syn::Result<()> {
     for (name, prop) in properties {
         let access = if prop.read && prop.write {
@@ -1336,43 +1618,59 @@ fn introspect_properties(
                 "property is neither readable nor writable",
             ));
         };
-        let ty = prop.ty.ok_or_else(|| {
-            Error::new_spanned(&name, "Write-only properties aren't supported yet")
-        })?;
-
-        let doc_comments = prop.doc_comments;
-        if prop.emits_changed_signal == PropertyEmitsChangedSignal::True {
-            let format_str = format!(
-                "{}<property name=\"{name}\" type=\"{}\" access=\"{access}\"/>",
-                "{:indent$}", "{}",
-            );
-            introspection.extend(quote!(
-                #doc_comments
-                ::std::writeln!(writer, #format_str, "", <#ty>::SIGNATURE, indent = level).unwrap();
-            ));
-        } else {
-            let emits_changed_signal = prop.emits_changed_signal.to_string();
-            let annot_name = "org.freedesktop.DBus.Property.EmitsChangedSignal";
-            let format_str = format!(
-                "{}<property name=\"{name}\" type=\"{}\" access=\"{access}\">\n\
-                    {}<annotation name=\"{annot_name}\" value=\"{emits_changed_signal}\"/>\n\
-                {}</property>",
-                "{:indent$}", "{}", "{:annot_indent$}", "{:indent$}",
-            );
-            introspection.extend(quote!(
-                #doc_comments
-                ::std::writeln!(
-                    writer,
-                    #format_str,
-                    "", <#ty>::SIGNATURE, "", "", indent = level, annot_indent = level + 2,
-                ).unwrap();
-            ));
-        }
+        let ty = prop
+            .ty
+            .ok_or_else(|| Error::new_spanned(&name, "Unknown property type"))?;
+
+        introspection.extend(introspect_property(
+            &name,
+            access,
+            &ty,
+            prop.emits_changed_signal,
+            prop.doc_comments,
+        ));
     }
 
     Ok(())
 }
 
+/// The code writing the introspection XML of a property.
+pub fn introspect_property(
+    name: &str,
+    access: &str,
+    ty: &Type,
+    emits_changed_signal: PropertyEmitsChangedSignal,
+    doc_comments: TokenStream,
+) -> TokenStream {
+    if emits_changed_signal == PropertyEmitsChangedSignal::True {
+        let format_str = format!(
+            "{}<property name=\"{name}\" type=\"{}\" access=\"{access}\"/>",
+            "{:indent$}", "{}",
+        );
+        quote!(
+            #doc_comments
+            ::std::writeln!(writer, #format_str, "", <#ty>::SIGNATURE, indent = level).unwrap();
+        )
+    } else {
+        let emits_changed_signal = emits_changed_signal.to_string();
+        let annot_name = "org.freedesktop.DBus.Property.EmitsChangedSignal";
+        let format_str = format!(
+            "{}<property name=\"{name}\" type=\"{}\" access=\"{access}\">\n\
+                {}<annotation name=\"{annot_name}\" value=\"{emits_changed_signal}\"/>\n\
+            {}</property>",
+            "{:indent$}", "{}", "{:annot_indent$}", "{:indent$}",
+        );
+        quote!(
+            #doc_comments
+            ::std::writeln!(
+                writer,
+                #format_str,
+                "", <#ty>::SIGNATURE, "", "", indent = level, annot_indent = level + 2,
+            ).unwrap();
+        )
+    }
+}
+
 pub fn to_xml_docs(lines: Vec<String>) -> TokenStream {
     let mut docs = quote!();
 
@@ -1453,7 +1751,7 @@ impl Proxy {
     fn add_method(
         &mut self,
         method_info: MethodInfo,
-        properties: &BTreeMap<String, Property<'_>>,
+        properties: &BTreeMap<String, Property>,
     ) -> syn::Result<()> {
         let inputs: Punctuated<PatType, Comma> = method_info
             .typed_inputs
@@ -1465,11 +1763,28 @@ impl Proxy {
                     && !a.header
                     && !a.signal_context
                     && !a.signal_emitter
+                    && !a.message
+                    && !a.object_path
+            })
+            .map(|input| {
+                let mut input = input.clone();
+                input.attrs.retain(|attr| !attr.path().is_ident("zbus"));
+                input
             })
-            .cloned()
             .collect();
         let zbus = &self.zbus;
         let ret = match &method_info.output {
+            // Absent properties are errors on the proxy side, so getters return the property type.
+            ReturnType::Type(_, _)
+                if method_info.method_type == MethodType::Property(PropertyType::Getter) =>
+            {
+                let ty = properties
+                    .get(&method_info.member_name)
+                    .unwrap()
+                    .ty
+                    .as_ref();
+                quote! { #zbus::Result<#ty> }
+            }
             ReturnType::Type(_, ty) => {
                 let ty = ty.as_ref();
 
@@ -1517,6 +1832,7 @@ impl Proxy {
             }
             MethodType::Other => quote!(),
         });
+        let mut no_reply = method_info.no_reply;
         if let Some(attrs) = method_info.proxy_attrs {
             if let Some(object) = attrs.object {
                 proxy_method_attrs.extend(quote! { object = #object, });
@@ -1527,9 +1843,7 @@ impl Proxy {
             if let Some(blocking_object) = attrs.blocking_object {
                 proxy_method_attrs.extend(quote! { blocking_object = #blocking_object, });
             }
-            if attrs.no_reply {
-                proxy_method_attrs.extend(quote! { no_reply, });
-            }
+            no_reply |= attrs.no_reply;
             if attrs.no_autostart {
                 proxy_method_attrs.extend(quote! { no_autostart, });
             }
@@ -1537,6 +1851,9 @@ impl Proxy {
                 proxy_method_attrs.extend(quote! { allow_interactive_auth, });
             }
         }
+        if no_reply {
+            proxy_method_attrs.extend(quote! { no_reply, });
+        }
         let cfg_attrs = method_info.cfg_attrs;
         let doc_attrs = method_info.doc_attrs;
         self.methods.extend(quote! {
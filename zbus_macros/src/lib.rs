@@ -18,6 +18,7 @@ use syn::{
 
 mod error;
 mod iface;
+mod properties;
 mod proxy;
 mod utils;
 
@@ -28,7 +29,10 @@ mod utils;
 /// Proxy implementation, named `TraitNameProxy` and a blocking one, named `TraitNameProxyBlocking`.
 /// The proxy instances can be created with the associated `new()` or `builder()` methods. The
 /// former doesn't take any argument and uses the default service name and path. The later allows
-/// you to specify non-default proxy arguments.
+/// you to specify non-default proxy arguments. A builder specific to the proxy is also generated,
+/// named `TraitNameProxyBuilder` (`TraitNameProxyBlockingBuilder` for the blocking proxy) and
+/// created with its `new()` method, taking the connection. Its setters only take typed names (e.g.
+/// [`zbus::names::BusName`]), so they can't fail. `into_inner()` gives the generic builder back.
 ///
 /// The following attributes are supported:
 ///
@@ -53,11 +57,18 @@ mod utils;
 ///   attribute nor one of the default values are specified. Please make sure to explicitly set
 ///   either this attribute or the default values, according to your needs.
 ///
+/// * `assert_xml` - the path of an introspection XML file, relative to the crate's manifest
+///   directory, describing the interface. The trait methods are checked against it at compile
+///   time: each must match a member of the same name and kind, and methods and signals must have
+///   the same number of (input) arguments. The argument types are not checked. Requires the `xml`
+///   feature of zbus.
+///
 /// Each trait method will be expanded to call to the associated D-Bus remote interface.
 ///
 /// Trait methods accept `proxy` attributes:
 ///
-/// * `name` - override the D-Bus name (pascal case form by default)
+/// * `name` - override the D-Bus name (pascal case form by default). The `r#` prefix of raw
+///   identifiers is dropped from the default name, so `r#type` maps to `Type`.
 ///
 /// * `property` - expose the method as a property. If the method takes an argument, it must be a
 ///   setter, with a `set_` prefix. Otherwise, it's a getter. Additional sub-attributes exists to
@@ -75,6 +86,9 @@ mod utils;
 ///     * `"false"` - change signal is not (guaranteed to be) emitted if the property changes. This
 ///       disables property value caching, and does not generate a listener method for the change
 ///       signal.
+///   * `cached` - set to `false` to always fetch the property value from the service, even if it
+///     emits change signals (e.g. because the value is large and seldom needed). Just like for
+///     `emits_changed_signal = "false"`, no cached getter or listener method is generated then.
 ///
 /// * `signal` - declare a signal just like a D-Bus method. Read the [Signals](#signals) section
 ///   below for details.
@@ -88,7 +102,9 @@ mod utils;
 ///   prompt for authorization or confirmation from the receiver.
 ///
 /// * `object` - methods that returns an [`ObjectPath`] can be annotated with the `object` attribute
-///   to specify the proxy object to be constructed from the returned [`ObjectPath`].
+///   to specify the proxy object to be constructed from the returned [`ObjectPath`]. This also
+///   applies to property getters, in which case the `cached_` and `receive_*_changed` methods still
+///   provide the [`ObjectPath`] itself.
 ///
 /// * `async_object` - if the assumptions made by `object` attribute about naming of the
 ///   asynchronous proxy type, don't fit your bill, you can use this to specify its exact name.
@@ -112,7 +128,7 @@ mod utils;
 /// ```no_run
 /// # use std::error::Error;
 /// use zbus_macros::proxy;
-/// use zbus::{blocking::Connection, Result, fdo, zvariant::Value};
+/// use zbus::{blocking::Connection, Result, fdo, zvariant::{OwnedObjectPath, Value}};
 /// use futures_util::stream::StreamExt;
 /// use async_io::block_on;
 ///
@@ -141,6 +157,10 @@ mod utils;
 ///     // `SomeOtherIfaceProxyBlock` would have been assumed and expected. We could also specify
 ///     // the specific name of the asynchronous proxy types, using the `async_object` attribute.
 ///     fn some_method(&self, arg1: &str);
+///
+///     #[zbus(property, object = "SomeOtherIface", blocking_object = "SomeOtherInterfaceBlock")]
+///     // The getter will return a `SomeOtherIfaceProxy` or `SomeOtherInterfaceBlock` as well.
+///     fn other(&self) -> fdo::Result<OwnedObjectPath>;
 /// }
 ///
 /// #[proxy(
@@ -159,6 +179,13 @@ mod utils;
 /// let _ = proxy.do_this("foo", 32, &Value::new(true));
 /// let _ = proxy.set_a_property("val");
 ///
+/// // Or the typed builder, if the names are known to be valid already.
+/// let destination = zbus::names::WellKnownName::from_static_str("org.another.Service")?;
+/// let proxy = SomeIfaceProxyBlockingBuilder::new(&connection)
+///                .destination(destination)
+///                .cache_properties(zbus::proxy::CacheProperties::No)
+///                .build()?;
+///
 /// let signal = proxy.receive_some_signal()?.next().unwrap();
 /// let args = signal.args()?;
 /// println!("arg1: {}, arg2: {}", args.arg1(), args.arg2());
@@ -187,6 +214,7 @@ mod utils;
 ///
 /// [`zbus_polkit`]: https://docs.rs/zbus_polkit/1.0.0/zbus_polkit/policykit1/index.html
 /// [`zbus::Proxy`]: https://docs.rs/zbus/latest/zbus/proxy/struct.Proxy.html
+/// [`zbus::names::BusName`]: https://docs.rs/zbus/latest/zbus/names/enum.BusName.html
 /// [`zbus::message::Message`]: https://docs.rs/zbus/latest/zbus/message/struct.Message.html
 /// [`zbus::blocking::Proxy`]: https://docs.rs/zbus/latest/zbus/blocking/proxy/struct.Proxy.html
 /// [`zbus::SignalStream`]: https://docs.rs/zbus/latest/zbus/proxy/struct.SignalStream.html
@@ -208,6 +236,30 @@ pub fn proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// properties or signal depending on the item attributes. It will implement the [`Interface`] trait
 /// `for T` on your behalf, to handle the message dispatching and introspection support.
 ///
+/// `T` may be generic, e.g. over the backend the service is implemented with. The generic
+/// parameters and bounds of the `impl` block are carried over to the generated implementations, so
+/// they must ensure `T` is `Send + Sync + 'static`:
+///
+/// ```
+/// use zbus::interface;
+///
+/// trait Backend: Send + Sync + 'static {
+///     fn level(&self) -> u8;
+/// }
+///
+/// struct Service<B> {
+///     backend: B,
+/// }
+///
+/// #[interface(name = "org.myservice.Level")]
+/// impl<B: Backend> Service<B> {
+///     #[zbus(property)]
+///     fn level(&self) -> u8 {
+///         self.backend.level()
+///     }
+/// }
+/// ```
+///
 /// The trait accepts the `interface` attributes:
 ///
 /// * `name` - the D-Bus interface name
@@ -235,14 +287,25 @@ pub fn proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///   (Default: `true`). If your interface is well-known or well-documented, you may want to set
 ///   this to `false` to reduce the the size of your binary and D-Bus traffic.
 ///
+/// * `derived_properties` - also expose the fields of the type marked as properties, through
+///   [`macro@DBusProperties`], alongside the property methods. Setting such a property through
+///   D-Bus emits the "PropertiesChanged" signal, like the property setter methods do.
+///
 /// The methods accepts the `interface` attributes:
 ///
-/// * `name` - override the D-Bus name (pascal case form of the method by default)
+/// * `name` - override the D-Bus name (pascal case form of the method by default). Useful when the
+///   D-Bus name doesn't follow Rust naming conventions. The `r#` prefix of raw identifiers, for
+///   names clashing with Rust keywords, is dropped from the default name and from argument names.
 ///
 /// * `property` - expose the method as a property. If the method takes an argument, it must be a
-///   setter, with a `set_` prefix. Otherwise, it's a getter. If it may fail, a property method must
-///   return `zbus::fdo::Result`. An additional sub-attribute exists to control the emission of
-///   signals on changes to the property:
+///   setter, with a `set_` prefix. Otherwise, it's a getter. A setter without a getter exposes a
+///   write-only property. If it may fail, a property method must return `zbus::fdo::Result`.
+///   Additional sub-attributes exist to control the presence of the property and the emission of
+///   signals on changes to it:
+///   * `optional` - the getter returns an `Option<T>` and exposes a property of type `T` that is
+///     only present when the getter returns `Some`. On `None`, getting the property fails with an
+///     `org.freedesktop.DBus.Error.UnknownProperty` error, it's left out of the `GetAll` reply and
+///     its "PropertiesChanged" signal invalidates it. The generated proxy getter returns `T`.
 ///   * `emits_changed_signal` - specifies how property changes are signaled. Valid values are those
 ///     documented in [DBus specifications][dbus_emits_changed_signal]:
 ///     * `"true"` - (default) the change signal is always emitted when the property's setter is
@@ -263,8 +326,16 @@ pub fn proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///   You can call a signal method from a an interface method, or from an [`ObjectServer::with`]
 ///   function.
 ///
+/// * `no_reply` - don't send any reply to the calls of the method, not even an error. The method
+///   is annotated accordingly in the introspection data, and must not return a value. This is
+///   also forwarded to the [`macro@proxy`] macro.
+///
+/// * `skip` - don't export the method on the bus. This allows keeping helper methods, including
+///   associated functions such as constructors, in the same `impl` block.
+///
 /// * `out_args` - When returning multiple values from a method, naming the out arguments become
-///   important. You can use `out_args` to specify their names.
+///   important. You can use `out_args` to specify their names. There must be exactly one name per
+///   returned value, including for methods returning a single (non-tuple) value.
 ///
 /// * `proxy` - Use this to specify the [`macro@proxy`]-specific method sub-attributes (e.g
 ///   `object`). The common sub-attributes (e.g `name`) are automatically forworded to the
@@ -274,12 +345,16 @@ pub fn proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///   In such case, your method must return a tuple containing
 ///   your out arguments, in the same order as passed to `out_args`.
 ///
+/// The return value of a method only needs to implement `Serialize` and `Type`, so it can borrow
+/// from `self` (e.g `&str` or `&[u8]`) and is serialized directly into the reply, without any
+/// copy.
+///
 /// The `struct_return` attribute (from zbus 1.x) is no longer supported. If you want to return a
 /// single structure from a method, declare it to return a tuple containing either a named structure
 /// or a nested tuple.
 ///
-/// Note: a `<property_name_in_snake_case>_changed` method is generated for each property: this
-/// method emits the "PropertiesChanged" signal for the associated property. The setter (if it
+/// Note: a `<property_name_in_snake_case>_changed` method is generated for each readable property:
+/// this method emits the "PropertiesChanged" signal for the associated property. The setter (if it
 /// exists) will automatically call this method. For instance, a property setter named `set_foo`
 /// will be called to set the property "Foo", and will emit the "PropertiesChanged" signal with the
 /// new value for "Foo". Other changes to the "Foo" property can be signaled manually with the
@@ -301,6 +376,13 @@ pub fn proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///   external property access.
 /// * `signal_emitter` - This marks the method argument to receive a [`SignalEmitter`] instance,
 ///   which is needed for emitting signals the easy way.
+/// * `message` - This marks the method argument to receive a reference to the [`Message`] of the
+///   method call being handled. It is not available for property methods.
+/// * `object_path` - This marks the method argument to receive a reference to the [`ObjectPath`]
+///   the method is called on, which is useful when the same interface type is served at many
+///   paths.
+/// * `name` - override the name of the argument in the introspection XML, for method and signal
+///   arguments (the Rust binding name by default), e.g `#[zbus(name = "newOwner")]`.
 ///
 /// # Example
 ///
@@ -370,6 +452,8 @@ pub fn proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// [`Connection`]: https://docs.rs/zbus/latest/zbus/connection/struct.Connection.html
 /// [`Connection::emit_signal()`]: https://docs.rs/zbus/latest/zbus/connection/struct.Connection.html#method.emit_signal
 /// [`SignalEmitter`]: https://docs.rs/zbus/latest/zbus/object_server/struct.SignalEmitter.html
+/// [`Message`]: https://docs.rs/zbus/latest/zbus/message/struct.Message.html
+/// [`ObjectPath`]: https://docs.rs/zvariant/latest/zvariant/struct.ObjectPath.html
 /// [`Interface`]: https://docs.rs/zbus/latest/zbus/object_server/trait.Interface.html
 /// [dbus_emits_changed_signal]: https://dbus.freedesktop.org/doc/dbus-specification.html#introspection-format
 #[proc_macro_attribute]
@@ -420,3 +504,68 @@ pub fn derive_dbus_error(input: TokenStream) -> TokenStream {
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
+
+/// Derive macro for exposing the fields of a struct as D-Bus properties.
+///
+/// This implements the [`zbus::object_server::DBusProperties`] trait, so that the fields marked
+/// with the `property` attribute are exposed as properties of the interface implemented on the
+/// type with the [`macro@interface`] macro, given the `derived_properties` attribute. This removes
+/// the need to write a trivial getter and setter for each of them.
+///
+/// The field type must be clonable, convertible into a [`zvariant::Value`] and from a
+/// [`zvariant::OwnedValue`].
+///
+/// The fields accept the following `zbus` attributes:
+///
+/// * `name` - override the D-Bus name (pascal case form of the field by default).
+///
+/// * `property` - expose the field as a property. The following sub-attributes are supported:
+///   * `access` - `"read"`, `"write"` or `"readwrite"` (default).
+///   * `emits_changed_signal` - specifies how property changes are signaled, with the same values
+///     as for the property methods of [`macro@interface`].
+///
+/// Just like for the property methods, a `<field>_changed` method (or `<field>_invalidate` method
+/// if `emits_changed_signal = "invalidates"`) is generated for each readable property, to signal
+/// changes made to the field outside of the property setter.
+///
+/// # Example
+///
+/// ```
+/// use zbus::{interface, object_server::SignalEmitter, DBusProperties};
+///
+/// #[derive(DBusProperties)]
+/// struct Counter {
+///     /// The current count.
+///     #[zbus(property(access = "read"))]
+///     count: u32,
+///     #[zbus(property, name = "StepSize")]
+///     step: u32,
+///     // Not exposed over D-Bus.
+///     _ticks: u64,
+/// }
+///
+/// #[interface(name = "org.myservice.Counter", derived_properties)]
+/// impl Counter {
+///     async fn increment(
+///         &mut self,
+///         #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+///     ) -> zbus::fdo::Result<()> {
+///         self.count += self.step;
+///         // Generated from the `count` field.
+///         self.count_changed(&emitter).await?;
+///
+///         Ok(())
+///     }
+/// }
+/// ```
+///
+/// [`zbus::object_server::DBusProperties`]: https://docs.rs/zbus/latest/zbus/object_server/trait.DBusProperties.html
+/// [`zvariant::Value`]: https://docs.rs/zvariant/latest/zvariant/enum.Value.html
+/// [`zvariant::OwnedValue`]: https://docs.rs/zvariant/latest/zvariant/struct.OwnedValue.html
+#[proc_macro_derive(DBusProperties, attributes(zbus))]
+pub fn derive_dbus_properties(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    properties::expand_derive(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
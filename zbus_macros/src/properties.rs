@@ -0,0 +1,252 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    ext::IdentExt, spanned::Spanned, Data, DeriveInput, Error, Expr, ExprLit, Fields, Lit::Str,
+    MetaNameValue,
+};
+use zvariant_utils::def_attrs;
+
+use crate::{
+    iface::{introspect_property, to_xml_docs},
+    utils::*,
+};
+
+def_attrs! {
+    crate zbus;
+
+    pub FieldAttributes("field") {
+        name str,
+        property {
+            pub PropertyAttributes("property") {
+                access str,
+                emits_changed_signal str
+            }
+        }
+    };
+}
+
+pub fn expand_derive(input: DeriveInput) -> Result<TokenStream, Error> {
+    let zbus = zbus_path();
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    input.span(),
+                    "only structs with named fields supported",
+                ))
+            }
+        },
+        _ => return Err(Error::new(input.span(), "only structs supported")),
+    };
+
+    let mut get_dispatch = quote!();
+    let mut get_all = quote!();
+    let mut set_dispatch = quote!();
+    let mut introspect = quote!();
+    let mut changed_methods = quote!();
+
+    for field in fields {
+        let FieldAttributes {
+            name: member_name,
+            property,
+        } = FieldAttributes::parse(&field.attrs)?;
+        let Some(PropertyAttributes {
+            access,
+            emits_changed_signal,
+        }) = property
+        else {
+            continue;
+        };
+        let ident = field.ident.as_ref().expect("named field");
+        let ty = &field.ty;
+        let member_name = member_name.unwrap_or_else(|| pascal_case(&ident.unraw().to_string()));
+        let access = access.as_deref().unwrap_or("readwrite");
+        let (read, write) = match access {
+            "read" => (true, false),
+            "write" => (false, true),
+            "readwrite" => (true, true),
+            other => {
+                return Err(Error::new(
+                    field.span(),
+                    format!("invalid value \"{other}\" for attribute `property(access)`"),
+                ))
+            }
+        };
+        let emits_changed_signal = match &emits_changed_signal {
+            Some(s) => PropertyEmitsChangedSignal::parse(s, field.span())?,
+            None => PropertyEmitsChangedSignal::True,
+        };
+
+        let docs = get_doc_attrs(&field.attrs)
+            .iter()
+            .filter_map(|attr| match &attr.meta.require_name_value() {
+                Ok(MetaNameValue {
+                    value: Expr::Lit(ExprLit { lit: Str(s), .. }),
+                    ..
+                }) => Some(s.value()),
+                _ => None,
+            })
+            .collect();
+        introspect.extend(introspect_property(
+            &member_name,
+            access,
+            ty,
+            emits_changed_signal.clone(),
+            to_xml_docs(docs),
+        ));
+
+        let value = quote!(
+            <#zbus::zvariant::OwnedValue as ::std::convert::TryFrom<_>>::try_from(
+                <#zbus::zvariant::Value as ::std::convert::From<_>>::from(
+                    ::std::clone::Clone::clone(&self.#ident),
+                ),
+            )
+            .map_err(|e| #zbus::fdo::Error::Failed(e.to_string()))
+        );
+        if read {
+            get_dispatch.extend(quote!(
+                #member_name => ::std::option::Option::Some(#value),
+            ));
+            get_all.extend(quote!(
+                props.insert(::std::string::ToString::to_string(#member_name), #value?);
+            ));
+        }
+
+        let notify = match emits_changed_signal {
+            PropertyEmitsChangedSignal::True if read => {
+                let prop_changed_method_name = format_ident!("{ident}_changed");
+                changed_methods.extend(quote!(
+                    #[doc = concat!("Emit the `PropertiesChanged` signal for the `", #member_name, "` property.")]
+                    pub async fn #prop_changed_method_name(
+                        &self,
+                        signal_emitter: &#zbus::object_server::SignalEmitter<'_>,
+                    ) -> #zbus::Result<()> {
+                        let mut changed = ::std::collections::HashMap::new();
+                        let value = <#zbus::zvariant::Value as ::std::convert::From<_>>::from(
+                            ::std::clone::Clone::clone(&self.#ident),
+                        );
+                        changed.insert(#member_name, value);
+                        #zbus::fdo::Properties::properties_changed(
+                            signal_emitter,
+                            <Self as #zbus::object_server::Interface>::name(),
+                            changed,
+                            ::std::borrow::Cow::Borrowed(&[]),
+                        ).await
+                    }
+                ));
+
+                quote!(self.#prop_changed_method_name(signal_emitter).await)
+            }
+            PropertyEmitsChangedSignal::Invalidates if read => {
+                let prop_invalidate_method_name = format_ident!("{ident}_invalidate");
+                changed_methods.extend(quote!(
+                    #[doc = concat!("Emit the `PropertiesChanged` signal invalidating the `", #member_name, "` property.")]
+                    pub async fn #prop_invalidate_method_name(
+                        &self,
+                        signal_emitter: &#zbus::object_server::SignalEmitter<'_>,
+                    ) -> #zbus::Result<()> {
+                        #zbus::fdo::Properties::properties_changed(
+                            signal_emitter,
+                            <Self as #zbus::object_server::Interface>::name(),
+                            ::std::collections::HashMap::new(),
+                            ::std::borrow::Cow::Borrowed(&[#member_name]),
+                        ).await
+                    }
+                ));
+
+                quote!(self.#prop_invalidate_method_name(signal_emitter).await)
+            }
+            // Write-only properties have no value to notify about.
+            _ => quote!(::std::result::Result::<(), #zbus::Error>::Ok(())),
+        };
+
+        let set = if write {
+            quote!({
+                let value = match #zbus::zvariant::Value::try_to_owned(value) {
+                    ::std::result::Result::Ok(value) => value,
+                    ::std::result::Result::Err(e) => {
+                        return ::std::option::Option::Some(::std::result::Result::Err(
+                            ::std::convert::Into::into(#zbus::Error::Variant(::std::convert::Into::into(e))),
+                        ));
+                    }
+                };
+                match <#ty as ::std::convert::TryFrom<_>>::try_from(value) {
+                    ::std::result::Result::Ok(value) => self.#ident = value,
+                    ::std::result::Result::Err(e) => {
+                        return ::std::option::Option::Some(::std::result::Result::Err(
+                            ::std::convert::Into::into(#zbus::Error::Variant(::std::convert::Into::into(e))),
+                        ));
+                    }
+                }
+
+                ::std::option::Option::Some(
+                    #notify.map_err(::std::convert::Into::into),
+                )
+            })
+        } else {
+            quote!(::std::option::Option::Some(::std::result::Result::Err(
+                #zbus::fdo::Error::PropertyReadOnly(
+                    ::std::format!("Property `{}` is read-only", #member_name),
+                ),
+            )))
+        };
+        set_dispatch.extend(quote!(
+            #member_name => #set,
+        ));
+    }
+
+    Ok(quote! {
+        #[#zbus::export::async_trait::async_trait]
+        impl #impl_generics #zbus::object_server::DBusProperties for #name #ty_generics
+        #where_clause
+        {
+            fn get_property(
+                &self,
+                property_name: &str,
+            ) -> ::std::option::Option<#zbus::fdo::Result<#zbus::zvariant::OwnedValue>> {
+                match property_name {
+                    #get_dispatch
+                    _ => ::std::option::Option::None,
+                }
+            }
+
+            fn get_all_properties(
+                &self,
+                props: &mut ::std::collections::HashMap<
+                    ::std::string::String,
+                    #zbus::zvariant::OwnedValue,
+                >,
+            ) -> #zbus::fdo::Result<()> {
+                #get_all
+                ::std::result::Result::Ok(())
+            }
+
+            async fn set_property(
+                &mut self,
+                property_name: &str,
+                value: &#zbus::zvariant::Value<'_>,
+                signal_emitter: &#zbus::object_server::SignalEmitter<'_>,
+            ) -> ::std::option::Option<#zbus::fdo::Result<()>> {
+                match property_name {
+                    #set_dispatch
+                    _ => ::std::option::Option::None,
+                }
+            }
+
+            fn introspect_properties(writer: &mut dyn ::std::fmt::Write, level: usize) {
+                use #zbus::zvariant::Type;
+
+                #introspect
+            }
+        }
+
+        impl #impl_generics #name #ty_generics
+        #where_clause
+        {
+            #changed_methods
+        }
+    })
+}
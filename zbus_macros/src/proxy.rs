@@ -2,8 +2,8 @@ use crate::utils::{pat_ident, typed_arg, zbus_path, PropertyEmitsChangedSignal};
 use proc_macro2::{Literal, Span, TokenStream};
 use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn::{
-    fold::Fold, parse_quote, parse_str, punctuated::Punctuated, spanned::Spanned, Error, FnArg,
-    Ident, ItemTrait, Meta, Path, ReturnType, Token, TraitItemFn, Visibility,
+    ext::IdentExt, fold::Fold, parse_quote, parse_str, punctuated::Punctuated, spanned::Spanned,
+    Error, FnArg, Ident, ItemTrait, Meta, Path, ReturnType, Token, TraitItemFn, Visibility,
 };
 use zvariant_utils::{case, def_attrs};
 
@@ -20,7 +20,8 @@ def_attrs! {
         async_name str,
         blocking_name str,
         gen_async bool,
-        gen_blocking bool
+        gen_blocking bool,
+        assert_xml str
     };
 
     // Keep this in sync with interface's proxy method attributes.
@@ -28,7 +29,8 @@ def_attrs! {
         name str,
         property {
             pub PropertyAttributes("property") {
-                emits_changed_signal str
+                emits_changed_signal str,
+                cached bool
             }
         },
         signal none,
@@ -73,6 +75,15 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, input: ItemTrait) -> Result<Tok
             "both `interface` and `name` attributes shouldn't be specified at the same time",
         )),
     }?;
+    let xml_dependency = match &attrs.assert_xml {
+        Some(path) => {
+            let iface_name = iface_name
+                .clone()
+                .unwrap_or_else(|| format!("org.freedesktop.{}", input.ident));
+            check_against_xml(&input, &iface_name, path)?
+        }
+        None => quote! {},
+    };
     let gen_async = attrs.gen_async.unwrap_or(true);
     #[cfg(feature = "blocking-api")]
     let gen_blocking = attrs.gen_blocking.unwrap_or(true);
@@ -136,12 +147,138 @@ pub fn expand(args: Punctuated<Meta, Token![,]>, input: ItemTrait) -> Result<Tok
     };
 
     Ok(quote! {
+        #xml_dependency
+
         #blocking_proxy
 
         #async_proxy
     })
 }
 
+/// The D-Bus name of the member for the given trait method.
+fn member_name(m: &TraitItemFn, method_attrs: &MethodAttributes) -> String {
+    method_attrs.name.clone().unwrap_or_else(|| {
+        let method_name = m.sig.ident.unraw().to_string();
+        let is_setter = method_attrs.property.is_some() && m.sig.inputs.len() > 1;
+
+        case::pascal_or_camel_case(
+            if is_setter {
+                assert!(method_name.starts_with("set_"));
+                &method_name[4..]
+            } else {
+                &method_name
+            },
+            true,
+        )
+    })
+}
+
+/// Check the members of the trait against the interface described in the given XML file.
+///
+/// Only the presence and kind of the members, and the number of input arguments, are checked since
+/// the signatures of the argument types are not known to the macro. The returned code makes the
+/// file a dependency of the crate, so the check is run again on changes.
+#[cfg(feature = "xml")]
+fn check_against_xml(
+    input: &ItemTrait,
+    iface_name: &str,
+    path: &str,
+) -> Result<TokenStream, Error> {
+    let path =
+        std::path::Path::new(&std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default()).join(path);
+    let file = std::fs::File::open(&path).map_err(|e| {
+        Error::new(
+            input.span(),
+            format!("failed to open `{}`: {e}", path.display()),
+        )
+    })?;
+    let node = zbus_xml::Node::from_reader(std::io::BufReader::new(file)).map_err(|e| {
+        Error::new(
+            input.span(),
+            format!("failed to parse `{}`: {e}", path.display()),
+        )
+    })?;
+    let iface = node
+        .interfaces()
+        .iter()
+        .find(|i| i.name() == iface_name)
+        .ok_or_else(|| {
+            Error::new(
+                input.span(),
+                format!("interface `{iface_name}` not found in `{}`", path.display()),
+            )
+        })?;
+
+    for item in &input.items {
+        let syn::TraitItem::Fn(m) = item else {
+            continue;
+        };
+        let method_attrs = MethodAttributes::parse(&m.attrs)?;
+        let name = member_name(m, &method_attrs);
+        let n_inputs = m.sig.inputs.iter().filter_map(typed_arg).count();
+
+        let error = if method_attrs.property.is_some() {
+            match iface
+                .properties()
+                .iter()
+                .find(|p| p.name() == name.as_str())
+            {
+                None => Some(format!("no `{name}` property in `{iface_name}`")),
+                Some(p) if n_inputs == 0 && !p.access().read() => {
+                    Some(format!("property `{name}` is not readable"))
+                }
+                Some(p) if n_inputs > 0 && !p.access().write() => {
+                    Some(format!("property `{name}` is not writable"))
+                }
+                Some(_) => None,
+            }
+        } else if method_attrs.signal {
+            match iface.signals().iter().find(|s| s.name() == name.as_str()) {
+                None => Some(format!("no `{name}` signal in `{iface_name}`")),
+                Some(s) if s.args().len() != n_inputs => Some(format!(
+                    "signal `{name}` has {} arguments, not {n_inputs}",
+                    s.args().len(),
+                )),
+                Some(_) => None,
+            }
+        } else {
+            match iface.methods().iter().find(|m| m.name() == name.as_str()) {
+                None => Some(format!("no `{name}` method in `{iface_name}`")),
+                Some(m) => {
+                    let n_in_args = m
+                        .args()
+                        .iter()
+                        .filter(|a| a.direction() != Some(zbus_xml::ArgDirection::Out))
+                        .count();
+                    (n_in_args != n_inputs).then(|| {
+                        format!("method `{name}` has {n_in_args} input arguments, not {n_inputs}")
+                    })
+                }
+            }
+        };
+        if let Some(error) = error {
+            return Err(Error::new_spanned(&m.sig, error));
+        }
+    }
+
+    let path = path.to_string_lossy();
+    Ok(quote! {
+        const _: &[u8] = ::std::include_bytes!(#path);
+    })
+}
+
+#[cfg(not(feature = "xml"))]
+fn check_against_xml(
+    input: &ItemTrait,
+    _iface_name: &str,
+    _path: &str,
+) -> Result<TokenStream, Error> {
+    Err(Error::new(
+        input.span(),
+        "`assert_xml` requires the `xml` feature of zbus",
+    ))
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn create_proxy(
     input: &ItemTrait,
@@ -208,23 +345,10 @@ pub fn create_proxy(
             let method_attrs = MethodAttributes::parse(&m.attrs)?;
             let property = method_attrs.property.as_ref();
 
-            let method_name = m.sig.ident.to_string();
+            let method_name = m.sig.ident.unraw().to_string();
 
             let is_signal = method_attrs.signal;
-            let is_property = property.is_some();
-            let has_inputs = m.sig.inputs.len() > 1;
-
-            let member_name = method_attrs.name.clone().unwrap_or_else(|| {
-                case::pascal_or_camel_case(
-                    if is_property && has_inputs {
-                        assert!(method_name.starts_with("set_"));
-                        &method_name[4..]
-                    } else {
-                        &method_name
-                    },
-                    true,
-                )
-            });
+            let member_name = member_name(m, &method_attrs);
 
             let m = if let Some(prop_attrs) = property {
                 has_properties = true;
@@ -234,6 +358,17 @@ pub fn create_proxy(
                 } else {
                     PropertyEmitsChangedSignal::True
                 };
+                // For the proxy, an uncached property is one without (reliable) change signals.
+                let emits_changed_signal = match (prop_attrs.cached, emits_changed_signal) {
+                    (Some(false), _) => PropertyEmitsChangedSignal::False,
+                    (Some(true), PropertyEmitsChangedSignal::False) => {
+                        return Err(Error::new_spanned(
+                            &m.sig,
+                            "`cached` can't be `true` for properties not emitting changed signals",
+                        ))
+                    }
+                    (_, emits_changed_signal) => emits_changed_signal,
+                };
 
                 if let PropertyEmitsChangedSignal::False = emits_changed_signal {
                     uncached_properties.push(member_name.clone());
@@ -243,9 +378,10 @@ pub fn create_proxy(
                     &member_name,
                     &method_name,
                     m,
+                    &method_attrs,
                     &async_opts,
                     emits_changed_signal,
-                )
+                )?
             } else if is_signal {
                 let (method, types) = gen_proxy_signal(
                     &proxy_name,
@@ -261,7 +397,7 @@ pub fn create_proxy(
 
                 method
             } else {
-                gen_proxy_method_call(&member_name, &method_name, m, method_attrs, &async_opts)?
+                gen_proxy_method_call(&member_name, m, method_attrs, &async_opts)?
             };
             methods.extend(m);
         }
@@ -366,6 +502,14 @@ pub fn create_proxy(
         None => quote! { &None },
     };
 
+    let typed_builder = format_ident!("{proxy_name}Builder");
+    let typed_builder_doc = format!(
+        "Builder for [`{proxy_name}`], to override its default destination, path, interface or \
+         properties caching mode.\n\n\
+         Unlike the generic builder returned by [`{proxy_name}::builder`], its setters take typed \
+         names so they can't fail."
+    );
+
     Ok(quote! {
         impl<'a> #zbus::proxy::Defaults for #proxy_name<'a> {
             const INTERFACE: &'static Option<#zbus::names::InterfaceName<'static>> =
@@ -378,6 +522,74 @@ pub fn create_proxy(
         #[derive(Clone, Debug)]
         #visibility struct #proxy_name<'p>(#proxy_struct<'p>);
 
+        #[doc = #typed_builder_doc]
+        #[derive(Clone, Debug)]
+        #visibility struct #typed_builder<'p>(#builder<'p, #proxy_name<'p>>);
+
+        impl<'p> #typed_builder<'p> {
+            /// Creates a builder for the proxy, with its default arguments.
+            pub fn new(conn: &#connection) -> Self {
+                Self(#proxy_name::builder(conn))
+            }
+
+            /// Set the proxy destination address.
+            pub fn destination<D>(self, destination: D) -> Self
+            where
+                D: ::std::convert::Into<#zbus::names::BusName<'p>>,
+            {
+                Self(
+                    self.0
+                        .destination(destination.into())
+                        .expect("conversion to the same type can't fail"),
+                )
+            }
+
+            /// Set the proxy path.
+            pub fn path<P>(self, path: P) -> Self
+            where
+                P: ::std::convert::Into<#zbus::zvariant::ObjectPath<'p>>,
+            {
+                Self(
+                    self.0
+                        .path(path.into())
+                        .expect("conversion to the same type can't fail"),
+                )
+            }
+
+            /// Set the proxy interface.
+            pub fn interface<I>(self, interface: I) -> Self
+            where
+                I: ::std::convert::Into<#zbus::names::InterfaceName<'p>>,
+            {
+                Self(
+                    self.0
+                        .interface(interface.into())
+                        .expect("conversion to the same type can't fail"),
+                )
+            }
+
+            /// Set the properties caching mode.
+            #[must_use]
+            pub fn cache_properties(self, cache: #zbus::proxy::CacheProperties) -> Self {
+                Self(self.0.cache_properties(cache))
+            }
+
+            /// Build the proxy.
+            ///
+            /// # Errors
+            ///
+            /// If the destination or the path are missing, i.e. the proxy doesn't have a default
+            /// one and none was set.
+            pub #usage fn build(self) -> #zbus::Result<#proxy_name<'p>> {
+                self.0.build()#wait
+            }
+
+            /// Consumes `self`, returning the underlying generic builder.
+            pub fn into_inner(self) -> #builder<'p, #proxy_name<'p>> {
+                self.0
+            }
+        }
+
         impl<'p> #proxy_name<'p> {
             #proxy_method_new
 
@@ -466,7 +678,6 @@ pub fn create_proxy(
 
 fn gen_proxy_method_call(
     method_name: &str,
-    snake_case_name: &str,
     m: &TraitItemFn,
     method_attrs: MethodAttributes,
     async_opts: &AsyncOpts,
@@ -490,23 +701,7 @@ fn gen_proxy_method_call(
         .filter_map(pat_ident)
         .collect();
 
-    let proxy_object = method_attrs.object.as_ref().map(|o| {
-        if *blocking {
-            // FIXME: for some reason Rust doesn't let us move `blocking_proxy_object` so we've to
-            // clone.
-            method_attrs
-                .blocking_object
-                .as_ref()
-                .cloned()
-                .unwrap_or_else(|| format!("{o}ProxyBlocking"))
-        } else {
-            method_attrs
-                .async_object
-                .as_ref()
-                .cloned()
-                .unwrap_or_else(|| format!("{o}Proxy"))
-        }
-    });
+    let proxy_object = proxy_object(&method_attrs, *blocking);
 
     let method_flags = match (
         method_attrs.no_reply,
@@ -542,7 +737,7 @@ fn gen_proxy_method_call(
         _ => None,
     };
 
-    let method = Ident::new(snake_case_name, Span::call_site());
+    let method = &m.sig.ident;
     let inputs = &m.sig.inputs;
     let mut generics = m.sig.generics.clone();
     let where_clause = generics.where_clause.get_or_insert(parse_quote!(where));
@@ -660,13 +855,31 @@ fn gen_proxy_method_call(
     }
 }
 
+/// The name of the proxy type to return from the method, if it's an `object` method.
+fn proxy_object(method_attrs: &MethodAttributes, blocking: bool) -> Option<String> {
+    method_attrs.object.as_ref().map(|o| {
+        if blocking {
+            method_attrs
+                .blocking_object
+                .clone()
+                .unwrap_or_else(|| format!("{o}ProxyBlocking"))
+        } else {
+            method_attrs
+                .async_object
+                .clone()
+                .unwrap_or_else(|| format!("{o}Proxy"))
+        }
+    })
+}
+
 fn gen_proxy_property(
     property_name: &str,
     method_name: &str,
     m: &TraitItemFn,
+    method_attrs: &MethodAttributes,
     async_opts: &AsyncOpts,
     emits_changed_signal: PropertyEmitsChangedSignal,
-) -> TokenStream {
+) -> Result<TokenStream, Error> {
     let AsyncOpts {
         usage,
         wait,
@@ -679,15 +892,22 @@ fn gen_proxy_property(
         .filter(|a| !a.path().is_ident("zbus"))
         .collect();
     let signature = &m.sig;
+    let proxy_object = proxy_object(method_attrs, *blocking);
     if signature.inputs.len() > 1 {
+        if proxy_object.is_some() {
+            return Err(Error::new_spanned(
+                signature,
+                "`object` can only be used on property getters",
+            ));
+        }
         let value = pat_ident(typed_arg(signature.inputs.last().unwrap()).unwrap()).unwrap();
-        quote! {
+        Ok(quote! {
             #(#other_attrs)*
             #[allow(clippy::needless_question_mark)]
             pub #usage #signature {
                 ::std::result::Result::Ok(self.0.set_property(#property_name, #value)#wait?)
             }
-        }
+        })
     } else {
         // This should fail to compile only if the return type is wrong,
         // so use that as the span.
@@ -759,17 +979,42 @@ fn gen_proxy_property(
             PropertyEmitsChangedSignal::False => quote! {},
         };
 
-        quote! {
-            #(#other_attrs)*
-            #[allow(clippy::needless_question_mark)]
-            pub #usage #signature {
-                #body
+        let getter = match proxy_object {
+            Some(proxy_path) => {
+                let proxy_path = parse_str::<Path>(&proxy_path)?;
+                let method = &signature.ident;
+                let (_, ty_generics, where_clause) = signature.generics.split_for_impl();
+
+                quote! {
+                    #(#other_attrs)*
+                    pub #usage fn #method #ty_generics(&self) -> #zbus::Result<#proxy_path<'p>>
+                    #where_clause
+                    {
+                        let object_path: #zbus::zvariant::OwnedObjectPath =
+                            self.0.get_property(#property_name)#wait?;
+                        #proxy_path::builder(&self.0.connection())
+                            .path(object_path)?
+                            .build()
+                            #wait
+                    }
+                }
             }
+            None => quote! {
+                #(#other_attrs)*
+                #[allow(clippy::needless_question_mark)]
+                pub #usage #signature {
+                    #body
+                }
+            },
+        };
+
+        Ok(quote! {
+            #getter
 
             #cached_getter_method
 
             #receive_method
-        }
+        })
     }
 }
 
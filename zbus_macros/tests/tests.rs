@@ -15,6 +15,29 @@ mod param {
     trait ProxyParam {
         #[zbus(object = "super::test::Test")]
         fn some_method<T>(&self, test: &T);
+
+        #[zbus(property, object = "super::test::Test")]
+        fn some_object(&self) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    }
+}
+
+#[cfg(feature = "xml")]
+mod checked {
+    #[zbus_macros::proxy(
+        interface = "org.freedesktop.zbus_macros.Checked",
+        assert_xml = "tests/data/checked.xml"
+    )]
+    trait Checked {
+        fn add(&self, a: u32, b: u32) -> zbus::Result<u32>;
+
+        #[zbus(signal)]
+        fn overflowed(&self, value: u32) -> zbus::Result<()>;
+
+        #[zbus(property)]
+        fn total(&self) -> zbus::Result<u32>;
+
+        #[zbus(property)]
+        fn set_total(&self, value: u32) -> zbus::Result<()>;
     }
 }
 
@@ -46,6 +69,9 @@ mod test {
         #[zbus(name = "CheckRENAMING")]
         fn check_renaming(&self) -> zbus::Result<Vec<u8>>;
 
+        /// Raw identifiers are called without their `r#` prefix.
+        fn r#match(&self, r#type: &str) -> zbus::Result<bool>;
+
         #[zbus(property)]
         fn property(&self) -> fdo::Result<Vec<String>>;
 
@@ -55,6 +81,9 @@ mod test {
         #[zbus(property(emits_changed_signal = "false"))]
         fn a_live_property(&self) -> fdo::Result<Vec<String>>;
 
+        #[zbus(property(cached = false))]
+        fn an_uncached_property(&self) -> fdo::Result<Vec<String>>;
+
         #[zbus(property)]
         fn set_property(&self, val: u16) -> fdo::Result<()>;
 
@@ -62,9 +91,93 @@ mod test {
         fn a_signal<T>(&self, arg: u8, other: T) -> fdo::Result<()>
         where
             T: AsRef<str>;
+
+        /// Raw identifiers of signals and their arguments are used without their `r#` prefix.
+        #[zbus(signal)]
+        fn r#loop(&self, r#ref: u8) -> fdo::Result<()>;
+    }
+}
+
+#[test]
+fn typed_proxy_builder() {
+    use zbus::{
+        names::{InterfaceName, WellKnownName},
+        zvariant::ObjectPath,
+    };
+
+    let destination = WellKnownName::from_static_str_unchecked("org.freedesktop.zbus_macros.Typed");
+    let path = ObjectPath::from_static_str_unchecked("/org/freedesktop/zbus_macros/typed");
+    let interface = InterfaceName::from_static_str_unchecked("org.freedesktop.zbus_macros.Typed");
+
+    block_on(async {
+        let connection = zbus::Connection::session().await.unwrap();
+
+        // The default destination is kept, but there's no default path.
+        let builder = test::TestProxyBuilder::new(&connection);
+        assert!(builder.clone().build().await.is_err());
+        let proxy = builder.path(path.clone()).build().await.unwrap();
+        assert_eq!(proxy.inner().destination(), "org.freedesktop.zbus_macros");
+        assert_eq!(
+            proxy.inner().interface(),
+            "org.freedesktop.zbus_macros.Test"
+        );
+
+        let proxy = test::TestProxyBuilder::new(&connection)
+            .destination(destination.clone())
+            .path(path.clone())
+            .interface(interface.clone())
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(proxy.inner().destination(), &destination);
+        assert_eq!(proxy.inner().path(), &path);
+        assert_eq!(proxy.inner().interface(), &interface);
+    });
+
+    #[cfg(feature = "blocking-api")]
+    {
+        let connection = zbus::blocking::Connection::session().unwrap();
+        let proxy = test::TestProxyBlockingBuilder::new(&connection)
+            .destination(destination.clone())
+            .path(path.clone())
+            .build()
+            .unwrap();
+        assert_eq!(proxy.inner().destination(), &destination);
+        assert_eq!(proxy.inner().path(), &path);
     }
 }
 
+#[test]
+fn raw_identifier_signal() {
+    block_on(async {
+        let connection = zbus::Connection::session().await.unwrap();
+        let proxy = test::TestProxy::builder(&connection)
+            .destination(connection.unique_name().unwrap().to_owned())
+            .unwrap()
+            .path("/org/freedesktop/zbus_macros/test")
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        let mut stream: test::LoopStream = proxy.receive_loop().await.unwrap();
+
+        connection
+            .emit_signal(
+                None::<zbus::names::BusName<'_>>,
+                "/org/freedesktop/zbus_macros/test",
+                "org.freedesktop.zbus_macros.Test",
+                "Loop",
+                &(7u8,),
+            )
+            .await
+            .unwrap();
+        let signal: test::Loop = stream.next().await.unwrap();
+        let args: test::LoopArgs<'_> = signal.args().unwrap();
+        assert_eq!(*args.r#ref(), 7);
+    });
+}
+
 #[test]
 fn test_proxy() {
     block_on(async move {
@@ -162,6 +275,7 @@ fn test_interface() {
         }
 
         // TODO: naming output arguments after "RFC: Structural Records #2584"
+        #[zbus(out_args("generic", "something"))]
         fn many_output(&self) -> zbus::fdo::Result<(&T, String)> {
             Ok((&self.generic, self.something.clone()))
         }
@@ -197,11 +311,27 @@ fn test_interface() {
             unimplemented!()
         }
 
-        #[zbus(name = "CheckVEC")]
+        #[zbus(name = "CheckVEC", out_args("vec"))]
         fn check_vec(&self) -> Vec<u8> {
             unimplemented!()
         }
 
+        // Raw identifiers don't leak into the D-Bus names.
+        fn r#match(&self, r#type: &str) -> bool {
+            r#type == self.something
+        }
+
+        #[zbus(no_reply)]
+        fn notify(&self, what: &str) {
+            assert!(!what.is_empty());
+        }
+
+        // Not exported, so not in the XML either.
+        #[zbus(skip)]
+        fn new(something: String, generic: T) -> Self {
+            Self { something, generic }
+        }
+
         /// Testing my_prop documentation is reflected in XML.
         ///
         /// And that too.
@@ -215,9 +345,25 @@ fn test_interface() {
             unimplemented!()
         }
 
+        // Absent when `None`.
+        #[zbus(property(optional))]
+        fn my_optional_prop(&self) -> Option<String> {
+            unimplemented!()
+        }
+
+        // Setter without a getter, i.e. a write-only property.
+        #[zbus(property)]
+        fn set_my_write_only(&mut self, _val: &str) {
+            unimplemented!()
+        }
+
         /// Emit a signal.
         #[zbus(signal)]
-        async fn signal(emitter: &SignalEmitter<'_>, arg: u8, other: &str) -> zbus::Result<()>;
+        async fn signal(
+            emitter: &SignalEmitter<'_>,
+            arg: u8,
+            #[zbus(name = "otherArg")] other: &str,
+        ) -> zbus::Result<()>;
     }
 
     const EXPECTED_XML: &str = r#"<interface name="org.freedesktop.zbus.Test">
@@ -231,21 +377,29 @@ fn test_interface() {
     <arg type="u" direction="out"/>
   </method>
   <method name="ManyOutput">
-    <arg type="u" direction="out"/>
-    <arg type="s" direction="out"/>
+    <arg name="generic" type="u" direction="out"/>
+    <arg name="something" type="s" direction="out"/>
   </method>
   <method name="PairOutput">
     <arg type="(us)" direction="out"/>
   </method>
   <method name="CheckVEC">
-    <arg type="ay" direction="out"/>
+    <arg name="vec" type="ay" direction="out"/>
+  </method>
+  <method name="Match">
+    <arg name="type" type="s" direction="in"/>
+    <arg type="b" direction="out"/>
+  </method>
+  <method name="Notify">
+    <arg name="what" type="s" direction="in"/>
+    <annotation name="org.freedesktop.DBus.Method.NoReply" value="true"/>
   </method>
   <!--
    Emit a signal.
    -->
   <signal name="Signal">
     <arg name="arg" type="y"/>
-    <arg name="other" type="s"/>
+    <arg name="otherArg" type="s"/>
   </signal>
   <property name="MyCustomProperty" type="u" access="readwrite"/>
   <property name="MyCustomPropertyEmitsConst" type="u" access="read">
@@ -257,21 +411,21 @@ fn test_interface() {
   <property name="MyCustomPropertyEmitsInvalidates" type="u" access="read">
     <annotation name="org.freedesktop.DBus.Property.EmitsChangedSignal" value="invalidates"/>
   </property>
+  <property name="MyOptionalProp" type="s" access="read"/>
   <!--
    Testing my_prop documentation is reflected in XML.
 
    And that too.
    -->
   <property name="MyProp" type="q" access="readwrite"/>
+  <property name="MyWriteOnly" type="s" access="write"/>
 </interface>
 "#;
-    let t = Test {
-        something: String::from("somewhere"),
-        generic: 42u32,
-    };
+    let t = Test::new(String::from("somewhere"), 42u32);
     let mut xml = String::new();
     t.introspect_to_writer(&mut xml, 0);
     assert_eq!(xml, EXPECTED_XML);
+    assert_eq!(Test::<u32>::introspection_xml(), EXPECTED_XML);
 
     assert_eq!(Test::<u32>::name(), "org.freedesktop.zbus.Test");
 
@@ -291,6 +445,57 @@ fn test_interface() {
     }
 }
 
+#[test]
+fn test_generic_interface() {
+    use zbus::object_server::Interface;
+
+    trait Backend: Send + Sync + 'static {
+        fn level(&self) -> u8;
+    }
+
+    struct Fixed;
+
+    impl Backend for Fixed {
+        fn level(&self) -> u8 {
+            42
+        }
+    }
+
+    struct Service<B> {
+        backend: B,
+        label: String,
+        data: Vec<u8>,
+    }
+
+    #[interface(name = "org.freedesktop.zbus.Service", spawn = false)]
+    impl<B: Backend> Service<B> {
+        #[zbus(property)]
+        fn level(&self) -> u8 {
+            self.backend.level()
+        }
+
+        // Replies can borrow from the interface.
+        async fn label(&self) -> &str {
+            &self.label
+        }
+
+        fn data(&self) -> zbus::fdo::Result<&[u8]> {
+            Ok(&self.data)
+        }
+
+        #[zbus(signal)]
+        async fn level_reached(emitter: &SignalEmitter<'_>, level: u8) -> zbus::Result<()>;
+    }
+
+    assert_eq!(Service::<Fixed>::name(), "org.freedesktop.zbus.Service");
+    let service = Service {
+        backend: Fixed,
+        label: String::from("fixed"),
+        data: vec![],
+    };
+    assert_eq!(service.level(), 42);
+}
+
 mod signal_from_message {
     use super::*;
     use zbus::message::Message;
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -10,14 +10,122 @@ pub struct Args {
 
     /// Specify the destination for saving the output. If no argument is provided, the parsed
     /// interfaces will be stored in separate files. If a filename is provided, the output will
-    /// be saved to that file. Use '-' to print the output to stdout.
+    /// be saved to that file. If a directory is provided (an existing one, or a path ending with
+    /// '/'), each interface will be stored in its own module in that directory, along with a
+    /// `mod.rs` declaring them. Use '-' to print the output to stdout.
     #[clap(short, long, allow_hyphen_values = true, global = true)]
     pub output: Option<String>,
+
+    /// Overwrite existing module files when generating into a directory. By default, they're
+    /// left untouched and only new modules are added to the `mod.rs`.
+    #[clap(long, global = true)]
+    pub overwrite: bool,
+
+    /// Don't write anything but compare the generated code with the existing output files,
+    /// printing the added, removed and changed members, and exit with a non-zero status if they
+    /// differ. Formatting and documentation changes are ignored.
+    #[clap(long, global = true)]
+    pub check: bool,
+
+    /// Generate server-side interface skeletons to be served through `zbus::ObjectServer`,
+    /// instead of client proxies.
+    #[clap(long, global = true)]
+    pub server: bool,
+
+    /// Don't generate the module documentation header describing the interfaces and how the code
+    /// was generated, e.g. to embed the output in an existing module.
+    #[clap(long, global = true)]
+    pub no_header: bool,
+
+    /// Don't format the generated code. By default, it's formatted with `rustfmt` if available,
+    /// or with a built-in formatter otherwise.
+    #[clap(long, global = true)]
+    pub no_format: bool,
+
+    /// Also generate owned structs for the arguments of the signals, with a conversion from the
+    /// received message.
+    #[clap(long, global = true)]
+    pub signal_args: bool,
+
+    /// Also generate a mock implementation of the interfaces, recording the calls and returning
+    /// configurable replies, to test code using the interfaces without the real service.
+    #[clap(long, global = true)]
+    pub mock: bool,
+
+    /// The default service of the generated proxies. When introspecting a service, it defaults to
+    /// that service.
+    #[clap(long, value_name = "NAME", global = true)]
+    pub default_service: Option<String>,
+
+    /// The default object path of the generated proxies. When introspecting a service, it
+    /// defaults to the path of the object the interface was found on.
+    #[clap(long, value_name = "PATH", global = true)]
+    pub default_path: Option<String>,
+
+    /// Don't set the default service and object path of the generated proxies, not even the ones
+    /// of the introspected service. They will then have to be given through the proxy builder.
+    #[clap(
+        long,
+        conflicts_with_all = ["default_service", "default_path"],
+        global = true
+    )]
+    pub no_defaults: bool,
+
+    /// The kind of client proxies to generate. Use `blocking` for applications that are entirely
+    /// synchronous.
+    #[clap(long, value_enum, default_value_t = ProxyKind::Both, global = true)]
+    pub proxy_kind: ProxyKind,
+
+    /// Only generate code for the interfaces whose name matches this glob pattern, e.g.
+    /// `org.example.*`. Can be given multiple times.
+    #[clap(long, value_name = "PATTERN", global = true)]
+    pub include: Vec<String>,
+
+    /// Skip the interfaces whose name matches this glob pattern, e.g. `*.Internal`. Takes
+    /// precedence over `--include`. Can be given multiple times.
+    #[clap(long, value_name = "PATTERN", global = true)]
+    pub exclude: Vec<String>,
+
+    /// Follow the child nodes of the object, recursively, and generate code for all the distinct
+    /// interfaces found in the object tree.
+    #[clap(short, long, global = true)]
+    pub recursive: bool,
+
+    /// Map a D-Bus signature to a Rust type, e.g. `a{sv}=my_crate::Metadata`. Nested occurrences
+    /// of the signature are mapped as well. Can be given multiple times.
+    #[clap(long, value_name = "SIGNATURE=TYPE", global = true)]
+    pub map_type: Vec<String>,
+
+    /// Map a specific property, or an argument given as `Member.arg`, to a Rust type, e.g.
+    /// `Metadata=my_crate::Metadata`. Takes precedence over `--map-type`. Can be given multiple
+    /// times.
+    #[clap(long, value_name = "MEMBER=TYPE", global = true)]
+    pub map_member: Vec<String>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum ProxyKind {
+    /// Only asynchronous proxies.
+    Async,
+    /// Only blocking proxies.
+    Blocking,
+    /// Both asynchronous and blocking proxies.
+    Both,
+}
+
+impl From<ProxyKind> for zbus_xmlgen::ProxyKind {
+    fn from(kind: ProxyKind) -> Self {
+        match kind {
+            ProxyKind::Async => Self::Async,
+            ProxyKind::Blocking => Self::Blocking,
+            ProxyKind::Both => Self::Both,
+        }
+    }
 }
 
 #[derive(Parser, Debug, Clone)]
 pub enum Command {
-    /// Generate code for interfaces in the specified file.
+    /// Generate code for interfaces in the specified file. Use '-' to read from stdin.
     #[clap()]
     File { path: PathBuf },
 
@@ -1,5 +1,37 @@
+//! D-Bus XML interface code generator.
+//!
+//! Besides the `zbus-xmlgen` tool, this crate can be used as a library to generate the code from
+//! D-Bus XML interface descriptions, e.g. in a build script. This allows projects to generate their
+//! proxies from checked-in XML files at build time, instead of committing the generated code:
+//!
+//! ```no_run
+//! // build.rs
+//! use zbus_xmlgen::{generate, GenerateOptions, Node};
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let xml = std::fs::read_to_string("interfaces/org.example.Foo.xml")?;
+//!     let node = Node::from_reader(xml.as_bytes())?;
+//!     let code = generate(&node, &GenerateOptions::default())?;
+//!
+//!     let out_dir = std::env::var("OUT_DIR")?;
+//!     std::fs::write(format!("{out_dir}/foo.rs"), code)?;
+//!     println!("cargo::rerun-if-changed=interfaces/org.example.Foo.xml");
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The generated code can then be included in a dedicated module of the project:
+//!
+//! ```ignore
+//! mod foo {
+//!     include!(concat!(env!("OUT_DIR"), "/foo.rs"));
+//! }
+//! ```
+
 use snakecase::ascii::to_snakecase;
 use std::{
+    collections::HashMap,
     error::Error,
     fmt::{Display, Formatter, Write},
     process::{Command, Stdio},
@@ -9,7 +41,66 @@ use zbus::{
     names::BusName,
     zvariant::{ObjectPath, Signature},
 };
-use zbus_xml::{Arg, ArgDirection, Interface};
+use zbus_xml::{Annotation, Arg, ArgDirection, Interface};
+
+pub use zbus_xml::Node;
+
+/// Options for [`generate`].
+#[derive(Debug, Default, Clone)]
+pub struct GenerateOptions<'a> {
+    /// The default service of the generated proxies.
+    pub service: Option<BusName<'a>>,
+    /// The default path of the generated proxies.
+    pub path: Option<ObjectPath<'a>>,
+    /// The kind of proxies to generate.
+    pub kind: ProxyKind,
+    /// Generate server-side interface skeletons, instead of client proxies.
+    pub server: bool,
+    /// Also generate a mock implementation of the interfaces, for tests.
+    pub mock: bool,
+    /// Also generate owned structs for the arguments of the signals.
+    pub signal_args: bool,
+    /// The user-provided mapping of D-Bus types to Rust types.
+    pub types: TypeMap,
+    /// The filter selecting the interfaces to generate code for.
+    pub filter: InterfaceFilter,
+    /// Format the generated code with `rustfmt`.
+    pub format: bool,
+}
+
+/// Generate the code for all the interfaces of `node`.
+///
+/// The standard `org.freedesktop.DBus` interfaces are skipped since zbus already provides them,
+/// in the [`zbus::fdo`] module.
+///
+/// Unlike the `zbus-xmlgen` tool, no module documentation header is generated, so the code can be
+/// included with [`include!`] in a module of its own.
+pub fn generate(node: &Node<'_>, options: &GenerateOptions<'_>) -> Result<String, Box<dyn Error>> {
+    let mut unformatted = String::new();
+
+    if options.server {
+        writeln!(unformatted, "use zbus::interface;")?;
+    } else {
+        writeln!(unformatted, "use zbus::proxy;")?;
+    }
+
+    for interface in node.interfaces() {
+        if interface.name().starts_with("org.freedesktop.DBus")
+            || !options.filter.matches(interface.name().as_str())
+        {
+            continue;
+        }
+
+        writeln!(unformatted)?;
+        write_interface_code(&mut unformatted, interface, options)?;
+    }
+
+    if options.format {
+        Ok(format_or_warn(unformatted))
+    } else {
+        Ok(unformatted)
+    }
+}
 
 pub fn write_interfaces(
     interfaces: &[Interface<'_>],
@@ -20,37 +111,115 @@ pub fn write_interfaces(
     cargo_bin_name: &str,
     cargo_bin_version: &str,
 ) -> Result<String, Box<dyn Error>> {
+    let options = GenerateOptions {
+        service,
+        path,
+        format: true,
+        ..Default::default()
+    };
+
+    write_interfaces_with_options(
+        interfaces,
+        standard_interfaces,
+        &options,
+        input_src,
+        cargo_bin_name,
+        cargo_bin_version,
+    )
+}
+
+/// Same as [`write_interfaces`] but generates the code as configured by `options`.
+///
+/// Only the interfaces matching the filter of `options` are generated and listed in the header.
+pub fn write_interfaces_with_options(
+    interfaces: &[Interface<'_>],
+    standard_interfaces: &[Interface<'_>],
+    options: &GenerateOptions<'_>,
+    input_src: &str,
+    cargo_bin_name: &str,
+    cargo_bin_version: &str,
+) -> Result<String, Box<dyn Error>> {
+    let interfaces: Vec<_> = interfaces
+        .iter()
+        .filter(|i| options.filter.matches(i.name().as_str()))
+        .cloned()
+        .collect();
     let mut unformatted = String::new();
 
     write_doc_header(
         &mut unformatted,
-        interfaces,
+        &interfaces,
         standard_interfaces,
         input_src,
         cargo_bin_name,
         cargo_bin_version,
+        options.server,
     )?;
 
-    for interface in interfaces {
+    for interface in &interfaces {
+        write_interface_code(&mut unformatted, interface, options)?;
+    }
+
+    if options.format {
+        Ok(format_or_warn(unformatted))
+    } else {
+        Ok(unformatted)
+    }
+}
+
+/// Write the unformatted code of `interface`, as configured by `options`.
+fn write_interface_code(
+    w: &mut String,
+    interface: &Interface<'_>,
+    options: &GenerateOptions<'_>,
+) -> std::fmt::Result {
+    if options.server {
+        GenInterface::new(interface, options).write_interface(w)?;
+    } else {
         let gen = GenTrait {
             interface,
-            service: service.as_ref(),
-            path: path.as_ref(),
+            service: options.service.as_ref(),
+            path: options.path.as_ref(),
             format: false,
         };
+        gen.write_interface(w, options.kind, &options.types)?;
+    }
 
-        write!(unformatted, "{}", gen)?;
+    if options.signal_args && interface.signals().iter().any(|s| !s.args().is_empty()) {
+        writeln!(w)?;
+        GenSignalArgs::new(interface, options).write_signal_args(w)?;
     }
 
-    let formatted = match format_generated_code(&unformatted) {
+    if options.mock {
+        writeln!(w)?;
+        GenMock::new(interface, options).write_mock(w)?;
+    }
+
+    Ok(())
+}
+
+/// Strip the module documentation header from the code `generated` by [`write_interfaces`], e.g.
+/// to embed it in an existing module.
+pub fn strip_doc_header(generated: &str) -> &str {
+    let mut rest = generated.trim_start();
+    while rest.starts_with("//!") {
+        rest = rest
+            .split_once('\n')
+            .map_or("", |(_, rest)| rest)
+            .trim_start();
+    }
+
+    rest
+}
+
+fn format_or_warn(unformatted: String) -> String {
+    match format_generated_code(&unformatted) {
         Ok(formatted) => formatted,
         Err(e) => {
             eprintln!("Failed to format generated code: {}", e);
             unformatted
         }
-    };
-
-    Ok(formatted)
+    }
 }
 
 /// Write a doc header, listing the included Interfaces and how the
@@ -62,18 +231,24 @@ fn write_doc_header<W: std::fmt::Write>(
     input_src: &str,
     cargo_bin_name: &str,
     cargo_bin_version: &str,
+    server: bool,
 ) -> std::fmt::Result {
+    let (kind, kinds) = if server {
+        ("skeleton", "skeletons")
+    } else {
+        ("proxy", "proxies")
+    };
     if let Some((first_iface, following_ifaces)) = interfaces.split_first() {
         if following_ifaces.is_empty() {
             writeln!(
                 w,
-                "//! # D-Bus interface proxy for: `{}`",
+                "//! # D-Bus interface {kind} for: `{}`",
                 first_iface.name()
             )?;
         } else {
             write!(
                 w,
-                "//! # D-Bus interface proxies for: `{}`",
+                "//! # D-Bus interface {kinds} for: `{}`",
                 first_iface.name()
             )?;
             for iface in following_ifaces {
@@ -83,6 +258,11 @@ fn write_doc_header<W: std::fmt::Write>(
         }
     }
 
+    let section = if server {
+        "[Writing a server interface]"
+    } else {
+        "[Writing a client proxy]"
+    };
     write!(
         w,
         "//!
@@ -91,7 +271,7 @@ fn write_doc_header<W: std::fmt::Write>(
          //!
          //! You may prefer to adapt it, instead of using it verbatim.
          //!
-         //! More information can be found in the [Writing a client proxy] section of the zbus
+         //! More information can be found in the {section} section of the zbus
          //! documentation.
          //!
         ",
@@ -107,7 +287,15 @@ fn write_doc_header<W: std::fmt::Write>(
         for iface in standard_interfaces {
             let idx = iface.name().rfind('.').unwrap() + 1;
             let name = &iface.name()[idx..];
-            writeln!(w, "//! * [`zbus::fdo::{name}Proxy`]")?;
+            if server {
+                writeln!(
+                    w,
+                    "//! * `{}`, served by [`zbus::ObjectServer`]",
+                    iface.name()
+                )?;
+            } else {
+                writeln!(w, "//! * [`zbus::fdo::{name}Proxy`]")?;
+            }
         }
         write!(
             w,
@@ -118,18 +306,206 @@ fn write_doc_header<W: std::fmt::Write>(
         )?;
     }
 
-    write!(
-        w,
-        "//!
-        //! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
-        //! [D-Bus standard interfaces]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces,
-        use zbus::proxy;
-        "
-    )?;
+    if server {
+        write!(
+            w,
+            "//!
+            //! [Writing a server interface]: https://dbus2.github.io/zbus/service.html
+            //! [D-Bus standard interfaces]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces,
+            use zbus::interface;
+            "
+        )?;
+    } else {
+        write!(
+            w,
+            "//!
+            //! [Writing a client proxy]: https://dbus2.github.io/zbus/client.html
+            //! [D-Bus standard interfaces]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces,
+            use zbus::proxy;
+            "
+        )?;
+    }
 
     Ok(())
 }
 
+/// User-provided mapping of D-Bus types to Rust types, used instead of the default ones.
+///
+/// This allows the generated code to use the application's own types (e.g. a `Metadata` struct
+/// for an `a{sv}` property), instead of having to edit the code after every regeneration.
+#[derive(Debug, Default, Clone)]
+pub struct TypeMap {
+    signatures: HashMap<String, String>,
+    members: HashMap<String, String>,
+}
+
+impl TypeMap {
+    /// Create an empty mapping.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map all the occurrences of `signature`, including nested ones, to the `ty` Rust type.
+    pub fn map_signature(
+        &mut self,
+        signature: &str,
+        ty: impl Into<String>,
+    ) -> Result<(), zbus::zvariant::Error> {
+        let signature = Signature::try_from(signature)?;
+        self.signatures.insert(signature.to_string(), ty.into());
+
+        Ok(())
+    }
+
+    /// Map a specific property or argument to the `ty` Rust type.
+    ///
+    /// Properties are specified by their name, e.g. `Metadata`, and arguments by the name of their
+    /// method or signal and their own name, separated by a dot, e.g. `SetMetadata.metadata`. This
+    /// takes precedence over the signature mappings.
+    pub fn map_member(&mut self, member: impl Into<String>, ty: impl Into<String>) {
+        self.members.insert(member.into(), ty.into());
+    }
+
+    fn property_type(&self, property: &str, ty: &Signature, input: bool, as_ref: bool) -> String {
+        match self.members.get(property) {
+            Some(mapped) => mapped_type(mapped, input, as_ref),
+            None => to_rust_type(ty, input, as_ref, self),
+        }
+    }
+
+    fn arg_type(&self, member: &str, arg: &Arg, input: bool, as_ref: bool) -> String {
+        let mapped = arg
+            .name()
+            .and_then(|name| self.members.get(&format!("{member}.{name}")));
+        match mapped {
+            Some(mapped) => mapped_type(mapped, input, as_ref),
+            None => to_rust_type(arg.ty(), input, as_ref, self),
+        }
+    }
+}
+
+fn mapped_type(ty: &str, input: bool, as_ref: bool) -> String {
+    if input && as_ref {
+        format!("&{ty}")
+    } else {
+        ty.to_string()
+    }
+}
+
+/// Selects interfaces by their name, through glob patterns.
+///
+/// In the patterns, `*` matches any sequence of characters, including dots, and `?` matches any
+/// single character.
+#[derive(Debug, Default, Clone)]
+pub struct InterfaceFilter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl InterfaceFilter {
+    /// Create a filter matching all the interfaces.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match the interfaces matching `pattern`, or any of the other included patterns.
+    pub fn include(&mut self, pattern: impl Into<String>) -> &mut Self {
+        self.include.push(pattern.into());
+
+        self
+    }
+
+    /// Don't match the interfaces matching `pattern`, even if they're included.
+    pub fn exclude(&mut self, pattern: impl Into<String>) -> &mut Self {
+        self.exclude.push(pattern.into());
+
+        self
+    }
+
+    /// Whether the interface named `name` is matched by the filter.
+    pub fn matches(&self, name: &str) -> bool {
+        let included = self.include.is_empty() || self.include.iter().any(|p| glob_match(p, name));
+
+        included && !self.exclude.iter().any(|p| glob_match(p, name))
+    }
+}
+
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // The position of the last `*` in the pattern and of the name when it was reached, to
+    // backtrack to when the rest of the pattern doesn't match.
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// The kind of proxies to generate.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyKind {
+    /// Only the asynchronous proxy.
+    Async,
+    /// Only the blocking proxy, named without the `Blocking` suffix.
+    Blocking,
+    /// Both the asynchronous and the blocking proxies.
+    #[default]
+    Both,
+}
+
+/// How the default service or object path of the generated proxies is chosen.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum ProxyDefault<T> {
+    /// The one of the introspected object, if any.
+    #[default]
+    Introspected,
+    /// The given one, even if the object was introspected.
+    Custom(T),
+    /// None at all, so it has to be given through the proxy builder.
+    None,
+}
+
+impl<T: Clone> ProxyDefault<T> {
+    /// The choice for the user-provided `custom` default, unless all defaults are `disabled`.
+    pub fn new(custom: Option<T>, disabled: bool) -> Self {
+        match custom {
+            _ if disabled => Self::None,
+            Some(custom) => Self::Custom(custom),
+            None => Self::Introspected,
+        }
+    }
+
+    /// The default to use for a proxy of an object with the `introspected` default.
+    pub fn resolve(&self, introspected: Option<T>) -> Option<T> {
+        match self {
+            Self::Introspected => introspected,
+            Self::Custom(custom) => Some(custom.clone()),
+            Self::None => None,
+        }
+    }
+}
+
 pub struct GenTrait<'i> {
     pub interface: &'i Interface<'i>,
     pub service: Option<&'i BusName<'i>>,
@@ -139,25 +515,32 @@ pub struct GenTrait<'i> {
 
 impl Display for GenTrait<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let types = TypeMap::new();
         if self.format {
             let mut unformatted = String::new();
-            self.write_interface(&mut unformatted)?;
+            self.write_interface(&mut unformatted, ProxyKind::Both, &types)?;
 
             let formatted = format_generated_code(&unformatted).unwrap_or(unformatted);
 
             write!(f, "{}", formatted)
         } else {
-            self.write_interface(f)
+            self.write_interface(f, ProxyKind::Both, &types)
         }
     }
 }
 
 impl GenTrait<'_> {
-    fn write_interface<W: Write>(&self, w: &mut W) -> std::fmt::Result {
+    fn write_interface<W: Write>(
+        &self,
+        w: &mut W,
+        kind: ProxyKind,
+        types: &TypeMap,
+    ) -> std::fmt::Result {
         let iface = self.interface;
         let idx = iface.name().rfind('.').unwrap() + 1;
         let name = &iface.name()[idx..];
 
+        write_doc_string(w, "", iface.annotations())?;
         write!(w, "#[proxy(interface = \"{}\"", iface.name())?;
         if let Some(service) = self.service {
             write!(w, ", default_service = \"{service}\"")?;
@@ -168,16 +551,24 @@ impl GenTrait<'_> {
         if self.path.is_none() || self.service.is_none() {
             write!(w, ", assume_defaults = true")?;
         }
+        match kind {
+            ProxyKind::Async => write!(w, ", gen_blocking = false")?,
+            ProxyKind::Blocking => write!(w, ", gen_async = false")?,
+            ProxyKind::Both => (),
+        }
         writeln!(w, ")]")?;
         writeln!(w, "pub trait {name} {{")?;
 
         let mut methods = iface.methods().to_vec();
         methods.sort_by(|a, b| a.name().partial_cmp(&b.name()).unwrap());
         for m in &methods {
-            let (inputs, output) = inputs_output_from_args(m.args());
+            let (inputs, output) = inputs_output_from_args(m.name().as_str(), m.args(), types);
             let name = to_identifier(&to_snakecase(m.name().as_str()));
             writeln!(w)?;
-            writeln!(w, "    /// {} method", m.name())?;
+            write_doc(w, "    ", m.annotations(), || {
+                format!("{} method", m.name())
+            })?;
+            write_deprecated(w, m.annotations())?;
             if pascal_case(&name) != m.name().as_str() {
                 writeln!(w, "    #[zbus(name = \"{}\")]", m.name())?;
             }
@@ -188,10 +579,13 @@ impl GenTrait<'_> {
         let mut signals = iface.signals().to_vec();
         signals.sort_by(|a, b| a.name().partial_cmp(&b.name()).unwrap());
         for signal in &signals {
-            let args = parse_signal_args(signal.args());
+            let args = parse_signal_args(signal.name().as_str(), signal.args(), types);
             let name = to_identifier(&to_snakecase(signal.name().as_str()));
             writeln!(w)?;
-            writeln!(w, "    /// {} signal", signal.name())?;
+            write_doc(w, "    ", signal.annotations(), || {
+                format!("{} signal", signal.name())
+            })?;
+            write_deprecated(w, signal.annotations())?;
             if pascal_case(&name) != signal.name().as_str() {
                 writeln!(w, "    #[zbus(signal, name = \"{}\")]", signal.name())?;
             } else {
@@ -204,24 +598,24 @@ impl GenTrait<'_> {
         props.sort_by(|a, b| a.name().partial_cmp(&b.name()).unwrap());
         for p in props {
             let name = to_identifier(&to_snakecase(p.name().as_str()));
-            let fn_attribute = if pascal_case(&name) != p.name().as_str() {
-                format!("    #[zbus(property, name = \"{}\")]", p.name())
-            } else {
-                "    #[zbus(property)]".to_string()
-            };
+            let (getter_attribute, setter_attribute) = property_attributes(iface, &p, &name);
 
             writeln!(w)?;
-            writeln!(w, "    /// {} property", p.name())?;
+            write_doc(w, "    ", p.annotations(), || {
+                format!("{} property", p.name())
+            })?;
             if p.access().read() {
-                writeln!(w, "{}", fn_attribute)?;
-                let output = to_rust_type(p.ty(), false, false);
+                write_deprecated(w, p.annotations())?;
+                writeln!(w, "{}", getter_attribute)?;
+                let output = types.property_type(p.name().as_str(), p.ty(), false, false);
                 hide_clippy_type_complexity_lint(w, p.ty())?;
                 writeln!(w, "    fn {name}(&self) -> zbus::Result<{output}>;",)?;
             }
 
             if p.access().write() {
-                writeln!(w, "{}", fn_attribute)?;
-                let input = to_rust_type(p.ty(), true, true);
+                write_deprecated(w, p.annotations())?;
+                writeln!(w, "{}", setter_attribute)?;
+                let input = types.property_type(p.name().as_str(), p.ty(), true, true);
                 writeln!(
                     w,
                     "    fn set_{name}(&self, value: {input}) -> zbus::Result<()>;",
@@ -232,6 +626,617 @@ impl GenTrait<'_> {
     }
 }
 
+pub struct GenInterface<'i> {
+    interface: &'i Interface<'i>,
+    options: &'i GenerateOptions<'i>,
+}
+
+impl<'i> GenInterface<'i> {
+    /// Generate the server-side skeleton of `interface`, with the types and formatting of `options`.
+    pub fn new(interface: &'i Interface<'i>, options: &'i GenerateOptions<'i>) -> Self {
+        Self { interface, options }
+    }
+}
+
+impl Display for GenInterface<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.options.format {
+            let mut unformatted = String::new();
+            self.write_interface(&mut unformatted)?;
+
+            let formatted = format_generated_code(&unformatted).unwrap_or(unformatted);
+
+            write!(f, "{}", formatted)
+        } else {
+            self.write_interface(f)
+        }
+    }
+}
+
+impl GenInterface<'_> {
+    fn write_interface<W: Write>(&self, w: &mut W) -> std::fmt::Result {
+        let iface = self.interface;
+        let idx = iface.name().rfind('.').unwrap() + 1;
+        let name = &iface.name()[idx..];
+
+        write_doc_string(w, "", iface.annotations())?;
+        writeln!(w, "pub struct {name};")?;
+        writeln!(w)?;
+        writeln!(w, "#[interface(name = \"{}\")]", iface.name())?;
+        writeln!(w, "impl {name} {{")?;
+
+        let mut methods = iface.methods().to_vec();
+        methods.sort_by(|a, b| a.name().partial_cmp(&b.name()).unwrap());
+        for m in &methods {
+            let (inputs, output) =
+                server_inputs_output_from_args(m.name().as_str(), m.args(), &self.options.types);
+            let name = to_identifier(&to_snakecase(m.name().as_str()));
+            writeln!(w)?;
+            write_doc(w, "    ", m.annotations(), || {
+                format!("{} method", m.name())
+            })?;
+            if pascal_case(&name) != m.name().as_str() {
+                writeln!(w, "    #[zbus(name = \"{}\")]", m.name())?;
+            }
+            hide_clippy_lints(w, m)?;
+            writeln!(w, "    async fn {name}({inputs}){output} {{")?;
+            writeln!(w, "        todo!()")?;
+            writeln!(w, "    }}")?;
+        }
+
+        write_server_signals(w, iface, &self.options.types)?;
+
+        let mut props = iface.properties().to_vec();
+        props.sort_by(|a, b| a.name().partial_cmp(&b.name()).unwrap());
+        for p in props {
+            let name = to_identifier(&to_snakecase(p.name().as_str()));
+            let (getter_attribute, setter_attribute) = property_attributes(iface, &p, &name);
+            let ty = self
+                .options
+                .types
+                .property_type(p.name().as_str(), p.ty(), false, false);
+
+            writeln!(w)?;
+            write_doc(w, "    ", p.annotations(), || {
+                format!("{} property", p.name())
+            })?;
+            if p.access().read() {
+                writeln!(w, "{}", getter_attribute)?;
+                hide_clippy_type_complexity_lint(w, p.ty())?;
+                writeln!(
+                    w,
+                    "    async fn {name}(&self) -> zbus::fdo::Result<{ty}> {{"
+                )?;
+                writeln!(w, "        todo!()")?;
+                writeln!(w, "    }}")?;
+            }
+
+            if p.access().write() {
+                writeln!(w, "{}", setter_attribute)?;
+                hide_clippy_type_complexity_lint(w, p.ty())?;
+                writeln!(
+                    w,
+                    "    async fn set_{name}(&mut self, value: {ty}) -> zbus::fdo::Result<()> {{",
+                )?;
+                writeln!(w, "        todo!()")?;
+                writeln!(w, "    }}")?;
+            }
+        }
+        writeln!(w, "}}")
+    }
+}
+
+/// Generates a mock implementation of an interface, to test code using the interface without the
+/// real service.
+pub struct GenMock<'i> {
+    interface: &'i Interface<'i>,
+    options: &'i GenerateOptions<'i>,
+}
+
+impl<'i> GenMock<'i> {
+    /// Generate a mock implementation of `interface`, with the types and formatting of `options`.
+    pub fn new(interface: &'i Interface<'i>, options: &'i GenerateOptions<'i>) -> Self {
+        Self { interface, options }
+    }
+}
+
+impl Display for GenMock<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.options.format {
+            let mut unformatted = String::new();
+            self.write_mock(&mut unformatted)?;
+
+            let formatted = format_generated_code(&unformatted).unwrap_or(unformatted);
+
+            write!(f, "{}", formatted)
+        } else {
+            self.write_mock(f)
+        }
+    }
+}
+
+impl GenMock<'_> {
+    fn write_mock<W: Write>(&self, w: &mut W) -> std::fmt::Result {
+        let iface = self.interface;
+        let idx = iface.name().rfind('.').unwrap() + 1;
+        let name = &iface.name()[idx..];
+        let mock = format!("{name}Mock");
+        let call = format!("{name}Call");
+
+        let mut methods = iface.methods().to_vec();
+        methods.sort_by(|a, b| a.name().partial_cmp(&b.name()).unwrap());
+        let mut props = iface.properties().to_vec();
+        props.sort_by(|a, b| a.name().partial_cmp(&b.name()).unwrap());
+
+        // The (field name, type) of the in args, and the output type of each method.
+        let methods: Vec<_> = methods
+            .iter()
+            .map(|m| {
+                let member = m.name();
+                let mut n = 0;
+                let mut inputs = vec![];
+                let mut outputs = vec![];
+                for a in m.args() {
+                    let ty = self
+                        .options
+                        .types
+                        .arg_type(member.as_str(), a, false, false);
+                    match a.direction() {
+                        None | Some(ArgDirection::In) => {
+                            n += 1;
+                            let arg = match a.name() {
+                                Some(name) => to_identifier(name),
+                                None => format!("arg_{n}"),
+                            };
+                            inputs.push((arg, ty));
+                        }
+                        Some(ArgDirection::Out) => outputs.push(ty),
+                    }
+                }
+                let output = match outputs.len() {
+                    0 => None,
+                    1 => Some(outputs.remove(0)),
+                    _ => Some(format!("({})", outputs.join(", "))),
+                };
+
+                (m, inputs, output)
+            })
+            .collect();
+        let variant_names: Vec<_> = methods
+            .iter()
+            .map(|(m, _, _)| pascal_case(&to_snakecase(m.name().as_str())))
+            .collect();
+
+        writeln!(
+            w,
+            "/// Mock implementation of the `{}` interface, for tests.",
+            iface.name()
+        )?;
+        writeln!(w, "///")?;
+        writeln!(
+            w,
+            "/// The method calls and property changes are recorded in `calls`, while the replies to"
+        )?;
+        writeln!(
+            w,
+            "/// the method calls and the property values are provided by the handlers. Unless their"
+        )?;
+        writeln!(
+            w,
+            "/// handler is set, methods without a return value succeed and the others fail."
+        )?;
+        writeln!(w, "#[allow(clippy::type_complexity)]")?;
+        writeln!(w, "#[derive(Default)]")?;
+        writeln!(w, "pub struct {mock} {{")?;
+        writeln!(
+            w,
+            "    /// The method calls and property changes received, in order."
+        )?;
+        writeln!(w, "    pub calls: Vec<{call}>,")?;
+        for (m, inputs, output) in &methods {
+            let field = to_identifier(&to_snakecase(m.name().as_str()));
+            let params: Vec<_> = inputs.iter().map(|(_, ty)| format!("&{ty}")).collect();
+            let output = output.as_deref().unwrap_or("()");
+            writeln!(w, "    /// The handler of the `{}` method.", m.name())?;
+            writeln!(
+                w,
+                "    pub {field}: Option<Box<dyn Fn({}) -> zbus::fdo::Result<{output}> + Send + Sync>>,",
+                params.join(", "),
+            )?;
+        }
+        for p in props.iter().filter(|p| p.access().read()) {
+            let field = to_identifier(&to_snakecase(p.name().as_str()));
+            let ty = self
+                .options
+                .types
+                .property_type(p.name().as_str(), p.ty(), false, false);
+            writeln!(w, "    /// The handler of the `{}` property.", p.name())?;
+            writeln!(
+                w,
+                "    pub {field}: Option<Box<dyn Fn() -> zbus::fdo::Result<{ty}> + Send + Sync>>,",
+            )?;
+        }
+        writeln!(w, "}}")?;
+        writeln!(w)?;
+
+        writeln!(
+            w,
+            "/// A method call or property change received by [`{mock}`]."
+        )?;
+        writeln!(w, "#[derive(Debug)]")?;
+        writeln!(w, "pub enum {call} {{")?;
+        for ((m, inputs, _), variant) in methods.iter().zip(&variant_names) {
+            writeln!(w, "    /// A `{}` method call.", m.name())?;
+            if inputs.is_empty() {
+                writeln!(w, "    {variant},")?;
+            } else {
+                let fields: Vec<_> = inputs
+                    .iter()
+                    .map(|(arg, ty)| format!("{arg}: {ty}"))
+                    .collect();
+                writeln!(w, "    {variant} {{ {} }},", fields.join(", "))?;
+            }
+        }
+        for p in props.iter().filter(|p| p.access().write()) {
+            let ty = self
+                .options
+                .types
+                .property_type(p.name().as_str(), p.ty(), false, false);
+            writeln!(w, "    /// A change of the `{}` property.", p.name())?;
+            writeln!(w, "    {}({ty}),", set_variant_name(p, &variant_names))?;
+        }
+        writeln!(w, "}}")?;
+        writeln!(w)?;
+
+        writeln!(w, "#[zbus::interface(name = \"{}\")]", iface.name())?;
+        writeln!(w, "impl {mock} {{")?;
+        for ((m, inputs, output), variant) in methods.iter().zip(&variant_names) {
+            let name = to_identifier(&to_snakecase(m.name().as_str()));
+            let params: Vec<_> = ["&mut self".to_string()]
+                .into_iter()
+                .chain(inputs.iter().map(|(arg, ty)| format!("{arg}: {ty}")))
+                .collect();
+            let args: Vec<_> = inputs.iter().map(|(arg, _)| format!("&{arg}")).collect();
+            let output_ty = output.as_deref().unwrap_or("()");
+            writeln!(w)?;
+            writeln!(w, "    /// {} method", m.name())?;
+            if pascal_case(&name) != m.name().as_str() {
+                writeln!(w, "    #[zbus(name = \"{}\")]", m.name())?;
+            }
+            hide_clippy_lints(w, m)?;
+            writeln!(
+                w,
+                "    async fn {name}({}) -> zbus::fdo::Result<{output_ty}> {{",
+                params.join(", "),
+            )?;
+            writeln!(w, "        let reply = match &self.{name} {{")?;
+            writeln!(
+                w,
+                "            Some(handler) => handler({}),",
+                args.join(", ")
+            )?;
+            if output.is_some() {
+                writeln!(
+                    w,
+                    "            None => Err(zbus::fdo::Error::Failed(\"No handler for `{}`\".to_string())),",
+                    m.name(),
+                )?;
+            } else {
+                writeln!(w, "            None => Ok(()),")?;
+            }
+            writeln!(w, "        }};")?;
+            if inputs.is_empty() {
+                writeln!(w, "        self.calls.push({call}::{variant});")?;
+            } else {
+                let fields: Vec<_> = inputs.iter().map(|(arg, _)| arg.as_str()).collect();
+                writeln!(
+                    w,
+                    "        self.calls.push({call}::{variant} {{ {} }});",
+                    fields.join(", "),
+                )?;
+            }
+            writeln!(w)?;
+            writeln!(w, "        reply")?;
+            writeln!(w, "    }}")?;
+        }
+
+        write_server_signals(w, iface, &self.options.types)?;
+
+        for p in &props {
+            let name = to_identifier(&to_snakecase(p.name().as_str()));
+            let (getter_attribute, setter_attribute) = property_attributes(iface, p, &name);
+            let ty = self
+                .options
+                .types
+                .property_type(p.name().as_str(), p.ty(), false, false);
+
+            writeln!(w)?;
+            writeln!(w, "    /// {} property", p.name())?;
+            if p.access().read() {
+                writeln!(w, "{}", getter_attribute)?;
+                hide_clippy_type_complexity_lint(w, p.ty())?;
+                writeln!(
+                    w,
+                    "    async fn {name}(&self) -> zbus::fdo::Result<{ty}> {{"
+                )?;
+                writeln!(w, "        match &self.{name} {{")?;
+                writeln!(w, "            Some(handler) => handler(),")?;
+                writeln!(
+                    w,
+                    "            None => Err(zbus::fdo::Error::Failed(\"No handler for `{}`\".to_string())),",
+                    p.name(),
+                )?;
+                writeln!(w, "        }}")?;
+                writeln!(w, "    }}")?;
+            }
+
+            if p.access().write() {
+                writeln!(w, "{}", setter_attribute)?;
+                hide_clippy_type_complexity_lint(w, p.ty())?;
+                writeln!(
+                    w,
+                    "    async fn set_{name}(&mut self, value: {ty}) -> zbus::fdo::Result<()> {{",
+                )?;
+                writeln!(
+                    w,
+                    "        self.calls.push({call}::{}(value));",
+                    set_variant_name(p, &variant_names),
+                )?;
+                writeln!(w)?;
+                writeln!(w, "        Ok(())")?;
+                writeln!(w, "    }}")?;
+            }
+        }
+        writeln!(w, "}}")
+    }
+}
+
+/// Generates owned structs for the arguments of the signals of an interface.
+///
+/// Unlike the `Args` structs generated by the [`zbus::proxy`] macro, these don't borrow from the
+/// message so they can be kept around, e.g. in the state of the application.
+pub struct GenSignalArgs<'i> {
+    interface: &'i Interface<'i>,
+    options: &'i GenerateOptions<'i>,
+}
+
+impl<'i> GenSignalArgs<'i> {
+    /// Generate the signal argument structs of `interface`, with the types and formatting of `options`.
+    pub fn new(interface: &'i Interface<'i>, options: &'i GenerateOptions<'i>) -> Self {
+        Self { interface, options }
+    }
+}
+
+impl Display for GenSignalArgs<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.options.format {
+            let mut unformatted = String::new();
+            self.write_signal_args(&mut unformatted)?;
+
+            let formatted = format_generated_code(&unformatted).unwrap_or(unformatted);
+
+            write!(f, "{}", formatted)
+        } else {
+            self.write_signal_args(f)
+        }
+    }
+}
+
+impl GenSignalArgs<'_> {
+    fn write_signal_args<W: Write>(&self, w: &mut W) -> std::fmt::Result {
+        let mut signals = self.interface.signals().to_vec();
+        signals.sort_by(|a, b| a.name().partial_cmp(&b.name()).unwrap());
+        for (i, signal) in signals.iter().filter(|s| !s.args().is_empty()).enumerate() {
+            let member = signal.name();
+            let name = format!("{}SignalArgs", pascal_case(&to_snakecase(member.as_str())));
+            let mut n = 0;
+            let fields: Vec<_> = signal
+                .args()
+                .iter()
+                .map(|a| {
+                    n += 1;
+                    let field = match a.name() {
+                        Some(name) => to_identifier(name),
+                        None => format!("arg_{n}"),
+                    };
+                    let ty = self
+                        .options
+                        .types
+                        .arg_type(member.as_str(), a, false, false);
+
+                    (field, ty)
+                })
+                .collect();
+            let names: Vec<_> = fields.iter().map(|(f, _)| f.as_str()).collect();
+            let types: Vec<_> = fields.iter().map(|(_, ty)| ty.as_str()).collect();
+
+            if i > 0 {
+                writeln!(w)?;
+            }
+            writeln!(w, "/// The arguments of the `{member}` signal.")?;
+            writeln!(w, "#[derive(Debug)]")?;
+            writeln!(w, "pub struct {name} {{")?;
+            for (field, ty) in &fields {
+                writeln!(w, "    pub {field}: {ty},")?;
+            }
+            writeln!(w, "}}")?;
+            writeln!(w)?;
+            writeln!(w, "impl TryFrom<&zbus::message::Message> for {name} {{")?;
+            writeln!(w, "    type Error = zbus::Error;")?;
+            writeln!(w)?;
+            writeln!(
+                w,
+                "    fn try_from(msg: &zbus::message::Message) -> zbus::Result<Self> {{"
+            )?;
+            writeln!(
+                w,
+                "        let ({},): ({},) = msg.body().deserialize()?;",
+                names.join(", "),
+                types.join(", "),
+            )?;
+            writeln!(w)?;
+            writeln!(w, "        Ok(Self {{ {} }})", names.join(", "))?;
+            writeln!(w, "    }}")?;
+            writeln!(w, "}}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The name of the mock call variant for setting `property`, avoiding conflicts with the method
+/// call variants.
+fn set_variant_name(property: &zbus_xml::Property<'_>, method_variants: &[String]) -> String {
+    let name = format!(
+        "Set{}",
+        pascal_case(&to_snakecase(property.name().as_str()))
+    );
+    if method_variants.contains(&name) {
+        format!("{name}Property")
+    } else {
+        name
+    }
+}
+
+fn write_server_signals<W: Write>(
+    w: &mut W,
+    iface: &Interface<'_>,
+    types: &TypeMap,
+) -> std::fmt::Result {
+    let mut signals = iface.signals().to_vec();
+    signals.sort_by(|a, b| a.name().partial_cmp(&b.name()).unwrap());
+    for signal in &signals {
+        let args = parse_signal_args(signal.name().as_str(), signal.args(), types).replacen(
+            "&self",
+            "emitter: &zbus::object_server::SignalEmitter<'_>",
+            1,
+        );
+        let name = to_identifier(&to_snakecase(signal.name().as_str()));
+        writeln!(w)?;
+        write_doc(w, "    ", signal.annotations(), || {
+            format!("{} signal", signal.name())
+        })?;
+        if pascal_case(&name) != signal.name().as_str() {
+            writeln!(w, "    #[zbus(signal, name = \"{}\")]", signal.name())?;
+        } else {
+            writeln!(w, "    #[zbus(signal)]")?;
+        }
+        writeln!(w, "    async fn {name}({args}) -> zbus::Result<()>;",)?;
+    }
+
+    Ok(())
+}
+
+/// Write a `#[deprecated]` attribute if the member is annotated as deprecated.
+fn write_deprecated<W: Write>(w: &mut W, annotations: &[Annotation]) -> std::fmt::Result {
+    let deprecated = annotations
+        .iter()
+        .any(|a| a.name() == "org.freedesktop.DBus.Deprecated" && a.value() == "true");
+    if deprecated {
+        writeln!(w, "    #[deprecated]")?;
+    }
+
+    Ok(())
+}
+
+/// The `zbus` attributes of the getter and of the setter of the property `p`, named `name` in Rust.
+fn property_attributes(
+    iface: &Interface<'_>,
+    p: &zbus_xml::Property<'_>,
+    name: &str,
+) -> (String, String) {
+    let name = if pascal_case(name) != p.name().as_str() {
+        format!(", name = \"{}\"", p.name())
+    } else {
+        String::new()
+    };
+    // The macro only accepts `emits_changed_signal` on getters.
+    let getter = match emits_changed_signal(iface, p) {
+        Some(value) => format!("    #[zbus(property(emits_changed_signal = \"{value}\"){name})]"),
+        None => format!("    #[zbus(property{name})]"),
+    };
+    let setter = format!("    #[zbus(property{name})]");
+
+    (getter, setter)
+}
+
+/// The value of the `EmitsChangedSignal` annotation of the property `p`, or else of `iface`, unless
+/// it's the default `true`.
+fn emits_changed_signal<'a>(
+    iface: &'a Interface<'_>,
+    p: &'a zbus_xml::Property<'_>,
+) -> Option<&'a str> {
+    p.annotations()
+        .iter()
+        .chain(iface.annotations())
+        .find(|a| a.name() == "org.freedesktop.DBus.Property.EmitsChangedSignal")
+        .map(|a| a.value())
+        .filter(|value| ["invalidates", "const", "false"].contains(value))
+}
+
+/// Write the documentation from the `DocString` annotations, if any, or the `fallback` one-liner.
+fn write_doc<W: Write>(
+    w: &mut W,
+    indent: &str,
+    annotations: &[Annotation],
+    fallback: impl FnOnce() -> String,
+) -> std::fmt::Result {
+    if !write_doc_string(w, indent, annotations)? {
+        writeln!(w, "{indent}/// {}", fallback())?;
+    }
+
+    Ok(())
+}
+
+/// Write the documentation from the `DocString` annotations, if any.
+///
+/// Returns whether any documentation was written.
+fn write_doc_string<W: Write>(
+    w: &mut W,
+    indent: &str,
+    annotations: &[Annotation],
+) -> Result<bool, std::fmt::Error> {
+    let Some(doc) = annotations
+        .iter()
+        .find(|a| DOC_STRING_ANNOTATIONS.contains(&a.name()))
+        .map(|a| a.value())
+    else {
+        return Ok(false);
+    };
+
+    // Strip the indentation common to all the lines, and the leading and trailing empty lines.
+    let lines: Vec<&str> = doc.lines().map(str::trim_end).collect();
+    let common_indent = lines
+        .iter()
+        .filter(|l| !l.is_empty())
+        .map(|l| &l[..l.len() - l.trim_start().len()])
+        .reduce(|common, indent| {
+            // The indentation may mix different, even multi-byte, whitespace characters.
+            let len = common
+                .char_indices()
+                .zip(indent.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or(common.len().min(indent.len()), |((i, _), _)| i);
+            &common[..len]
+        });
+    let Some(common_indent) = common_indent else {
+        return Ok(false);
+    };
+    let first = lines.iter().position(|l| !l.is_empty()).unwrap();
+    let last = lines.iter().rposition(|l| !l.is_empty()).unwrap();
+    for line in &lines[first..=last] {
+        match line.strip_prefix(common_indent) {
+            Some("") | None => writeln!(w, "{indent}///")?,
+            Some(line) => writeln!(w, "{indent}/// {line}")?,
+        }
+    }
+
+    Ok(true)
+}
+
+const DOC_STRING_ANNOTATIONS: &[&str] =
+    &["org.freedesktop.DBus.DocString", "org.gtk.GDBus.DocString"];
+
 fn hide_clippy_lints<W: Write>(write: &mut W, method: &zbus_xml::Method<'_>) -> std::fmt::Result {
     // check for <https://rust-lang.github.io/rust-clippy/master/index.html#/too_many_arguments>
     // triggers when a functions has at least 7 paramters
@@ -259,7 +1264,7 @@ fn hide_clippy_type_complexity_lint<W: Write>(
     Ok(())
 }
 
-fn inputs_output_from_args(args: &[Arg]) -> (String, String) {
+fn inputs_output_from_args(member: &str, args: &[Arg], types: &TypeMap) -> (String, String) {
     let mut inputs = vec!["&self".to_string()];
     let mut output = vec![];
     let mut n = 0;
@@ -271,7 +1276,7 @@ fn inputs_output_from_args(args: &[Arg]) -> (String, String) {
     for a in args {
         match a.direction() {
             None | Some(ArgDirection::In) => {
-                let ty = to_rust_type(a.ty(), true, true);
+                let ty = types.arg_type(member, a, true, true);
                 let arg = if let Some(name) = a.name() {
                     to_identifier(name)
                 } else {
@@ -280,7 +1285,7 @@ fn inputs_output_from_args(args: &[Arg]) -> (String, String) {
                 inputs.push(format!("{arg}: {ty}"));
             }
             Some(ArgDirection::Out) => {
-                let ty = to_rust_type(a.ty(), false, false);
+                let ty = types.arg_type(member, a, false, false);
                 output.push(ty);
             }
         }
@@ -295,8 +1300,9 @@ fn inputs_output_from_args(args: &[Arg]) -> (String, String) {
     (inputs.join(", "), format!(" -> zbus::Result<{output}>"))
 }
 
-fn parse_signal_args(args: &[Arg]) -> String {
+fn server_inputs_output_from_args(member: &str, args: &[Arg], types: &TypeMap) -> (String, String) {
     let mut inputs = vec!["&self".to_string()];
+    let mut output = vec![];
     let mut n = 0;
     let mut gen_name = || {
         n += 1;
@@ -304,7 +1310,44 @@ fn parse_signal_args(args: &[Arg]) -> String {
     };
 
     for a in args {
-        let ty = to_rust_type(a.ty(), true, false);
+        // Unlike the proxy, the arguments are received and the outputs sent so owned types are
+        // used for both.
+        let ty = types.arg_type(member, a, false, false);
+        match a.direction() {
+            None | Some(ArgDirection::In) => {
+                let arg = if let Some(name) = a.name() {
+                    to_identifier(name)
+                } else {
+                    gen_name()
+                };
+                inputs.push(format!("{arg}: {ty}"));
+            }
+            Some(ArgDirection::Out) => output.push(ty),
+        }
+    }
+
+    let output = match output.len() {
+        0 => "()".to_string(),
+        1 => output[0].to_string(),
+        _ => format!("({})", output.join(", ")),
+    };
+
+    (
+        inputs.join(", "),
+        format!(" -> zbus::fdo::Result<{output}>"),
+    )
+}
+
+fn parse_signal_args(member: &str, args: &[Arg], types: &TypeMap) -> String {
+    let mut inputs = vec!["&self".to_string()];
+    let mut n = 0;
+    let mut gen_name = || {
+        n += 1;
+        format!("arg_{n}")
+    };
+
+    for a in args {
+        let ty = types.arg_type(member, a, true, false);
         let arg = if let Some(name) = a.name() {
             to_identifier(name)
         } else {
@@ -316,9 +1359,18 @@ fn parse_signal_args(args: &[Arg]) -> String {
     inputs.join(", ")
 }
 
-fn to_rust_type(ty: &Signature, input: bool, as_ref: bool) -> String {
+fn to_rust_type(ty: &Signature, input: bool, as_ref: bool, types: &TypeMap) -> String {
     // can't haz recursive closure, yet
-    fn signature_to_rust_type(signature: &Signature, input: bool, as_ref: bool) -> String {
+    fn signature_to_rust_type(
+        signature: &Signature,
+        input: bool,
+        as_ref: bool,
+        types: &TypeMap,
+    ) -> String {
+        if let Some(mapped) = types.signatures.get(&signature.to_string()) {
+            return mapped_type(mapped, input, as_ref);
+        }
+
         match signature {
             Signature::Unit => "".into(),
             Signature::U8 => "u8".into(),
@@ -361,7 +1413,7 @@ fn to_rust_type(ty: &Signature, input: bool, as_ref: bool) -> String {
             }
             Signature::Variant => "zbus::zvariant::OwnedValue".into(),
             Signature::Array(child) => {
-                let child_ty = signature_to_rust_type(child, input, as_ref);
+                let child_ty = signature_to_rust_type(child, input, as_ref, types);
                 if input && as_ref {
                     format!("&[{}]", child_ty)
                 } else {
@@ -369,15 +1421,15 @@ fn to_rust_type(ty: &Signature, input: bool, as_ref: bool) -> String {
                 }
             }
             Signature::Dict { key, value } => {
-                let key_ty = signature_to_rust_type(key, input, as_ref);
-                let value_ty = signature_to_rust_type(value, input, as_ref);
+                let key_ty = signature_to_rust_type(key, input, as_ref, types);
+                let value_ty = signature_to_rust_type(value, input, as_ref, types);
 
                 format!("std::collections::HashMap<{}, {}>", key_ty, value_ty)
             }
             Signature::Structure(fields) => {
                 let fields = fields
                     .iter()
-                    .map(|f| signature_to_rust_type(f, input, as_ref))
+                    .map(|f| signature_to_rust_type(f, input, as_ref, types))
                     .collect::<Vec<_>>();
 
                 if fields.len() > 1 {
@@ -391,7 +1443,7 @@ fn to_rust_type(ty: &Signature, input: bool, as_ref: bool) -> String {
         }
     }
 
-    signature_to_rust_type(ty, input, as_ref)
+    signature_to_rust_type(ty, input, as_ref, types)
 }
 
 static KWORDS: &[&str] = &[
@@ -464,10 +1516,222 @@ fn estimate_type_complexity(signature: &Signature) -> u32 {
     score
 }
 
+/// A difference between two versions of the generated code, see [`diff_members`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemberDiff {
+    /// A declaration only present in the newly generated code.
+    Added(String),
+    /// A declaration only present in the existing code.
+    Removed(String),
+    /// A declaration that changed.
+    Changed {
+        /// The existing declaration.
+        old: String,
+        /// The newly generated declaration.
+        new: String,
+    },
+}
+
+impl Display for MemberDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let write_lines = |f: &mut Formatter<'_>, prefix, decl: &str| {
+            let lines: Vec<_> = decl.lines().map(|l| format!("{prefix} {l}")).collect();
+            write!(f, "{}", lines.join("\n"))
+        };
+
+        match self {
+            MemberDiff::Added(new) => write_lines(f, '+', new),
+            MemberDiff::Removed(old) => write_lines(f, '-', old),
+            MemberDiff::Changed { old, new } => {
+                write_lines(f, '-', old)?;
+                writeln!(f)?;
+                write_lines(f, '+', new)
+            }
+        }
+    }
+}
+
+/// Compare the `existing` generated code with the newly `generated` one, member by member.
+///
+/// The items are matched by name, and the members of traits and `impl` blocks (the methods,
+/// properties and signals) by their name within the item. Formatting, documentation and imports
+/// are ignored, so only the changes to the interfaces themselves are reported. This is useful to
+/// detect that a service's API drifted away from the code generated for it.
+pub fn diff_members(existing: &str, generated: &str) -> Result<Vec<MemberDiff>, syn::Error> {
+    let existing = declarations(existing)?;
+    let generated = declarations(generated)?;
+
+    let mut diff = vec![];
+    for (key, old) in &existing {
+        match generated.iter().find(|(k, _)| k == key) {
+            Some((_, new)) if new != old => diff.push(MemberDiff::Changed {
+                old: old.clone(),
+                new: new.clone(),
+            }),
+            Some(_) => (),
+            None => diff.push(MemberDiff::Removed(old.clone())),
+        }
+    }
+    for (key, new) in &generated {
+        if !existing.iter().any(|(k, _)| k == key) {
+            diff.push(MemberDiff::Added(new.clone()));
+        }
+    }
+
+    Ok(diff)
+}
+
+/// The declarations of the items in `code`, and of the members of its traits and `impl` blocks,
+/// keyed by their (qualified) name.
+fn declarations(code: &str) -> Result<Vec<(String, String)>, syn::Error> {
+    use syn::{ImplItem, Item, TraitItem};
+
+    let strip_docs = |attrs: &mut Vec<syn::Attribute>| attrs.retain(|a| !a.path().is_ident("doc"));
+    let unparse = |item: Item| {
+        let file = syn::File {
+            shebang: None,
+            attrs: vec![],
+            items: vec![item],
+        };
+        prettyplease::unparse(&file).trim_end().to_string()
+    };
+    // The members are declared in a dummy item, to be rendered on their own.
+    let unparse_member = |item: Item| {
+        let decl = unparse(item);
+        let lines: Vec<_> = decl.lines().collect();
+        lines[1..lines.len() - 1]
+            .iter()
+            .map(|l| l.strip_prefix("    ").unwrap_or(l))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let mut decls = vec![];
+    for item in syn::parse_file(code)?.items {
+        match item {
+            Item::Use(_) => (),
+            Item::Trait(mut t) => {
+                strip_docs(&mut t.attrs);
+                let name = t.ident.to_string();
+                let members = std::mem::take(&mut t.items);
+                decls.push((name.clone(), unparse(Item::Trait(t))));
+
+                for mut member in members {
+                    let ident = match &mut member {
+                        TraitItem::Fn(f) => {
+                            strip_docs(&mut f.attrs);
+                            Some(&f.sig.ident)
+                        }
+                        TraitItem::Const(c) => {
+                            strip_docs(&mut c.attrs);
+                            Some(&c.ident)
+                        }
+                        TraitItem::Type(t) => {
+                            strip_docs(&mut t.attrs);
+                            Some(&t.ident)
+                        }
+                        _ => None,
+                    }
+                    .map(ToString::to_string);
+                    let mut wrapper: syn::ItemTrait = syn::parse_str("trait T {}")?;
+                    wrapper.items.push(member);
+                    let decl = unparse_member(Item::Trait(wrapper));
+                    let key = format!("{name}::{}", ident.unwrap_or_else(|| decl.clone()));
+                    decls.push((key, decl));
+                }
+            }
+            Item::Impl(mut i) => {
+                strip_docs(&mut i.attrs);
+                let members = std::mem::take(&mut i.items);
+                let mut header = i.clone();
+                header.attrs.clear();
+                let name = unparse(Item::Impl(header));
+                decls.push((name.clone(), unparse(Item::Impl(i))));
+
+                for mut member in members {
+                    let ident = match &mut member {
+                        ImplItem::Fn(f) => {
+                            strip_docs(&mut f.attrs);
+                            Some(&f.sig.ident)
+                        }
+                        ImplItem::Const(c) => {
+                            strip_docs(&mut c.attrs);
+                            Some(&c.ident)
+                        }
+                        ImplItem::Type(t) => {
+                            strip_docs(&mut t.attrs);
+                            Some(&t.ident)
+                        }
+                        _ => None,
+                    }
+                    .map(ToString::to_string);
+                    let mut wrapper: syn::ItemImpl = syn::parse_str("impl T {}")?;
+                    wrapper.items.push(member);
+                    let decl = unparse_member(Item::Impl(wrapper));
+                    let key = format!("{name}::{}", ident.unwrap_or_else(|| decl.clone()));
+                    decls.push((key, decl));
+                }
+            }
+            mut item => {
+                let ident = match &mut item {
+                    Item::Struct(s) => {
+                        strip_docs(&mut s.attrs);
+                        s.fields.iter_mut().for_each(|f| strip_docs(&mut f.attrs));
+                        Some(&s.ident)
+                    }
+                    Item::Enum(e) => {
+                        strip_docs(&mut e.attrs);
+                        for variant in &mut e.variants {
+                            strip_docs(&mut variant.attrs);
+                            variant
+                                .fields
+                                .iter_mut()
+                                .for_each(|f| strip_docs(&mut f.attrs));
+                        }
+                        Some(&e.ident)
+                    }
+                    Item::Type(t) => {
+                        strip_docs(&mut t.attrs);
+                        Some(&t.ident)
+                    }
+                    Item::Const(c) => {
+                        strip_docs(&mut c.attrs);
+                        Some(&c.ident)
+                    }
+                    Item::Fn(f) => {
+                        strip_docs(&mut f.attrs);
+                        Some(&f.sig.ident)
+                    }
+                    _ => None,
+                }
+                .map(ToString::to_string);
+                let decl = unparse(item);
+                decls.push((ident.unwrap_or_else(|| decl.clone()), decl));
+            }
+        }
+    }
+
+    Ok(decls)
+}
+
 fn format_generated_code(generated_code: &str) -> std::io::Result<String> {
+    rustfmt(generated_code).or_else(|e| {
+        // `rustfmt` isn't always available, e.g. in minimal containers and build environments, so
+        // fall back to the in-process formatter. It doesn't preserve the blank lines between items
+        // but that's better than no formatting at all.
+        let file = syn::parse_file(generated_code)
+            .map_err(|parse_err| std::io::Error::other(format!("{e}, {parse_err}")))?;
+
+        Ok(prettyplease::unparse(&file))
+    })
+}
+
+fn rustfmt(generated_code: &str) -> std::io::Result<String> {
     use std::io::{Read, Write};
 
     let mut process = Command::new("rustfmt")
+        // Needed for `async fn` in the server skeletons.
+        .args(["--edition", "2021"])
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         // rustfmt may post warnings about features not being enabled on stable rust
@@ -479,7 +1743,10 @@ fn format_generated_code(generated_code: &str) -> std::io::Result<String> {
     writeln!(rustfmt_stdin)?;
     rustfmt_stdin.write_all(generated_code.as_bytes())?;
 
-    process.wait()?;
+    let status = process.wait()?;
+    if !status.success() {
+        return Err(std::io::Error::other(format!("rustfmt failed: {status}")));
+    }
     let mut formatted = String::new();
     rustfmt_stdout.read_to_string(&mut formatted)?;
 
@@ -1,9 +1,11 @@
 #![deny(rust_2018_idioms)]
 
 use std::{
+    collections::VecDeque,
     error::Error,
     fs::{File, OpenOptions},
     io::Write,
+    path::{Path, PathBuf},
 };
 
 use clap::Parser;
@@ -15,7 +17,10 @@ use zbus::{
 };
 use zbus_xml::{Interface, Node};
 
-use zbus_xmlgen::write_interfaces;
+use zbus_xmlgen::{
+    diff_members, strip_doc_header, write_interfaces_with_options, GenerateOptions,
+    InterfaceFilter, ProxyDefault, TypeMap,
+};
 
 mod cli;
 
@@ -23,20 +28,21 @@ enum OutputTarget {
     SingleFile(File),
     Stdout,
     MultipleFiles,
+    Directory(PathBuf),
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     let args = cli::Args::parse();
 
-    let DBusInfo(node, service, path, input_src) = match args.command {
+    let DBusInfo(interfaces, service, input_src) = match args.command {
         cli::Command::System {
             service,
             object_path,
-        } => DBusInfo::new(Connection::system()?, service, object_path)?,
+        } => DBusInfo::new(Connection::system()?, service, object_path, args.recursive)?,
         cli::Command::Session {
             service,
             object_path,
-        } => DBusInfo::new(Connection::session()?, service, object_path)?,
+        } => DBusInfo::new(Connection::session()?, service, object_path, args.recursive)?,
         cli::Command::Address {
             address,
             service,
@@ -45,27 +51,114 @@ fn main() -> Result<(), Box<dyn Error>> {
             connection::Builder::address(&*address)?.build()?,
             service,
             object_path,
+            args.recursive,
         )?,
         cli::Command::File { path } => {
-            let input_src = path.file_name().unwrap().to_string_lossy().to_string();
-            let f = File::open(path)?;
-            DBusInfo(Node::from_reader(f)?, None, None, input_src)
+            let (input_src, node) = if path.as_os_str() == "-" {
+                let node = Node::from_reader(std::io::stdin().lock())?;
+                ("standard input".to_string(), node)
+            } else {
+                let input_src = path.file_name().unwrap().to_string_lossy().to_string();
+                let node = Node::from_reader(File::open(path)?)?;
+                (input_src, node)
+            };
+            let mut interfaces = vec![];
+            collect_interfaces(&node, None, &mut interfaces);
+            if args.recursive {
+                let mut nodes: VecDeque<_> = node.nodes().iter().collect();
+                while let Some(node) = nodes.pop_front() {
+                    collect_interfaces(node, None, &mut interfaces);
+                    nodes.extend(node.nodes());
+                }
+            }
+            DBusInfo(interfaces, None, input_src)
         }
     };
 
     let fdo_iface_prefix = "org.freedesktop.DBus";
-    let (fdo_standard_ifaces, needed_ifaces): (Vec<Interface<'_>>, Vec<Interface<'_>>) = node
-        .interfaces()
-        .iter()
-        .cloned()
-        .partition(|i| i.name().starts_with(fdo_iface_prefix));
+    let (fdo_standard_ifaces, needed_ifaces): (Vec<_>, Vec<_>) = interfaces
+        .into_iter()
+        .partition(|(i, _)| i.name().starts_with(fdo_iface_prefix));
+    let fdo_standard_ifaces: Vec<Interface<'_>> =
+        fdo_standard_ifaces.into_iter().map(|(i, _)| i).collect();
+
+    let mut filter = InterfaceFilter::new();
+    for pattern in &args.include {
+        filter.include(pattern);
+    }
+    for pattern in &args.exclude {
+        filter.exclude(pattern);
+    }
+    let needed_ifaces: Vec<_> = needed_ifaces
+        .into_iter()
+        .filter(|(i, _)| filter.matches(i.name().as_str()))
+        .collect();
 
     if !fdo_standard_ifaces.is_empty() {
         eprintln!("Skipping `org.freedesktop.DBus` interfaces, please use https://docs.rs/zbus/latest/zbus/fdo/index.html")
     }
 
+    let mut types = TypeMap::new();
+    for mapping in &args.map_type {
+        let (signature, ty) = parse_mapping(mapping)?;
+        types.map_signature(signature, ty)?;
+    }
+    for mapping in &args.map_member {
+        let (member, ty) = parse_mapping(mapping)?;
+        types.map_member(member, ty);
+    }
+
+    let default_service = args.default_service.map(BusName::try_from).transpose()?;
+    let default_service = ProxyDefault::new(default_service, args.no_defaults);
+    let default_path = args.default_path.map(ObjectPath::try_from).transpose()?;
+    let default_path = ProxyDefault::new(default_path, args.no_defaults);
+
+    let mut options = GenerateOptions {
+        service: default_service.resolve(service),
+        path: None,
+        kind: args.proxy_kind.into(),
+        server: args.server,
+        mock: args.mock,
+        signal_args: args.signal_args,
+        types,
+        filter,
+        format: !args.no_format,
+    };
+
+    let mut generated = vec![];
+    for (interface, path) in needed_ifaces {
+        options.path = default_path.resolve(path);
+
+        let mut output = write_interfaces_with_options(
+            std::slice::from_ref(&interface),
+            &fdo_standard_ifaces,
+            &options,
+            &input_src,
+            env!("CARGO_BIN_NAME"),
+            env!("CARGO_PKG_VERSION"),
+        )?;
+
+        if args.no_header {
+            output = strip_doc_header(&output).to_string();
+        }
+
+        generated.push((interface.name().to_string(), output));
+    }
+
+    if args.check {
+        if !check_outputs(args.output.as_deref(), &generated)? {
+            std::process::exit(1);
+        }
+
+        return Ok(());
+    }
+
     let mut output_target = match args.output.as_deref() {
         Some("-") => OutputTarget::Stdout,
+        Some(path) if path.ends_with('/') || Path::new(path).is_dir() => {
+            std::fs::create_dir_all(path)?;
+            OutputTarget::Directory(PathBuf::from(path))
+        }
         Some(path) => {
             let file = OpenOptions::new()
                 .create(true)
@@ -77,43 +170,250 @@ fn main() -> Result<(), Box<dyn Error>> {
         _ => OutputTarget::MultipleFiles,
     };
 
-    for interface in needed_ifaces {
-        let output = write_interfaces(
-            &[interface.clone()],
-            &fdo_standard_ifaces,
-            service.clone(),
-            path.clone(),
-            &input_src,
-            env!("CARGO_BIN_NAME"),
-            env!("CARGO_PKG_VERSION"),
-        )?;
-
-        let interface_name = interface.name();
+    let mut modules = match output_target {
+        OutputTarget::MultipleFiles | OutputTarget::Directory(_) => {
+            module_names(generated.iter().map(|(name, _)| name.as_str()))?
+        }
+        OutputTarget::SingleFile(_) | OutputTarget::Stdout => vec![],
+    }
+    .into_iter();
+    let mut declared_modules = vec![];
+    for (interface_name, output) in generated {
         match output_target {
-            OutputTarget::Stdout => println!("{}", output),
+            OutputTarget::Stdout => match writeln!(std::io::stdout(), "{}", output) {
+                // The reader is gone, e.g. `head` in a pipeline, so there's nothing left to do.
+                Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => return Ok(()),
+                res => res?,
+            },
             OutputTarget::SingleFile(ref mut file) => {
                 file.write_all(output.as_bytes())?;
                 println!("Generated code for `{}`", interface_name);
             }
             OutputTarget::MultipleFiles => {
-                let filename = interface_name
-                    .split('.')
-                    .last()
-                    .expect("Failed to split name");
-                let filename = to_snakecase(filename);
+                let filename = modules.next().expect("one module per interface");
                 std::fs::write(format!("{}.rs", &filename), output)?;
                 println!("Generated code for `{}` in {}.rs", interface_name, filename);
             }
+            OutputTarget::Directory(ref dir) => {
+                let module = modules.next().expect("one module per interface");
+                let path = dir.join(format!("{}.rs", module));
+                if path.exists() && !args.overwrite {
+                    eprintln!(
+                        "Skipping `{}`: {} already exists, use --overwrite to replace it",
+                        interface_name,
+                        path.display()
+                    );
+                } else {
+                    std::fs::write(&path, output)?;
+                    println!(
+                        "Generated code for `{}` in {}",
+                        interface_name,
+                        path.display()
+                    );
+                }
+                declared_modules.push(module);
+            }
+        };
+    }
+
+    if let OutputTarget::Directory(dir) = output_target {
+        write_mod_rs(&dir, &declared_modules)?;
+    }
+
+    Ok(())
+}
+
+/// Compare the `generated` code with the existing output files, printing the differences.
+///
+/// Returns whether all the files are up to date.
+fn check_outputs(
+    output: Option<&str>,
+    generated: &[(String, String)],
+) -> Result<bool, Box<dyn Error>> {
+    let files: Vec<(PathBuf, String)> = match output {
+        Some("-") => return Err("`--check` needs an output file or directory to compare to".into()),
+        Some(path) if path.ends_with('/') || Path::new(path).is_dir() => {
+            module_names(generated.iter().map(|(name, _)| name.as_str()))?
+                .into_iter()
+                .zip(generated)
+                .map(|(module, (_, code))| {
+                    let path = Path::new(path).join(format!("{}.rs", module));
+                    (path, code.clone())
+                })
+                .collect()
+        }
+        Some(path) => vec![(
+            PathBuf::from(path),
+            generated.iter().map(|(_, code)| code.as_str()).collect(),
+        )],
+        None => module_names(generated.iter().map(|(name, _)| name.as_str()))?
+            .into_iter()
+            .zip(generated)
+            .map(|(module, (_, code))| (PathBuf::from(format!("{}.rs", module)), code.clone()))
+            .collect(),
+    };
+
+    let mut up_to_date = true;
+    for (path, code) in files {
+        let existing = match std::fs::read_to_string(&path) {
+            Ok(existing) => existing,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                println!("{} is missing", path.display());
+                up_to_date = false;
+                continue;
+            }
+            Err(e) => return Err(e.into()),
         };
+        let diff = diff_members(&existing, &code)
+            .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?;
+        if diff.is_empty() {
+            continue;
+        }
+
+        up_to_date = false;
+        println!("{} is out of date:", path.display());
+        for change in diff {
+            println!("{change}");
+        }
+    }
+
+    Ok(up_to_date)
+}
+
+fn parse_mapping(mapping: &str) -> Result<(&str, &str), Box<dyn Error>> {
+    mapping
+        .split_once('=')
+        .map(|(key, ty)| (key.trim(), ty.trim()))
+        .filter(|(key, ty)| !key.is_empty() && !ty.is_empty())
+        .ok_or_else(|| format!("Invalid type mapping `{mapping}`, expected `KEY=TYPE`").into())
+}
+
+/// The name of the module, and of its file, for the code generated for `interface_name`.
+///
+/// The keywords that can't be used as raw identifiers, e.g. `self`, get a trailing `_`. The other
+/// keywords are escaped with `r#` by [`module_declaration`].
+fn module_name(interface_name: &str) -> String {
+    let name = interface_name
+        .split('.')
+        .next_back()
+        .expect("Failed to split name");
+    let name = to_snakecase(name);
+
+    match &*name {
+        "crate" | "self" | "super" => format!("{name}_"),
+        _ => name.into_owned(),
+    }
+}
+
+/// The module names of the code generated for `interface_names`, in the same order.
+///
+/// Fails if several interfaces end up in the same module, since one would overwrite the other.
+fn module_names<'a>(
+    interface_names: impl Iterator<Item = &'a str>,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut modules: Vec<(String, &str)> = vec![];
+    for interface_name in interface_names {
+        let module = module_name(interface_name);
+        if let Some((_, other)) = modules.iter().find(|(m, _)| *m == module) {
+            return Err(format!(
+                "`{other}` and `{interface_name}` would both be generated in the `{module}` \
+                 module, use `--exclude` to skip one of them or `--output` to generate them in a \
+                 single file"
+            )
+            .into());
+        }
+        modules.push((module, interface_name));
+    }
+
+    Ok(modules.into_iter().map(|(module, _)| module).collect())
+}
+
+/// The `pub mod` declaration of `module`, escaping it if it's a keyword.
+fn module_declaration(module: &str) -> String {
+    if syn::parse_str::<syn::Ident>(module).is_ok() {
+        format!("pub mod {module};\n")
+    } else {
+        format!("pub mod r#{module};\n")
+    }
+}
+
+/// Write the `mod.rs` declaring `modules` in `dir`.
+///
+/// If the file already exists, the modules it already declares are kept as they are and only the
+/// missing ones are appended to it.
+fn write_mod_rs(dir: &Path, modules: &[String]) -> Result<(), Box<dyn Error>> {
+    let path = dir.join("mod.rs");
+    let mut content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => format!(
+            "//! D-Bus interfaces generated by `{}`.\n\n",
+            env!("CARGO_BIN_NAME")
+        ),
+        Err(e) => return Err(e.into()),
+    };
+    let declared: Vec<String> = content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix("pub ").unwrap_or(line);
+            line.strip_prefix("mod ")
+                .and_then(|m| m.strip_suffix(';'))
+                .map(|m| {
+                    let m = m.trim();
+                    m.strip_prefix("r#").unwrap_or(m).to_string()
+                })
+        })
+        .collect();
+
+    let mut added = false;
+    for module in modules {
+        if declared.contains(module) {
+            continue;
+        }
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&module_declaration(module));
+        added = true;
+    }
+
+    if added || !path.exists() {
+        std::fs::write(&path, content)?;
+        println!("Updated {}", path.display());
     }
 
     Ok(())
 }
 
+/// Add the interfaces of `node` that are not in `interfaces` yet, along with their object `path`.
+fn collect_interfaces<'a>(
+    node: &Node<'a>,
+    path: Option<&ObjectPath<'a>>,
+    interfaces: &mut Vec<(Interface<'a>, Option<ObjectPath<'a>>)>,
+) {
+    for iface in node.interfaces() {
+        if !interfaces.iter().any(|(i, _)| i.name() == iface.name()) {
+            interfaces.push((iface.clone(), path.cloned()));
+        }
+    }
+}
+
+/// The path of the child node `name` of the object at `parent`.
+fn child_path(parent: &ObjectPath<'_>, name: &str) -> zbus::zvariant::Result<ObjectPath<'static>> {
+    let path = if name.starts_with('/') {
+        name.to_string()
+    } else if parent.as_str() == "/" {
+        format!("/{name}")
+    } else {
+        format!("{parent}/{name}")
+    };
+
+    ObjectPath::try_from(path)
+}
+
 struct DBusInfo<'a>(
-    Node<'a>,
+    Vec<(Interface<'a>, Option<ObjectPath<'a>>)>,
     Option<BusName<'a>>,
-    Option<ObjectPath<'a>>,
     String,
 );
 
@@ -122,29 +422,120 @@ impl DBusInfo<'_> {
         connection: Connection,
         service: String,
         object_path: String,
+        recursive: bool,
     ) -> Result<Self, Box<dyn Error>> {
         let service: BusName<'_> = service.try_into()?;
         let path: ObjectPath<'_> = object_path.try_into()?;
 
-        let input_src = format!(
-            "Interface '{}' from service '{}' on system bus",
-            path, service,
+        let input_src = if recursive {
+            format!(
+                "Interfaces of the objects under '{}' from service '{}' on system bus",
+                path, service,
+            )
+        } else {
+            format!(
+                "Interface '{}' from service '{}' on system bus",
+                path, service,
+            )
+        };
+
+        let mut interfaces = vec![];
+        let mut paths = VecDeque::from([path.clone()]);
+        while let Some(obj_path) = paths.pop_front() {
+            let xml = IntrospectableProxy::builder(&connection)
+                .destination(service.clone())
+                .expect("invalid destination")
+                .path(obj_path.clone())
+                .expect("invalid path")
+                .build()
+                .unwrap()
+                .introspect();
+            let xml = match xml {
+                Ok(xml) => xml,
+                // Don't give up on the whole tree because of a single object.
+                Err(e) if obj_path != path => {
+                    eprintln!("Failed to introspect `{}`: {}", obj_path, e);
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+            let node = Node::from_reader(xml.as_bytes())?;
+            collect_interfaces(&node, Some(&obj_path), &mut interfaces);
+
+            if !recursive {
+                break;
+            }
+            for child in node.nodes() {
+                let Some(name) = child.name() else {
+                    continue;
+                };
+                match child_path(&obj_path, name) {
+                    Ok(child_path) => paths.push_back(child_path),
+                    // Don't give up on the whole tree because of a single bogus node either.
+                    Err(e) => eprintln!("Skipping child `{}` of `{}`: {}", name, obj_path, e),
+                }
+            }
+        }
+
+        Ok(DBusInfo(interfaces, Some(service), input_src))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_names_of_interfaces() {
+        assert_eq!(
+            module_names(
+                ["org.example.FooBar", "org.example.Type", "org.example.Self"].into_iter()
+            )
+            .unwrap(),
+            ["foo_bar", "type", "self_"]
+        );
+        assert_eq!(module_declaration("foo_bar"), "pub mod foo_bar;\n");
+        assert_eq!(module_declaration("type"), "pub mod r#type;\n");
+        assert_eq!(module_declaration("self_"), "pub mod self_;\n");
+
+        let err = module_names(["org.example.Foo", "com.example.Foo"].into_iter()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("`org.example.Foo` and `com.example.Foo`"));
+    }
+
+    #[test]
+    fn child_paths() {
+        let root = ObjectPath::from_static_str_unchecked("/");
+        let parent = ObjectPath::from_static_str_unchecked("/org/example");
+
+        assert_eq!(child_path(&root, "org").unwrap(), "/org");
+        assert_eq!(child_path(&parent, "foo").unwrap(), "/org/example/foo");
+        assert_eq!(child_path(&parent, "/org/other").unwrap(), "/org/other");
+        assert!(child_path(&parent, "foo-bar").is_err());
+        assert!(child_path(&parent, "foo/").is_err());
+    }
+
+    #[test]
+    fn mod_rs_declarations() -> Result<(), Box<dyn Error>> {
+        let dir = std::env::temp_dir().join(format!("zbus-xmlgen-mod-rs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join("mod.rs");
+        std::fs::write(&path, "pub mod foo;\npub mod r#type;\n")?;
+
+        write_mod_rs(&dir, &["foo".to_string(), "type".to_string()])?;
+        assert_eq!(
+            std::fs::read_to_string(&path)?,
+            "pub mod foo;\npub mod r#type;\n"
+        );
+
+        write_mod_rs(&dir, &["type".to_string(), "match".to_string()])?;
+        assert_eq!(
+            std::fs::read_to_string(&path)?,
+            "pub mod foo;\npub mod r#type;\npub mod r#match;\n"
         );
 
-        let xml = IntrospectableProxy::builder(&connection)
-            .destination(service.clone())
-            .expect("invalid destination")
-            .path(path.clone())
-            .expect("invalid path")
-            .build()
-            .unwrap()
-            .introspect()?;
-
-        Ok(DBusInfo(
-            Node::from_reader(xml.as_bytes())?,
-            Some(service),
-            Some(path),
-            input_src,
-        ))
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
     }
 }
@@ -1,3 +1,4 @@
+/// A sample interface.
 #[proxy(interface = "com.example.SampleInterface0", assume_defaults = true)]
 pub trait SampleInterface0 {
     /// BarplexSig method
@@ -16,13 +17,16 @@ pub trait SampleInterface0 {
         ),
     ) -> zbus::Result<Vec<(String, zbus::zvariant::OwnedObjectPath)>>;
 
-    /// Bazic method
+    /// Bazic does the basic things.
+    ///
+    /// It's very basic.
     fn bazic(&self, bar: &(i32, i32), foo: &(i32,)) -> zbus::Result<((i32, i32), Vec<(i32,)>)>;
 
     /// Bazify method
     fn bazify(&self, bar: &(i32, i32, u32)) -> zbus::Result<zbus::zvariant::OwnedValue>;
 
     /// Frobate method
+    #[deprecated]
     fn frobate(
         &self,
         foz: i32,
@@ -50,6 +54,7 @@ pub trait SampleInterface0 {
     fn changed(&self, new_value: bool) -> zbus::Result<()>;
 
     /// Changed2 signal
+    #[deprecated]
     #[zbus(signal)]
     fn changed2(&self, new_value: bool, new_value2: bool) -> zbus::Result<()>;
 
@@ -69,19 +74,21 @@ pub trait SampleInterface0 {
     fn signal_value(&self, value: zbus::zvariant::Value<'_>) -> zbus::Result<()>;
 
     /// Bar property
+    #[deprecated]
     #[zbus(property)]
     fn bar(&self) -> zbus::Result<u8>;
+    #[deprecated]
     #[zbus(property)]
     fn set_bar(&self, value: u8) -> zbus::Result<()>;
 
     /// Foo-Bar property
-    #[zbus(property, name = "Foo-Bar")]
+    #[zbus(property(emits_changed_signal = "invalidates"), name = "Foo-Bar")]
     fn foo_bar(&self) -> zbus::Result<u8>;
     #[zbus(property, name = "Foo-Bar")]
     fn set_foo_bar(&self, value: u8) -> zbus::Result<()>;
 
     /// Matryoshkas property
-    #[zbus(property)]
+    #[zbus(property(emits_changed_signal = "const"))]
     #[allow(clippy::type_complexity)]
     fn matryoshkas(
         &self,
@@ -94,4 +101,8 @@ pub trait SampleInterface0 {
             std::collections::HashMap<String, zbus::zvariant::OwnedValue>,
         )>,
     >;
+
+    /// Secret property
+    #[zbus(property)]
+    fn set_secret(&self, value: &str) -> zbus::Result<()>;
 }
@@ -0,0 +1,374 @@
+/// Mock implementation of the `com.example.SampleInterface0` interface, for tests.
+///
+/// The method calls and property changes are recorded in `calls`, while the replies to
+/// the method calls and the property values are provided by the handlers. Unless their
+/// handler is set, methods without a return value succeed and the others fail.
+#[allow(clippy::type_complexity)]
+#[derive(Default)]
+pub struct SampleInterface0Mock {
+    /// The method calls and property changes received, in order.
+    pub calls: Vec<SampleInterface0Call>,
+    /// The handler of the `BarplexSig` method.
+    pub barplex_sig: Option<
+        Box<
+            dyn Fn(
+                    &(
+                        Vec<i32>,
+                        i32,
+                        std::collections::HashMap<String, String>,
+                        i32,
+                        Vec<i32>,
+                        i32,
+                        Vec<String>,
+                        i32,
+                        bool,
+                    ),
+                )
+                    -> zbus::fdo::Result<Vec<(String, zbus::zvariant::OwnedObjectPath)>>
+                + Send
+                + Sync,
+        >,
+    >,
+    /// The handler of the `Bazic` method.
+    pub bazic: Option<
+        Box<
+            dyn Fn(&(i32, i32), &(i32,)) -> zbus::fdo::Result<((i32, i32), Vec<(i32,)>)>
+                + Send
+                + Sync,
+        >,
+    >,
+    /// The handler of the `Bazify` method.
+    pub bazify: Option<
+        Box<
+            dyn Fn(&(i32, i32, u32)) -> zbus::fdo::Result<zbus::zvariant::OwnedValue> + Send + Sync,
+        >,
+    >,
+    /// The handler of the `Frobate` method.
+    pub frobate: Option<
+        Box<
+            dyn Fn(
+                    &i32,
+                    &i32,
+                )
+                    -> zbus::fdo::Result<(String, std::collections::HashMap<u32, String>)>
+                + Send
+                + Sync,
+        >,
+    >,
+    /// The handler of the `MogrifyMe` method.
+    pub mogrify_me: Option<
+        Box<
+            dyn Fn(&(i32, i32, Vec<zbus::zvariant::OwnedValue>)) -> zbus::fdo::Result<()>
+                + Send
+                + Sync,
+        >,
+    >,
+    /// The handler of the `Odyssey` method.
+    pub odyssey: Option<
+        Box<
+            dyn Fn(
+                    &i32,
+                    &String,
+                    &u32,
+                    &i32,
+                    &bool,
+                    &i32,
+                    &zbus::zvariant::OwnedValue,
+                ) -> zbus::fdo::Result<()>
+                + Send
+                + Sync,
+        >,
+    >,
+    /// The handler of the `Bar` property.
+    pub bar: Option<Box<dyn Fn() -> zbus::fdo::Result<u8> + Send + Sync>>,
+    /// The handler of the `Foo-Bar` property.
+    pub foo_bar: Option<Box<dyn Fn() -> zbus::fdo::Result<u8> + Send + Sync>>,
+    /// The handler of the `Matryoshkas` property.
+    pub matryoshkas: Option<
+        Box<
+            dyn Fn() -> zbus::fdo::Result<
+                    Vec<(
+                        zbus::zvariant::OwnedObjectPath,
+                        i32,
+                        Vec<String>,
+                        u64,
+                        std::collections::HashMap<String, zbus::zvariant::OwnedValue>,
+                    )>,
+                > + Send
+                + Sync,
+        >,
+    >,
+}
+
+/// A method call or property change received by [`SampleInterface0Mock`].
+#[derive(Debug)]
+pub enum SampleInterface0Call {
+    /// A `BarplexSig` method call.
+    BarplexSig {
+        rule: (
+            Vec<i32>,
+            i32,
+            std::collections::HashMap<String, String>,
+            i32,
+            Vec<i32>,
+            i32,
+            Vec<String>,
+            i32,
+            bool,
+        ),
+    },
+    /// A `Bazic` method call.
+    Bazic { bar: (i32, i32), foo: (i32,) },
+    /// A `Bazify` method call.
+    Bazify { bar: (i32, i32, u32) },
+    /// A `Frobate` method call.
+    Frobate { foz: i32, foo: i32 },
+    /// A `MogrifyMe` method call.
+    MogrifyMe {
+        bar: (i32, i32, Vec<zbus::zvariant::OwnedValue>),
+    },
+    /// A `Odyssey` method call.
+    Odyssey {
+        odysseus: i32,
+        penelope: String,
+        telemachus: u32,
+        circe: i32,
+        athena: bool,
+        polyphemus: i32,
+        calypso: zbus::zvariant::OwnedValue,
+    },
+    /// A change of the `Bar` property.
+    SetBar(u8),
+    /// A change of the `Foo-Bar` property.
+    SetFooBar(u8),
+    /// A change of the `Secret` property.
+    SetSecret(String),
+}
+
+#[zbus::interface(name = "com.example.SampleInterface0")]
+impl SampleInterface0Mock {
+    /// BarplexSig method
+    async fn barplex_sig(
+        &mut self,
+        rule: (
+            Vec<i32>,
+            i32,
+            std::collections::HashMap<String, String>,
+            i32,
+            Vec<i32>,
+            i32,
+            Vec<String>,
+            i32,
+            bool,
+        ),
+    ) -> zbus::fdo::Result<Vec<(String, zbus::zvariant::OwnedObjectPath)>> {
+        let reply = match &self.barplex_sig {
+            Some(handler) => handler(&rule),
+            None => Err(zbus::fdo::Error::Failed(
+                "No handler for `BarplexSig`".to_string(),
+            )),
+        };
+        self.calls.push(SampleInterface0Call::BarplexSig { rule });
+
+        reply
+    }
+
+    /// Bazic method
+    async fn bazic(
+        &mut self,
+        bar: (i32, i32),
+        foo: (i32,),
+    ) -> zbus::fdo::Result<((i32, i32), Vec<(i32,)>)> {
+        let reply = match &self.bazic {
+            Some(handler) => handler(&bar, &foo),
+            None => Err(zbus::fdo::Error::Failed(
+                "No handler for `Bazic`".to_string(),
+            )),
+        };
+        self.calls.push(SampleInterface0Call::Bazic { bar, foo });
+
+        reply
+    }
+
+    /// Bazify method
+    async fn bazify(
+        &mut self,
+        bar: (i32, i32, u32),
+    ) -> zbus::fdo::Result<zbus::zvariant::OwnedValue> {
+        let reply = match &self.bazify {
+            Some(handler) => handler(&bar),
+            None => Err(zbus::fdo::Error::Failed(
+                "No handler for `Bazify`".to_string(),
+            )),
+        };
+        self.calls.push(SampleInterface0Call::Bazify { bar });
+
+        reply
+    }
+
+    /// Frobate method
+    async fn frobate(
+        &mut self,
+        foz: i32,
+        foo: i32,
+    ) -> zbus::fdo::Result<(String, std::collections::HashMap<u32, String>)> {
+        let reply = match &self.frobate {
+            Some(handler) => handler(&foz, &foo),
+            None => Err(zbus::fdo::Error::Failed(
+                "No handler for `Frobate`".to_string(),
+            )),
+        };
+        self.calls.push(SampleInterface0Call::Frobate { foz, foo });
+
+        reply
+    }
+
+    /// MogrifyMe method
+    async fn mogrify_me(
+        &mut self,
+        bar: (i32, i32, Vec<zbus::zvariant::OwnedValue>),
+    ) -> zbus::fdo::Result<()> {
+        let reply = match &self.mogrify_me {
+            Some(handler) => handler(&bar),
+            None => Ok(()),
+        };
+        self.calls.push(SampleInterface0Call::MogrifyMe { bar });
+
+        reply
+    }
+
+    /// Odyssey method
+    #[allow(clippy::too_many_arguments)]
+    async fn odyssey(
+        &mut self,
+        odysseus: i32,
+        penelope: String,
+        telemachus: u32,
+        circe: i32,
+        athena: bool,
+        polyphemus: i32,
+        calypso: zbus::zvariant::OwnedValue,
+    ) -> zbus::fdo::Result<()> {
+        let reply = match &self.odyssey {
+            Some(handler) => handler(
+                &odysseus,
+                &penelope,
+                &telemachus,
+                &circe,
+                &athena,
+                &polyphemus,
+                &calypso,
+            ),
+            None => Ok(()),
+        };
+        self.calls.push(SampleInterface0Call::Odyssey {
+            odysseus,
+            penelope,
+            telemachus,
+            circe,
+            athena,
+            polyphemus,
+            calypso,
+        });
+
+        reply
+    }
+
+    /// Changed signal
+    #[zbus(signal)]
+    async fn changed(
+        emitter: &zbus::object_server::SignalEmitter<'_>,
+        new_value: bool,
+    ) -> zbus::Result<()>;
+
+    /// Changed2 signal
+    #[zbus(signal)]
+    async fn changed2(
+        emitter: &zbus::object_server::SignalEmitter<'_>,
+        new_value: bool,
+        new_value2: bool,
+    ) -> zbus::Result<()>;
+
+    /// SignalArrayOfStrings signal
+    #[zbus(signal)]
+    async fn signal_array_of_strings(
+        emitter: &zbus::object_server::SignalEmitter<'_>,
+        array: Vec<&str>,
+    ) -> zbus::Result<()>;
+
+    /// SignalDictStringToValue signal
+    #[zbus(signal)]
+    async fn signal_dict_string_to_value(
+        emitter: &zbus::object_server::SignalEmitter<'_>,
+        dict: std::collections::HashMap<&str, zbus::zvariant::Value<'_>>,
+    ) -> zbus::Result<()>;
+
+    /// SignalValue signal
+    #[zbus(signal)]
+    async fn signal_value(
+        emitter: &zbus::object_server::SignalEmitter<'_>,
+        value: zbus::zvariant::Value<'_>,
+    ) -> zbus::Result<()>;
+
+    /// Bar property
+    #[zbus(property)]
+    async fn bar(&self) -> zbus::fdo::Result<u8> {
+        match &self.bar {
+            Some(handler) => handler(),
+            None => Err(zbus::fdo::Error::Failed("No handler for `Bar`".to_string())),
+        }
+    }
+    #[zbus(property)]
+    async fn set_bar(&mut self, value: u8) -> zbus::fdo::Result<()> {
+        self.calls.push(SampleInterface0Call::SetBar(value));
+
+        Ok(())
+    }
+
+    /// Foo-Bar property
+    #[zbus(property(emits_changed_signal = "invalidates"), name = "Foo-Bar")]
+    async fn foo_bar(&self) -> zbus::fdo::Result<u8> {
+        match &self.foo_bar {
+            Some(handler) => handler(),
+            None => Err(zbus::fdo::Error::Failed(
+                "No handler for `Foo-Bar`".to_string(),
+            )),
+        }
+    }
+    #[zbus(property, name = "Foo-Bar")]
+    async fn set_foo_bar(&mut self, value: u8) -> zbus::fdo::Result<()> {
+        self.calls.push(SampleInterface0Call::SetFooBar(value));
+
+        Ok(())
+    }
+
+    /// Matryoshkas property
+    #[zbus(property(emits_changed_signal = "const"))]
+    #[allow(clippy::type_complexity)]
+    async fn matryoshkas(
+        &self,
+    ) -> zbus::fdo::Result<
+        Vec<(
+            zbus::zvariant::OwnedObjectPath,
+            i32,
+            Vec<String>,
+            u64,
+            std::collections::HashMap<String, zbus::zvariant::OwnedValue>,
+        )>,
+    > {
+        match &self.matryoshkas {
+            Some(handler) => handler(),
+            None => Err(zbus::fdo::Error::Failed(
+                "No handler for `Matryoshkas`".to_string(),
+            )),
+        }
+    }
+
+    /// Secret property
+    #[zbus(property)]
+    async fn set_secret(&mut self, value: String) -> zbus::fdo::Result<()> {
+        self.calls.push(SampleInterface0Call::SetSecret(value));
+
+        Ok(())
+    }
+}
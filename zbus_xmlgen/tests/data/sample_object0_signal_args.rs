@@ -0,0 +1,84 @@
+/// The arguments of the `Changed` signal.
+#[derive(Debug)]
+pub struct ChangedSignalArgs {
+    pub new_value: bool,
+}
+
+impl TryFrom<&zbus::message::Message> for ChangedSignalArgs {
+    type Error = zbus::Error;
+
+    fn try_from(msg: &zbus::message::Message) -> zbus::Result<Self> {
+        let (new_value,): (bool,) = msg.body().deserialize()?;
+
+        Ok(Self { new_value })
+    }
+}
+
+/// The arguments of the `Changed2` signal.
+#[derive(Debug)]
+pub struct Changed2SignalArgs {
+    pub new_value: bool,
+    pub new_value2: bool,
+}
+
+impl TryFrom<&zbus::message::Message> for Changed2SignalArgs {
+    type Error = zbus::Error;
+
+    fn try_from(msg: &zbus::message::Message) -> zbus::Result<Self> {
+        let (new_value, new_value2): (bool, bool) = msg.body().deserialize()?;
+
+        Ok(Self {
+            new_value,
+            new_value2,
+        })
+    }
+}
+
+/// The arguments of the `SignalArrayOfStrings` signal.
+#[derive(Debug)]
+pub struct SignalArrayOfStringsSignalArgs {
+    pub array: Vec<String>,
+}
+
+impl TryFrom<&zbus::message::Message> for SignalArrayOfStringsSignalArgs {
+    type Error = zbus::Error;
+
+    fn try_from(msg: &zbus::message::Message) -> zbus::Result<Self> {
+        let (array,): (Vec<String>,) = msg.body().deserialize()?;
+
+        Ok(Self { array })
+    }
+}
+
+/// The arguments of the `SignalDictStringToValue` signal.
+#[derive(Debug)]
+pub struct SignalDictStringToValueSignalArgs {
+    pub dict: std::collections::HashMap<String, zbus::zvariant::OwnedValue>,
+}
+
+impl TryFrom<&zbus::message::Message> for SignalDictStringToValueSignalArgs {
+    type Error = zbus::Error;
+
+    fn try_from(msg: &zbus::message::Message) -> zbus::Result<Self> {
+        let (dict,): (std::collections::HashMap<String, zbus::zvariant::OwnedValue>,) =
+            msg.body().deserialize()?;
+
+        Ok(Self { dict })
+    }
+}
+
+/// The arguments of the `SignalValue` signal.
+#[derive(Debug)]
+pub struct SignalValueSignalArgs {
+    pub value: zbus::zvariant::OwnedValue,
+}
+
+impl TryFrom<&zbus::message::Message> for SignalValueSignalArgs {
+    type Error = zbus::Error;
+
+    fn try_from(msg: &zbus::message::Message) -> zbus::Result<Self> {
+        let (value,): (zbus::zvariant::OwnedValue,) = msg.body().deserialize()?;
+
+        Ok(Self { value })
+    }
+}
@@ -1,23 +1,36 @@
 use pretty_assertions::assert_eq;
-use std::{env, error::Error, io::Write, path::Path};
+use std::{
+    env,
+    error::Error,
+    io::{Read, Write},
+    path::Path,
+    process::{Command, Stdio},
+};
 
 use zbus_xml::Node;
-use zbus_xmlgen::GenTrait;
+use zbus_xmlgen::{
+    diff_members, generate, strip_doc_header, write_interfaces_with_options, GenInterface, GenMock,
+    GenSignalArgs, GenTrait, GenerateOptions, InterfaceFilter, MemberDiff, ProxyDefault, ProxyKind,
+    TypeMap,
+};
 
 macro_rules! gen_diff {
     ($infile:literal, $outfile:literal) => {{
+        gen_diff!($infile, $outfile, |interface| GenTrait {
+            interface,
+            path: None,
+            service: None,
+            format: true,
+        }
+        .to_string())
+    }};
+    ($infile:literal, $outfile:literal, $gen:expr) => {{
         let input = include_str!(concat!("data/", $infile));
         let expected = include_str!(concat!("data/", $outfile));
         #[cfg(windows)]
         let expected = expected.replace("\r\n", "\n");
         let node = Node::from_reader(input.as_bytes())?;
-        let gen = GenTrait {
-            interface: &node.interfaces()[0],
-            path: None,
-            service: None,
-            format: true,
-        }
-        .to_string();
+        let gen = ($gen)(&node.interfaces()[0]);
 
         if env::var("TEST_OVERWRITE").is_ok() {
             let path = Path::new(env!("CARGO_MANIFEST_DIR"))
@@ -25,6 +38,7 @@ macro_rules! gen_diff {
                 .join("data")
                 .join($outfile);
             let mut f = std::fs::OpenOptions::new()
+                .create(true)
                 .write(true)
                 .truncate(true)
                 .open(path)?;
@@ -42,3 +56,298 @@ macro_rules! gen_diff {
 fn sample_object0() -> Result<(), Box<dyn Error>> {
     gen_diff!("sample_object0.xml", "sample_object0.rs")
 }
+
+#[test]
+fn sample_object0_server() -> Result<(), Box<dyn Error>> {
+    let options = GenerateOptions {
+        format: true,
+        ..Default::default()
+    };
+    gen_diff!(
+        "sample_object0.xml",
+        "sample_object0_server.rs",
+        |interface| GenInterface::new(interface, &options).to_string()
+    )
+}
+
+#[test]
+fn sample_object0_mock() -> Result<(), Box<dyn Error>> {
+    let options = GenerateOptions {
+        format: true,
+        ..Default::default()
+    };
+    gen_diff!(
+        "sample_object0.xml",
+        "sample_object0_mock.rs",
+        |interface| GenMock::new(interface, &options).to_string()
+    )
+}
+
+#[test]
+fn sample_object0_signal_args() -> Result<(), Box<dyn Error>> {
+    let options = GenerateOptions {
+        format: true,
+        ..Default::default()
+    };
+    gen_diff!(
+        "sample_object0.xml",
+        "sample_object0_signal_args.rs",
+        |interface| GenSignalArgs::new(interface, &options).to_string()
+    )
+}
+
+#[test]
+fn type_map() -> Result<(), Box<dyn Error>> {
+    let input = include_str!("data/sample_object0.xml");
+    let node = Node::from_reader(input.as_bytes())?;
+    let mut types = TypeMap::new();
+    types.map_signature("a{ss}", "my_crate::Dict")?;
+    types.map_signature("(ii)", "my_crate::Point")?;
+    types.map_member("Frobate.foo", "my_crate::Foo");
+    types.map_member("Bar", "my_crate::Bar");
+
+    let options = GenerateOptions {
+        types,
+        ..Default::default()
+    };
+    let gen = generate(&node, &options)?;
+    assert!(gen.contains("my_crate::Dict,"));
+    assert!(gen.contains(
+        "fn bazic(&self, bar: &my_crate::Point, foo: &(i32,)) -> \
+         zbus::Result<(my_crate::Point, Vec<(i32,)>)>;"
+    ));
+    assert!(gen.contains("fn frobate(&self, foz: i32, foo: &my_crate::Foo)"));
+    assert!(gen.contains("fn bar(&self) -> zbus::Result<my_crate::Bar>;"));
+    assert!(gen.contains("fn set_bar(&self, value: &my_crate::Bar) -> zbus::Result<()>;"));
+
+    let gen = GenInterface::new(&node.interfaces()[0], &options).to_string();
+    assert!(gen.contains("async fn bazic(&self, bar: my_crate::Point, foo: (i32,))"));
+    assert!(gen.contains("async fn set_bar(&mut self, value: my_crate::Bar)"));
+
+    Ok(())
+}
+
+#[test]
+fn generate_from_node() -> Result<(), Box<dyn Error>> {
+    let input = include_str!("data/sample_object0.xml");
+    let node = Node::from_reader(input.as_bytes())?;
+
+    let options = GenerateOptions {
+        format: true,
+        ..Default::default()
+    };
+    let code = generate(&node, &options)?;
+    let expected = include_str!("data/sample_object0.rs");
+    #[cfg(windows)]
+    let expected = expected.replace("\r\n", "\n");
+    assert_eq!(code, format!("use zbus::proxy;\n\n{expected}"));
+
+    let options = GenerateOptions {
+        server: true,
+        format: true,
+        ..Default::default()
+    };
+    let code = generate(&node, &options)?;
+    let expected = include_str!("data/sample_object0_server.rs");
+    #[cfg(windows)]
+    let expected = expected.replace("\r\n", "\n");
+    assert_eq!(code, format!("use zbus::interface;\n\n{expected}"));
+
+    Ok(())
+}
+
+#[test]
+fn doc_string_indentation() -> Result<(), Box<dyn Error>> {
+    // The first line is indented with more bytes but the second one with more characters.
+    let doc = "\n  \u{3000}Indented with an ideographic space.\n   Less indented.\n";
+    let input = format!(
+        "<node><interface name=\"org.example.Foo\"><method name=\"Bar\">\
+         <annotation name=\"org.freedesktop.DBus.DocString\" value=\"{doc}\"/>\
+         </method></interface></node>"
+    );
+    let node = Node::from_reader(input.as_bytes())?;
+    let code = generate(&node, &GenerateOptions::default())?;
+    assert!(code.contains(
+        "    /// \u{3000}Indented with an ideographic space.\n    ///  Less indented.\n"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn proxy_kind() -> Result<(), Box<dyn Error>> {
+    let input = include_str!("data/sample_object0.xml");
+    let node = Node::from_reader(input.as_bytes())?;
+
+    for (kind, attribute) in [
+        (ProxyKind::Async, Some(", gen_blocking = false)]")),
+        (ProxyKind::Blocking, Some(", gen_async = false)]")),
+        (ProxyKind::Both, None),
+    ] {
+        let options = GenerateOptions {
+            kind,
+            ..Default::default()
+        };
+        let code = generate(&node, &options)?;
+        let proxy_attribute = code
+            .lines()
+            .find(|line| line.starts_with("#[proxy("))
+            .unwrap();
+        match attribute {
+            Some(attribute) => assert!(proxy_attribute.ends_with(attribute), "{proxy_attribute}"),
+            None => assert!(
+                !proxy_attribute.contains("gen_async") && !proxy_attribute.contains("gen_blocking"),
+                "{proxy_attribute}"
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn proxy_defaults() {
+    let introspected = Some("/org/example/Introspected");
+
+    let default = ProxyDefault::new(None, false);
+    assert_eq!(default, ProxyDefault::Introspected);
+    assert_eq!(default.resolve(introspected), introspected);
+    assert_eq!(default.resolve(None), None);
+
+    let default = ProxyDefault::new(Some("/org/example/Custom"), false);
+    assert_eq!(default, ProxyDefault::Custom("/org/example/Custom"));
+    assert_eq!(default.resolve(introspected), Some("/org/example/Custom"));
+    assert_eq!(default.resolve(None), Some("/org/example/Custom"));
+
+    let default = ProxyDefault::<&str>::new(None, true);
+    assert_eq!(default, ProxyDefault::None);
+    assert_eq!(default.resolve(introspected), None);
+}
+
+#[test]
+fn interface_filter() {
+    let mut filter = InterfaceFilter::new();
+    assert!(filter.matches("org.example.Foo"));
+
+    filter.include("org.example.*").include("com.example.?ar");
+    assert!(filter.matches("org.example.Foo"));
+    assert!(filter.matches("org.example.Foo.Internal"));
+    assert!(filter.matches("com.example.Bar"));
+    assert!(!filter.matches("com.example.Baar"));
+    assert!(!filter.matches("org.other.Foo"));
+
+    filter.exclude("*.Internal");
+    assert!(filter.matches("org.example.Foo"));
+    assert!(!filter.matches("org.example.Foo.Internal"));
+}
+
+#[test]
+fn diff_generated_members() -> Result<(), Box<dyn Error>> {
+    let existing = r#"
+        //! Old header.
+        use zbus::proxy;
+
+        /// Foo.
+        #[proxy(interface = "org.example.Foo")]
+        pub trait Foo {
+            /// Bar method
+            fn bar(&self) -> zbus::Result<()>;
+
+            fn baz(&self, x: i32) -> zbus::Result<()>;
+
+            fn gone(&self) -> zbus::Result<()>;
+        }
+    "#;
+    let generated = r#"
+        //! New header.
+        use zbus::proxy;
+        #[proxy(interface = "org.example.Foo")]
+        pub trait Foo {
+            /// Bar the foo.
+            fn bar(&self) -> zbus::Result<()>;
+            fn baz(&self, x: u32) -> zbus::Result<()>;
+            #[zbus(property)]
+            fn new_prop(&self) -> zbus::Result<u32>;
+        }
+    "#;
+
+    assert!(diff_members(existing, existing)?.is_empty());
+    assert_eq!(
+        diff_members(existing, generated)?,
+        [
+            MemberDiff::Changed {
+                old: "fn baz(&self, x: i32) -> zbus::Result<()>;".to_string(),
+                new: "fn baz(&self, x: u32) -> zbus::Result<()>;".to_string(),
+            },
+            MemberDiff::Removed("fn gone(&self) -> zbus::Result<()>;".to_string()),
+            MemberDiff::Added(
+                "#[zbus(property)]\nfn new_prop(&self) -> zbus::Result<u32>;".to_string()
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn format_without_rustfmt() -> Result<(), Box<dyn Error>> {
+    let input = include_str!("data/sample_object0.xml");
+    let node = Node::from_reader(input.as_bytes())?;
+
+    // Without `rustfmt` in the `PATH`, the code is formatted with `prettyplease` instead.
+    let output = Command::new(env!("CARGO_BIN_EXE_zbus-xmlgen"))
+        .args(["file", "tests/data/sample_object0.xml", "--output", "-"])
+        .env("PATH", "")
+        .stderr(Stdio::inherit())
+        .output()?;
+    assert!(output.status.success());
+
+    let unformatted = write_interfaces_with_options(
+        node.interfaces(),
+        &[],
+        &GenerateOptions::default(),
+        "sample_object0.xml",
+        "zbus-xmlgen",
+        env!("CARGO_PKG_VERSION"),
+    )?;
+    let expected = prettyplease::unparse(&syn::parse_file(&unformatted)?);
+    assert_eq!(String::from_utf8(output.stdout)?, format!("{expected}\n"));
+
+    Ok(())
+}
+
+#[test]
+fn strip_header() {
+    let code = "//! # Header\n//!\n//! More header.\n\nuse zbus::proxy;\n//! Not a header.\n";
+    assert_eq!(
+        strip_doc_header(code),
+        "use zbus::proxy;\n//! Not a header.\n"
+    );
+    assert_eq!(strip_doc_header("use zbus::proxy;\n"), "use zbus::proxy;\n");
+    assert_eq!(strip_doc_header("//! Only a header."), "");
+}
+
+#[test]
+fn stdin_without_header() -> Result<(), Box<dyn Error>> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_zbus-xmlgen"))
+        .args(["file", "-", "--output", "-", "--no-header"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(include_bytes!("data/sample_object0.xml"))?;
+    let mut output = String::new();
+    child.stdout.take().unwrap().read_to_string(&mut output)?;
+    assert!(child.wait()?.success());
+
+    let expected = include_str!("data/sample_object0.rs");
+    #[cfg(windows)]
+    let expected = expected.replace("\r\n", "\n");
+    assert_eq!(output, format!("use zbus::proxy;\n{expected}\n"));
+
+    Ok(())
+}
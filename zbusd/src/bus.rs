@@ -0,0 +1,592 @@
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    num::NonZeroU32,
+    os::unix::net::{UnixListener, UnixStream},
+    path::PathBuf,
+    pin::Pin,
+};
+
+use async_channel::{Receiver, Sender, TrySendError};
+use async_io::Async;
+use futures_util::{
+    future::{self, AbortHandle, AbortRegistration, Abortable, FutureExt},
+    stream::{self, FuturesUnordered, SelectAll, Stream, StreamExt},
+};
+use tracing::{debug, trace, warn};
+use zbus::{
+    address::transport::{Transport, Unix, UnixSocket},
+    connection, fdo,
+    message::{self, Flags, Type},
+    names::{BusName, OwnedUniqueName, UniqueName},
+    zvariant::OwnedFd,
+    Address, Connection, DBusError, Error, Guid, Message, MessageStream, OwnedGuid, OwnedMatchRule,
+    Result,
+};
+
+use crate::{dbus, names::NameChange, names::NameRegistry};
+
+/// The name of the bus itself.
+pub(crate) const BUS_NAME: &str = "org.freedesktop.DBus";
+/// The object path of the bus itself.
+pub(crate) const BUS_PATH: &str = "/org/freedesktop/DBus";
+/// The maximum number of messages waiting to be written to a peer.
+///
+/// Like `dbus-daemon`, we disconnect the peers that don't read their messages fast enough, instead
+/// of keeping an ever growing queue for them or blocking the whole bus.
+const MAX_QUEUED_MESSAGES: usize = 1024;
+
+type Policy = Box<dyn Fn(&Message) -> bool + Send + Sync>;
+type PeerStream = Pin<Box<dyn Stream<Item = (OwnedUniqueName, Option<Result<Message>>)> + Send>>;
+type PeerTask = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// A D-Bus message bus broker.
+///
+/// The bus listens on a unix domain socket, and routes the messages between the peers connected to
+/// it. It implements the core of the `org.freedesktop.DBus` interface: the registry of unique and
+/// well-known names, and the match rules for the delivery of broadcast signals. This is enough to
+/// run a private bus, e.g for tests or sandboxes.
+///
+/// All the bus activity happens in the future returned by [`Bus::run`], so it can be run on any
+/// executor.
+///
+/// # Example
+///
+/// ```no_run
+/// # zbus::block_on(async {
+/// let bus = zbusd::Bus::for_address("unix:path=/tmp/zbusd-example").await?;
+/// println!("Listening on {}", bus.address());
+/// bus.run().await?;
+/// # Ok::<(), zbus::Error>(())
+/// # }).unwrap();
+/// ```
+pub struct Bus {
+    listener: Async<UnixListener>,
+    address: String,
+    socket_path: Option<PathBuf>,
+    guid: OwnedGuid,
+    policy: Option<Policy>,
+}
+
+impl std::fmt::Debug for Bus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Bus")
+            .field("address", &self.address)
+            .field("guid", &self.guid)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Bus {
+    /// Create a bus listening on the given address.
+    ///
+    /// Only `unix:` addresses with a `path` (or on Linux, `abstract`) key are supported.
+    pub async fn for_address(address: &str) -> Result<Self> {
+        let address: Address = address.parse()?;
+        let (listener, socket_path) = match address.transport() {
+            Transport::Unix(unix) => match unix.path() {
+                UnixSocket::File(path) => (UnixListener::bind(path)?, Some(path.clone())),
+                #[cfg(target_os = "linux")]
+                UnixSocket::Abstract(name) => {
+                    use std::os::{linux::net::SocketAddrExt, unix::ffi::OsStrExt};
+
+                    let addr = std::os::unix::net::SocketAddr::from_abstract_name(name.as_bytes())?;
+
+                    (UnixListener::bind_addr(&addr)?, None)
+                }
+                _ => return Err(Error::Unsupported),
+            },
+            _ => return Err(Error::Unsupported),
+        };
+        let guid = OwnedGuid::from(Guid::generate());
+        // Only keep the parts of the address that are relevant for the clients.
+        let address = Address::from(Transport::Unix(Unix::new(match address.transport() {
+            Transport::Unix(unix) => unix.path().clone(),
+            _ => unreachable!("checked above"),
+        })))
+        .to_string();
+
+        Ok(Self {
+            listener: Async::new(listener)?,
+            address,
+            socket_path,
+            guid,
+            policy: None,
+        })
+    }
+
+    /// The address of the bus, to connect to it.
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// The GUID of the bus.
+    pub fn guid(&self) -> &OwnedGuid {
+        &self.guid
+    }
+
+    /// Set the policy for the messages going through the bus.
+    ///
+    /// `policy` is called for every message sent by a peer, including the calls to the bus itself,
+    /// after its sender has been set. Messages it returns `false` for are dropped, and if a reply
+    /// is expected, an `org.freedesktop.DBus.Error.AccessDenied` error is sent back instead.
+    pub fn set_policy<P>(mut self, policy: P) -> Self
+    where
+        P: Fn(&Message) -> bool + Send + Sync + 'static,
+    {
+        self.policy = Some(Box::new(policy));
+
+        self
+    }
+
+    /// Run the bus.
+    ///
+    /// Failures to accept new connections are logged, and the bus keeps running.
+    pub async fn run(self) -> Result<()> {
+        let mut state = State {
+            guid: self.guid.clone(),
+            policy: self.policy.as_ref(),
+            peers: HashMap::new(),
+            names: NameRegistry::default(),
+            pending_replies: HashSet::new(),
+            to_disconnect: vec![],
+            next_id: 0,
+        };
+        let mut handshakes = FuturesUnordered::new();
+        let mut incoming = SelectAll::<PeerStream>::new();
+        let mut peer_tasks = FuturesUnordered::<PeerTask>::new();
+
+        loop {
+            enum Event {
+                Accepted(std::io::Result<UnixStream>),
+                Connected(OwnedUniqueName, Result<(Connection, MessageStream)>),
+                Message(OwnedUniqueName, Option<Result<Message>>),
+                PeerTaskDone,
+            }
+
+            let event = {
+                let accept = self.listener.accept().fuse();
+                futures_util::pin_mut!(accept);
+
+                futures_util::select! {
+                    res = accept => Event::Accepted(
+                        res.and_then(|(socket, _)| socket.into_inner()),
+                    ),
+                    (name, res) = handshakes.select_next_some() => Event::Connected(name, res),
+                    (name, msg) = incoming.select_next_some() => Event::Message(name, msg),
+                    () = peer_tasks.select_next_some() => Event::PeerTaskDone,
+                }
+            };
+
+            match event {
+                Event::Accepted(Err(e)) => {
+                    warn!("Failed to accept a connection: {e}");
+                }
+                Event::Accepted(Ok(socket)) => {
+                    let unique_name = state.next_unique_name()?;
+                    let guid = state.guid.clone();
+                    debug!("Accepted connection for `{unique_name}`");
+                    handshakes.push(async move {
+                        let res = async {
+                            // We tick the executor ourselves once we listen to the messages from
+                            // the peer, so we don't miss the first ones (i-e `Hello`).
+                            let conn = connection::Builder::unix_stream(socket)
+                                .server(guid)?
+                                .p2p()
+                                .internal_executor(false)
+                                .strict_validation(true)
+                                .build()
+                                .await?;
+                            let stream = MessageStream::from(&conn);
+
+                            Ok((conn, stream))
+                        }
+                        .await;
+
+                        (unique_name, res)
+                    });
+                }
+                Event::Connected(unique_name, Ok((conn, stream))) => {
+                    debug!("Peer `{unique_name}` authenticated");
+                    let name = unique_name.clone();
+                    let stream = stream
+                        .map(move |msg| (name.clone(), Some(msg)))
+                        .chain(stream::once(future::ready((unique_name.clone(), None))));
+                    incoming.push(Box::pin(stream));
+                    let (queue, outgoing) = async_channel::bounded(MAX_QUEUED_MESSAGES);
+                    let (abort, registration) = AbortHandle::new_pair();
+                    peer_tasks.push(Box::pin(run_peer(conn, outgoing, registration)));
+                    state.peers.insert(
+                        unique_name,
+                        Peer {
+                            queue,
+                            abort,
+                            hello_done: false,
+                            match_rules: vec![],
+                        },
+                    );
+                }
+                Event::Connected(unique_name, Err(e)) => {
+                    debug!("Handshake for `{unique_name}` failed: {e}");
+                }
+                Event::Message(unique_name, Some(Ok(msg))) => {
+                    if let Err(e) = state.handle_message(unique_name.as_ref(), &msg) {
+                        warn!("Failed to handle message from `{unique_name}`: {e}");
+                    }
+                }
+                Event::Message(unique_name, Some(Err(e))) => {
+                    debug!("Failed to receive message from `{unique_name}`: {e}");
+                }
+                Event::Message(unique_name, None) => {
+                    debug!("Peer `{unique_name}` disconnected");
+                    state.to_disconnect.push(unique_name);
+                }
+                Event::PeerTaskDone => (),
+            }
+
+            // Disconnecting peers can result in more peers to disconnect, as it emits signals.
+            while let Some(unique_name) = state.to_disconnect.pop() {
+                if let Err(e) = state.remove_peer(unique_name.as_ref()) {
+                    warn!("Failed to handle disconnection of `{unique_name}`: {e}");
+                }
+            }
+        }
+    }
+}
+
+/// Drive the connection to a peer, and write the messages queued for it.
+///
+/// The connection is closed when the peer is removed from the bus, or when writing to it fails.
+async fn run_peer(conn: Connection, outgoing: Receiver<Message>, registration: AbortRegistration) {
+    // We tick the executor of the connection ourselves, so that all the peers are served from the
+    // executor running the bus.
+    let executor = conn.executor().clone();
+    let ticker = async move {
+        while !executor.is_empty() {
+            executor.tick().await;
+        }
+    };
+    let writer = async move {
+        let write = async {
+            while let Ok(msg) = outgoing.recv().await {
+                if let Err(e) = conn.send(&msg).await {
+                    debug!("Failed to send message to {:?}: {e}", conn.unique_name());
+
+                    break;
+                }
+            }
+        };
+        // Either the peer was removed from the bus, or writing to it failed.
+        let _ = Abortable::new(write, registration).await;
+        // Errors are expected if the peer is gone already.
+        let _ = conn.close().await;
+    };
+
+    future::join(ticker, writer).await;
+}
+
+impl Drop for Bus {
+    fn drop(&mut self) {
+        if let Some(path) = &self.socket_path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// A peer connected to the bus.
+#[derive(Debug)]
+pub(crate) struct Peer {
+    /// The messages to write to the peer.
+    queue: Sender<Message>,
+    /// Stops the writing to the peer, and closes its connection.
+    abort: AbortHandle,
+    pub hello_done: bool,
+    pub match_rules: Vec<OwnedMatchRule>,
+}
+
+impl Drop for Peer {
+    fn drop(&mut self) {
+        self.abort.abort();
+    }
+}
+
+/// The state of a running bus.
+pub(crate) struct State<'p> {
+    pub guid: OwnedGuid,
+    policy: Option<&'p Policy>,
+    pub peers: HashMap<OwnedUniqueName, Peer>,
+    pub names: NameRegistry,
+    /// The method calls awaiting a reply, as the caller, the serial of the call and the callee.
+    pending_replies: HashSet<(OwnedUniqueName, NonZeroU32, OwnedUniqueName)>,
+    /// The peers to disconnect.
+    to_disconnect: Vec<OwnedUniqueName>,
+    next_id: u64,
+}
+
+impl State<'_> {
+    fn next_unique_name(&mut self) -> Result<OwnedUniqueName> {
+        self.next_id += 1;
+
+        OwnedUniqueName::try_from(format!(":1.{}", self.next_id)).map_err(Into::into)
+    }
+
+    fn handle_message(&mut self, sender: UniqueName<'_>, msg: &Message) -> Result<()> {
+        let Some(peer) = self.peers.get(sender.as_str()) else {
+            // A message the peer sent before it got disconnected.
+            return Ok(());
+        };
+        if !peer.hello_done && !is_hello(msg) {
+            debug!("Disconnecting `{sender}` as its first message isn't `Hello`");
+            self.to_disconnect.push(sender.into());
+
+            return Ok(());
+        }
+
+        let msg = with_sender(msg, &sender)?;
+        trace!("Routing {msg:?}");
+        let hdr = msg.header();
+
+        if matches!(msg.message_type(), Type::MethodReturn | Type::Error) {
+            let call = hdr
+                .destination()
+                .and_then(|d| self.resolve(d))
+                .zip(hdr.reply_serial())
+                .map(|(caller, serial)| (caller.clone(), serial, sender.to_owned().into()));
+            if !call.is_some_and(|call| self.pending_replies.remove(&call)) {
+                debug!("Dropping reply {msg:?}, as no such reply is expected");
+
+                return Ok(());
+            }
+        }
+
+        if let Some(policy) = self.policy {
+            if !policy(&msg) {
+                debug!("Message {msg:?} denied by the policy");
+                if expects_reply(&msg) {
+                    let e = fdo::Error::AccessDenied("Denied by the bus policy".to_string());
+                    self.send(&error_reply(&msg, &e)?);
+                }
+
+                return Ok(());
+            }
+        }
+
+        match hdr.destination() {
+            Some(destination) if destination.as_str() == BUS_NAME => {
+                let (reply, signals) = match dbus::handle_call(self, &msg) {
+                    Ok((reply, signals)) => (reply, signals),
+                    Err(e) => (error_reply(&msg, &e)?, vec![]),
+                };
+                if expects_reply(&msg) {
+                    self.send(&reply);
+                }
+                for signal in signals {
+                    self.route(&signal);
+                }
+            }
+            Some(destination) => match self.resolve(destination) {
+                Some(callee) => {
+                    if expects_reply(&msg) {
+                        let call = (
+                            sender.into(),
+                            msg.primary_header().serial_num(),
+                            callee.clone(),
+                        );
+                        self.pending_replies.insert(call);
+                    }
+                    self.route(&msg);
+                }
+                None if expects_reply(&msg) => {
+                    let e = fdo::Error::ServiceUnknown(format!(
+                        "The name {destination} was not provided by any .service files"
+                    ));
+                    self.send(&error_reply(&msg, &e)?);
+                }
+                None => trace!("Dropping message for unknown destination: {msg:?}"),
+            },
+            None => self.route(&msg),
+        }
+
+        Ok(())
+    }
+
+    fn remove_peer(&mut self, unique_name: UniqueName<'_>) -> Result<()> {
+        let Some(peer) = self.peers.remove(unique_name.as_str()) else {
+            return Ok(());
+        };
+        debug!("Removing peer `{unique_name}`");
+        self.pending_replies
+            .retain(|(caller, _, callee)| *caller != unique_name && *callee != unique_name);
+        if !peer.hello_done {
+            // Nobody knows about the peer.
+            return Ok(());
+        }
+
+        let changes = self.names.release_all(unique_name.clone());
+        let mut signals = name_change_signals(changes)?;
+        signals.push(name_owner_changed(&unique_name, Some(&unique_name), None)?);
+        for signal in signals {
+            self.route(&signal);
+        }
+
+        Ok(())
+    }
+
+    /// The unique name of the owner of `name`, if any.
+    ///
+    /// Peers only get their unique name once they sent the `Hello` message.
+    pub fn resolve(&self, name: &BusName<'_>) -> Option<&OwnedUniqueName> {
+        match name {
+            BusName::Unique(name) => self
+                .peers
+                .get_key_value(name.as_str())
+                .filter(|(_, peer)| peer.hello_done)
+                .map(|(name, _)| name),
+            BusName::WellKnown(name) => self.names.owner(name),
+        }
+    }
+
+    /// Deliver `msg` to its destination, or to all the peers with a matching rule if it has none.
+    fn route(&mut self, msg: &Message) {
+        let hdr = msg.header();
+        match hdr.destination() {
+            Some(destination) => {
+                let Some(name) = self.resolve(destination) else {
+                    return;
+                };
+                if !self.peers.get(name).is_some_and(|peer| enqueue(peer, msg)) {
+                    self.to_disconnect.push(name.clone());
+                }
+            }
+            None if msg.message_type() == Type::Signal => {
+                for (name, peer) in &self.peers {
+                    if matches(&self.names, peer, msg) && !enqueue(peer, msg) {
+                        self.to_disconnect.push(name.clone());
+                    }
+                }
+            }
+            None => trace!("Dropping message without destination: {msg:?}"),
+        }
+    }
+
+    /// Send `msg`, that originates from the bus, to its destination.
+    fn send(&mut self, msg: &Message) {
+        self.route(msg)
+    }
+}
+
+/// If any of the match rules of `peer` matches `msg`.
+fn matches(names: &NameRegistry, peer: &Peer, msg: &Message) -> bool {
+    let hdr = msg.header();
+    peer.match_rules.iter().any(|rule| {
+        // `MatchRule::matches` can't check well-known sender names, but we can.
+        let sender_matches = match rule.sender() {
+            Some(BusName::WellKnown(name)) if *name != BUS_NAME => {
+                names.owner(name).map(|o| o.inner()) == hdr.sender()
+            }
+            _ => true,
+        };
+
+        sender_matches && rule.matches(msg).unwrap_or(false)
+    })
+}
+
+/// Queue `msg` to be written to `peer`.
+///
+/// Returns `false` if the peer should be disconnected, as it has too many messages queued already.
+fn enqueue(peer: &Peer, msg: &Message) -> bool {
+    match peer.queue.try_send(msg.clone()) {
+        Ok(()) => true,
+        Err(TrySendError::Full(_)) => {
+            warn!("Too many messages queued for a peer, disconnecting it");
+
+            false
+        }
+        // The peer is gone already.
+        Err(TrySendError::Closed(_)) => true,
+    }
+}
+
+/// If `msg` is a call to the `Hello` method of the bus.
+fn is_hello(msg: &Message) -> bool {
+    let hdr = msg.header();
+
+    msg.message_type() == Type::MethodCall
+        && hdr.destination().map(|d| d.as_str()) == Some(BUS_NAME)
+        && matches!(hdr.interface().map(|i| i.as_str()), Some(BUS_NAME) | None)
+        && hdr.member().map(|m| m.as_str()) == Some("Hello")
+}
+
+/// If the sender of `msg` expects a reply to it.
+fn expects_reply(msg: &Message) -> bool {
+    msg.message_type() == Type::MethodCall
+        && !msg
+            .primary_header()
+            .flags()
+            .contains(Flags::NoReplyExpected)
+}
+
+/// A copy of `msg`, with the given sender.
+fn with_sender(msg: &Message, sender: &UniqueName<'_>) -> Result<Message> {
+    let body = msg.body();
+    let data = body.data();
+    let fds = data
+        .fds()
+        .iter()
+        .map(|fd| fd.try_to_owned().map(OwnedFd::from))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let builder = message::Builder::from(msg.header()).sender(sender)?;
+
+    // SAFETY: The body and its FDs come from a valid message with the same signature.
+    unsafe { builder.build_raw_body(data.bytes(), body.signature().clone(), fds) }
+}
+
+/// An error reply from the bus to `call`.
+pub(crate) fn error_reply(call: &Message, e: &fdo::Error) -> Result<Message> {
+    Message::error(&call.header(), e.name())?
+        .sender(BUS_NAME)?
+        .build(&(e.description().unwrap_or_default(),))
+}
+
+/// The signals to emit for the given changes of well-known name owners.
+pub(crate) fn name_change_signals(changes: Vec<NameChange>) -> Result<Vec<Message>> {
+    let mut signals = vec![];
+    for change in changes {
+        let name = change.name.as_str();
+        if let Some(old_owner) = &change.old_owner {
+            signals.push(name_signal("NameLost", name, old_owner)?);
+        }
+        signals.push(name_owner_changed(
+            name,
+            change.old_owner.as_deref(),
+            change.new_owner.as_deref(),
+        )?);
+        if let Some(new_owner) = &change.new_owner {
+            signals.push(name_signal("NameAcquired", name, new_owner)?);
+        }
+    }
+
+    Ok(signals)
+}
+
+/// A `NameOwnerChanged` signal.
+pub(crate) fn name_owner_changed(
+    name: &str,
+    old_owner: Option<&UniqueName<'_>>,
+    new_owner: Option<&UniqueName<'_>>,
+) -> Result<Message> {
+    let old_owner = old_owner.map(|n| n.as_str()).unwrap_or("");
+    let new_owner = new_owner.map(|n| n.as_str()).unwrap_or("");
+
+    Message::signal(BUS_PATH, BUS_NAME, "NameOwnerChanged")?
+        .sender(BUS_NAME)?
+        .build(&(name, old_owner, new_owner))
+}
+
+/// A `NameAcquired` or `NameLost` signal, sent to `owner`.
+pub(crate) fn name_signal(member: &str, name: &str, owner: &UniqueName<'_>) -> Result<Message> {
+    Message::signal(BUS_PATH, BUS_NAME, member)?
+        .sender(BUS_NAME)?
+        .destination(owner.as_str())?
+        .build(&(name,))
+}
@@ -0,0 +1,172 @@
+//! The `org.freedesktop.DBus` interface of the bus itself.
+
+use enumflags2::BitFlags;
+use zbus::{
+    fdo::{self, RequestNameFlags},
+    names::{BusName, OwnedUniqueName, WellKnownName},
+    Message, OwnedMatchRule,
+};
+
+use crate::bus::{name_change_signals, name_owner_changed, name_signal, State, BUS_NAME, BUS_PATH};
+
+/// Handle a method call to the bus, from the peer the call's sender.
+///
+/// Returns the reply and the signals to emit as a result of the call.
+pub(crate) fn handle_call(
+    state: &mut State<'_>,
+    msg: &Message,
+) -> fdo::Result<(Message, Vec<Message>)> {
+    let hdr = msg.header();
+    let sender: OwnedUniqueName = hdr
+        .sender()
+        .expect("the bus sets the sender of all messages")
+        .to_owned()
+        .into();
+    let body = msg.body();
+    let reply = Message::method_return(&hdr)?.sender(BUS_NAME)?;
+    let member = hdr.member().map(|m| m.as_str()).unwrap_or_default();
+    let interface = hdr.interface().map(|i| i.as_str());
+    let mut signals = vec![];
+
+    if hdr.path().map(|p| p.as_str()) != Some(BUS_PATH)
+        && interface != Some("org.freedesktop.DBus.Peer")
+    {
+        return Err(fdo::Error::UnknownObject(format!(
+            "Unknown object '{}'",
+            hdr.path().map(|p| p.as_str()).unwrap_or_default()
+        )));
+    }
+
+    let reply = match (interface, member) {
+        (Some("org.freedesktop.DBus.Peer"), "Ping") => reply.build(&())?,
+        (Some("org.freedesktop.DBus") | None, member) => match member {
+            "Hello" => {
+                let peer = state.peers.get_mut(&sender).expect("sender is connected");
+                if peer.hello_done {
+                    return Err(fdo::Error::Failed(
+                        "Already handled an Hello message".to_string(),
+                    ));
+                }
+                peer.hello_done = true;
+                signals.push(name_owner_changed(&sender, None, Some(&*sender))?);
+                signals.push(name_signal("NameAcquired", &sender, &sender)?);
+
+                reply.build(&(sender.as_str(),))?
+            }
+            "RequestName" => {
+                let (name, flags): (WellKnownName<'_>, BitFlags<RequestNameFlags>) =
+                    body.deserialize()?;
+                check_not_bus_name(&name)?;
+                let (res, change) = state.names.request(name, sender.as_ref(), flags);
+                signals.extend(name_change_signals(change.into_iter().collect())?);
+
+                reply.build(&(res,))?
+            }
+            "ReleaseName" => {
+                let (name,): (WellKnownName<'_>,) = body.deserialize()?;
+                check_not_bus_name(&name)?;
+                let (res, change) = state.names.release(name, sender.as_ref());
+                signals.extend(name_change_signals(change.into_iter().collect())?);
+
+                reply.build(&(res,))?
+            }
+            "GetNameOwner" => {
+                let (name,): (BusName<'_>,) = body.deserialize()?;
+                let owner = if name == BUS_NAME {
+                    BUS_NAME
+                } else {
+                    state
+                        .resolve(&name)
+                        .ok_or_else(|| {
+                            fdo::Error::NameHasNoOwner(format!(
+                                "Could not get owner of name '{name}': no such name"
+                            ))
+                        })?
+                        .as_str()
+                };
+
+                reply.build(&(owner,))?
+            }
+            "NameHasOwner" => {
+                let (name,): (BusName<'_>,) = body.deserialize()?;
+                let has_owner = name == BUS_NAME || state.resolve(&name).is_some();
+
+                reply.build(&(has_owner,))?
+            }
+            "ListNames" => {
+                let names: Vec<&str> = std::iter::once(BUS_NAME)
+                    .chain(
+                        state
+                            .peers
+                            .iter()
+                            .filter(|(_, peer)| peer.hello_done)
+                            .map(|(n, _)| n.as_str()),
+                    )
+                    .chain(state.names.names().map(|n| n.as_str()))
+                    .collect();
+
+                reply.build(&(names,))?
+            }
+            "ListActivatableNames" => reply.build(&(vec![BUS_NAME],))?,
+            "ListQueuedOwners" => {
+                let (name,): (WellKnownName<'_>,) = body.deserialize()?;
+                let owners = state.names.queued_owners(&name).ok_or_else(|| {
+                    fdo::Error::NameHasNoOwner(format!(
+                        "Could not get owners of name '{name}': no such name"
+                    ))
+                })?;
+
+                reply.build(&(owners,))?
+            }
+            "AddMatch" => {
+                let (rule,): (&str,) = body.deserialize()?;
+                let rule = OwnedMatchRule::try_from(rule)
+                    .map_err(|e| fdo::Error::MatchRuleInvalid(e.to_string()))?;
+                let peer = state.peers.get_mut(&sender).expect("sender is connected");
+                peer.match_rules.push(rule);
+
+                reply.build(&())?
+            }
+            "RemoveMatch" => {
+                let (rule,): (&str,) = body.deserialize()?;
+                let rule = OwnedMatchRule::try_from(rule)
+                    .map_err(|e| fdo::Error::MatchRuleInvalid(e.to_string()))?;
+                let peer = state.peers.get_mut(&sender).expect("sender is connected");
+                let pos = peer
+                    .match_rules
+                    .iter()
+                    .position(|r| *r == rule)
+                    .ok_or_else(|| {
+                        fdo::Error::MatchRuleNotFound("The given match rule wasn't found".into())
+                    })?;
+                peer.match_rules.remove(pos);
+
+                reply.build(&())?
+            }
+            "GetId" => reply.build(&(state.guid.as_str(),))?,
+            member => {
+                return Err(fdo::Error::UnknownMethod(format!(
+                    "Unknown method '{member}'"
+                )))
+            }
+        },
+        (Some(interface), _) => {
+            return Err(fdo::Error::UnknownInterface(format!(
+                "Unknown interface '{interface}'"
+            )))
+        }
+    };
+
+    Ok((reply, signals))
+}
+
+/// Nobody but the bus can own its own name.
+fn check_not_bus_name(name: &WellKnownName<'_>) -> fdo::Result<()> {
+    if *name == BUS_NAME {
+        return Err(fdo::Error::InvalidArgs(format!(
+            "Connection is not allowed to own the service '{BUS_NAME}'"
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,18 @@
+#![deny(rust_2018_idioms)]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/dbus2/zbus/9f7a90d2b594ddc48b7a5f39fda5e00cd56a7dfb/logo.png"
+)]
+#![doc = include_str!("../README.md")]
+#![doc(test(attr(
+    warn(unused),
+    deny(warnings),
+    allow(dead_code),
+    // W/o this, we seem to get some bogus warning about `extern crate zbus`.
+    allow(unused_extern_crates),
+)))]
+
+mod bus;
+pub use bus::Bus;
+
+mod dbus;
+mod names;
@@ -0,0 +1,55 @@
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: zbusd [--address ADDRESS]";
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let mut address = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--address" => match args.next() {
+                Some(value) => address = Some(value),
+                None => {
+                    eprintln!("{USAGE}");
+
+                    return ExitCode::FAILURE;
+                }
+            },
+            "--help" | "-h" => {
+                println!("{USAGE}");
+
+                return ExitCode::SUCCESS;
+            }
+            _ => {
+                eprintln!("Unknown argument `{arg}`\n{USAGE}");
+
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let address = address.unwrap_or_else(|| {
+        let dir = std::env::var_os("XDG_RUNTIME_DIR")
+            .map(Into::into)
+            .unwrap_or_else(std::env::temp_dir);
+
+        format!(
+            "unix:path={}",
+            dir.join(format!("zbusd-{}", std::process::id())).display()
+        )
+    });
+
+    let res = zbus::block_on(async {
+        let bus = zbusd::Bus::for_address(&address).await?;
+        // Print the address for the clients, like `dbus-daemon --print-address`.
+        println!("{}", bus.address());
+
+        bus.run().await
+    });
+    if let Err(e) = res {
+        eprintln!("zbusd: {e}");
+
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,266 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use enumflags2::BitFlags;
+use zbus::{
+    fdo::{ReleaseNameReply, RequestNameFlags, RequestNameReply},
+    names::{OwnedUniqueName, OwnedWellKnownName, UniqueName, WellKnownName},
+};
+
+/// A (primary or queued) owner of a well-known name.
+#[derive(Debug, Clone)]
+struct NameOwner {
+    unique_name: OwnedUniqueName,
+    flags: BitFlags<RequestNameFlags>,
+}
+
+#[derive(Debug)]
+struct NameEntry {
+    owner: NameOwner,
+    queue: VecDeque<NameOwner>,
+}
+
+/// A change of the primary owner of a well-known name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NameChange {
+    pub name: OwnedWellKnownName,
+    pub old_owner: Option<OwnedUniqueName>,
+    pub new_owner: Option<OwnedUniqueName>,
+}
+
+/// The registry of the well-known names on the bus, and their owners.
+#[derive(Debug, Default)]
+pub(crate) struct NameRegistry {
+    names: BTreeMap<OwnedWellKnownName, NameEntry>,
+}
+
+impl NameRegistry {
+    /// Handle a `RequestName` call from `unique_name`.
+    pub fn request(
+        &mut self,
+        name: WellKnownName<'_>,
+        unique_name: UniqueName<'_>,
+        flags: BitFlags<RequestNameFlags>,
+    ) -> (RequestNameReply, Option<NameChange>) {
+        let requester = NameOwner {
+            unique_name: unique_name.into(),
+            flags,
+        };
+        let Some(entry) = self.names.get_mut(name.as_str()) else {
+            let change = NameChange {
+                name: name.clone().into(),
+                old_owner: None,
+                new_owner: Some(requester.unique_name.clone()),
+            };
+            self.names.insert(
+                name.into(),
+                NameEntry {
+                    owner: requester,
+                    queue: VecDeque::new(),
+                },
+            );
+
+            return (RequestNameReply::PrimaryOwner, Some(change));
+        };
+
+        if entry.owner.unique_name == requester.unique_name {
+            entry.owner.flags = flags;
+
+            return (RequestNameReply::AlreadyOwner, None);
+        }
+
+        entry
+            .queue
+            .retain(|owner| owner.unique_name != requester.unique_name);
+        if flags.contains(RequestNameFlags::ReplaceExisting)
+            && entry
+                .owner
+                .flags
+                .contains(RequestNameFlags::AllowReplacement)
+        {
+            let new_owner = requester.unique_name.clone();
+            let old_owner = std::mem::replace(&mut entry.owner, requester);
+            let old_owner_name = old_owner.unique_name.clone();
+            if !old_owner.flags.contains(RequestNameFlags::DoNotQueue) {
+                entry.queue.push_front(old_owner);
+            }
+
+            return (
+                RequestNameReply::PrimaryOwner,
+                Some(NameChange {
+                    name: name.into(),
+                    old_owner: Some(old_owner_name),
+                    new_owner: Some(new_owner),
+                }),
+            );
+        }
+
+        if flags.contains(RequestNameFlags::DoNotQueue) {
+            return (RequestNameReply::Exists, None);
+        }
+        entry.queue.push_back(requester);
+
+        (RequestNameReply::InQueue, None)
+    }
+
+    /// Handle a `ReleaseName` call from `unique_name`.
+    pub fn release(
+        &mut self,
+        name: WellKnownName<'_>,
+        unique_name: UniqueName<'_>,
+    ) -> (ReleaseNameReply, Option<NameChange>) {
+        let Some(entry) = self.names.get_mut(name.as_str()) else {
+            return (ReleaseNameReply::NonExistent, None);
+        };
+
+        if entry.owner.unique_name != unique_name {
+            let queued = entry.queue.len();
+            entry.queue.retain(|owner| owner.unique_name != unique_name);
+            if entry.queue.len() == queued {
+                return (ReleaseNameReply::NotOwner, None);
+            }
+
+            return (ReleaseNameReply::Released, None);
+        }
+
+        let new_owner = match entry.queue.pop_front() {
+            Some(next) => {
+                let new_owner = next.unique_name.clone();
+                entry.owner = next;
+
+                Some(new_owner)
+            }
+            None => {
+                self.names.remove(name.as_str());
+
+                None
+            }
+        };
+
+        (
+            ReleaseNameReply::Released,
+            Some(NameChange {
+                name: name.into(),
+                old_owner: Some(unique_name.into()),
+                new_owner,
+            }),
+        )
+    }
+
+    /// Release all the names owned or queued for by `unique_name`, e.g on disconnection.
+    pub fn release_all(&mut self, unique_name: UniqueName<'_>) -> Vec<NameChange> {
+        let names: Vec<OwnedWellKnownName> = self
+            .names
+            .iter()
+            .filter(|(_, entry)| {
+                entry.owner.unique_name == unique_name
+                    || entry
+                        .queue
+                        .iter()
+                        .any(|owner| owner.unique_name == unique_name)
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        names
+            .into_iter()
+            .filter_map(|name| self.release(name.into_inner(), unique_name.clone()).1)
+            .collect()
+    }
+
+    /// The primary owner of `name`.
+    pub fn owner(&self, name: &WellKnownName<'_>) -> Option<&OwnedUniqueName> {
+        self.names
+            .get(name.as_str())
+            .map(|entry| &entry.owner.unique_name)
+    }
+
+    /// The primary owner of `name`, followed by the queued owners.
+    pub fn queued_owners(&self, name: &WellKnownName<'_>) -> Option<Vec<OwnedUniqueName>> {
+        self.names.get(name.as_str()).map(|entry| {
+            std::iter::once(&entry.owner)
+                .chain(&entry.queue)
+                .map(|owner| owner.unique_name.clone())
+                .collect()
+        })
+    }
+
+    /// All the names with an owner.
+    pub fn names(&self) -> impl Iterator<Item = &OwnedWellKnownName> {
+        self.names.keys()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unique(name: &'static str) -> UniqueName<'static> {
+        UniqueName::from_static_str(name).unwrap()
+    }
+
+    #[test]
+    fn request_and_release() {
+        let name = WellKnownName::from_static_str("org.zbus.Test").unwrap();
+        let mut registry = NameRegistry::default();
+
+        let (reply, change) = registry.request(
+            name.clone(),
+            unique(":1.1"),
+            RequestNameFlags::AllowReplacement.into(),
+        );
+        assert_eq!(reply, RequestNameReply::PrimaryOwner);
+        assert_eq!(change.unwrap().new_owner.unwrap(), ":1.1");
+        let (reply, change) = registry.request(name.clone(), unique(":1.1"), BitFlags::empty());
+        assert_eq!(reply, RequestNameReply::AlreadyOwner);
+        assert!(change.is_none());
+
+        // `:1.1` doesn't allow replacement anymore.
+        let (reply, _) = registry.request(
+            name.clone(),
+            unique(":1.2"),
+            RequestNameFlags::ReplaceExisting | RequestNameFlags::DoNotQueue,
+        );
+        assert_eq!(reply, RequestNameReply::Exists);
+        let (reply, _) = registry.request(name.clone(), unique(":1.2"), BitFlags::empty());
+        assert_eq!(reply, RequestNameReply::InQueue);
+        assert_eq!(registry.queued_owners(&name).unwrap(), [":1.1", ":1.2"]);
+
+        // The next in the queue takes over on release.
+        let (reply, change) = registry.release(name.clone(), unique(":1.1"));
+        assert_eq!(reply, ReleaseNameReply::Released);
+        let change = change.unwrap();
+        assert_eq!(change.old_owner.unwrap(), ":1.1");
+        assert_eq!(change.new_owner.unwrap(), ":1.2");
+        assert_eq!(registry.owner(&name).unwrap().as_str(), ":1.2");
+        let (reply, _) = registry.release(name.clone(), unique(":1.1"));
+        assert_eq!(reply, ReleaseNameReply::NotOwner);
+
+        let changes = registry.release_all(unique(":1.2"));
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].new_owner.is_none());
+        assert!(registry.owner(&name).is_none());
+        let (reply, _) = registry.release(name, unique(":1.2"));
+        assert_eq!(reply, ReleaseNameReply::NonExistent);
+    }
+
+    #[test]
+    fn replacement() {
+        let name = WellKnownName::from_static_str("org.zbus.Test").unwrap();
+        let mut registry = NameRegistry::default();
+
+        registry.request(
+            name.clone(),
+            unique(":1.1"),
+            RequestNameFlags::AllowReplacement.into(),
+        );
+        let (reply, change) = registry.request(
+            name.clone(),
+            unique(":1.2"),
+            RequestNameFlags::ReplaceExisting.into(),
+        );
+        assert_eq!(reply, RequestNameReply::PrimaryOwner);
+        assert_eq!(change.unwrap().old_owner.unwrap(), ":1.1");
+        // The previous owner is queued since it didn't ask not to be.
+        assert_eq!(registry.queued_owners(&name).unwrap(), [":1.2", ":1.1"]);
+    }
+}
@@ -0,0 +1,263 @@
+use futures_util::StreamExt;
+use ntest::timeout;
+use zbus::{block_on, connection, fdo, interface, object_server::SignalEmitter, proxy, Connection};
+use zbusd::Bus;
+
+struct Greeter;
+
+#[interface(name = "org.zbus.Greeter")]
+impl Greeter {
+    async fn greet(
+        &self,
+        name: &str,
+        #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+    ) -> fdo::Result<String> {
+        emitter.greeted(name).await?;
+
+        Ok(format!("Hello {name}!"))
+    }
+
+    fn forbidden(&self) {}
+
+    #[zbus(signal)]
+    async fn greeted(emitter: &SignalEmitter<'_>, name: &str) -> zbus::Result<()>;
+}
+
+#[proxy(
+    interface = "org.zbus.Greeter",
+    default_service = "org.zbus.Greeter",
+    default_path = "/org/zbus/Greeter"
+)]
+trait Greeter {
+    fn greet(&self, name: &str) -> zbus::Result<String>;
+
+    fn forbidden(&self) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn greeted(&self, name: &str) -> zbus::Result<()>;
+}
+
+/// Start a bus in its own thread, returning its address.
+fn start_bus(test_name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("zbusd-{test_name}-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let bus = block_on(Bus::for_address(&format!("unix:path={}", path.display())))
+        .unwrap()
+        // Deny calls to `Forbidden`.
+        .set_policy(|msg| {
+            msg.header()
+                .member()
+                .map(|member| member != "Forbidden")
+                .unwrap_or(true)
+        });
+    let address = bus.address().to_string();
+    std::thread::spawn(move || block_on(bus.run()));
+
+    address
+}
+
+async fn connect(address: &str) -> zbus::Result<Connection> {
+    connection::Builder::address(address)?.build().await
+}
+
+#[test]
+#[timeout(15000)]
+fn names_and_routing() {
+    let address = start_bus("routing");
+
+    block_on(async {
+        let client = connect(&address).await?;
+        let dbus = fdo::DBusProxy::new(&client).await?;
+        let mut owner_changes = dbus
+            .receive_name_owner_changed_with_args(&[(0, "org.zbus.Greeter")])
+            .await?;
+
+        let service = connection::Builder::address(address.as_str())?
+            .name("org.zbus.Greeter")?
+            .serve_at("/org/zbus/Greeter", Greeter)?
+            .build()
+            .await?;
+        assert_ne!(client.unique_name(), service.unique_name());
+        let change = owner_changes.next().await.unwrap();
+        let args = change.args()?;
+        assert!(args.old_owner().is_none());
+        assert_eq!(args.new_owner().as_ref(), service.unique_name().map(|n| &**n));
+
+        let names = dbus.list_names().await?;
+        assert!(names.iter().any(|n| *n == "org.zbus.Greeter"));
+        assert_eq!(
+            dbus.get_name_owner("org.zbus.Greeter".try_into()?).await?,
+            *service.unique_name().unwrap()
+        );
+        assert_eq!(dbus.get_id().await?.as_str().len(), 32);
+
+        // Method calls and broadcast signals.
+        let greeter = GreeterProxy::new(&client).await?;
+        let mut greeted = greeter.receive_greeted().await?;
+        assert_eq!(greeter.greet("zbus").await?, "Hello zbus!");
+        let signal = greeted.next().await.unwrap();
+        assert_eq!(signal.args()?.name(), &"zbus");
+
+        // The policy.
+        assert!(matches!(
+            greeter.forbidden().await.unwrap_err(),
+            zbus::Error::MethodError(name, _, _) if name == "org.freedesktop.DBus.Error.AccessDenied"
+        ));
+
+        // Unknown destinations.
+        let err = client
+            .call_method(
+                Some("org.zbus.NoSuchService"),
+                "/",
+                Some("org.zbus.NoSuchService"),
+                "Nothing",
+                &(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            zbus::Error::MethodError(name, _, _) if name == "org.freedesktop.DBus.Error.ServiceUnknown"
+        ));
+
+        // The name is released when the owner disconnects.
+        drop(greeter);
+        drop(service);
+        let change = owner_changes.next().await.unwrap();
+        let args = change.args()?;
+        assert!(args.new_owner().is_none());
+        assert!(!dbus.name_has_owner("org.zbus.Greeter".try_into()?).await?);
+
+        Ok::<_, zbus::Error>(())
+    })
+    .unwrap();
+}
+
+#[test]
+#[timeout(15000)]
+fn name_queue() {
+    let address = start_bus("queue");
+
+    block_on(async {
+        let first = connect(&address).await?;
+        let second = connect(&address).await?;
+        let name = "org.zbus.Queued";
+
+        let first_dbus = fdo::DBusProxy::new(&first).await?;
+        let second_dbus = fdo::DBusProxy::new(&second).await?;
+        assert_eq!(
+            first_dbus
+                .request_name(
+                    name.try_into()?,
+                    fdo::RequestNameFlags::AllowReplacement.into()
+                )
+                .await?,
+            fdo::RequestNameReply::PrimaryOwner
+        );
+        assert_eq!(
+            second_dbus
+                .request_name(name.try_into()?, Default::default())
+                .await?,
+            fdo::RequestNameReply::InQueue
+        );
+        assert_eq!(
+            second_dbus
+                .request_name(
+                    name.try_into()?,
+                    fdo::RequestNameFlags::ReplaceExisting.into()
+                )
+                .await?,
+            fdo::RequestNameReply::PrimaryOwner
+        );
+        assert_eq!(
+            first_dbus.get_name_owner(name.try_into()?).await?,
+            *second.unique_name().unwrap()
+        );
+        assert_eq!(
+            first_dbus.release_name(name.try_into()?).await?,
+            fdo::ReleaseNameReply::Released
+        );
+        assert_eq!(
+            second_dbus.release_name(name.try_into()?).await?,
+            fdo::ReleaseNameReply::Released
+        );
+        assert!(!first_dbus.name_has_owner(name.try_into()?).await?);
+
+        Ok::<_, zbus::Error>(())
+    })
+    .unwrap();
+}
+
+#[test]
+#[timeout(15000)]
+fn hello_first() {
+    let address = start_bus("hello");
+
+    block_on(async {
+        let client = connect(&address).await?;
+        let dbus = fdo::DBusProxy::new(&client).await?;
+
+        // Without `Hello`, the bus disconnects us on the first message.
+        let rude = connection::Builder::address(address.as_str())?
+            .p2p()
+            .build()
+            .await?;
+        rude.call_method(
+            Some("org.freedesktop.DBus"),
+            "/org/freedesktop/DBus",
+            Some("org.freedesktop.DBus"),
+            "GetId",
+            &(),
+        )
+        .await
+        .unwrap_err();
+
+        // Nobody else ever hears about it.
+        let names = dbus.list_names().await?;
+        assert_eq!(names.iter().filter(|n| n.starts_with(':')).count(), 1);
+
+        Ok::<_, zbus::Error>(())
+    })
+    .unwrap();
+}
+
+#[test]
+#[timeout(15000)]
+fn unsolicited_replies() {
+    let address = start_bus("replies");
+
+    block_on(async {
+        let sender = connect(&address).await?;
+        let receiver = connect(&address).await?;
+        let receiver_name = receiver.unique_name().unwrap().to_owned();
+        let mut stream = zbus::MessageStream::from(&receiver);
+
+        // A reply to a call `receiver` never made.
+        let call = zbus::Message::method_call("/", "Nothing")?
+            .sender(receiver_name.as_str())?
+            .build(&())?;
+        let reply = zbus::Message::method_return(&call.header())?.build(&())?;
+        sender.send(&reply).await?;
+        sender
+            .emit_signal(
+                Some(receiver_name.as_str()),
+                "/",
+                "org.zbus.Test",
+                "Marker",
+                &(),
+            )
+            .await?;
+
+        // Only the signal makes it through.
+        let msg = loop {
+            let msg = stream.next().await.unwrap()?;
+            if msg.header().sender().map(|s| s.as_str()) != Some("org.freedesktop.DBus") {
+                break msg;
+            }
+        };
+        assert_eq!(msg.message_type(), zbus::message::Type::Signal);
+
+        Ok::<_, zbus::Error>(())
+    })
+    .unwrap();
+}
@@ -5,7 +5,7 @@ use std::{collections::HashMap, vec};
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
-use zvariant::{serialized::Context, to_bytes, Type, Value, LE};
+use zvariant::{serialized::Context, to_bytes, Bytes, Type, Value, LE};
 
 macro_rules! benchmark {
     ($c:ident, $data:ident, $data_type:ty, $func_prefix:literal) => {
@@ -65,6 +65,12 @@ fn byte_array(c: &mut Criterion) {
     benchmark!(c, ay, ByteBuf, "byte_array");
 }
 
+fn zvariant_byte_array(c: &mut Criterion) {
+    let ay = Bytes::from(vec![77u8; 100_000]);
+
+    benchmark!(c, ay, Bytes<'_>, "zvariant_byte_array");
+}
+
 fn fixed_size_array(c: &mut Criterion) {
     let ay = vec![77u8; 100_000];
 
@@ -193,6 +199,7 @@ criterion_group!(
     benches,
     big_array,
     byte_array,
+    zvariant_byte_array,
     fixed_size_array,
     signature_parse,
     object_path_parse
@@ -201,6 +208,7 @@ criterion_group!(
 criterion_group!(
     benches,
     big_array,
+    zvariant_byte_array,
     fixed_size_array,
     signature_parse,
     object_path_parse
@@ -73,16 +73,64 @@ impl<'a> Array<'a> {
     }
 
     /// Get the value at the given index.
-    pub fn get<V>(&'a self, idx: usize) -> Result<Option<V>>
+    pub fn get<'s, V>(&'s self, idx: usize) -> Result<Option<V>>
     where
-        V: TryFrom<&'a Value<'a>>,
-        <V as TryFrom<&'a Value<'a>>>::Error: Into<crate::Error>,
+        V: TryFrom<&'s Value<'s>>,
+        <V as TryFrom<&'s Value<'s>>>::Error: Into<crate::Error>,
     {
         self.elements
             .get(idx)
             .map(|v| v.downcast_ref::<V>())
             .transpose()
-            .map_err(Into::into)
+    }
+
+    /// Iterate over the elements, without cloning them.
+    pub fn iter(&self) -> std::slice::Iter<'_, Value<'a>> {
+        self.elements.iter()
+    }
+
+    /// Iterate over the elements, converting each of them to `V` without cloning.
+    ///
+    /// Each item is the result of [`Value::downcast_ref`] on the respective element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zvariant::Array;
+    ///
+    /// let array = Array::from(vec!["one", "two", "three"]);
+    /// let strs = array.iter_as::<&str>().collect::<zvariant::Result<Vec<_>>>().unwrap();
+    /// assert_eq!(strs, ["one", "two", "three"]);
+    ///
+    /// assert!(array.iter_as::<u32>().all(|e| e.is_err()));
+    /// ```
+    pub fn iter_as<'s, V>(&'s self) -> impl Iterator<Item = Result<V>> + 's
+    where
+        V: TryFrom<&'s Value<'s>> + 's,
+        <V as TryFrom<&'s Value<'s>>>::Error: Into<crate::Error>,
+    {
+        self.elements.iter().map(|v| v.downcast_ref::<V>())
+    }
+
+    /// Convert into the given type, moving the elements rather than cloning them.
+    ///
+    /// This is a convenient alternative to [`TryFrom`] conversions, e.g to [`Vec<T>`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zvariant::{Array, Str};
+    ///
+    /// let array = Array::from(vec!["one".to_string(), "two".to_string()]);
+    /// let strs: Vec<Str<'_>> = array.try_into_typed().unwrap();
+    /// assert_eq!(strs, ["one", "two"]);
+    /// ```
+    pub fn try_into_typed<T>(self) -> Result<T>
+    where
+        T: TryFrom<Self>,
+        T::Error: Into<crate::Error>,
+    {
+        T::try_from(self).map_err(Into::into)
     }
 
     /// Get the number of elements.
@@ -240,6 +288,24 @@ impl<'a> std::ops::Deref for Array<'a> {
     }
 }
 
+impl<'a> IntoIterator for Array<'a> {
+    type Item = Value<'a>;
+    type IntoIter = std::vec::IntoIter<Value<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.elements.into_iter()
+    }
+}
+
+impl<'s, 'a> IntoIterator for &'s Array<'a> {
+    type Item = &'s Value<'a>;
+    type IntoIter = std::slice::Iter<'s, Value<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 impl<'a, T> From<Vec<T>> for Array<'a>
 where
     T: Type + Into<Value<'a>>,
@@ -9,19 +9,38 @@ use crate::{Error, MaxDepthExceeded, Result};
 // [mistake]: https://gitlab.gnome.org/GNOME/glib/-/commit/7c4e6e9fbe473de0401c778c6b0c4aad27d5145a
 const MAX_STRUCT_DEPTH: u8 = 32;
 const MAX_ARRAY_DEPTH: u8 = 32;
-const MAX_TOTAL_DEPTH: u8 = 64;
+pub(crate) const MAX_TOTAL_DEPTH: u8 = 64;
 
 // Represents the current depth of all container being (de)serialized.
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
 pub(crate) struct ContainerDepths {
     structure: u8,
     array: u8,
     variant: u8,
     #[cfg(feature = "gvariant")]
     maybe: u8,
+    // The maximum total depth, which can be lower than `MAX_TOTAL_DEPTH`.
+    max_total: u8,
+}
+
+impl Default for ContainerDepths {
+    fn default() -> Self {
+        Self::new(MAX_TOTAL_DEPTH)
+    }
 }
 
 impl ContainerDepths {
+    pub fn new(max_total: u8) -> Self {
+        Self {
+            structure: 0,
+            array: 0,
+            variant: 0,
+            #[cfg(feature = "gvariant")]
+            maybe: 0,
+            max_total,
+        }
+    }
+
     pub fn inc_structure(mut self) -> Result<Self> {
         self.structure += 1;
         self.check()
@@ -73,7 +92,7 @@ impl ContainerDepths {
         #[cfg(feature = "gvariant")]
         let total = self.structure + self.array + self.variant + self.maybe;
 
-        if total > MAX_TOTAL_DEPTH {
+        if total > self.max_total {
             return Err(Error::MaxDepthExceeded(MaxDepthExceeded::Container));
         }
 
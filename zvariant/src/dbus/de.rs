@@ -1,15 +1,20 @@
 use serde::de::{self, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, Visitor};
 
-use std::{marker::PhantomData, str};
+use std::{borrow::Cow, marker::PhantomData, str};
 
 #[cfg(unix)]
 use std::os::fd::AsFd;
 
 use crate::{
-    de::{DeserializerCommon, ValueParseStage},
+    container_depths::ContainerDepths,
+    de::{
+        native_endian_elements, numeric_element_size, DeserializerCommon, ValueParseStage,
+        ELEMENT_DECODED_SIZE,
+    },
     serialized::{Context, Format},
+    slice::slice_element_signature,
     utils::*,
-    Basic, Error, ObjectPath, Result, Signature,
+    Basic, Error, LimitExceeded, ObjectPath, Result, Signature,
 };
 
 /// Our D-Bus deserialization implementation.
@@ -28,6 +33,9 @@ impl<'de, 'sig, 'f, F> Deserializer<'de, 'sig, 'f, F> {
         ctxt: Context,
     ) -> Result<Self> {
         assert_eq!(ctxt.format(), Format::DBus);
+        if ctxt.max_size().is_some_and(|max| bytes.len() > max) {
+            return Err(Error::LimitExceeded(LimitExceeded::Size));
+        }
 
         Ok(Self(DeserializerCommon {
             ctxt,
@@ -38,7 +46,8 @@ impl<'de, 'sig, 'f, F> Deserializer<'de, 'sig, 'f, F> {
             #[cfg(not(unix))]
             fds: PhantomData,
             pos: 0,
-            container_depths: Default::default(),
+            container_depths: ContainerDepths::new(ctxt.max_depth()),
+            decoded_size: 0,
         }))
     }
 }
@@ -147,7 +156,25 @@ impl<'de, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deserializer<'de>
     deserialize_as!(deserialize_tuple_struct(n: &'static str, _l: usize) => deserialize_struct(n, &[]));
     deserialize_as!(deserialize_struct(_n: &'static str, _f: &'static [&'static str]) => deserialize_seq());
     deserialize_as!(deserialize_map => deserialize_seq);
-    deserialize_as!(deserialize_ignored_any => deserialize_any);
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let element_alignment = match self.0.signature {
+            Signature::Array(child) => child.alignment(Format::DBus),
+            Signature::Dict { .. } => DICT_ENTRY_ALIGNMENT_DBUS,
+            _ => return self.deserialize_any(visitor),
+        };
+
+        // Arrays are prefixed with their length so we can skip them without parsing the elements.
+        self.0.parse_padding(ARRAY_ALIGNMENT_DBUS)?;
+        let len = self.0.ctxt.endian().read_u32(self.0.next_slice(4)?) as usize;
+        self.0.parse_padding(element_alignment)?;
+        self.0.next_slice(len)?;
+
+        visitor.visit_unit()
+    }
 
     fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value>
     where
@@ -158,6 +185,7 @@ impl<'de, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deserializer<'de>
             Signature::Fd => {
                 let alignment = u32::alignment(Format::DBus);
                 self.0.parse_padding(alignment)?;
+                self.0.add_decoded_size(alignment)?;
                 let idx = self.0.ctxt.endian().read_u32(self.0.next_slice(alignment)?);
                 self.0.get_fd(idx)?
             }
@@ -226,15 +254,16 @@ impl<'de, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deserializer<'de>
                 return Err(Error::SignatureMismatch(self.0.signature.clone(), expected));
             }
         };
+        self.0.add_decoded_size(len)?;
         let slice = self.0.next_slice(len)?;
-        if slice.contains(&0) {
+        if contains_nul(slice) {
             return Err(serde::de::Error::invalid_value(
                 serde::de::Unexpected::Char('\0'),
                 &"D-Bus string type must not contain interior null bytes",
             ));
         }
         self.0.pos += 1; // skip trailing null byte
-        let s = str::from_utf8(slice).map_err(Error::Utf8)?;
+        let s = str_from_utf8(slice)?;
 
         visitor.visit_borrowed_str(s)
     }
@@ -281,11 +310,20 @@ impl<'de, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deserializer<'de>
         visitor.visit_unit()
     }
 
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_newtype_struct(self)
+        let Some(element_signature) = slice_element_signature(name) else {
+            return visitor.visit_newtype_struct(self);
+        };
+
+        // A `Slice` of numbers, which we hand out as raw bytes in native byte order.
+        let (bytes, element_size) = deserialize_numeric_array(self, element_signature)?;
+        match native_endian_elements(bytes, element_size, self.0.ctxt.endian()) {
+            Cow::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
+            Cow::Owned(bytes) => visitor.visit_byte_buf(bytes),
+        }
     }
 
     fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
@@ -386,6 +424,8 @@ struct ArrayDeserializer<'d, 'de, 'sig, 'f, F> {
     de: &'d mut Deserializer<'de, 'sig, 'f, F>,
     len: usize,
     start: usize,
+    // number of elements deserialized so far
+    num_elements: usize,
     // alignment of element
     element_alignment: usize,
     array_signature: &'sig Signature,
@@ -426,6 +466,7 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>
             de,
             len,
             start,
+            num_elements: 0,
             element_alignment,
             array_signature,
         })
@@ -456,6 +497,9 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>
 
             return Ok(None);
         }
+        self.num_elements += 1;
+        self.de.0.check_num_elements(self.num_elements)?;
+        self.de.0.add_decoded_size(ELEMENT_DECODED_SIZE)?;
         // Redundant for normal arrays but dict requires each entry to be padded by 8 bytes.
         self.de.0.parse_padding(self.element_alignment)?;
 
@@ -479,11 +523,42 @@ fn deserialize_ay<'de, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>(
         return Err(de::Error::invalid_type(de::Unexpected::Seq, &"ay"));
     }
 
+    deserialize_numeric_array(de, &Signature::U8).map(|(bytes, _)| bytes)
+}
+
+/// Deserialize an array of fixed-sized numbers of the given signature as raw bytes, along with the
+/// size of each element.
+fn deserialize_numeric_array<'de, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>(
+    de: &mut Deserializer<'de, '_, '_, F>,
+    element_signature: &Signature,
+) -> Result<(&'de [u8], usize)> {
+    let element_size = match de.0.signature {
+        Signature::Array(child) if child.signature() == element_signature => {
+            numeric_element_size(element_signature)
+        }
+        _ => None,
+    };
+    let Some(element_size) = element_size else {
+        return Err(Error::SignatureMismatch(
+            de.0.signature.clone(),
+            format!("`a{element_signature}`"),
+        ));
+    };
+
     let mut ad = ArrayDeserializer::new(de)?;
     let len = ad.len;
     ad.end();
 
-    de.0.next_slice(len)
+    if len % element_size != 0 {
+        return Err(de::Error::invalid_length(
+            len,
+            &format!("a multiple of {element_size}").as_str(),
+        ));
+    }
+    de.0.check_num_elements(len / element_size)?;
+    de.0.add_decoded_size(len)?;
+
+    de.0.next_slice(len).map(|bytes| (bytes, element_size))
 }
 
 struct ArraySeqDeserializer<'d, 'de, 'sig, 'f, F>(ArrayDeserializer<'d, 'de, 'sig, 'f, F>);
@@ -614,9 +689,11 @@ impl<'de, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de>
             bytes: self.de.0.bytes,
             pos: self.de.0.pos,
             container_depths: self.de.0.container_depths,
+            decoded_size: self.de.0.decoded_size,
         });
         let v = seed.deserialize(&mut de)?;
         self.de.0.pos = de.0.pos;
+        self.de.0.decoded_size = de.0.decoded_size;
 
         if self.field_idx == self.num_fields {
             // All fields have been deserialized.
@@ -660,7 +737,14 @@ impl<'de, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de>
             ValueParseStage::Signature => {
                 self.stage = ValueParseStage::Value;
 
-                seed.deserialize(&mut *self.de).map(Some)
+                // The signature of the value is encoded as a `g`. Not switching to it would make
+                // seeds that deserialize based on the signature (e.g `IgnoredAny`) recurse forever.
+                let signature = self.de.0.signature;
+                self.de.0.signature = &Signature::Signature;
+                let v = seed.deserialize(&mut *self.de).map(Some);
+                self.de.0.signature = signature;
+
+                v
             }
             ValueParseStage::Value => {
                 self.stage = ValueParseStage::Done;
@@ -675,11 +759,11 @@ impl<'de, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de>
                 let slice = subslice(self.de.0.bytes, sig_start..sig_end)?;
                 let signature = Signature::from_bytes(slice)?;
 
-                let ctxt = Context::new(
-                    Format::DBus,
-                    self.de.0.ctxt.endian(),
-                    self.de.0.ctxt.position() + value_start,
-                );
+                let ctxt = self
+                    .de
+                    .0
+                    .ctxt
+                    .with_position(self.de.0.ctxt.position() + value_start);
                 let mut de = Deserializer::<F>(DeserializerCommon {
                     ctxt,
                     signature: &signature,
@@ -687,10 +771,12 @@ impl<'de, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de>
                     fds: self.de.0.fds,
                     pos: 0,
                     container_depths: self.de.0.container_depths.inc_variant()?,
+                    decoded_size: self.de.0.decoded_size,
                 });
 
                 let v = seed.deserialize(&mut de).map(Some);
                 self.de.0.pos += de.0.pos;
+                self.de.0.decoded_size = de.0.decoded_size;
 
                 v
             }
@@ -1,6 +1,7 @@
 use serde::de::{self, DeserializeSeed, VariantAccess, Visitor};
 
-use std::{marker::PhantomData, str};
+use endi::{Endian, NATIVE_ENDIAN};
+use std::{borrow::Cow, marker::PhantomData, str};
 
 #[cfg(unix)]
 use std::os::fd::{AsFd, AsRawFd};
@@ -9,7 +10,7 @@ use std::os::fd::{AsFd, AsRawFd};
 use crate::gvariant::Deserializer as GVDeserializer;
 use crate::{
     container_depths::ContainerDepths, dbus::Deserializer as DBusDeserializer, serialized::Context,
-    utils::*, Basic, Error, Result, Signature,
+    utils::*, Basic, Error, LimitExceeded, Result, Signature,
 };
 
 /// Our deserialization implementation.
@@ -28,8 +29,15 @@ pub(crate) struct DeserializerCommon<'de, 'sig, 'f, F> {
     pub(crate) signature: &'sig Signature,
 
     pub(crate) container_depths: ContainerDepths,
+
+    // The total size of the values decoded so far, checked against the maximum size of the context.
+    pub(crate) decoded_size: usize,
 }
 
+/// The size of each element of an array or dictionary, accounted for on top of the size of the
+/// element itself, since the decoded container needs to refer to each of its elements somehow.
+pub(crate) const ELEMENT_DECODED_SIZE: usize = std::mem::size_of::<usize>();
+
 /// Our deserialization implementation.
 ///
 /// Using this deserializer involves an redirection to the actual deserializer. It's best
@@ -54,6 +62,27 @@ where
 }
 
 impl<'de, F> DeserializerCommon<'de, '_, '_, F> {
+    /// Check the number of elements of an array against the limit of the context.
+    pub fn check_num_elements(&self, num_elements: usize) -> Result<()> {
+        match self.ctxt.max_elements() {
+            Some(max) if num_elements > max => Err(Error::LimitExceeded(LimitExceeded::Elements)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Account for `size` more bytes of decoded data and check the total against the limit of the
+    /// context.
+    ///
+    /// This is separate from the size of the input, since the same input bytes can be decoded into
+    /// more data than they take, e.g every element of an array needs to be stored somewhere.
+    pub fn add_decoded_size(&mut self, size: usize) -> Result<()> {
+        self.decoded_size = self.decoded_size.saturating_add(size);
+        match self.ctxt.max_size() {
+            Some(max) if self.decoded_size > max => Err(Error::LimitExceeded(LimitExceeded::Size)),
+            _ => Ok(()),
+        }
+    }
+
     pub fn parse_padding(&mut self, alignment: usize) -> Result<usize> {
         let padding = padding_for_n_bytes(self.abs_pos(), alignment);
         if padding > 0 {
@@ -104,8 +133,10 @@ impl<'de, F> DeserializerCommon<'de, '_, '_, F> {
         T: Basic,
     {
         self.prep_deserialize_basic::<T>()?;
+        let size = T::alignment(self.ctxt.format());
+        self.add_decoded_size(size)?;
 
-        self.next_slice(T::alignment(self.ctxt.format()))
+        self.next_slice(size)
     }
 
     pub fn abs_pos(&self) -> usize {
@@ -179,6 +210,41 @@ impl<'de, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deserializer<'de>
     }
 }
 
+/// The size of each element of an array with the given element signature, if the elements are
+/// of a fixed-sized numeric type.
+///
+/// Arrays of such elements are encoded as a contiguous sequence of elements (in both formats), so
+/// they can be accessed directly as raw bytes.
+pub(crate) fn numeric_element_size(signature: &Signature) -> Option<usize> {
+    match signature {
+        Signature::U8 => Some(1),
+        Signature::I16 | Signature::U16 => Some(2),
+        Signature::I32 | Signature::U32 => Some(4),
+        Signature::I64 | Signature::U64 | Signature::F64 => Some(8),
+        _ => None,
+    }
+}
+
+/// Get the raw bytes of numeric array elements in native byte order.
+///
+/// This only copies the data if a byte swap is required.
+pub(crate) fn native_endian_elements(
+    bytes: &[u8],
+    element_size: usize,
+    endian: Endian,
+) -> Cow<'_, [u8]> {
+    if element_size == 1 || endian == NATIVE_ENDIAN {
+        return Cow::Borrowed(bytes);
+    }
+
+    let mut swapped = bytes.to_vec();
+    for element in swapped.chunks_exact_mut(element_size) {
+        element.reverse();
+    }
+
+    Cow::Owned(swapped)
+}
+
 #[derive(Debug)]
 pub(crate) enum ValueParseStage {
     Signature,
@@ -97,6 +97,21 @@ impl<'k, 'v> Dict<'k, 'v> {
         self.map.get(&key).map(|v| v.downcast_ref()).transpose()
     }
 
+    /// Get the value for the given key, without any conversion.
+    pub(crate) fn get_value<'d>(&'d self, key: &Value<'d>) -> Option<&'d Value<'v>> {
+        self.map.get(key)
+    }
+
+    /// Get the number of entries.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Whether there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
     /// Get the signature of this `Dict`.
     pub fn signature(&self) -> &Signature {
         &self.signature
@@ -142,14 +157,69 @@ impl<'k, 'v> Dict<'k, 'v> {
         }
     }
 
+    /// Iterate over the entries, without cloning them.
     pub fn iter(&self) -> impl Iterator<Item = (&Value<'k>, &Value<'v>)> {
         self.map.iter()
     }
 
+    /// Iterate over the entries, with mutable access to the values.
     pub fn iter_mut(&mut self) -> impl Iterator<Item = (&Value<'k>, &mut Value<'v>)> {
         self.map.iter_mut()
     }
 
+    /// Iterate over the entries, converting the keys to `K` and the values to `V` without
+    /// cloning.
+    ///
+    /// Each item is the result of [`Value::downcast_ref`] on the respective key and value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use zvariant::Dict;
+    ///
+    /// let dict = Dict::from(HashMap::from([("one", 1u32), ("two", 2)]));
+    /// let entries = dict
+    ///     .iter_as::<&str, u32>()
+    ///     .collect::<zvariant::Result<Vec<_>>>()
+    ///     .unwrap();
+    /// assert_eq!(entries, [("one", 1), ("two", 2)]);
+    /// ```
+    pub fn iter_as<'d, K, V>(&'d self) -> impl Iterator<Item = Result<(K, V), Error>> + 'd
+    where
+        K: TryFrom<&'d Value<'d>> + 'd,
+        <K as TryFrom<&'d Value<'d>>>::Error: Into<crate::Error>,
+        V: TryFrom<&'d Value<'d>> + 'd,
+        <V as TryFrom<&'d Value<'d>>>::Error: Into<crate::Error>,
+    {
+        self.map
+            .iter()
+            .map(|(k, v)| Ok((k.downcast_ref::<K>()?, v.downcast_ref::<V>()?)))
+    }
+
+    /// Convert into the given type, moving the entries rather than cloning them.
+    ///
+    /// This is a convenient alternative to [`TryFrom`] conversions, e.g to
+    /// [`HashMap`](std::collections::HashMap).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use zvariant::{Dict, Str};
+    ///
+    /// let dict = Dict::from(HashMap::from([("one".to_string(), 1u32)]));
+    /// let map: HashMap<Str<'_>, u32> = dict.try_into_typed().unwrap();
+    /// assert_eq!(map[&Str::from("one")], 1);
+    /// ```
+    pub fn try_into_typed<T>(self) -> Result<T, Error>
+    where
+        T: TryFrom<Self>,
+        T::Error: Into<crate::Error>,
+    {
+        T::try_from(self).map_err(Into::into)
+    }
+
     // TODO: Provide more API like https://docs.rs/toml/0.5.5/toml/map/struct.Map.html
 }
 
@@ -159,6 +229,15 @@ impl Display for Dict<'_, '_> {
     }
 }
 
+impl<'d, 'k, 'v> IntoIterator for &'d Dict<'k, 'v> {
+    type Item = (&'d Value<'k>, &'d Value<'v>);
+    type IntoIter = <&'d BTreeMap<Value<'k>, Value<'v>> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter()
+    }
+}
+
 impl<'k, 'v> IntoIterator for Dict<'k, 'v> {
     type Item = (Value<'k>, Value<'v>);
     type IntoIter = <BTreeMap<Value<'k>, Value<'v>> as IntoIterator>::IntoIter;
@@ -34,6 +34,30 @@ impl fmt::Display for MaxDepthExceeded {
     }
 }
 
+/// Enum representing the deserialization limit that was exceeded.
+///
+/// See [`Context`](crate::serialized::Context#limits) for how to set these limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LimitExceeded {
+    /// The maximum number of elements in an array or dictionary was exceeded.
+    Elements,
+    /// The maximum size of the data to deserialize was exceeded.
+    Size,
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Elements => write!(
+                f,
+                "Maximum allowed number of elements in an array or dictionary was exceeded"
+            ),
+            Self::Size => write!(f, "Maximum allowed size of the data was exceeded"),
+        }
+    }
+}
+
 /// Error type used by zvariant API.
 #[derive(Debug)]
 #[non_exhaustive]
@@ -68,6 +92,8 @@ pub enum Error {
     EmptyStructure,
     /// Invalid object path.
     InvalidObjectPath,
+    /// A limit set on the deserialization context was exceeded.
+    LimitExceeded(LimitExceeded),
 }
 
 assert_impl_all!(Error: Send, Sync, Unpin);
@@ -95,6 +121,7 @@ impl PartialEq for Error {
             (Error::SignatureParse(e1), Error::SignatureParse(e2)) => e1 == e2,
             (Error::EmptyStructure, Error::EmptyStructure) => true,
             (Error::InvalidObjectPath, Error::InvalidObjectPath) => true,
+            (Error::LimitExceeded(limit1), Error::LimitExceeded(limit2)) => limit1 == limit2,
             (_, _) => false,
         }
     }
@@ -139,6 +166,7 @@ impl fmt::Display for Error {
             Error::SignatureParse(e) => write!(f, "{e}"),
             Error::EmptyStructure => write!(f, "Attempted to create an empty structure"),
             Error::InvalidObjectPath => write!(f, "Invalid object path"),
+            Error::LimitExceeded(limit) => write!(f, "{limit}"),
         }
     }
 }
@@ -164,6 +192,7 @@ impl Clone for Error {
             Error::SignatureParse(e) => Error::SignatureParse(*e),
             Error::EmptyStructure => Error::EmptyStructure,
             Error::InvalidObjectPath => Error::InvalidObjectPath,
+            Error::LimitExceeded(limit) => Error::LimitExceeded(*limit),
         }
     }
 }
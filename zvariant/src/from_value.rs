@@ -110,6 +110,18 @@ value_try_from!(Array, Array<'a>);
 value_try_from_ref!(Array, Array<'a>);
 value_try_from_ref_try_clone!(Array, Array<'a>);
 
+impl<'a, 'v> TryFrom<&'a Value<'v>> for &'a [Value<'v>] {
+    type Error = Error;
+
+    fn try_from(value: &'a Value<'v>) -> Result<Self, Self::Error> {
+        if let Value::Array(value) = value {
+            Ok(value.inner())
+        } else {
+            Err(Error::IncorrectType)
+        }
+    }
+}
+
 #[cfg(feature = "gvariant")]
 value_try_from!(Maybe, Maybe<'a>);
 #[cfg(feature = "gvariant")]
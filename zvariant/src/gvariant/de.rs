@@ -1,17 +1,22 @@
 use serde::de::{self, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, Visitor};
 
-use std::{ffi::CStr, marker::PhantomData, str};
+use std::{borrow::Cow, ffi::CStr, marker::PhantomData, str};
 
 #[cfg(unix)]
 use std::os::fd::AsFd;
 
 use crate::{
-    de::{DeserializerCommon, ValueParseStage},
+    container_depths::ContainerDepths,
+    de::{
+        native_endian_elements, numeric_element_size, DeserializerCommon, ValueParseStage,
+        ELEMENT_DECODED_SIZE,
+    },
     framing_offset_size::FramingOffsetSize,
     framing_offsets::FramingOffsets,
     serialized::{Context, Format},
+    slice::slice_element_signature,
     utils::*,
-    Basic, Error, ObjectPath, Result, Signature,
+    Basic, Error, LimitExceeded, ObjectPath, Result, Signature,
 };
 
 /// Our GVariant deserialization implementation.
@@ -29,6 +34,9 @@ impl<'de, 'sig, 'f, F> Deserializer<'de, 'sig, 'f, F> {
         ctxt: Context,
     ) -> Result<Self> {
         assert_eq!(ctxt.format(), Format::GVariant);
+        if ctxt.max_size().is_some_and(|max| bytes.len() > max) {
+            return Err(Error::LimitExceeded(LimitExceeded::Size));
+        }
 
         Ok(Self(DeserializerCommon {
             ctxt,
@@ -39,7 +47,8 @@ impl<'de, 'sig, 'f, F> Deserializer<'de, 'sig, 'f, F> {
             #[cfg(not(unix))]
             fds: PhantomData,
             pos: 0,
-            container_depths: Default::default(),
+            container_depths: ContainerDepths::new(ctxt.max_depth()),
+            decoded_size: 0,
         }))
     }
 }
@@ -59,11 +68,13 @@ macro_rules! deserialize_basic {
                 fds: self.0.fds,
                 pos: 0,
                 container_depths: self.0.container_depths,
+                decoded_size: self.0.decoded_size,
             });
 
             let v = dbus_de.$method(visitor)?;
             self.0.signature = dbus_de.0.signature;
             self.0.pos += dbus_de.0.pos;
+            self.0.decoded_size = dbus_de.0.decoded_size;
             // Basic types don't have anything to do with container depths so not updating it here.
 
             Ok(v)
@@ -95,7 +106,7 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
     where
         V: Visitor<'de>,
     {
-        crate::de::deserialize_any::<Self, V>(self, &self.0.signature, visitor)
+        crate::de::deserialize_any::<Self, V>(self, self.0.signature, visitor)
     }
 
     deserialize_basic!(deserialize_bool);
@@ -133,7 +144,23 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
     deserialize_as!(deserialize_tuple_struct(n: &'static str, _l: usize) => deserialize_struct(n, &[]));
     deserialize_as!(deserialize_struct(_n: &'static str, _f: &'static [&'static str]) => deserialize_seq());
     deserialize_as!(deserialize_map => deserialize_seq);
-    deserialize_as!(deserialize_ignored_any => deserialize_any);
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.0.signature.is_fixed_sized() {
+            return self.deserialize_any(visitor);
+        }
+
+        // The end of a non-fixed-sized value is determined by its container (through the framing
+        // offsets), so it always extends to the end of the bytes we're given and we can skip them.
+        let alignment = self.0.signature.alignment(Format::GVariant);
+        self.0.parse_padding(alignment)?;
+        self.0.pos = self.0.bytes.len();
+
+        visitor.visit_unit()
+    }
 
     fn deserialize_str<V>(self, visitor: V) -> Result<V::Value>
     where
@@ -143,7 +170,7 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
 
         let s = match self.0.signature {
             Signature::Variant => {
-                if slice.contains(&0) {
+                if contains_nul(slice) {
                     return Err(serde::de::Error::invalid_value(
                         serde::de::Unexpected::Char('\0'),
                         &"GVariant string type must not contain interior null bytes",
@@ -151,7 +178,7 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
                 }
 
                 // GVariant decided to skip the trailing nul at the end of signature string
-                str::from_utf8(slice).map_err(Error::Utf8)?
+                str_from_utf8(slice)?
             }
             Signature::Str | Signature::Signature | Signature::ObjectPath => {
                 let cstr = CStr::from_bytes_with_nul(slice).map_err(|_| -> Error {
@@ -164,7 +191,7 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
 
                     de::Error::invalid_value(unexpected, &"nul byte expected at the end of strings")
                 })?;
-                let s = cstr.to_str().map_err(Error::Utf8)?;
+                let s = str_from_utf8(cstr.to_bytes())?;
                 self.0.pos += s.len() + 1; // string and trailing null byte
 
                 s
@@ -180,6 +207,8 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
                 return Err(Error::SignatureMismatch(self.0.signature.clone(), expected));
             }
         };
+        self.0.add_decoded_size(s.len())?;
+
         visitor.visit_borrowed_str(s)
     }
 
@@ -204,11 +233,10 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
         if self.0.pos == self.0.bytes.len() {
             visitor.visit_none()
         } else {
-            let ctxt = Context::new(
-                self.0.ctxt.format(),
-                self.0.ctxt.endian(),
-                self.0.ctxt.position() + self.0.pos,
-            );
+            let ctxt = self
+                .0
+                .ctxt
+                .with_position(self.0.ctxt.position() + self.0.pos);
             let end = if fixed_sized_child {
                 self.0.bytes.len()
             } else {
@@ -222,10 +250,12 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
                 fds: self.0.fds,
                 pos: 0,
                 container_depths: self.0.container_depths.inc_maybe()?,
+                decoded_size: self.0.decoded_size,
             });
 
             let v = visitor.visit_some(&mut de)?;
             self.0.pos += de.0.pos;
+            self.0.decoded_size = de.0.decoded_size;
             // No need for retaking the container depths as the underlying type can't be incomplete.
 
             if !fixed_sized_child {
@@ -268,11 +298,20 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
         visitor.visit_unit()
     }
 
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_newtype_struct(self)
+        let Some(element_signature) = slice_element_signature(name) else {
+            return visitor.visit_newtype_struct(self);
+        };
+
+        // A `Slice` of numbers, which we hand out as raw bytes in native byte order.
+        let (bytes, element_size) = deserialize_numeric_array(self, element_signature)?;
+        match native_endian_elements(bytes, element_size, self.0.ctxt.endian()) {
+            Cow::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
+            Cow::Owned(bytes) => visitor.visit_byte_buf(bytes),
+        }
     }
 
     fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
@@ -350,16 +389,49 @@ fn deserialize_ay<'de, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>(
         return Err(de::Error::invalid_type(de::Unexpected::Seq, &"ay"));
     }
 
+    deserialize_numeric_array(de, &Signature::U8).map(|(bytes, _)| bytes)
+}
+
+/// Deserialize an array of fixed-sized numbers of the given signature as raw bytes, along with the
+/// size of each element.
+fn deserialize_numeric_array<'de, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>(
+    de: &mut Deserializer<'de, '_, '_, F>,
+    element_signature: &Signature,
+) -> Result<(&'de [u8], usize)> {
+    let element_size = match de.0.signature {
+        Signature::Array(child) if child.signature() == element_signature => {
+            numeric_element_size(element_signature)
+        }
+        _ => None,
+    };
+    let Some(element_size) = element_size else {
+        return Err(Error::SignatureMismatch(
+            de.0.signature.clone(),
+            format!("`a{element_signature}`"),
+        ));
+    };
+
     let ad = ArrayDeserializer::new(de)?;
     let len = ad.len;
     de.0.container_depths = de.0.container_depths.dec_array();
 
-    de.0.next_slice(len)
+    if len % element_size != 0 {
+        return Err(de::Error::invalid_length(
+            len,
+            &format!("a multiple of {element_size}").as_str(),
+        ));
+    }
+    de.0.check_num_elements(len / element_size)?;
+    de.0.add_decoded_size(len)?;
+
+    de.0.next_slice(len).map(|bytes| (bytes, element_size))
 }
 
 struct ArrayDeserializer<'d, 'de, 'sig, 'f, F> {
     de: &'d mut Deserializer<'de, 'sig, 'f, F>,
     len: usize,
+    // number of elements deserialized so far
+    num_elements: usize,
     start: usize,
     // alignment of element
     element_alignment: usize,
@@ -422,6 +494,7 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>
         Ok(Self {
             de,
             len,
+            num_elements: 0,
             start,
             element_alignment: alignment,
             child_signature,
@@ -471,25 +544,30 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de
 
             return Ok(None);
         }
-
-        let ctxt = Context::new(
-            self.de.0.ctxt.format(),
-            self.de.0.ctxt.endian(),
-            self.de.0.ctxt.position() + self.de.0.pos,
-        );
+        self.num_elements += 1;
+        self.de.0.check_num_elements(self.num_elements)?;
+        self.de.0.add_decoded_size(ELEMENT_DECODED_SIZE)?;
+
+        let ctxt = self
+            .de
+            .0
+            .ctxt
+            .with_position(self.de.0.ctxt.position() + self.de.0.pos);
         let end = self.element_end(true)?;
 
         let mut de = Deserializer::<F>(DeserializerCommon {
             ctxt,
-            signature: &self.child_signature,
+            signature: self.child_signature,
             bytes: subslice(self.de.0.bytes, self.de.0.pos..end)?,
             fds: self.de.0.fds,
             pos: 0,
             container_depths: self.de.0.container_depths,
+            decoded_size: self.de.0.decoded_size,
         });
 
         let v = seed.deserialize(&mut de).map(Some);
         self.de.0.pos += de.0.pos;
+        self.de.0.decoded_size = de.0.decoded_size;
         // No need for retaking the container depths as the child can't be incomplete.
 
         if self.de.0.pos > self.start + self.len {
@@ -518,14 +596,17 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> MapAccess<'de
 
             return Ok(None);
         }
+        self.num_elements += 1;
+        self.de.0.check_num_elements(self.num_elements)?;
+        self.de.0.add_decoded_size(ELEMENT_DECODED_SIZE)?;
 
         self.de.0.parse_padding(self.element_alignment)?;
 
-        let ctxt = Context::new(
-            self.de.0.ctxt.format(),
-            self.de.0.ctxt.endian(),
-            self.de.0.ctxt.position() + self.de.0.pos,
-        );
+        let ctxt = self
+            .de
+            .0
+            .ctxt
+            .with_position(self.de.0.ctxt.position() + self.de.0.pos);
         let element_end = self.element_end(false)?;
 
         let key_end = match self.key_offset_size {
@@ -549,14 +630,16 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> MapAccess<'de
 
         let mut de = Deserializer::<F>(DeserializerCommon {
             ctxt,
-            signature: &self.child_signature,
+            signature: self.child_signature,
             bytes: subslice(self.de.0.bytes, self.de.0.pos..key_end)?,
             fds: self.de.0.fds,
             pos: 0,
             container_depths: self.de.0.container_depths,
+            decoded_size: self.de.0.decoded_size,
         });
         let v = seed.deserialize(&mut de).map(Some);
         self.de.0.pos += de.0.pos;
+        self.de.0.decoded_size = de.0.decoded_size;
         // No need for retaking the container depths as the key can't be incomplete.
 
         if self.de.0.pos > self.start + self.len {
@@ -573,11 +656,11 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> MapAccess<'de
     where
         V: DeserializeSeed<'de>,
     {
-        let ctxt = Context::new(
-            self.de.0.ctxt.format(),
-            self.de.0.ctxt.endian(),
-            self.de.0.ctxt.position() + self.de.0.pos,
-        );
+        let ctxt = self
+            .de
+            .0
+            .ctxt
+            .with_position(self.de.0.ctxt.position() + self.de.0.pos);
         let element_end = self.element_end(true)?;
         let value_end = match self.key_offset_size {
             Some(key_offset_size) => {
@@ -599,9 +682,11 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> MapAccess<'de
             fds: self.de.0.fds,
             pos: 0,
             container_depths: self.de.0.container_depths,
+            decoded_size: self.de.0.decoded_size,
         });
         let v = seed.deserialize(&mut de);
         self.de.0.pos += de.0.pos;
+        self.de.0.decoded_size = de.0.decoded_size;
         // No need for retaking the container depths as the value can't be incomplete.
 
         if let Some(key_offset_size) = self.key_offset_size {
@@ -675,11 +760,11 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de
             return Ok(None);
         }
 
-        let ctxt = Context::new(
-            self.de.0.ctxt.format(),
-            self.de.0.ctxt.endian(),
-            self.de.0.ctxt.position() + self.de.0.pos,
-        );
+        let ctxt = self
+            .de
+            .0
+            .ctxt
+            .with_position(self.de.0.ctxt.position() + self.de.0.pos);
         let signature = self.de.0.signature;
         let field_signature = match signature {
             Signature::Structure(fields) => {
@@ -726,9 +811,11 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de
             fds: self.de.0.fds,
             pos: 0,
             container_depths: self.de.0.container_depths,
+            decoded_size: self.de.0.decoded_size,
         });
         let v = seed.deserialize(&mut de).map(Some);
         self.de.0.pos += de.0.pos;
+        self.de.0.decoded_size = de.0.decoded_size;
         // No need for retaking the container depths as the field can't be incomplete.
 
         if self.field_idx == self.num_fields {
@@ -760,8 +847,6 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>
         de.0.parse_padding(VARIANT_ALIGNMENT_GVARIANT)?;
 
         // GVariant format has signature at the end
-        let mut separator_pos = None;
-
         if de.0.bytes.is_empty() {
             return Err(de::Error::invalid_value(
                 de::Unexpected::Other("end of byte stream"),
@@ -770,13 +855,11 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>
         }
 
         // Search for the nul byte separator
-        for i in (de.0.pos..de.0.bytes.len() - 1).rev() {
-            if de.0.bytes[i] == b'\0' {
-                separator_pos = Some(i);
-
-                break;
-            }
-        }
+        let separator_pos =
+            de.0.bytes
+                .get(de.0.pos..de.0.bytes.len() - 1)
+                .and_then(|bytes| memchr::memrchr(b'\0', bytes))
+                .map(|i| de.0.pos + i);
 
         let (sig_start, sig_end, value_start, value_end) = match separator_pos {
             None => {
@@ -820,9 +903,13 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de
                     fds: self.de.0.fds,
                     pos: 0,
                     container_depths: self.de.0.container_depths,
+                    decoded_size: self.de.0.decoded_size,
                 });
 
-                seed.deserialize(&mut de).map(Some)
+                let v = seed.deserialize(&mut de).map(Some);
+                self.de.0.decoded_size = de.0.decoded_size;
+
+                v
             }
             ValueParseStage::Value => {
                 self.stage = ValueParseStage::Done;
@@ -830,11 +917,11 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de
                 let slice = subslice(self.de.0.bytes, self.sig_start..self.sig_end)?;
                 let signature = Signature::from_bytes(slice)?;
 
-                let ctxt = Context::new(
-                    self.de.0.ctxt.format(),
-                    self.de.0.ctxt.endian(),
-                    self.de.0.ctxt.position() + self.value_start,
-                );
+                let ctxt = self
+                    .de
+                    .0
+                    .ctxt
+                    .with_position(self.de.0.ctxt.position() + self.value_start);
                 let mut de = Deserializer::<F>(DeserializerCommon {
                     ctxt,
                     signature: &signature,
@@ -842,9 +929,11 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de
                     fds: self.de.0.fds,
                     pos: 0,
                     container_depths: self.de.0.container_depths.inc_variant()?,
+                    decoded_size: self.de.0.decoded_size,
                 });
 
                 let v = seed.deserialize(&mut de).map(Some);
+                self.de.0.decoded_size = de.0.decoded_size;
 
                 self.de.0.pos = self.sig_end;
 
@@ -1,3 +1,30 @@
+//! GVariant format specifics.
+//!
+//! # Non-normal data
+//!
+//! The [GVariant specification] defines a single *normal form* for each value but, unlike the
+//! D-Bus format, a lot of data that isn't in normal form can still be interpreted. The
+//! specification allows implementations to deal with such data by substituting default values for
+//! the parts that can't be interpreted. GLib does that, which means that GLib never fails to read
+//! GVariant data.
+//!
+//! zvariant has the following policy instead:
+//!
+//! * Data that can be interpreted unambiguously is accepted, even if it's not in normal form. This
+//!   includes unused bytes after a fixed-sized value, e.g. inside a variant, and trailing bytes
+//!   after the top-level value.
+//! * Data that can't be interpreted (e.g. framing offsets that are out of bounds or out of order,
+//!   missing nul terminators, non-zero padding or booleans that are neither `0` nor `1`) results in
+//!   an error, instead of the default values GLib would substitute. Malformed data never results in
+//!   a panic.
+//!
+//! Use [`Data::is_normal_form`] to reject non-normal data upfront, e.g. when consuming data from
+//! untrusted sources like dconf databases or GSettings backends, where silently accepting
+//! non-normal data could lead to two different encodings for the same value.
+//!
+//! [GVariant specification]: https://developer.gnome.org/documentation/specifications/gvariant-specification-1.0.html
+//! [`Data::is_normal_form`]: crate::serialized::Data::is_normal_form
+
 mod de;
 pub(crate) use de::*;
 mod ser;
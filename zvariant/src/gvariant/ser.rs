@@ -339,12 +339,10 @@ where
             Signature::Structure(_) => {
                 StructSerializer::structure(self).map(StructSeqSerializer::Struct)
             }
-            _ => {
-                return Err(Error::SignatureMismatch(
-                    self.0.signature.clone(),
-                    "a struct, array or variant".to_string(),
-                ));
-            }
+            _ => Err(Error::SignatureMismatch(
+                self.0.signature.clone(),
+                "a struct, array or variant".to_string(),
+            )),
         }
     }
 
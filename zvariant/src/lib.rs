@@ -51,9 +51,18 @@ pub use signature::Signature;
 mod str;
 pub use crate::str::*;
 
+mod slice;
+pub use crate::slice::*;
+
 mod structure;
 pub use crate::structure::*;
 
+mod typed_dict;
+pub use crate::typed_dict::*;
+
+mod registry;
+pub use crate::registry::*;
+
 #[cfg(feature = "gvariant")]
 mod maybe;
 #[cfg(feature = "gvariant")]
@@ -62,15 +71,30 @@ pub use crate::maybe::*;
 mod optional;
 pub use crate::optional::*;
 
+#[cfg(feature = "transcode")]
+mod transcode;
+#[cfg(feature = "transcode")]
+pub use crate::transcode::*;
+
 mod value;
 pub use value::*;
 
+#[macro_use]
+mod value_macro;
+
 mod serialize_value;
 pub use serialize_value::*;
 
 mod deserialize_value;
 pub use deserialize_value::*;
 
+mod validate;
+pub use validate::validate;
+
+mod normal_form;
+
+pub mod timestamp;
+
 mod error;
 pub use error::*;
 
@@ -103,6 +127,8 @@ extern crate self as zvariant;
 // Macro support module, not part of the public API.
 #[doc(hidden)]
 pub mod export {
+    #[cfg(feature = "bitflags")]
+    pub use bitflags;
     pub use serde;
 }
 
@@ -122,14 +148,15 @@ mod tests {
 
     use serde::{Deserialize, Serialize};
 
-    use crate::{to_bytes, to_bytes_for_signature, MaxDepthExceeded};
+    use crate::{destructure, to_bytes, to_bytes_for_signature, MaxDepthExceeded, Signature};
 
     #[cfg(unix)]
     use crate::Fd;
     use crate::{
         serialized::{Context, Format},
         Array, Basic, DeserializeDict, DeserializeValue, Dict, Error, ObjectPath, Result,
-        SerializeDict, SerializeValue, Str, Structure, Type, Value, BE, LE, NATIVE_ENDIAN,
+        SerializeDict, SerializeValue, Str, Structure, Type, TypedDict, Value, BE, LE,
+        NATIVE_ENDIAN,
     };
 
     // Test through both generic and specific API (wrt byte order)
@@ -742,6 +769,7 @@ mod tests {
             let ctxt = Context::new_gvariant(LE, 0);
             let gv_encoded = to_bytes(ctxt, &ar).unwrap();
             assert_eq!(gv_encoded.len(), 54);
+            #[allow(clippy::type_complexity)]
             let decoded: Vec<(u8, u32, (i64, bool, i64, Vec<&str>), &str)> =
                 gv_encoded.deserialize().unwrap().0;
             assert_eq!(decoded.len(), 1);
@@ -1006,7 +1034,7 @@ mod tests {
         assert_eq!(map[&2], "456");
         // Use iterator
         let mut dict = Dict::from(map);
-        let expect = vec![
+        let expect = [
             (Value::from(1i64), Value::from("123")),
             (Value::from(2i64), Value::from("456")),
         ];
@@ -1167,6 +1195,122 @@ mod tests {
         assert_eq!(dict1, dict2);
     }
 
+    #[test]
+    fn container_iteration() {
+        let ctxt = Context::new_dbus(LE, 0);
+
+        // Elements wrapped in variants are unwrapped on access.
+        let strs = vec![Value::new("first"), Value::new("second")];
+        let encoded = to_bytes(ctxt, &Value::new(strs)).unwrap();
+        let array: Array<'_> = encoded
+            .deserialize::<Value<'_>>()
+            .unwrap()
+            .0
+            .try_into()
+            .unwrap();
+        assert_eq!(array.get::<&str>(1).unwrap(), Some("second"));
+        assert_eq!(array.get::<&str>(2).unwrap(), None);
+        let elements: Vec<&str> = array.iter_as().collect::<Result<_>>().unwrap();
+        assert_eq!(elements, ["first", "second"]);
+        assert_eq!((&array).into_iter().count(), 2);
+        let elements: Vec<String> = array.try_into_typed().unwrap();
+        assert_eq!(elements, ["first", "second"]);
+
+        let mut map = HashMap::new();
+        map.insert("one", Value::new(1u8));
+        map.insert("two", Value::new(2u8));
+        let encoded = to_bytes(ctxt, &Value::new(map.clone())).unwrap();
+        let dict: Dict<'_, '_> = encoded
+            .deserialize::<Value<'_>>()
+            .unwrap()
+            .0
+            .try_into()
+            .unwrap();
+        for entry in dict.iter_as::<&str, u8>() {
+            let (key, value) = entry.unwrap();
+            assert_eq!(map[key], Value::new(value));
+        }
+        assert!(dict.iter_as::<&str, &str>().all(|e| e.is_err()));
+        for (key, _) in &dict {
+            assert!(map.contains_key(<&str>::try_from(key).unwrap()));
+        }
+        let decoded: BTreeMap<String, u8> = dict.try_into_typed().unwrap();
+        assert_eq!(
+            decoded.into_iter().collect::<Vec<_>>(),
+            [("one".into(), 1), ("two".into(), 2)]
+        );
+    }
+
+    #[test]
+    fn structure_fields() -> Result<()> {
+        let ctxt = Context::new_dbus(LE, 0);
+        let encoded = to_bytes(ctxt, &Value::new(("hello", Value::new(7u8), vec![1u16]))).unwrap();
+        let structure: Structure<'_> = encoded.deserialize::<Value<'_>>()?.0.try_into()?;
+
+        assert_eq!(structure.field::<&str>(0)?, "hello");
+        // Variants are unwrapped.
+        assert_eq!(structure.field::<u8>(1)?, 7);
+        assert_eq!(structure.field::<&Array<'_>>(2)?.len(), 1);
+        assert_eq!(
+            structure.field::<u8>(0),
+            Err(Error::SignatureMismatch(
+                Signature::Str,
+                "`u8` for field 0 of structure `(svaq)`".into()
+            ))
+        );
+        assert!(matches!(structure.field::<u8>(3), Err(Error::Message(_))));
+
+        destructure!((s: &str, byte: u8, array: &Array<'_>) = structure);
+        assert_eq!((s, byte, array.len()), ("hello", 7, 1));
+
+        fn destructure_pair(structure: &Structure<'_>) -> Result<(String, u8)> {
+            destructure!((s, byte) = structure);
+
+            Ok((s, byte))
+        }
+        assert!(matches!(
+            destructure_pair(&structure),
+            Err(Error::Message(m)) if m == "expected a structure with 2 fields, got `(svaq)`"
+        ));
+        let pair = Structure::from(("bye", Value::new(9u8)));
+        assert_eq!(destructure_pair(&pair)?, ("bye".to_string(), 9));
+
+        Ok(())
+    }
+
+    #[test]
+    fn typed_dict() -> Result<()> {
+        let ctxt = Context::new_dbus(LE, 0);
+
+        let mut props = TypedDict::<'_, &str, Value<'_>>::new();
+        props.insert("name", Value::new("zbus"))?;
+        props.insert("version", Value::new(5u32))?;
+        props.insert("name", Value::new("zvariant"))?;
+        assert_eq!(props.len(), 2);
+        assert_eq!(TypedDict::<'_, &str, Value<'_>>::SIGNATURE, "a{sv}");
+
+        let encoded = to_bytes(ctxt, &props)?;
+        let decoded: TypedDict<'_, &str, Value<'_>> = encoded.deserialize()?.0;
+        assert_eq!(decoded, props);
+        assert_eq!(decoded.get(&"name")?, Some(Value::new("zvariant")));
+        let entries: Vec<(&str, Value<'_>)> = decoded.iter().collect::<Result<_>>()?;
+        assert_eq!(entries[1], ("version", Value::new(5u32)));
+
+        // The signature is checked on conversion.
+        assert!(matches!(
+            TypedDict::<'_, &str, u32>::try_from(Value::from(decoded.into_dict())),
+            Err(Error::SignatureMismatch(s, _)) if s == "a{sv}"
+        ));
+
+        let map = BTreeMap::from([(1u8, "one".to_string()), (2, "two".into())]);
+        let dict = TypedDict::from(map.clone());
+        assert_eq!(dict.get(&1)?, Some("one".to_string()));
+        assert_eq!(dict.get(&3)?, None::<String>);
+        assert_eq!(BTreeMap::<u8, String>::try_from(dict)?, map);
+
+        Ok(())
+    }
+
     #[test]
     fn value_value() {
         let ctxt = Context::new_dbus(BE, 0);
@@ -1511,6 +1655,16 @@ mod tests {
         let v = vec![1, 2];
         let l = crate::serialized_size(ctxt, &('a', "abc", &v)).unwrap();
         assert_eq!(*l, 28);
+
+        // Fixed-sized types are sized without the serializer, so compare with the actual encoding.
+        let value = (1_u8, (2_i16, true), 3_u32, 4.0_f64, (5_u8,), 6_u64);
+        for position in 0..8 {
+            let ctxt = Context::new_dbus(LE, position);
+            let l = crate::serialized_size(ctxt, &value).unwrap();
+            assert_eq!(*l, to_bytes(ctxt, &value).unwrap().len());
+            let l = crate::serialized_size(ctxt, &7_u16).unwrap();
+            assert_eq!(*l, to_bytes(ctxt, &7_u16).unwrap().len());
+        }
     }
 
     #[test]
@@ -1640,7 +1794,7 @@ mod tests {
             Value::Array(array) => {
                 assert_eq!(
                     String::try_from(array[0].try_clone().unwrap()).unwrap(),
-                    ms.unwrap()
+                    "hello world"
                 )
             }
             _ => panic!("unexpected value {v:?}"),
@@ -1661,7 +1815,7 @@ mod tests {
             Value::Array(array) => {
                 assert_eq!(
                     String::try_from(array[0].try_clone().unwrap()).unwrap(),
-                    ms.unwrap()
+                    "hello world"
                 )
             }
             _ => panic!("unexpected value {decoded:?}"),
@@ -1813,6 +1967,36 @@ mod tests {
         let _: Summary<'_> = encoded.deserialize().unwrap().0;
         // If we're able to deserialize all the data successfully, don't bother checking the summary
         // data.
+
+        // Data produced by GLib is expected to be in normal form.
+        assert!(encoded.is_normal_form(Summary::SIGNATURE).unwrap());
+    }
+
+    #[cfg(feature = "gvariant")]
+    #[test]
+    fn gvariant_non_normal() {
+        let ctxt = Context::new_gvariant(LE, 0);
+
+        // Unused bytes after a fixed-sized value inside a variant are accepted.
+        let encoded = to_bytes(ctxt, &Value::from(42u32)).unwrap();
+        assert_eq!(encoded.bytes(), b"\x2a\0\0\0\0u");
+        assert!(encoded.is_normal_form("v").unwrap());
+        let encoded = crate::serialized::Data::new(&b"\x2a\0\0\0\xff\0u"[..], ctxt);
+        let decoded: Value<'_> = encoded.deserialize().unwrap().0;
+        assert_eq!(decoded, Value::from(42u32));
+        assert!(!encoded.is_normal_form("v").unwrap());
+
+        // Trailing bytes after a fixed-sized value.
+        let encoded = crate::serialized::Data::new(&b"\x2a\0\0\0\0"[..], ctxt);
+        assert_eq!(encoded.deserialize::<u32>().unwrap(), (42, 4));
+        assert!(!encoded.is_normal_form("u").unwrap());
+
+        // Out-of-bounds and out-of-order framing offsets are errors.
+        let encoded = crate::serialized::Data::new(&b"a\0bc\0\x02\x10"[..], ctxt);
+        encoded.deserialize::<Vec<&str>>().unwrap_err();
+        encoded.is_normal_form("as").unwrap_err();
+        let encoded = crate::serialized::Data::new(&b"a\0bc\0\x05\x02"[..], ctxt);
+        encoded.deserialize::<Vec<&str>>().unwrap_err();
     }
 
     #[test]
@@ -1871,4 +2055,178 @@ mod tests {
         // * Test deserializers.
         // * Test gvariant format.
     }
+
+    #[test]
+    fn deserialization_limits() {
+        use crate::{serialized::Data, LimitExceeded};
+
+        let ctxt = Context::new_dbus(LE, 0);
+        let nested = vec![vec![vec![1u8, 2, 3]]];
+        let encoded = to_bytes(ctxt, &nested).unwrap();
+
+        // Depth.
+        let data = Data::new(encoded.bytes(), ctxt.with_max_depth(3));
+        assert_eq!(data.deserialize::<Vec<Vec<Vec<u8>>>>().unwrap().0, nested);
+        let data = Data::new(encoded.bytes(), ctxt.with_max_depth(2));
+        assert_eq!(
+            data.deserialize::<Vec<Vec<Vec<u8>>>>().unwrap_err(),
+            Error::MaxDepthExceeded(MaxDepthExceeded::Container)
+        );
+        // Also applies to values in variants.
+        let encoded_value = to_bytes(ctxt, &Value::new(nested.clone())).unwrap();
+        let data = Data::new(encoded_value.bytes(), ctxt.with_max_depth(3));
+        assert_eq!(
+            data.deserialize::<Value<'_>>().unwrap_err(),
+            Error::MaxDepthExceeded(MaxDepthExceeded::Container)
+        );
+
+        // Elements, including when deserializing as bytes.
+        let data = Data::new(encoded.bytes(), ctxt.with_max_elements(3));
+        assert_eq!(data.deserialize::<Vec<Vec<Vec<u8>>>>().unwrap().0, nested);
+        let data = Data::new(encoded.bytes(), ctxt.with_max_elements(2));
+        assert_eq!(
+            data.deserialize::<Vec<Vec<Vec<u8>>>>().unwrap_err(),
+            Error::LimitExceeded(LimitExceeded::Elements)
+        );
+        assert_eq!(
+            data.deserialize::<Vec<Vec<crate::Bytes<'_>>>>()
+                .unwrap_err(),
+            Error::LimitExceeded(LimitExceeded::Elements)
+        );
+        let map = HashMap::from([(1u8, "one"), (2, "two"), (3, "three")]);
+        let encoded_map = to_bytes(ctxt, &map).unwrap();
+        let data = Data::new(encoded_map.bytes(), ctxt.with_max_elements(2));
+        assert_eq!(
+            data.deserialize::<HashMap<u8, &str>>().unwrap_err(),
+            Error::LimitExceeded(LimitExceeded::Elements)
+        );
+
+        // Size, of both the input and the decoded data.
+        let data = Data::new(encoded.bytes(), ctxt.with_max_size(encoded.len() - 1));
+        assert_eq!(
+            data.deserialize::<Vec<Vec<Vec<u8>>>>().unwrap_err(),
+            Error::LimitExceeded(LimitExceeded::Size)
+        );
+        // 5 elements in all the arrays, 3 of which are bytes.
+        let decoded_size = 5 * std::mem::size_of::<usize>() + 3;
+        let data = Data::new(encoded.bytes(), ctxt.with_max_size(decoded_size));
+        assert_eq!(data.deserialize::<Vec<Vec<Vec<u8>>>>().unwrap().0, nested);
+        let data = Data::new(encoded.bytes(), ctxt.with_max_size(decoded_size - 1));
+        assert_eq!(
+            data.deserialize::<Vec<Vec<Vec<u8>>>>().unwrap_err(),
+            Error::LimitExceeded(LimitExceeded::Size)
+        );
+        // Byte arrays are decoded at once.
+        let data = Data::new(
+            encoded.bytes(),
+            ctxt.with_max_size(2 * std::mem::size_of::<usize>() + 3),
+        );
+        data.deserialize::<Vec<Vec<crate::Bytes<'_>>>>().unwrap();
+        // A small input can decode into a lot more data, e.g. when the bytes in a variant are
+        // decoded one by one. The `ay` signature counts as a string.
+        let bytes = Value::new(vec![1u8, 2, 3, 4]);
+        let encoded_bytes = to_bytes(ctxt, &bytes).unwrap();
+        assert_eq!(encoded_bytes.len(), 12);
+        let decoded_size = 2 + 4 * (std::mem::size_of::<usize>() + 1);
+        let data = Data::new(encoded_bytes.bytes(), ctxt.with_max_size(decoded_size));
+        assert_eq!(data.deserialize::<Value<'_>>().unwrap().0, bytes);
+        let data = Data::new(encoded_bytes.bytes(), ctxt.with_max_size(decoded_size - 1));
+        assert_eq!(
+            data.deserialize::<Value<'_>>().unwrap_err(),
+            Error::LimitExceeded(LimitExceeded::Size)
+        );
+
+        #[cfg(feature = "gvariant")]
+        {
+            let ctxt = Context::new_gvariant(LE, 0);
+            let encoded = to_bytes(ctxt, &vec!["a", "b", "c"]).unwrap();
+            let data = Data::new(encoded.bytes(), ctxt.with_max_elements(2));
+            assert_eq!(
+                data.deserialize::<Vec<&str>>().unwrap_err(),
+                Error::LimitExceeded(LimitExceeded::Elements)
+            );
+            let data = Data::new(encoded.bytes(), ctxt.with_max_depth(0));
+            assert_eq!(
+                data.deserialize::<Vec<&str>>().unwrap_err(),
+                Error::MaxDepthExceeded(MaxDepthExceeded::Container)
+            );
+            let decoded_size = 3 * std::mem::size_of::<usize>() + 3;
+            let data = Data::new(encoded.bytes(), ctxt.with_max_size(decoded_size));
+            data.deserialize::<Vec<&str>>().unwrap();
+            let data = Data::new(encoded.bytes(), ctxt.with_max_size(decoded_size - 1));
+            assert_eq!(
+                data.deserialize::<Vec<&str>>().unwrap_err(),
+                Error::LimitExceeded(LimitExceeded::Size)
+            );
+        }
+    }
+    #[test]
+    fn deserialize_at() {
+        use crate::serialized::PathSegment::{self, Variant};
+        use serde::de::IgnoredAny;
+
+        let props = HashMap::from([
+            ("Name", Value::new("zbus")),
+            ("Features", Value::new(vec!["tokio", "url"])),
+            ("Nested", Value::new(Value::new((7u8, "seven")))),
+        ]);
+        let value = (props, vec![(1u32, vec!["a"]), (2, vec!["b", "c"])], 42u64);
+        let signature = "(a{sv}a(uas)t)";
+        let contexts = [
+            Context::new_dbus(LE, 0),
+            #[cfg(feature = "gvariant")]
+            Context::new_gvariant(LE, 0),
+        ];
+
+        for ctxt in contexts {
+            let encoded = to_bytes(ctxt, &value).unwrap();
+            let at = |path: &[PathSegment<'_>]| encoded.deserialize_at::<_, &str>(signature, path);
+
+            assert_eq!(
+                at(&[0.into(), "Name".into(), Variant]).unwrap(),
+                Some("zbus")
+            );
+            assert_eq!(
+                at(&[0.into(), "Features".into(), Variant, 1.into()]).unwrap(),
+                Some("url")
+            );
+            assert_eq!(
+                at(&[0.into(), "Nested".into(), Variant, Variant, 1.into()]).unwrap(),
+                Some("seven")
+            );
+            assert_eq!(
+                at(&[1.into(), 1.into(), 1.into(), 1.into()]).unwrap(),
+                Some("c")
+            );
+            assert_eq!(
+                encoded
+                    .deserialize_at::<_, u64>(signature, &[2.into()])
+                    .unwrap(),
+                Some(42)
+            );
+            assert_eq!(
+                encoded
+                    .deserialize_at::<_, (u32, Vec<&str>)>(signature, &[1.into(), 0.into()])
+                    .unwrap(),
+                Some((1, vec!["a"]))
+            );
+
+            // Missing entries.
+            assert_eq!(at(&[0.into(), "Vendor".into(), Variant]).unwrap(), None);
+            assert_eq!(at(&[1.into(), 2.into(), 1.into(), 0.into()]).unwrap(), None);
+            assert_eq!(at(&[1.into(), 1.into(), 1.into(), 2.into()]).unwrap(), None);
+
+            // Paths not matching the signature.
+            at(&[3.into()]).unwrap_err();
+            at(&["Name".into()]).unwrap_err();
+            at(&[0.into(), 0.into()]).unwrap_err();
+            at(&[0.into(), "Name".into(), 0.into()]).unwrap_err();
+
+            // Everything can be ignored, including variants.
+            let (ignored, _) = encoded
+                .deserialize_for_signature::<_, (IgnoredAny, IgnoredAny, u64)>(signature)
+                .unwrap();
+            assert_eq!(ignored.2, 42);
+        }
+    }
 }
@@ -0,0 +1,220 @@
+use serde::{
+    de::{DeserializeSeed, Deserializer, Error, MapAccess, SeqAccess, Unexpected, Visitor},
+    ser::{
+        Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeTupleStruct, Serializer,
+    },
+};
+
+use crate::{value::ValueSeed, DynamicType, Signature, Value};
+
+/// A deserialized value, keeping the exact order and number of all the elements and entries.
+///
+/// Unlike [`Value`], dictionary entries are neither sorted nor deduplicated, so serializing a
+/// `Node` gives back the normal form of the data it was deserialized from.
+pub(crate) enum Node<'de> {
+    Unit,
+    Basic(Value<'de>),
+    Array(Vec<Node<'de>>),
+    Dict(Vec<(Node<'de>, Node<'de>)>),
+    Structure(Vec<Node<'de>>),
+    Variant(Signature, Box<Node<'de>>),
+    #[cfg(feature = "gvariant")]
+    Maybe(Option<Box<Node<'de>>>),
+}
+
+impl Serialize for Node<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Node::Unit => serializer.serialize_unit(),
+            Node::Basic(value) => value.serialize_value_as_newtype(serializer),
+            Node::Array(elements) => {
+                let mut seq = serializer.serialize_seq(Some(elements.len()))?;
+                for element in elements {
+                    seq.serialize_element(element)?;
+                }
+
+                seq.end()
+            }
+            Node::Dict(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+
+                map.end()
+            }
+            Node::Structure(fields) => {
+                let mut structure = serializer.serialize_tuple_struct("Structure", fields.len())?;
+                for field in fields {
+                    structure.serialize_field(field)?;
+                }
+
+                structure.end()
+            }
+            Node::Variant(signature, value) => {
+                let mut structure = serializer.serialize_struct("Variant", 2)?;
+                structure.serialize_field("signature", signature)?;
+                structure.serialize_field("value", value)?;
+
+                structure.end()
+            }
+            #[cfg(feature = "gvariant")]
+            Node::Maybe(Some(value)) => serializer.serialize_some(value),
+            #[cfg(feature = "gvariant")]
+            Node::Maybe(None) => serializer.serialize_none(),
+        }
+    }
+}
+
+/// A seed for deserializing a [`Node`] of the given signature.
+pub(crate) struct NodeSeed<'sig> {
+    pub(crate) signature: &'sig Signature,
+}
+
+impl NodeSeed<'_> {
+    fn child(signature: &Signature) -> NodeSeed<'_> {
+        NodeSeed { signature }
+    }
+
+    fn unexpected_signature<E>(&self, expected: &str) -> E
+    where
+        E: Error,
+    {
+        E::invalid_type(Unexpected::Str(&self.signature.to_string()), &expected)
+    }
+}
+
+impl DynamicType for NodeSeed<'_> {
+    fn signature(&self) -> Signature {
+        self.signature.clone()
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for NodeSeed<'_> {
+    type Value = Node<'de>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Node<'de>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match self.signature {
+            Signature::Unit
+            | Signature::Array(_)
+            | Signature::Dict { .. }
+            | Signature::Structure(_)
+            | Signature::Variant => deserializer.deserialize_any(self),
+            #[cfg(feature = "gvariant")]
+            Signature::Maybe(_) => deserializer.deserialize_any(self),
+            _ => ValueSeed::<Value<'_>>::new(self.signature)
+                .deserialize(deserializer)
+                .map(Node::Basic),
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for NodeSeed<'_> {
+    type Value = Node<'de>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "a value of signature `{}`", self.signature)
+    }
+
+    fn visit_unit<E>(self) -> Result<Node<'de>, E>
+    where
+        E: Error,
+    {
+        Ok(Node::Unit)
+    }
+
+    fn visit_seq<V>(self, mut visitor: V) -> Result<Node<'de>, V::Error>
+    where
+        V: SeqAccess<'de>,
+    {
+        match self.signature {
+            Signature::Array(child) => {
+                let mut elements = Vec::new();
+                while let Some(element) = visitor.next_element_seed(Self::child(child))? {
+                    elements.push(element);
+                }
+
+                Ok(Node::Array(elements))
+            }
+            Signature::Structure(fields) => fields
+                .iter()
+                .map(|field| {
+                    visitor
+                        .next_element_seed(Self::child(field))?
+                        .ok_or_else(|| {
+                            Error::invalid_length(fields.len(), &"all fields of the structure")
+                        })
+                })
+                .collect::<Result<_, _>>()
+                .map(Node::Structure),
+            Signature::Variant => {
+                let signature = visitor.next_element::<Signature>()?.ok_or_else(|| {
+                    Error::invalid_value(Unexpected::Other("nothing"), &"a Value signature")
+                })?;
+                let value = visitor
+                    .next_element_seed(Self::child(&signature))?
+                    .ok_or_else(|| {
+                        Error::invalid_value(Unexpected::Other("nothing"), &"a Value value")
+                    })?;
+
+                Ok(Node::Variant(signature, Box::new(value)))
+            }
+            _ => Err(self.unexpected_signature("an array, structure or variant signature")),
+        }
+    }
+
+    fn visit_map<V>(self, mut visitor: V) -> Result<Node<'de>, V::Error>
+    where
+        V: MapAccess<'de>,
+    {
+        match self.signature {
+            Signature::Dict { key, value } => {
+                let mut entries = Vec::new();
+                while let Some(entry) =
+                    visitor.next_entry_seed(Self::child(key), Self::child(value))?
+                {
+                    entries.push(entry);
+                }
+
+                Ok(Node::Dict(entries))
+            }
+            Signature::Variant => {
+                let (_, signature) = visitor.next_entry::<&str, Signature>()?.ok_or_else(|| {
+                    Error::invalid_value(Unexpected::Other("nothing"), &"a Value signature")
+                })?;
+                let _ = visitor.next_key::<&str>()?;
+                let value = visitor.next_value_seed(Self::child(&signature))?;
+
+                Ok(Node::Variant(signature, Box::new(value)))
+            }
+            _ => Err(self.unexpected_signature("a dict or variant signature")),
+        }
+    }
+
+    #[cfg(feature = "gvariant")]
+    fn visit_some<D>(self, deserializer: D) -> Result<Node<'de>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match self.signature {
+            Signature::Maybe(child) => Self::child(child)
+                .deserialize(deserializer)
+                .map(|value| Node::Maybe(Some(Box::new(value)))),
+            _ => Err(self.unexpected_signature("a maybe signature")),
+        }
+    }
+
+    #[cfg(feature = "gvariant")]
+    fn visit_none<E>(self) -> Result<Node<'de>, E>
+    where
+        E: Error,
+    {
+        Ok(Node::Maybe(None))
+    }
+}
@@ -247,7 +247,6 @@ impl<'de> Visitor<'de> for ObjectPathVisitor {
 }
 
 fn validate(path: &[u8]) -> Result<()> {
-    use winnow::{combinator::separated, stream::AsChar, token::take_while, Parser};
     // Rules
     //
     // * At least 1 character.
@@ -255,12 +254,32 @@ fn validate(path: &[u8]) -> Result<()> {
     // * No trailing `/`
     // * No `//`
     // * Only ASCII alphanumeric, `_` or '/'
+    //
+    // This is checked for every object path we deserialize, so we go through the bytes in a single
+    // pass instead of using a parser.
+    let Some((b'/', rest)) = path.split_first() else {
+        return Err(Error::InvalidObjectPath);
+    };
+    if rest.is_empty() {
+        return Ok(());
+    }
+
+    let mut prev = b'/';
+    for &c in rest {
+        match c {
+            b'/' if prev == b'/' => return Err(Error::InvalidObjectPath),
+            b'/' | b'_' => (),
+            c if c.is_ascii_alphanumeric() => (),
+            _ => return Err(Error::InvalidObjectPath),
+        }
+        prev = c;
+    }
 
-    let allowed_chars = (AsChar::is_alphanum, b'_');
-    let name = take_while::<_, _, ()>(1.., allowed_chars);
-    let mut full_path = (b'/', separated(0.., name, b'/')).map(|_: (u8, ())| ());
+    if prev == b'/' {
+        return Err(Error::InvalidObjectPath);
+    }
 
-    full_path.parse(path).map_err(|_| Error::InvalidObjectPath)
+    Ok(())
 }
 
 /// Owned [`ObjectPath`](struct.ObjectPath.html)
@@ -355,4 +374,23 @@ mod unit {
         let json_str = "\"/some/path\"";
         serde_json::de::from_reader::<_, OwnedObjectPath>(json_str.as_bytes()).unwrap();
     }
+
+    #[test]
+    fn validation() {
+        for path in ["/", "/a", "/a/b", "/_/A_1/z9", "/org/freedesktop/DBus"] {
+            assert!(
+                ObjectPath::try_from(path).is_ok(),
+                "`{path}` should be valid"
+            );
+        }
+        for path in [
+            "", "a", "a/b", "//", "/a/", "/a//b", "/a b", "/a-b", "/a.b", "/é", "/a/ü/b", "/\0",
+        ] {
+            assert_eq!(
+                ObjectPath::try_from(path),
+                Err(Error::InvalidObjectPath),
+                "`{path}` should be invalid"
+            );
+        }
+    }
 }
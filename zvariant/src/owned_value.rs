@@ -17,7 +17,10 @@ use crate::Maybe;
 // https://github.com/dbus2/zbus/issues/138
 
 /// Owned [`Value`](enum.Value.html)
-#[derive(Debug, PartialEq, Serialize, Type)]
+///
+/// Just like `Value`, `OwnedValue` is totally ordered and hashable. See the [`Value`
+/// documentation](enum.Value.html#ordering-and-hashing) for details.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Type)]
 pub struct OwnedValue(pub(crate) Value<'static>);
 
 assert_impl_all!(OwnedValue: Send, Sync, Unpin);
@@ -96,6 +99,7 @@ ov_try_from_ref!(&'a str);
 ov_try_from_ref!(&'a Signature);
 ov_try_from_ref!(&'a ObjectPath<'a>);
 ov_try_from_ref!(&'a Array<'a>);
+ov_try_from_ref!(&'a [Value<'a>]);
 ov_try_from_ref!(&'a Dict<'a, 'a>);
 ov_try_from_ref!(&'a Str<'a>);
 ov_try_from_ref!(&'a Structure<'a>);
@@ -298,9 +302,12 @@ impl Clone for OwnedValue {
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashMap, error::Error};
+    use std::{
+        collections::{BTreeSet, HashMap, HashSet},
+        error::Error,
+    };
 
-    use crate::{serialized::Context, to_bytes, OwnedValue, Value, LE};
+    use crate::{serialized::Context, to_bytes, Array, OwnedValue, Value, LE};
 
     #[cfg(feature = "enumflags2")]
     #[test]
@@ -327,6 +334,16 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn borrow() -> Result<(), Box<dyn Error>> {
+        let ov: OwnedValue = Value::new(Array::from(vec!["one", "two"])).try_into()?;
+        let elements = <&[Value<'_>]>::try_from(&ov)?;
+        assert_eq!(elements[1].downcast_ref::<&str>()?, "two");
+        assert_eq!(ov.downcast_ref::<&Array<'_>>()?.len(), 2);
+        assert!(<&str>::try_from(&ov).is_err());
+        Ok(())
+    }
+
     #[test]
     fn serde() -> Result<(), Box<dyn Error>> {
         let ec = Context::new_dbus(LE, 0);
@@ -350,4 +367,38 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn ordering_and_hashing() -> Result<(), Box<dyn Error>> {
+        let values: Vec<OwnedValue> = [
+            Value::from(Array::from(vec![f64::NAN])),
+            Value::from("a"),
+            Value::from(-0.0f64),
+            Value::from(7u8),
+            Value::from(0.0f64),
+            Value::from(Array::from(vec![f64::NAN])),
+            Value::from(u32::MAX),
+            Value::from(1u8),
+        ]
+        .into_iter()
+        .map(OwnedValue::try_from)
+        .collect::<Result<_, _>>()?;
+
+        // Different types are ordered by type, not by their contents.
+        let sorted: Vec<_> = values.iter().collect::<BTreeSet<_>>().into_iter().collect();
+        assert_eq!(sorted.len(), 6);
+        assert_eq!(sorted[0], &Value::from(1u8).try_into()?);
+        assert_eq!(sorted[1], &Value::from(7u8).try_into()?);
+        assert_eq!(sorted[2], &Value::from(u32::MAX).try_into()?);
+        assert_eq!(sorted[3], &Value::from(0.0f64).try_into()?);
+        assert_eq!(sorted[4], &Value::from("a").try_into()?);
+
+        // Nested NaNs are equal to themselves and +0.0 and -0.0 are equal, both hashing the same.
+        assert_eq!(values[0], values[5]);
+        assert_eq!(values[2], values[4]);
+        let set: HashSet<_> = values.iter().collect();
+        assert_eq!(set.len(), 6);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,260 @@
+use static_assertions::assert_impl_all;
+use std::{any::Any, collections::HashMap, fmt, sync::Arc};
+
+use crate::{Error, OwnedValue, Result, Signature, Type, Value};
+
+/// A decoded value, as returned by [`TypeRegistry`].
+///
+/// Use [`Box::downcast`] to get the concrete type back.
+pub type Decoded = Box<dyn Any + Send + Sync>;
+
+type Decoder = Arc<dyn Fn(&Value<'_>) -> Result<Decoded> + Send + Sync>;
+
+/// A runtime registry of Rust types to decode [`Value`]s into.
+///
+/// Dynamic consumers, like bridges to other IPC systems or hosts of plugins, often receive values
+/// whose type is only known at runtime. A `TypeRegistry` maps either signatures or names (e.g.
+/// `interface.property`) to decoder functions, so these values can be turned into the right Rust
+/// type without having to match on signatures manually.
+///
+/// Types are registered by signature with [`TypeRegistry::register`], and by name with
+/// [`TypeRegistry::register_name`]. Any type that implements [`Type`] and can be converted from an
+/// [`OwnedValue`] can be registered, including types deriving [`Type`] and `OwnedValue`. Custom
+/// decoder functions can be registered as well.
+///
+/// Values wrapped in a variant are unwrapped before decoding. Registering a type for a signature
+/// or name that's already registered replaces the previous registration.
+///
+/// # Examples
+///
+/// ```
+/// use zvariant::{OwnedValue, Structure, Type, TypeRegistry, Value};
+///
+/// #[derive(Debug, PartialEq, Type, OwnedValue)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let mut registry = TypeRegistry::new();
+/// registry
+///     .register::<Point>()
+///     .register::<String>()
+///     .register_name::<u32>("org.example.Counter.Count");
+///
+/// let value = Value::new(Structure::from((1i32, 2i32)));
+/// let point = registry.decode(&value).unwrap().unwrap();
+/// assert_eq!(point.downcast_ref::<Point>(), Some(&Point { x: 1, y: 2 }));
+///
+/// let count = registry.decode_named_as::<u32>("org.example.Counter.Count", &Value::from(7u32));
+/// assert_eq!(count.unwrap().unwrap(), 7);
+///
+/// // No type registered for this signature.
+/// assert!(registry.decode(&Value::from(7u8)).is_none());
+/// ```
+#[derive(Clone, Default)]
+pub struct TypeRegistry {
+    by_signature: HashMap<Signature, Decoder>,
+    by_name: HashMap<String, (Signature, Decoder)>,
+}
+
+assert_impl_all!(TypeRegistry: Send, Sync, Unpin);
+
+impl TypeRegistry {
+    /// Create a new empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `T` as the type to decode values of signature `T::SIGNATURE` into.
+    pub fn register<T>(&mut self) -> &mut Self
+    where
+        T: Type + TryFrom<OwnedValue> + Send + Sync + 'static,
+        T::Error: Into<Error>,
+    {
+        self.register_with(T::SIGNATURE.clone(), decode::<T>)
+    }
+
+    /// Register a custom decoder function for values of the given signature.
+    pub fn register_with<F, T>(&mut self, signature: Signature, decoder: F) -> &mut Self
+    where
+        F: Fn(&Value<'_>) -> Result<T> + Send + Sync + 'static,
+        T: Send + Sync + 'static,
+    {
+        self.by_signature.insert(signature, boxed(decoder));
+
+        self
+    }
+
+    /// Register `T` as the type to decode values with the given name into.
+    ///
+    /// The name can be anything that identifies the values, e.g. `interface.property` for the
+    /// values of D-Bus properties.
+    pub fn register_name<T>(&mut self, name: impl Into<String>) -> &mut Self
+    where
+        T: Type + TryFrom<OwnedValue> + Send + Sync + 'static,
+        T::Error: Into<Error>,
+    {
+        self.by_name
+            .insert(name.into(), (T::SIGNATURE.clone(), boxed(decode::<T>)));
+
+        self
+    }
+
+    /// Whether a type is registered for the given signature.
+    pub fn contains(&self, signature: &Signature) -> bool {
+        self.by_signature.contains_key(signature)
+    }
+
+    /// Whether a type is registered for the given name.
+    pub fn contains_name(&self, name: &str) -> bool {
+        self.by_name.contains_key(name)
+    }
+
+    /// Decode `value` into the type registered for its signature.
+    ///
+    /// Returns `None` if no type is registered for the signature of `value`.
+    pub fn decode(&self, value: &Value<'_>) -> Option<Result<Decoded>> {
+        let value = unwrap_variant(value);
+
+        self.by_signature
+            .get(value.value_signature())
+            .map(|decoder| decoder(value))
+    }
+
+    /// Decode `value` into the type registered for `name`.
+    ///
+    /// Returns `None` if no type is registered for `name`.
+    ///
+    /// # Errors
+    ///
+    /// If the signature of `value` doesn't match the signature of the registered type, or the
+    /// conversion fails.
+    pub fn decode_named(&self, name: &str, value: &Value<'_>) -> Option<Result<Decoded>> {
+        let value = unwrap_variant(value);
+
+        self.by_name.get(name).map(|(signature, decoder)| {
+            if value.value_signature() != signature {
+                return Err(Error::SignatureMismatch(
+                    value.value_signature().clone(),
+                    format!("`{signature}` for `{name}`"),
+                ));
+            }
+
+            decoder(value)
+        })
+    }
+
+    /// Same as [`TypeRegistry::decode`], but also downcasts the decoded value to `T`.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors of `decode`, if the registered type isn't `T`.
+    pub fn decode_as<T>(&self, value: &Value<'_>) -> Option<Result<T>>
+    where
+        T: 'static,
+    {
+        self.decode(value).map(|decoded| decoded.and_then(downcast))
+    }
+
+    /// Same as [`TypeRegistry::decode_named`], but also downcasts the decoded value to `T`.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors of `decode_named`, if the registered type isn't `T`.
+    pub fn decode_named_as<T>(&self, name: &str, value: &Value<'_>) -> Option<Result<T>>
+    where
+        T: 'static,
+    {
+        self.decode_named(name, value)
+            .map(|decoded| decoded.and_then(downcast))
+    }
+}
+
+impl fmt::Debug for TypeRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypeRegistry")
+            .field("signatures", &self.by_signature.keys().collect::<Vec<_>>())
+            .field("names", &self.by_name.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+fn decode<T>(value: &Value<'_>) -> Result<T>
+where
+    T: TryFrom<OwnedValue>,
+    T::Error: Into<Error>,
+{
+    T::try_from(value.try_to_owned()?).map_err(Into::into)
+}
+
+fn boxed<F, T>(decoder: F) -> Decoder
+where
+    F: Fn(&Value<'_>) -> Result<T> + Send + Sync + 'static,
+    T: Send + Sync + 'static,
+{
+    Arc::new(move |value| decoder(value).map(|v| Box::new(v) as Decoded))
+}
+
+fn downcast<T>(decoded: Decoded) -> Result<T>
+where
+    T: 'static,
+{
+    let decoded: Box<dyn Any> = decoded;
+
+    decoded
+        .downcast()
+        .map(|t| *t)
+        .map_err(|_| Error::IncorrectType)
+}
+
+fn unwrap_variant<'a, 'v>(value: &'a Value<'v>) -> &'a Value<'v> {
+    match value {
+        Value::Value(inner) => inner,
+        _ => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TypeRegistry;
+    use crate::{Error, Signature, Value};
+
+    #[test]
+    fn registry() {
+        let mut registry = TypeRegistry::new();
+        registry
+            .register::<(String, u32)>()
+            .register_with(Signature::U8, |value| {
+                u8::try_from(value).map(|byte| char::from(byte).to_ascii_uppercase())
+            })
+            .register_name::<String>("org.example.Item.Name");
+        assert!(registry.contains(&Signature::U8));
+        assert!(!registry.contains_name("org.example.Item.Label"));
+
+        let value = Value::new(Value::new(("hello", 42u32)));
+        let decoded = registry
+            .decode_as::<(String, u32)>(&value)
+            .unwrap()
+            .unwrap();
+        assert_eq!(decoded, ("hello".to_string(), 42));
+        // Wrong type requested.
+        assert_eq!(
+            registry.decode_as::<u32>(&value).unwrap(),
+            Err(Error::IncorrectType)
+        );
+
+        let upper = registry.decode_as::<char>(&Value::from(b'a')).unwrap();
+        assert_eq!(upper.unwrap(), 'A');
+
+        let name = registry.decode_named_as::<String>("org.example.Item.Name", &Value::from("n"));
+        assert_eq!(name.unwrap().unwrap(), "n");
+        assert!(matches!(
+            registry.decode_named("org.example.Item.Name", &Value::from(1u32)),
+            Some(Err(Error::SignatureMismatch(..)))
+        ));
+        assert!(registry
+            .decode_named("org.example.Item.Label", &Value::from("n"))
+            .is_none());
+    }
+}
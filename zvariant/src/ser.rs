@@ -55,6 +55,24 @@ where
     #[cfg(unix)]
     let mut fds = FdList::Number(0);
 
+    // Fixed-sized D-Bus types don't need to go through the serializer.
+    if ctxt.format() == Format::DBus {
+        #[cfg(unix)]
+        let mut num_fds = 0;
+        if let Some(end) = fixed_size_dbus(
+            &signature,
+            ctxt.position(),
+            #[cfg(unix)]
+            &mut num_fds,
+        ) {
+            let size = Size::new(end - ctxt.position(), ctxt);
+            #[cfg(unix)]
+            let size = size.set_num_fds(num_fds);
+
+            return Ok(size);
+        }
+    }
+
     let len = match ctxt.format() {
         Format::DBus => {
             let mut ser = DBusSerializer::<NullWriteSeek>::new(
@@ -91,6 +109,49 @@ where
     Ok(size)
 }
 
+/// The position at the end of a value of a fixed-sized D-Bus type, starting at `pos`.
+///
+/// Returns `None` if the type isn't fixed-sized.
+fn fixed_size_dbus(
+    signature: &Signature,
+    pos: usize,
+    #[cfg(unix)] num_fds: &mut u32,
+) -> Option<usize> {
+    let size = match signature {
+        // Nothing is written for the unit type, not even padding.
+        Signature::Unit => return Some(pos),
+        Signature::U8 => 1,
+        Signature::I16 | Signature::U16 => 2,
+        Signature::Bool | Signature::I32 | Signature::U32 => 4,
+        #[cfg(unix)]
+        Signature::Fd => {
+            *num_fds += 1;
+
+            4
+        }
+        Signature::I64 | Signature::U64 | Signature::F64 => 8,
+        Signature::Structure(fields) => {
+            let mut pos = pos + padding_for_n_bytes(pos, signature.alignment(Format::DBus));
+            for field in fields.iter() {
+                if matches!(field, Signature::Unit) {
+                    return None;
+                }
+                pos = fixed_size_dbus(
+                    field,
+                    pos,
+                    #[cfg(unix)]
+                    num_fds,
+                )?;
+            }
+
+            return Some(pos);
+        }
+        _ => return None,
+    };
+
+    Some(pos + padding_for_n_bytes(pos, signature.alignment(Format::DBus)) + size)
+}
+
 /// Serialize `T` to the given `writer`.
 ///
 /// # Examples
@@ -1,6 +1,6 @@
 use static_assertions::assert_impl_all;
 
-use crate::{serialized::Format, Endian};
+use crate::{container_depths::MAX_TOTAL_DEPTH, serialized::Format, Endian};
 
 /// The encoding context to use with the [serialization and deserialization] API.
 ///
@@ -26,6 +26,27 @@ use crate::{serialized::Format, Endian};
 /// assert_eq!(decoded, "World");
 /// ```
 ///
+/// # Limits
+///
+/// The context also carries limits that are enforced during deserialization, so that untrusted
+/// input can't make the deserializer consume excessive amounts of memory or stack. By default,
+/// only the limits of the D-Bus specification apply but tighter limits can be set with
+/// [`Context::with_max_depth`], [`Context::with_max_elements`] and [`Context::with_max_size`]:
+///
+/// ```
+/// use zvariant::{serialized::Context, to_bytes, Error, LimitExceeded, LE};
+///
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let encoded = to_bytes(ctxt, &vec![1u32, 2, 3]).unwrap();
+///
+/// let ctxt = ctxt.with_max_elements(2);
+/// let data = zvariant::serialized::Data::new(encoded.bytes(), ctxt);
+/// assert_eq!(
+///     data.deserialize::<Vec<u32>>().unwrap_err(),
+///     Error::LimitExceeded(LimitExceeded::Elements),
+/// );
+/// ```
+///
 /// [serialization and deserialization]: index.html#functions
 /// [specify]: #method.new
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -33,6 +54,9 @@ pub struct Context {
     format: Format,
     position: usize,
     endian: Endian,
+    max_depth: u8,
+    max_elements: Option<usize>,
+    max_size: Option<usize>,
 }
 
 assert_impl_all!(Context: Send, Sync, Unpin);
@@ -44,6 +68,9 @@ impl Context {
             format,
             position,
             endian,
+            max_depth: MAX_TOTAL_DEPTH,
+            max_elements: None,
+            max_size: None,
         }
     }
 
@@ -76,4 +103,68 @@ impl Context {
     pub fn position(self) -> usize {
         self.position
     }
+
+    /// Set the maximum total depth of nested containers allowed during deserialization.
+    ///
+    /// Exceeding it results in a [`MaxDepthExceeded::Container`] error. The limits of the D-Bus
+    /// specification (a total depth of 64, with at most 32 nested arrays and 32 nested structures)
+    /// always apply, so `depth` values larger than 64 have no effect.
+    ///
+    /// [`MaxDepthExceeded::Container`]: crate::MaxDepthExceeded::Container
+    pub fn with_max_depth(mut self, depth: u8) -> Self {
+        self.max_depth = depth.min(MAX_TOTAL_DEPTH);
+
+        self
+    }
+
+    /// Set the maximum number of elements in a single array or dictionary, allowed during
+    /// deserialization.
+    ///
+    /// Exceeding it results in a [`LimitExceeded::Elements`] error. By default, there is no limit.
+    ///
+    /// [`LimitExceeded::Elements`]: crate::LimitExceeded::Elements
+    pub fn with_max_elements(mut self, elements: usize) -> Self {
+        self.max_elements = Some(elements);
+
+        self
+    }
+
+    /// Set the maximum size in bytes of the data to deserialize, and of the values decoded from it.
+    ///
+    /// The size of the decoded values is the total size of all the basic values (strings count
+    /// with their length), plus the size of a pointer for each element of an array or dictionary.
+    /// It's an estimate of the memory the deserialized values need, so that a small input can't
+    /// result in large allocations, e.g. through long arrays of tiny elements.
+    ///
+    /// Exceeding it results in a [`LimitExceeded::Size`] error. By default, there is no limit.
+    ///
+    /// [`LimitExceeded::Size`]: crate::LimitExceeded::Size
+    pub fn with_max_size(mut self, size: usize) -> Self {
+        self.max_size = Some(size);
+
+        self
+    }
+
+    /// The maximum total depth of nested containers allowed during deserialization.
+    pub fn max_depth(self) -> u8 {
+        self.max_depth
+    }
+
+    /// The maximum number of elements in a single array or dictionary, if limited.
+    pub fn max_elements(self) -> Option<usize> {
+        self.max_elements
+    }
+
+    /// The maximum size in bytes of the data to deserialize and of the values decoded from it, if
+    /// limited.
+    pub fn max_size(self) -> Option<usize> {
+        self.max_size
+    }
+
+    /// The same context, for the value at the given `position` in the entire message.
+    pub(crate) fn with_position(mut self, position: usize) -> Self {
+        self.position = position;
+
+        self
+    }
 }
@@ -2,6 +2,7 @@
 use crate::{Fd, OwnedFd};
 use std::{
     borrow::Cow,
+    marker::PhantomData,
     ops::{Bound, Deref, Range, RangeBounds},
     sync::Arc,
 };
@@ -10,7 +11,9 @@ use serde::{de::DeserializeSeed, Deserialize};
 
 use crate::{
     de::Deserializer,
-    serialized::{Context, Format},
+    normal_form::NodeSeed,
+    serialized::{path::PathSeed, Context, Format, PathSegment},
+    validate::ValidationSeed,
     DynamicDeserialize, DynamicType, Error, Result, Signature, Type,
 };
 
@@ -104,11 +107,7 @@ impl<'bytes, 'fds> Data<'bytes, 'fds> {
         );
         assert!(end <= len, "range end out of bounds: {end:?} > {len:?}");
 
-        let context = Context::new(
-            self.context.format(),
-            self.context.endian(),
-            self.context.position() + start,
-        );
+        let context = self.context.with_position(self.context.position() + start);
         let range = Range {
             start: self.range.start + start,
             end: self.range.start + end,
@@ -291,6 +290,51 @@ impl<'bytes, 'fds> Data<'bytes, 'fds> {
         self.deserialize_with_seed(seed)
     }
 
+    /// Transcode `self`, with the given signature, into `serializer` of any other serde format.
+    ///
+    /// The data is directly fed from the deserializer into `serializer`, without building any
+    /// intermediate representation (e.g a [`crate::Value`]). This is meant for gateways between
+    /// D-Bus and other formats. To go the other way around, see [`crate::transcode_to_bytes`].
+    ///
+    /// Structures are transcoded as sequences, dictionaries as maps, and variants as a sequence of
+    /// their signature and value. File descriptors are transcoded as their index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zvariant::{serialized::Context, to_bytes, LE};
+    ///
+    /// let ctxt = Context::new_dbus(LE, 0);
+    /// let encoded = to_bytes(ctxt, &("hello", vec![4u32, 2])).unwrap();
+    ///
+    /// let mut json = vec![];
+    /// let mut serializer = serde_json::Serializer::new(&mut json);
+    /// encoded.transcode_for_signature("(sau)", &mut serializer).unwrap();
+    /// assert_eq!(json, br#"["hello",[4,2]]"#);
+    /// ```
+    ///
+    /// # Return value
+    ///
+    /// A tuple containing the output of `serializer` and the number of bytes parsed from `bytes`.
+    #[cfg(feature = "transcode")]
+    pub fn transcode_for_signature<S, Ser>(
+        &self,
+        signature: S,
+        serializer: Ser,
+    ) -> Result<(Ser::Ok, usize)>
+    where
+        S: TryInto<Signature>,
+        S::Error: Into<Error>,
+        Ser: serde::Serializer,
+    {
+        let signature = signature.try_into().map_err(Into::into)?;
+
+        self.deserialize_with_seed(crate::transcode::TranscodeSeed {
+            signature,
+            serializer,
+        })
+    }
+
     /// Deserialize `T` from `self`, using the given seed.
     ///
     /// # Return value
@@ -346,6 +390,166 @@ impl<'bytes, 'fds> Data<'bytes, 'fds> {
             Deserializer::DBus(de) => (t, de.0.pos),
         })
     }
+
+    /// Deserialize `T` from the value at the given path in `self`, without deserializing the rest.
+    ///
+    /// `signature` is the signature of the whole of `self`, and `path` leads to the value to
+    /// deserialize, through structure fields and array elements ([`PathSegment::Index`]),
+    /// dictionary entries ([`PathSegment::Key`]) and variants ([`PathSegment::Variant`]).
+    /// Everything that's not on the way is skipped over as cheaply as the format allows: arrays
+    /// are skipped using their length in the D-Bus format and non-fixed-sized values are skipped
+    /// using the framing offsets of their container in the GVariant format. This makes it a lot
+    /// cheaper than deserializing everything when only a small part of a big value, e.g. a single
+    /// property in the reply to a `GetAll` call, is needed.
+    ///
+    /// `T` must be deserializable from the value at the end of `path`, whose signature is the
+    /// respective part of `signature`. An empty `path` is the same as
+    /// [`Data::deserialize_for_signature`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use zvariant::{serialized::{Context, PathSegment}, to_bytes, Value, LE};
+    ///
+    /// let ctxt = Context::new_dbus(LE, 0);
+    /// let props = HashMap::from([
+    ///     ("Name", Value::new("zbus")),
+    ///     ("Features", Value::new(vec!["tokio", "url"])),
+    ///     ("Version", Value::new(5u32)),
+    /// ]);
+    /// let encoded = to_bytes(ctxt, &(props,)).unwrap();
+    ///
+    /// let version: u32 = encoded
+    ///     .deserialize_at("(a{sv})", &[0.into(), "Version".into(), PathSegment::Variant])
+    ///     .unwrap()
+    ///     .unwrap();
+    /// assert_eq!(version, 5);
+    ///
+    /// let feature: &str = encoded
+    ///     .deserialize_at(
+    ///         "(a{sv})",
+    ///         &[0.into(), "Features".into(), PathSegment::Variant, 1.into()],
+    ///     )
+    ///     .unwrap()
+    ///     .unwrap();
+    /// assert_eq!(feature, "url");
+    ///
+    /// // Non-existent keys and out-of-bounds array indices give `None`.
+    /// let missing: Option<Value<'_>> = encoded
+    ///     .deserialize_at("(a{sv})", &[0.into(), "Vendor".into()])
+    ///     .unwrap();
+    /// assert!(missing.is_none());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If `path` doesn't match `signature`, e.g. a key is given for an array or the index of a
+    /// structure field is out of bounds, or if the data is invalid on the way.
+    pub fn deserialize_at<'d, S, T>(
+        &'d self,
+        signature: S,
+        path: &[PathSegment<'_>],
+    ) -> Result<Option<T>>
+    where
+        T: Deserialize<'d>,
+        S: TryInto<Signature>,
+        S::Error: Into<Error>,
+    {
+        let signature = signature.try_into().map_err(Into::into)?;
+        let seed = PathSeed {
+            path,
+            signature: &signature,
+            phantom: PhantomData,
+        };
+
+        self.deserialize_with_seed(seed).map(|(value, _)| value)
+    }
+
+    /// Validate `self` against the given signature, without deserializing it.
+    ///
+    /// This is the same as [`crate::validate`], except that file descriptor indices are also
+    /// checked against the file descriptors in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zvariant::{serialized::Context, to_bytes, LE};
+    ///
+    /// let ctxt = Context::new_dbus(LE, 0);
+    /// let encoded = to_bytes(ctxt, &("/hello/world", 42u32)).unwrap();
+    /// assert_eq!(encoded.validate("(su)").unwrap(), encoded.len());
+    /// assert_eq!(encoded.validate("(ou)").unwrap(), encoded.len());
+    ///
+    /// let encoded = to_bytes(ctxt, &("hello world", 42u32)).unwrap();
+    /// // Not a valid object path.
+    /// assert!(encoded.validate("(ou)").is_err());
+    /// ```
+    ///
+    /// # Return value
+    ///
+    /// The number of bytes validated from `bytes`.
+    pub fn validate<S>(&self, signature: S) -> Result<usize>
+    where
+        S: TryInto<Signature>,
+        S::Error: Into<Error>,
+    {
+        let signature = signature.try_into().map_err(Into::into)?;
+        let seed = ValidationSeed {
+            signature: &signature,
+            #[cfg(unix)]
+            num_fds: Some(self.inner.fds.len()),
+            #[cfg(not(unix))]
+            num_fds: None,
+        };
+
+        self.deserialize_with_seed(seed).map(|(_, len)| len)
+    }
+
+    /// Check if `self` is in normal form for the given signature.
+    ///
+    /// The data is in normal form if it's exactly the serialization of the value it represents,
+    /// without any trailing bytes. This is mostly relevant for the GVariant format, where a lot of
+    /// non-normal data (e.g. unused bytes between the elements of a container or non-minimal
+    /// framing offsets) can still be deserialized. See the documentation of the `gvariant` module
+    /// for details on how such data is handled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zvariant::{serialized::{Context, Data}, to_bytes, LE};
+    ///
+    /// let ctxt = Context::new_dbus(LE, 0);
+    /// let encoded = to_bytes(ctxt, &("hello", 42u32)).unwrap();
+    /// assert!(encoded.is_normal_form("(su)").unwrap());
+    ///
+    /// // Trailing bytes are not part of the normal form.
+    /// let mut bytes = encoded.to_vec();
+    /// bytes.push(0);
+    /// let data = Data::new(bytes, ctxt);
+    /// assert_eq!(data.deserialize::<(&str, u32)>().unwrap().0, ("hello", 42));
+    /// assert!(!data.is_normal_form("(su)").unwrap());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If `self` can't be deserialized for `signature` at all.
+    pub fn is_normal_form<S>(&self, signature: S) -> Result<bool>
+    where
+        S: TryInto<Signature>,
+        S::Error: Into<Error>,
+    {
+        let signature = signature.try_into().map_err(Into::into)?;
+        let (node, len) = self.deserialize_with_seed(NodeSeed {
+            signature: &signature,
+        })?;
+        if len != self.len() {
+            return Ok(false);
+        }
+        let encoded = crate::to_bytes_for_signature(self.context, &signature, &node)?;
+
+        Ok(encoded.bytes() == self.bytes())
+    }
 }
 
 impl<'bytes> Data<'bytes, 'static> {
@@ -1,5 +1,7 @@
 mod data;
 pub use data::Data;
+mod path;
+pub use path::PathSegment;
 mod size;
 pub use size::Size;
 mod written;
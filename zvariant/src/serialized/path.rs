@@ -0,0 +1,172 @@
+use serde::de::{DeserializeSeed, Deserializer, Error, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use std::{fmt, marker::PhantomData};
+
+use crate::{value::ValueSeed, DynamicType, Signature, Value};
+
+/// A segment of a path into serialized data.
+///
+/// See [`Data::deserialize_at`](super::Data::deserialize_at) for details.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSegment<'a> {
+    /// The field of a structure, or the element of an array, at the given index.
+    Index(usize),
+    /// The value of the dictionary entry with the given key.
+    Key(Value<'a>),
+    /// The value contained in a variant.
+    Variant,
+}
+
+impl From<usize> for PathSegment<'_> {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
+}
+
+impl<'a> From<&'a str> for PathSegment<'a> {
+    fn from(key: &'a str) -> Self {
+        Self::Key(Value::from(key))
+    }
+}
+
+impl fmt::Display for PathSegment<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Index(index) => write!(f, "[{index}]"),
+            Self::Key(key) => write!(f, "[{key}]"),
+            Self::Variant => f.write_str("variant"),
+        }
+    }
+}
+
+/// A seed that follows a path through the data of the given signature, skipping over everything
+/// else, and deserializes `T` from the value at the end of it.
+pub(crate) struct PathSeed<'p, 'sig, T> {
+    pub(crate) path: &'p [PathSegment<'p>],
+    pub(crate) signature: &'sig Signature,
+    pub(crate) phantom: PhantomData<T>,
+}
+
+impl<'p, T> PathSeed<'p, '_, T> {
+    fn child<'s>(path: &'p [PathSegment<'p>], signature: &'s Signature) -> PathSeed<'p, 's, T> {
+        PathSeed {
+            path,
+            signature,
+            phantom: PhantomData,
+        }
+    }
+
+    fn mismatch<E>(&self) -> E
+    where
+        E: Error,
+    {
+        E::custom(format_args!(
+            "path segment `{}` doesn't apply to signature `{}`",
+            self.path[0], self.signature
+        ))
+    }
+}
+
+impl<T> DynamicType for PathSeed<'_, '_, T> {
+    fn signature(&self) -> Signature {
+        self.signature.clone()
+    }
+}
+
+impl<'de, T> DeserializeSeed<'de> for PathSeed<'_, '_, T>
+where
+    T: serde::Deserialize<'de>,
+{
+    type Value = Option<T>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match (self.path.first(), self.signature) {
+            (None, _) => T::deserialize(deserializer).map(Some),
+            (Some(PathSegment::Index(_)), Signature::Structure(_) | Signature::Array(_))
+            | (Some(PathSegment::Key(_)), Signature::Dict { .. })
+            | (Some(PathSegment::Variant), Signature::Variant) => {
+                deserializer.deserialize_any(self)
+            }
+            _ => Err(self.mismatch()),
+        }
+    }
+}
+
+impl<'de, T> Visitor<'de> for PathSeed<'_, '_, T>
+where
+    T: serde::Deserialize<'de>,
+{
+    type Value = Option<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "a value of signature `{}`", self.signature)
+    }
+
+    fn visit_seq<V>(self, mut visitor: V) -> Result<Option<T>, V::Error>
+    where
+        V: SeqAccess<'de>,
+    {
+        let rest = &self.path[1..];
+        match (&self.path[0], self.signature) {
+            (PathSegment::Index(index), Signature::Structure(fields)) => {
+                let field = fields.iter().nth(*index).ok_or_else(|| self.mismatch())?;
+                for _ in 0..*index {
+                    visitor.next_element::<IgnoredAny>()?;
+                }
+
+                visitor
+                    .next_element_seed(Self::child(rest, field))
+                    .map(Option::flatten)
+            }
+            (PathSegment::Index(index), Signature::Array(child)) => {
+                for _ in 0..*index {
+                    if visitor.next_element::<IgnoredAny>()?.is_none() {
+                        return Ok(None);
+                    }
+                }
+
+                visitor
+                    .next_element_seed(Self::child(rest, child.signature()))
+                    .map(Option::flatten)
+            }
+            (PathSegment::Variant, Signature::Variant) => {
+                let signature = visitor
+                    .next_element::<Signature>()?
+                    .ok_or_else(|| Error::custom("missing signature of the variant"))?;
+
+                visitor
+                    .next_element_seed(Self::child(rest, &signature))
+                    .map(Option::flatten)
+            }
+            _ => Err(self.mismatch()),
+        }
+    }
+
+    fn visit_map<V>(self, mut visitor: V) -> Result<Option<T>, V::Error>
+    where
+        V: MapAccess<'de>,
+    {
+        let rest = &self.path[1..];
+        match (&self.path[0], self.signature) {
+            (
+                PathSegment::Key(key),
+                Signature::Dict {
+                    key: key_sig,
+                    value,
+                },
+            ) => {
+                while let Some(k) = visitor.next_key_seed(ValueSeed::<Value<'_>>::new(key_sig))? {
+                    if k == *key {
+                        return visitor.next_value_seed(Self::child(rest, value));
+                    }
+                    visitor.next_value::<IgnoredAny>()?;
+                }
+
+                Ok(None)
+            }
+            _ => Err(self.mismatch()),
+        }
+    }
+}
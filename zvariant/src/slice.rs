@@ -0,0 +1,674 @@
+use serde::{
+    de::{self, Deserialize, Deserializer, SeqAccess, Visitor},
+    ser::{Serialize, Serializer},
+};
+use static_assertions::assert_impl_all;
+use std::{
+    borrow::Cow,
+    marker::PhantomData,
+    mem::{align_of, size_of},
+    ops::{Deref, DerefMut},
+};
+
+use crate::{Signature, Type};
+
+/// The element signature of the `Slice` deserializing itself as a newtype struct with the given
+/// name, if any.
+///
+/// Each `Slice` uses a name specific to its element type, for our deserializers to check the
+/// element type before handing out the raw bytes of numeric arrays.
+pub(crate) fn slice_element_signature(name: &str) -> Option<&'static Signature> {
+    fn element<T: SliceElement>(name: &str) -> Option<&'static Signature> {
+        (name == T::SLICE_STRUCT_NAME).then_some(T::SIGNATURE)
+    }
+
+    if !name.starts_with("zvariant::Slice<") {
+        return None;
+    }
+
+    element::<i16>(name)
+        .or_else(|| element::<u16>(name))
+        .or_else(|| element::<i32>(name))
+        .or_else(|| element::<u32>(name))
+        .or_else(|| element::<i64>(name))
+        .or_else(|| element::<u64>(name))
+        .or_else(|| element::<f64>(name))
+}
+
+/// A slice of fixed-sized numbers, borrowed from the serialized data whenever possible.
+///
+/// Deserializing a `Vec<T>` involves decoding each element individually. For large arrays of
+/// numbers (e.g `ay`, `aq`, `ai` or `ad`), this can be very costly. This type allows deserializing
+/// such arrays without any copying, by borrowing directly from the serialized data. This is
+/// possible when the data is in the native byte order and the elements are suitably aligned in
+/// memory. Otherwise, the elements are copied (and byte-swapped if needed) in a single pass.
+///
+/// Serializing an instance of `Slice<'_, u8>` writes all the bytes at once.
+///
+/// # Examples
+///
+/// ```
+/// use zvariant::{serialized::Context, to_bytes, Slice, NATIVE_ENDIAN};
+///
+/// let ctxt = Context::new_dbus(NATIVE_ENDIAN, 0);
+/// let encoded = to_bytes(ctxt, &vec![1u32, 2, 3, 4]).unwrap();
+/// let decoded: Slice<'_, u32> = encoded.deserialize().unwrap().0;
+/// assert_eq!(&*decoded, &[1, 2, 3, 4]);
+///
+/// let encoded = to_bytes(ctxt, &Slice::from(&b"hello"[..])).unwrap();
+/// let decoded: Slice<'_, u8> = encoded.deserialize().unwrap().0;
+/// assert!(decoded.is_borrowed());
+/// assert_eq!(&*decoded, b"hello");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Slice<'a, T>(Cow<'a, [T]>)
+where
+    T: SliceElement;
+
+assert_impl_all!(Slice<'_, u8>: Send, Sync, Unpin);
+
+/// A byte array (`ay`), borrowed from the serialized data.
+///
+/// Both serialization and deserialization of this type go through the fast path for byte arrays,
+/// i-e the bytes are copied (or borrowed, in case of deserialization) at once, rather than
+/// individually going through serde's sequence machinery. It's the built-in equivalent of
+/// `serde_bytes::Bytes`, which is also supported if `serde_bytes` feature is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use zvariant::{serialized::Context, to_bytes, ByteBuf, Bytes, LE};
+///
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let ay = Bytes::from(&[77u8; 1024][..]);
+/// let encoded = to_bytes(ctxt, &ay).unwrap();
+/// assert_eq!(encoded.len(), 1028);
+///
+/// let decoded: Bytes<'_> = encoded.deserialize().unwrap().0;
+/// assert!(decoded.is_borrowed());
+/// assert_eq!(decoded, ay);
+///
+/// // An owned version that doesn't borrow from the serialized data.
+/// let decoded: ByteBuf = encoded.deserialize().unwrap().0;
+/// assert_eq!(decoded.into_vec(), vec![77u8; 1024]);
+/// ```
+pub type Bytes<'a> = Slice<'a, u8>;
+
+/// An owned byte array (`ay`).
+///
+/// Unlike [`Bytes`], this can be deserialized from any input, including short-lived buffers. See
+/// [`Bytes`] for details.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct ByteBuf(Vec<u8>);
+
+assert_impl_all!(ByteBuf: Send, Sync, Unpin);
+
+impl<'a, T> Slice<'a, T>
+where
+    T: SliceElement,
+{
+    /// Whether the elements are borrowed from the source.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.0, Cow::Borrowed(_))
+    }
+
+    /// The elements as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    /// Creates an owned clone of `self`.
+    pub fn to_owned(&self) -> Slice<'static, T> {
+        Slice(Cow::Owned(self.0.to_vec()))
+    }
+
+    /// Creates an owned version of `self`, without copying if `self` is already owned.
+    pub fn into_owned(self) -> Slice<'static, T> {
+        Slice(Cow::Owned(self.0.into_owned()))
+    }
+
+    /// The elements as a `Vec`, without copying if `self` is already owned.
+    pub fn into_vec(self) -> Vec<T> {
+        self.0.into_owned()
+    }
+}
+
+impl<T> Deref for Slice<'_, T>
+where
+    T: SliceElement,
+{
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T> AsRef<[T]> for Slice<'_, T>
+where
+    T: SliceElement,
+{
+    fn as_ref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<'a, T> From<&'a [T]> for Slice<'a, T>
+where
+    T: SliceElement,
+{
+    fn from(slice: &'a [T]) -> Self {
+        Slice(Cow::Borrowed(slice))
+    }
+}
+
+impl<T> From<Vec<T>> for Slice<'_, T>
+where
+    T: SliceElement,
+{
+    fn from(vec: Vec<T>) -> Self {
+        Slice(Cow::Owned(vec))
+    }
+}
+
+impl<'a, T> From<Cow<'a, [T]>> for Slice<'a, T>
+where
+    T: SliceElement,
+{
+    fn from(cow: Cow<'a, [T]>) -> Self {
+        Slice(cow)
+    }
+}
+
+impl<T> From<Slice<'_, T>> for Vec<T>
+where
+    T: SliceElement,
+{
+    fn from(slice: Slice<'_, T>) -> Self {
+        slice.into_vec()
+    }
+}
+
+impl<T> Type for Slice<'_, T>
+where
+    T: SliceElement,
+{
+    const SIGNATURE: &'static Signature = &Signature::static_array(T::SIGNATURE);
+}
+
+impl<T> Serialize for Slice<'_, T>
+where
+    T: SliceElement,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        T::serialize_slice(&self.0, serializer)
+    }
+}
+
+impl<'de: 'a, 'a, T> Deserialize<'de> for Slice<'a, T>
+where
+    T: SliceElement,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let visitor = SliceVisitor(PhantomData);
+        if T::SIGNATURE == u8::SIGNATURE {
+            deserializer.deserialize_bytes(visitor)
+        } else {
+            deserializer.deserialize_newtype_struct(T::SLICE_STRUCT_NAME, visitor)
+        }
+    }
+}
+
+struct SliceVisitor<T>(PhantomData<T>);
+
+impl<T> SliceVisitor<T>
+where
+    T: SliceElement,
+{
+    fn check_len<E>(bytes: &[u8]) -> Result<(), E>
+    where
+        E: de::Error,
+    {
+        if bytes.len() % size_of::<T>() != 0 {
+            return Err(E::invalid_length(
+                bytes.len(),
+                &format!("a multiple of {}", size_of::<T>()).as_str(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl<'de, T> Visitor<'de> for SliceVisitor<T>
+where
+    T: SliceElement,
+{
+    type Value = Slice<'de, T>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "an array of `{}`", T::SIGNATURE)
+    }
+
+    fn visit_borrowed_bytes<E>(self, bytes: &'de [u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Self::check_len(bytes)?;
+
+        if bytes.as_ptr().align_offset(align_of::<T>()) != 0 {
+            // Unaligned, so we've no choice but to copy.
+            return self.visit_bytes(bytes);
+        }
+
+        // SAFETY: The pointer is non-null and suitably aligned for `T`, the length was checked to
+        // be a multiple of the size of `T` and all bit patterns are valid for all the `T` we
+        // support.
+        let slice = unsafe {
+            std::slice::from_raw_parts(bytes.as_ptr() as *const T, bytes.len() / size_of::<T>())
+        };
+
+        Ok(Slice(Cow::Borrowed(slice)))
+    }
+
+    fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Self::check_len(bytes)?;
+
+        let vec = bytes
+            .chunks_exact(size_of::<T>())
+            .map(T::from_ne_bytes)
+            .collect();
+
+        Ok(Slice(Cow::Owned(vec)))
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // Other deserializers only know about sequences.
+        deserializer.deserialize_seq(self)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut vec = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(element) = seq.next_element()? {
+            vec.push(element);
+        }
+
+        Ok(Slice(Cow::Owned(vec)))
+    }
+}
+
+impl ByteBuf {
+    /// Creates an empty byte array.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The bytes as a slice.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// The bytes as a `Vec`.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl Deref for ByteBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl DerefMut for ByteBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl AsRef<[u8]> for ByteBuf {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<Vec<u8>> for ByteBuf {
+    fn from(vec: Vec<u8>) -> Self {
+        Self(vec)
+    }
+}
+
+impl From<&[u8]> for ByteBuf {
+    fn from(bytes: &[u8]) -> Self {
+        Self(bytes.to_vec())
+    }
+}
+
+impl From<Bytes<'_>> for ByteBuf {
+    fn from(bytes: Bytes<'_>) -> Self {
+        Self(bytes.into_vec())
+    }
+}
+
+impl From<ByteBuf> for Vec<u8> {
+    fn from(bytes: ByteBuf) -> Self {
+        bytes.0
+    }
+}
+
+impl From<ByteBuf> for Bytes<'static> {
+    fn from(bytes: ByteBuf) -> Self {
+        Slice(Cow::Owned(bytes.0))
+    }
+}
+
+impl Type for ByteBuf {
+    const SIGNATURE: &'static Signature = &Signature::static_array(&Signature::U8);
+}
+
+impl Serialize for ByteBuf {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_byte_buf(ByteBufVisitor)
+    }
+}
+
+struct ByteBufVisitor;
+
+impl<'de> Visitor<'de> for ByteBufVisitor {
+    type Value = ByteBuf;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("a byte array")
+    }
+
+    fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(ByteBuf(bytes.to_vec()))
+    }
+
+    fn visit_byte_buf<E>(self, bytes: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(ByteBuf(bytes))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut vec = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            vec.push(byte);
+        }
+
+        Ok(ByteBuf(vec))
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Types that can be elements of a [`Slice`].
+///
+/// This trait is sealed and implemented for all the fixed-sized numeric types: `u8`, `i16`, `u16`,
+/// `i32`, `u32`, `i64`, `u64` and `f64`.
+pub trait SliceElement:
+    Type + Copy + Serialize + for<'de> Deserialize<'de> + private::Sealed + 'static
+{
+    #[doc(hidden)]
+    const SLICE_STRUCT_NAME: &'static str;
+
+    #[doc(hidden)]
+    fn from_ne_bytes(bytes: &[u8]) -> Self;
+
+    #[doc(hidden)]
+    fn serialize_slice<S>(slice: &[Self], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(slice)
+    }
+}
+
+macro_rules! slice_element {
+    ($($type:ty => $signature:literal)+) => {
+        $(
+            impl private::Sealed for $type {}
+
+            impl SliceElement for $type {
+                const SLICE_STRUCT_NAME: &'static str = concat!("zvariant::Slice<", $signature, ">");
+
+                fn from_ne_bytes(bytes: &[u8]) -> Self {
+                    <$type>::from_ne_bytes(bytes.try_into().expect("invalid element size"))
+                }
+            }
+        )+
+    };
+}
+
+slice_element!(i16 => "n" u16 => "q" i32 => "i" u32 => "u" i64 => "x" u64 => "t" f64 => "d");
+
+impl private::Sealed for u8 {}
+
+impl SliceElement for u8 {
+    // Never used, since byte arrays go through `deserialize_bytes`.
+    const SLICE_STRUCT_NAME: &'static str = "zvariant::Slice<y>";
+
+    fn from_ne_bytes(bytes: &[u8]) -> Self {
+        bytes[0]
+    }
+
+    fn serialize_slice<S>(slice: &[Self], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::align_of;
+
+    use crate::{serialized::Context, to_bytes, Bytes, Error, Slice, BE, LE, NATIVE_ENDIAN};
+
+    #[test]
+    fn borrowed() {
+        let ctxt = Context::new_dbus(NATIVE_ENDIAN, 0);
+        let encoded = to_bytes(ctxt, &vec![1u8, 2, 3]).unwrap();
+        let decoded: Slice<'_, u8> = encoded.deserialize().unwrap().0;
+        assert!(decoded.is_borrowed());
+        assert_eq!(&*decoded, &[1, 2, 3]);
+
+        let values = vec![1.5f64, -2.0, 3.25];
+        let encoded = to_bytes(ctxt, &values).unwrap();
+        let (decoded, parsed): (Slice<'_, f64>, _) = encoded.deserialize().unwrap();
+        assert_eq!(parsed, encoded.len());
+        assert_eq!(&*decoded, &values[..]);
+        // Whether the data is borrowed depends on alignment of the buffer in memory.
+        let aligned = encoded.bytes()[8..]
+            .as_ptr()
+            .align_offset(align_of::<f64>())
+            == 0;
+        assert_eq!(decoded.is_borrowed(), aligned);
+
+        // Round-trip.
+        let encoded_slice = to_bytes(ctxt, &decoded).unwrap();
+        assert_eq!(encoded_slice.bytes(), encoded.bytes());
+    }
+
+    #[test]
+    fn byte_swapped() {
+        for endian in [LE, BE] {
+            let ctxt = Context::new_dbus(endian, 0);
+            let values = vec![1u32, 0xdeadbeef, 3];
+            let encoded = to_bytes(ctxt, &values).unwrap();
+            let decoded: Slice<'_, u32> = encoded.deserialize().unwrap().0;
+            assert_eq!(decoded.into_vec(), values);
+
+            let values = vec![-1i16, 0x1234, 3];
+            let encoded = to_bytes(ctxt, &values).unwrap();
+            let decoded: Slice<'_, i16> = encoded.deserialize().unwrap().0;
+            assert_eq!(&*decoded, &values[..]);
+
+            // Empty arrays.
+            let encoded = to_bytes(ctxt, &(Vec::<u64>::new(), 42u8)).unwrap();
+            let decoded: (Slice<'_, u64>, u8) = encoded.deserialize().unwrap().0;
+            assert!(decoded.0.is_empty());
+            assert_eq!(decoded.1, 42);
+        }
+    }
+
+    #[cfg(feature = "gvariant")]
+    #[test]
+    fn gvariant() {
+        for endian in [LE, BE] {
+            let ctxt = Context::new_gvariant(endian, 0);
+            let values = (vec![1u64, 2, 3], "hello", vec![7u16, 8]);
+            let encoded = to_bytes(ctxt, &values).unwrap();
+            let decoded: (Slice<'_, u64>, &str, Slice<'_, u16>) = encoded.deserialize().unwrap().0;
+            assert_eq!(&*decoded.0, &values.0[..]);
+            assert_eq!(decoded.1, values.1);
+            assert_eq!(&*decoded.2, &values.2[..]);
+        }
+    }
+
+    #[test]
+    fn bytes() {
+        use crate::{ByteBuf, Type};
+
+        assert_eq!(Bytes::SIGNATURE, "ay");
+        let ay = ByteBuf::from(vec![77u8; 100_000]);
+        let ctxt = Context::new_dbus(LE, 0);
+        let encoded = to_bytes(ctxt, &(42u8, &ay)).unwrap();
+        assert_eq!(encoded.len(), 100_008);
+        let decoded: (u8, Bytes<'_>) = encoded.deserialize().unwrap().0;
+        assert!(decoded.1.is_borrowed());
+        assert_eq!(decoded.1.as_slice(), ay.as_slice());
+        // Same encoding as a `Vec<u8>`.
+        let encoded_vec = to_bytes(ctxt, &(42u8, ay.to_vec())).unwrap();
+        assert_eq!(encoded_vec.bytes(), encoded.bytes());
+
+        #[cfg(feature = "gvariant")]
+        {
+            let ctxt = Context::new_gvariant(LE, 0);
+            let encoded = to_bytes(ctxt, &(&ay, 42u8)).unwrap();
+            let decoded: (Bytes<'_>, u8) = encoded.deserialize().unwrap().0;
+            assert_eq!(decoded.0.as_slice(), ay.as_slice());
+            assert_eq!(decoded.1, 42);
+        }
+    }
+
+    #[test]
+    fn byte_buf() {
+        use crate::{serialized::Data, ByteBuf, Type};
+
+        // Decoded from a buffer that doesn't outlive it.
+        fn decode(bytes: &[u8], ctxt: Context) -> ByteBuf {
+            Data::new(bytes, ctxt).deserialize().unwrap().0
+        }
+
+        assert_eq!(ByteBuf::SIGNATURE, "ay");
+        let ay = ByteBuf::from(vec![77u8; 1024]);
+        let ctxt = Context::new_dbus(LE, 0);
+        let encoded = to_bytes(ctxt, &ay).unwrap();
+        assert_eq!(encoded.len(), 1028);
+        assert_eq!(decode(encoded.bytes(), ctxt), ay);
+        #[cfg(feature = "gvariant")]
+        {
+            let ctxt = Context::new_gvariant(LE, 0);
+            let encoded = to_bytes(ctxt, &ay).unwrap();
+            assert_eq!(decode(encoded.bytes(), ctxt), ay);
+        }
+
+        // Formats that don't support byte arrays give us a sequence.
+        let json = serde_json::to_string(&ay).unwrap();
+        let decoded: ByteBuf = serde_json::from_reader(json.as_bytes()).unwrap();
+        assert_eq!(decoded, ay);
+    }
+
+    #[test]
+    fn signature_mismatch() {
+        let ctxt = Context::new_dbus(LE, 0);
+        let encoded = to_bytes(ctxt, &vec!["a", "b"]).unwrap();
+        assert!(encoded
+            .deserialize_for_signature::<_, Slice<'_, u32>>("as")
+            .is_err());
+
+        // Only `ay` is a byte array.
+        let encoded = to_bytes(ctxt, &vec![1u32, 2]).unwrap();
+        assert!(encoded
+            .deserialize_for_signature::<_, Bytes<'_>>("au")
+            .is_err());
+
+        // Numeric elements must be of the exact type, whatever their size.
+        let mismatch = |signature: &str, expected: &str| {
+            Error::SignatureMismatch(signature.try_into().unwrap(), format!("`{expected}`"))
+        };
+        let encoded = to_bytes(ctxt, &vec![1.5f64, -2.0]).unwrap();
+        assert_eq!(
+            encoded
+                .deserialize_for_signature::<_, Slice<'_, u32>>("ad")
+                .unwrap_err(),
+            mismatch("ad", "au"),
+        );
+        let encoded = to_bytes(ctxt, &vec![1i32, 2]).unwrap();
+        assert_eq!(
+            encoded
+                .deserialize_for_signature::<_, Slice<'_, u16>>("ai")
+                .unwrap_err(),
+            mismatch("ai", "aq"),
+        );
+        assert_eq!(
+            encoded
+                .deserialize_for_signature::<_, Slice<'_, u32>>("ai")
+                .unwrap_err(),
+            mismatch("ai", "au"),
+        );
+
+        #[cfg(feature = "gvariant")]
+        {
+            let ctxt = Context::new_gvariant(LE, 0);
+            let encoded = to_bytes(ctxt, &vec![1.5f64, -2.0]).unwrap();
+            assert_eq!(
+                encoded
+                    .deserialize_for_signature::<_, Slice<'_, u32>>("ad")
+                    .unwrap_err(),
+                mismatch("ad", "au"),
+            );
+        }
+    }
+}
@@ -187,6 +187,57 @@ impl<'a> Structure<'a> {
         &self.fields
     }
 
+    /// Get the field at the given index, converted to `T`.
+    ///
+    /// Fields wrapped in a variant are unwrapped, just like [`Value::downcast_ref`] does.
+    ///
+    /// # Errors
+    ///
+    /// If `self` has no field at `index`, or if the field can't be converted to `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zvariant::Structure;
+    ///
+    /// let s = Structure::from((42u32, "hello"));
+    /// assert_eq!(s.field::<u32>(0).unwrap(), 42);
+    /// assert_eq!(s.field::<&str>(1).unwrap(), "hello");
+    ///
+    /// assert_eq!(
+    ///     s.field::<&str>(0).unwrap_err().to_string(),
+    ///     "Signature mismatch: got `u`, expected `&str` for field 0 of structure `(us)`",
+    /// );
+    /// assert_eq!(
+    ///     s.field::<u32>(2).unwrap_err().to_string(),
+    ///     "structure `(us)` has no field at index 2",
+    /// );
+    /// ```
+    pub fn field<'s, T>(&'s self, index: usize) -> crate::Result<T>
+    where
+        T: TryFrom<&'s Value<'s>>,
+        <T as TryFrom<&'s Value<'s>>>::Error: Into<crate::Error>,
+    {
+        let field = self.fields.get(index).ok_or_else(|| {
+            crate::Error::Message(format!(
+                "structure `{}` has no field at index {index}",
+                self.signature
+            ))
+        })?;
+
+        field.downcast_ref().map_err(|e| match e {
+            crate::Error::IncorrectType => crate::Error::SignatureMismatch(
+                field.value_signature().clone(),
+                format!(
+                    "`{}` for field {index} of structure `{}`",
+                    std::any::type_name::<T>(),
+                    self.signature
+                ),
+            ),
+            e => e,
+        })
+    }
+
     /// Converts `self` to a `Vec` containing all its fields.
     pub fn into_fields(self) -> Vec<Value<'a>> {
         self.fields
@@ -381,6 +432,73 @@ tuple_impls! {
     16 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14 15 T15)
 }
 
+/// Extract the fields of a [`Structure`] into variables.
+///
+/// `destructure!((a, b, c) = structure)` binds each field of `structure` to the respective
+/// variable, in order, using [`Structure::field`]. The types of the variables can be given
+/// explicitly, as in `destructure!((a: u32, b: &str) = structure)`, or left to inference. The
+/// structure is borrowed, so borrowed types like `&str` can be extracted without copying.
+///
+/// This expands to a `let` statement and uses the `?` operator for errors, so it can only be used
+/// in functions returning a `Result` whose error type can be converted from [`Error`]. An error is
+/// returned if the number of fields doesn't match, or if a field can't be converted to the type of
+/// the respective variable.
+///
+/// # Examples
+///
+/// ```
+/// use zvariant::{destructure, Structure};
+///
+/// fn print(structure: &Structure<'_>) -> zvariant::Result<String> {
+///     destructure!((name: &str, version, stable: bool) = structure);
+///     let version: u32 = version;
+///
+///     Ok(format!("{name} {version} (stable: {stable})"))
+/// }
+///
+/// let s = Structure::from(("zbus", 5u32, true));
+/// assert_eq!(print(&s).unwrap(), "zbus 5 (stable: true)");
+///
+/// let s = Structure::from(("zbus", 5u32));
+/// assert_eq!(
+///     print(&s).unwrap_err().to_string(),
+///     "expected a structure with 3 fields, got `(su)`",
+/// );
+/// ```
+///
+/// [`Error`]: enum.Error.html
+#[macro_export]
+macro_rules! destructure {
+    (($($name:ident $(: $ty:ty)?),+ $(,)?) = $structure:expr) => {
+        let ($($name,)+) = {
+            let structure: &$crate::Structure<'_> = &$structure;
+            let num_fields = [$($crate::destructure!(@unit $name)),+].len();
+            if structure.fields().len() != num_fields {
+                return ::std::result::Result::Err(
+                    $crate::Error::Message(::std::format!(
+                        "expected a structure with {} fields, got `{}`",
+                        num_fields,
+                        structure.signature(),
+                    ))
+                    .into(),
+                );
+            }
+            let mut indices = 0..;
+
+            ($(
+                {
+                    let $name $(: $ty)? = structure.field(indices.next().unwrap_or_default())?;
+
+                    $name
+                },
+            )+)
+        };
+    };
+    (@unit $name:ident) => {
+        ()
+    };
+}
+
 /// Owned [`Structure`]
 #[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct OwnedStructure(pub Structure<'static>);
@@ -0,0 +1,248 @@
+//! Encoding of timestamps as integers.
+//!
+//! By default, timestamp types are encoded in the same way as their [`serde`] implementations
+//! encode them, e.g. [`std::time::SystemTime`] as a `(tu)` structure and `chrono::DateTime` as an
+//! RFC 3339 string. D-Bus APIs however mostly use plain integers for timestamps, counted from the
+//! Unix epoch (1970-01-01 00:00:00 UTC):
+//!
+//! * [`seconds`] encodes a timestamp as the (signed) number of seconds since the Unix epoch, with
+//!   the signature `x`.
+//! * [`microseconds`] encodes a timestamp as the (unsigned) number of microseconds since the Unix
+//!   epoch, with the signature `t`. This is the convention used by systemd, e.g. for the
+//!   `*Timestamp` properties of units.
+//!
+//! Both are meant to be used with serde's `with` attribute, together with the `signature` attribute
+//! of the [`Type`] derive macro, on fields of any type that implements [`Timestamp`]:
+//!
+//! ```
+//! use std::time::{Duration, SystemTime, UNIX_EPOCH};
+//!
+//! use serde::{Deserialize, Serialize};
+//! use zvariant::{serialized::Context, to_bytes, timestamp, Type, LE};
+//!
+//! #[derive(Deserialize, Serialize, Type, PartialEq, Debug)]
+//! struct Unit {
+//!     name: String,
+//!     #[zvariant(signature = "t")]
+//!     #[serde(with = "timestamp::microseconds")]
+//!     active_enter_timestamp: SystemTime,
+//!     #[zvariant(signature = "x")]
+//!     #[serde(with = "timestamp::seconds")]
+//!     modified: SystemTime,
+//! }
+//! assert_eq!(Unit::SIGNATURE, "(stx)");
+//!
+//! let unit = Unit {
+//!     name: "dbus.service".into(),
+//!     active_enter_timestamp: UNIX_EPOCH + Duration::from_micros(1_700_000_000_123_456),
+//!     modified: UNIX_EPOCH + Duration::from_secs(1_600_000_000),
+//! };
+//! let ctxt = Context::new_dbus(LE, 0);
+//! let encoded = to_bytes(ctxt, &unit).unwrap();
+//! let (_, micros, secs): (&str, u64, i64) = encoded.deserialize().unwrap().0;
+//! assert_eq!(micros, 1_700_000_000_123_456);
+//! assert_eq!(secs, 1_600_000_000);
+//!
+//! let decoded: Unit = encoded.deserialize().unwrap().0;
+//! assert_eq!(decoded, unit);
+//! ```
+//!
+//! Precision beyond the unit of the encoding is truncated, towards the past.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A point in time that can be encoded as an offset from the Unix epoch.
+///
+/// This is implemented for [`SystemTime`], and for `chrono::DateTime<Utc>` and
+/// `time::OffsetDateTime` if the `chrono` and `time` features are enabled, respectively.
+pub trait Timestamp: Sized {
+    /// The number of microseconds since the Unix epoch, or `None` if it doesn't fit in an `i64`.
+    fn unix_micros(&self) -> Option<i64>;
+
+    /// Create a timestamp from the number of microseconds since the Unix epoch.
+    ///
+    /// Returns `None` if the timestamp is out of the range of the type.
+    fn from_unix_micros(micros: i64) -> Option<Self>;
+}
+
+impl Timestamp for SystemTime {
+    fn unix_micros(&self) -> Option<i64> {
+        match self.duration_since(UNIX_EPOCH) {
+            Ok(since) => i64::try_from(since.as_micros()).ok(),
+            Err(e) => {
+                let before = e.duration();
+                // Round away from the epoch, so we truncate towards the past.
+                let micros = before.as_micros() + u128::from(before.subsec_nanos() % 1_000 != 0);
+
+                i64::try_from(micros).ok().map(|micros| -micros)
+            }
+        }
+    }
+
+    fn from_unix_micros(micros: i64) -> Option<Self> {
+        let offset = Duration::from_micros(micros.unsigned_abs());
+        if micros >= 0 {
+            UNIX_EPOCH.checked_add(offset)
+        } else {
+            UNIX_EPOCH.checked_sub(offset)
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Timestamp for chrono::DateTime<chrono::Utc> {
+    fn unix_micros(&self) -> Option<i64> {
+        Some(self.timestamp_micros())
+    }
+
+    fn from_unix_micros(micros: i64) -> Option<Self> {
+        chrono::DateTime::from_timestamp_micros(micros)
+    }
+}
+
+#[cfg(feature = "time")]
+impl Timestamp for time::OffsetDateTime {
+    fn unix_micros(&self) -> Option<i64> {
+        i64::try_from(self.unix_timestamp_nanos().div_euclid(1_000)).ok()
+    }
+
+    fn from_unix_micros(micros: i64) -> Option<Self> {
+        time::OffsetDateTime::from_unix_timestamp_nanos(i128::from(micros) * 1_000).ok()
+    }
+}
+
+/// Encode a [`Timestamp`] as the number of seconds since the Unix epoch (signature `x`).
+///
+/// See the [module documentation](super) for an example.
+pub mod seconds {
+    use serde::{de, ser, Deserialize, Deserializer, Serializer};
+
+    use super::Timestamp;
+
+    /// Serialize `timestamp` as an `i64`.
+    pub fn serialize<T, S>(timestamp: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Timestamp,
+        S: Serializer,
+    {
+        let micros = timestamp
+            .unix_micros()
+            .ok_or_else(|| ser::Error::custom("timestamp out of range"))?;
+
+        serializer.serialize_i64(micros.div_euclid(1_000_000))
+    }
+
+    /// Deserialize a timestamp from an `i64`.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Timestamp,
+        D: Deserializer<'de>,
+    {
+        let secs = i64::deserialize(deserializer)?;
+
+        secs.checked_mul(1_000_000)
+            .and_then(T::from_unix_micros)
+            .ok_or_else(|| {
+                de::Error::invalid_value(de::Unexpected::Signed(secs), &"a timestamp in range")
+            })
+    }
+}
+
+/// Encode a [`Timestamp`] as the number of microseconds since the Unix epoch (signature `t`).
+///
+/// Timestamps before the Unix epoch can't be encoded. See the [module documentation](super) for an
+/// example.
+pub mod microseconds {
+    use serde::{de, ser, Deserialize, Deserializer, Serializer};
+
+    use super::Timestamp;
+
+    /// Serialize `timestamp` as a `u64`.
+    pub fn serialize<T, S>(timestamp: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Timestamp,
+        S: Serializer,
+    {
+        let micros = timestamp
+            .unix_micros()
+            .and_then(|micros| u64::try_from(micros).ok())
+            .ok_or_else(|| ser::Error::custom("timestamp out of range"))?;
+
+        serializer.serialize_u64(micros)
+    }
+
+    /// Deserialize a timestamp from a `u64`.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Timestamp,
+        D: Deserializer<'de>,
+    {
+        let micros = u64::deserialize(deserializer)?;
+
+        i64::try_from(micros)
+            .ok()
+            .and_then(T::from_unix_micros)
+            .ok_or_else(|| {
+                de::Error::invalid_value(de::Unexpected::Unsigned(micros), &"a timestamp in range")
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use serde::{Deserialize, Serialize};
+
+    use super::Timestamp;
+    use crate::{serialized::Context, to_bytes, Type, LE};
+
+    #[test]
+    fn system_time() {
+        let t = UNIX_EPOCH + Duration::new(1_600_000_000, 123_456_789);
+        assert_eq!(t.unix_micros(), Some(1_600_000_000_123_456));
+        assert_eq!(
+            SystemTime::from_unix_micros(1_600_000_000_123_456),
+            Some(UNIX_EPOCH + Duration::new(1_600_000_000, 123_456_000))
+        );
+
+        let t = UNIX_EPOCH - Duration::new(1, 500);
+        assert_eq!(t.unix_micros(), Some(-1_000_001));
+        assert_eq!(
+            SystemTime::from_unix_micros(-1_000_001),
+            Some(UNIX_EPOCH - Duration::from_micros(1_000_001))
+        );
+    }
+
+    #[test]
+    fn with_attributes() {
+        #[derive(Deserialize, Serialize, Type, PartialEq, Debug)]
+        struct Times {
+            #[zvariant(signature = "x")]
+            #[serde(with = "super::seconds")]
+            secs: SystemTime,
+            #[zvariant(signature = "t")]
+            #[serde(with = "super::microseconds")]
+            micros: SystemTime,
+        }
+        assert_eq!(Times::SIGNATURE, "(xt)");
+
+        let ctxt = Context::new_dbus(LE, 0);
+        let times = Times {
+            secs: UNIX_EPOCH - Duration::from_millis(1_500),
+            micros: UNIX_EPOCH + Duration::from_micros(42),
+        };
+        let encoded = to_bytes(ctxt, &times).unwrap();
+        assert_eq!(encoded.deserialize::<(i64, u64)>().unwrap().0, (-2, 42));
+        let decoded: Times = encoded.deserialize().unwrap().0;
+        assert_eq!(decoded.secs, UNIX_EPOCH - Duration::from_secs(2));
+        assert_eq!(decoded.micros, times.micros);
+
+        // Timestamps before the epoch can't be encoded in microseconds.
+        let times = Times {
+            secs: UNIX_EPOCH,
+            micros: UNIX_EPOCH - Duration::from_micros(1),
+        };
+        to_bytes(ctxt, &times).unwrap_err();
+    }
+}
@@ -0,0 +1,370 @@
+use serde::{
+    de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    ser::{self, SerializeMap, SerializeSeq, SerializeTuple},
+    Deserializer, Serialize, Serializer,
+};
+use std::{cell::RefCell, fmt};
+
+use crate::{
+    serialized::{Context, Data},
+    to_bytes_for_signature, DynamicType, Error, Result, Signature,
+};
+
+/// Transcode the data from `deserializer`, of any other serde format, to a new byte vector.
+///
+/// The data is directly fed into the serializer for the given signature, without building any
+/// intermediate representation (e.g a [`crate::Value`]). This is meant for gateways between D-Bus
+/// and other formats. To go the other way around, see [`Data::transcode_for_signature`].
+///
+/// Since other formats don't carry the D-Bus type information, the transcoding fails if
+/// `signature` contains variants or file descriptors. Moreover, numbers are encoded with the width
+/// the source format provides them in. Self-describing formats like JSON give out 64-bit integers
+/// and doubles so the signature must use `x`, `t` and `d` for numbers in that case.
+///
+/// # Examples
+///
+/// ```
+/// use zvariant::{serialized::Context, transcode_to_bytes, LE};
+///
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let mut json = serde_json::Deserializer::from_str(r#"["hello", {"answer": [4, 2]}]"#);
+/// let encoded = transcode_to_bytes(ctxt, "(sa{sat})", &mut json).unwrap();
+///
+/// let (s, map): (String, std::collections::HashMap<String, Vec<u64>>) =
+///     encoded.deserialize().unwrap().0;
+/// assert_eq!(s, "hello");
+/// assert_eq!(map["answer"], [4, 2]);
+/// ```
+pub fn transcode_to_bytes<'de, S, D>(
+    ctxt: Context,
+    signature: S,
+    deserializer: D,
+) -> Result<Data<'static, 'static>>
+where
+    S: TryInto<Signature>,
+    S::Error: Into<Error>,
+    D: Deserializer<'de>,
+{
+    let signature = signature.try_into().map_err(Into::into)?;
+    let transcoder = Transcoder::new(&signature, deserializer);
+
+    to_bytes_for_signature(ctxt, &signature, &transcoder)
+}
+
+/// A [`Serialize`] implementation feeding `deserializer` into the serializer it's given.
+///
+/// Unlike [`serde_transcode::Transcoder`], this keeps track of the signature, so that sequences
+/// are serialized as tuples where a structure is expected. Formats without tuples (e.g JSON)
+/// represent structures as sequences.
+struct Transcoder<'s, D> {
+    signature: &'s Signature,
+    deserializer: RefCell<Option<D>>,
+}
+
+impl<'s, D> Transcoder<'s, D> {
+    fn new(signature: &'s Signature, deserializer: D) -> Self {
+        Self {
+            signature,
+            deserializer: RefCell::new(Some(deserializer)),
+        }
+    }
+}
+
+impl<'de, D> Serialize for Transcoder<'_, D>
+where
+    D: Deserializer<'de>,
+{
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let deserializer = self
+            .deserializer
+            .borrow_mut()
+            .take()
+            .expect("Transcoder can only be serialized once");
+        let visitor = ContainerVisitor {
+            signature: self.signature,
+            serializer,
+        };
+
+        match self.signature {
+            Signature::Array(_) | Signature::Dict { .. } | Signature::Structure(_) => {
+                deserializer.deserialize_any(visitor)
+            }
+            #[cfg(feature = "gvariant")]
+            Signature::Maybe(_) => deserializer.deserialize_option(visitor),
+            _ => return serde_transcode::transcode(deserializer, visitor.serializer),
+        }
+        .map_err(ser::Error::custom)
+    }
+}
+
+/// Transcodes the containers, as per the signature.
+struct ContainerVisitor<'s, S> {
+    signature: &'s Signature,
+    serializer: S,
+}
+
+impl<'de, S> Visitor<'de> for ContainerVisitor<'_, S>
+where
+    S: Serializer,
+{
+    type Value = S::Ok;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a value of signature `{}`", self.signature)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        match self.signature {
+            Signature::Structure(fields) => {
+                let mut tuple = self
+                    .serializer
+                    .serialize_tuple(fields.len())
+                    .map_err(de::Error::custom)?;
+                let expected = || format!("a structure of {} fields", fields.len());
+                for (i, signature) in fields.iter().enumerate() {
+                    let seed = TupleElementSeed {
+                        signature,
+                        serializer: &mut tuple,
+                    };
+                    if seq.next_element_seed(seed)?.is_none() {
+                        return Err(de::Error::invalid_length(i, &expected().as_str()));
+                    }
+                }
+                if seq.next_element::<IgnoredAny>()?.is_some() {
+                    return Err(de::Error::invalid_length(
+                        fields.len() + 1,
+                        &expected().as_str(),
+                    ));
+                }
+
+                SerializeTuple::end(tuple).map_err(de::Error::custom)
+            }
+            Signature::Array(child) => {
+                let mut array = self
+                    .serializer
+                    .serialize_seq(seq.size_hint())
+                    .map_err(de::Error::custom)?;
+                while seq
+                    .next_element_seed(SeqElementSeed {
+                        signature: child.signature(),
+                        serializer: &mut array,
+                    })?
+                    .is_some()
+                {}
+
+                SerializeSeq::end(array).map_err(de::Error::custom)
+            }
+            _ => Err(de::Error::invalid_type(de::Unexpected::Seq, &self)),
+        }
+    }
+
+    fn visit_map<A>(self, mut map: A) -> std::result::Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let Signature::Dict { key, value } = self.signature else {
+            return Err(de::Error::invalid_type(de::Unexpected::Map, &self));
+        };
+        let mut dict = self
+            .serializer
+            .serialize_map(map.size_hint())
+            .map_err(de::Error::custom)?;
+        while map
+            .next_key_seed(KeySeed {
+                signature: key.signature(),
+                serializer: &mut dict,
+            })?
+            .is_some()
+        {
+            map.next_value_seed(ValueSeed {
+                signature: value.signature(),
+                serializer: &mut dict,
+            })?;
+        }
+
+        dict.end().map_err(de::Error::custom)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> std::result::Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.serializer.serialize_bytes(v).map_err(E::custom)
+    }
+
+    fn visit_none<E>(self) -> std::result::Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.serializer.serialize_none().map_err(E::custom)
+    }
+
+    fn visit_unit<E>(self) -> std::result::Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_none()
+    }
+
+    #[cfg(feature = "gvariant")]
+    fn visit_some<D>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let Signature::Maybe(child) = self.signature else {
+            return Err(de::Error::invalid_type(de::Unexpected::Option, &self));
+        };
+        let transcoder = Transcoder::new(child.signature(), deserializer);
+
+        self.serializer
+            .serialize_some(&transcoder)
+            .map_err(de::Error::custom)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Transcoder::new(self.signature, deserializer)
+            .serialize(self.serializer)
+            .map_err(de::Error::custom)
+    }
+}
+
+macro_rules! transcode_seed {
+    ($name:ident, $trait:ident, $method:ident) => {
+        /// Transcodes the next deserialized value through
+        #[doc = concat!("[`", stringify!($trait), "::", stringify!($method), "`].")]
+        struct $name<'s, 'a, S> {
+            signature: &'s Signature,
+            serializer: &'a mut S,
+        }
+
+        impl<'de, S> DeserializeSeed<'de> for $name<'_, '_, S>
+        where
+            S: $trait,
+        {
+            type Value = ();
+
+            fn deserialize<D>(self, deserializer: D) -> std::result::Result<(), D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let transcoder = Transcoder::new(self.signature, deserializer);
+
+                self.serializer
+                    .$method(&transcoder)
+                    .map_err(de::Error::custom)
+            }
+        }
+    };
+}
+
+transcode_seed!(TupleElementSeed, SerializeTuple, serialize_element);
+transcode_seed!(SeqElementSeed, SerializeSeq, serialize_element);
+transcode_seed!(KeySeed, SerializeMap, serialize_key);
+transcode_seed!(ValueSeed, SerializeMap, serialize_value);
+
+/// A [`DeserializeSeed`] feeding the deserializer it's given into a serializer.
+pub(crate) struct TranscodeSeed<S> {
+    pub(crate) signature: Signature,
+    pub(crate) serializer: S,
+}
+
+impl<S> DynamicType for TranscodeSeed<S> {
+    fn signature(&self) -> Signature {
+        self.signature.clone()
+    }
+}
+
+impl<'de, S> DeserializeSeed<'de> for TranscodeSeed<S>
+where
+    S: Serializer,
+{
+    type Value = S::Ok;
+
+    fn deserialize<D>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        serde_transcode::transcode(deserializer, self.serializer).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{
+        serialized::{Context, Format},
+        to_bytes, to_bytes_for_signature, transcode_to_bytes, LE,
+    };
+
+    const SIGNATURE: &str = "(sa{sat}a(sxb))";
+    const JSON: &str = r#"["hello",{"answer":[4,2]},[["a",-1,true],["b",-2,false]]]"#;
+
+    fn transcode(format: Format) {
+        let ctxt = Context::new(format, LE, 0);
+        let value = (
+            "hello",
+            HashMap::from([("answer", vec![4u64, 2])]),
+            vec![("a", -1i64, true), ("b", -2, false)],
+        );
+        let expected = to_bytes(ctxt, &value).unwrap();
+
+        // From JSON, the structures come in as sequences.
+        let mut json = serde_json::Deserializer::from_str(JSON);
+        let encoded = transcode_to_bytes(ctxt, SIGNATURE, &mut json).unwrap();
+        assert_eq!(encoded.bytes(), expected.bytes());
+
+        // And the other way around.
+        let mut json = vec![];
+        let mut serializer = serde_json::Serializer::new(&mut json);
+        let (_, parsed) = expected
+            .transcode_for_signature(SIGNATURE, &mut serializer)
+            .unwrap();
+        assert_eq!(parsed, expected.len());
+        assert_eq!(std::str::from_utf8(&json).unwrap(), JSON);
+    }
+
+    #[test]
+    fn structure_length_mismatch() {
+        let ctxt = Context::new_dbus(LE, 0);
+        for json in [r#"["hello"]"#, r#"["hello",1,2]"#] {
+            let mut json = serde_json::Deserializer::from_str(json);
+            transcode_to_bytes(ctxt, "(sx)", &mut json).unwrap_err();
+        }
+
+        // Only the transcoder turns sequences into structures.
+        to_bytes_for_signature(ctxt, "(ss)", &vec!["a", "b"]).unwrap_err();
+    }
+
+    #[test]
+    fn dbus() {
+        transcode(Format::DBus);
+    }
+
+    #[cfg(feature = "gvariant")]
+    #[test]
+    fn gvariant() {
+        transcode(Format::GVariant);
+    }
+
+    #[cfg(all(feature = "gvariant", not(feature = "option-as-array")))]
+    #[test]
+    fn gvariant_maybe() {
+        let ctxt = Context::new_gvariant(LE, 0);
+        let value = (None::<(&str, i64)>, Some(("a", -1i64)));
+        let expected = to_bytes(ctxt, &value).unwrap();
+
+        let mut json = serde_json::Deserializer::from_str(r#"[null,["a",-1]]"#);
+        let encoded = transcode_to_bytes(ctxt, "(m(sx)m(sx))", &mut json).unwrap();
+        assert_eq!(encoded.bytes(), expected.bytes());
+    }
+}
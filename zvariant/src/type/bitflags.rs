@@ -0,0 +1,157 @@
+/// Implement [`Type`], [`Serialize`] and [`Deserialize`] for a [`bitflags`] type.
+///
+/// The flags are encoded as their underlying integer type, e.g. a `u32` (signature `u`), which is
+/// how flags are represented in most D-Bus APIs.
+///
+/// By default, deserialization fails if any bits are set that don't correspond to a defined flag.
+/// Since services often add new flags over time, `unknown_bits = retain` can be passed to keep
+/// unknown bits instead, and `unknown_bits = truncate` to silently drop them.
+///
+/// The type must not implement [`Serialize`] and [`Deserialize`] already, so the `serde` feature
+/// of `bitflags` must not be used for it.
+///
+/// This macro is only available if the `bitflags` feature is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use zvariant::{bitflags_type, serialized::Context, to_bytes, Type, LE};
+///
+/// bitflags::bitflags! {
+///     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///     pub struct Capabilities: u32 {
+///         const CLIPBOARD = 1 << 0;
+///         const DRAG_AND_DROP = 1 << 1;
+///     }
+/// }
+/// bitflags_type!(Capabilities);
+///
+/// bitflags::bitflags! {
+///     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///     pub struct Hints: u8 {
+///         const URGENT = 1 << 0;
+///     }
+/// }
+/// bitflags_type!(Hints, unknown_bits = retain);
+///
+/// assert_eq!(Capabilities::SIGNATURE, "u");
+/// assert_eq!(Hints::SIGNATURE, "y");
+///
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let encoded = to_bytes(ctxt, &Capabilities::all()).unwrap();
+/// assert_eq!(encoded.deserialize::<u32>().unwrap().0, 0b11);
+///
+/// // Unknown bits are rejected by default.
+/// let encoded = to_bytes(ctxt, &0b101u32).unwrap();
+/// assert!(encoded.deserialize::<Capabilities>().is_err());
+///
+/// // ..unless asked to retain them.
+/// let encoded = to_bytes(ctxt, &0b101u8).unwrap();
+/// assert_eq!(encoded.deserialize::<Hints>().unwrap().0.bits(), 0b101);
+/// ```
+///
+/// [`Type`]: trait.Type.html
+/// [`Serialize`]: https://docs.serde.rs/serde/trait.Serialize.html
+/// [`Deserialize`]: https://docs.serde.rs/serde/de/trait.Deserialize.html
+/// [`bitflags`]: https://docs.rs/bitflags/2
+#[macro_export]
+macro_rules! bitflags_type {
+    ($ty:ty) => {
+        $crate::bitflags_type!(@impl $ty, |bits| {
+            <$ty as $crate::export::bitflags::Flags>::from_bits(bits).ok_or_else(|| {
+                let unknown = bits & !<$ty as $crate::export::bitflags::Flags>::all().bits();
+
+                $crate::export::serde::de::Error::custom(::std::format_args!(
+                    "unknown bits set for `{}`: {:#x}",
+                    ::std::stringify!($ty),
+                    unknown,
+                ))
+            })
+        });
+    };
+    ($ty:ty, unknown_bits = retain) => {
+        $crate::bitflags_type!(@impl $ty, |bits| {
+            ::std::result::Result::Ok(
+                <$ty as $crate::export::bitflags::Flags>::from_bits_retain(bits),
+            )
+        });
+    };
+    ($ty:ty, unknown_bits = truncate) => {
+        $crate::bitflags_type!(@impl $ty, |bits| {
+            ::std::result::Result::Ok(
+                <$ty as $crate::export::bitflags::Flags>::from_bits_truncate(bits),
+            )
+        });
+    };
+    (@impl $ty:ty, |$bits:ident| $from_bits:block) => {
+        impl $crate::Type for $ty {
+            const SIGNATURE: &'static $crate::Signature =
+                <<$ty as $crate::export::bitflags::Flags>::Bits as $crate::Type>::SIGNATURE;
+        }
+
+        impl $crate::export::serde::Serialize for $ty {
+            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::export::serde::Serializer,
+            {
+                $crate::export::serde::Serialize::serialize(
+                    &<$ty as $crate::export::bitflags::Flags>::bits(self),
+                    serializer,
+                )
+            }
+        }
+
+        impl<'de> $crate::export::serde::Deserialize<'de> for $ty {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+            where
+                D: $crate::export::serde::Deserializer<'de>,
+            {
+                let $bits = <<$ty as $crate::export::bitflags::Flags>::Bits as
+                    $crate::export::serde::Deserialize>::deserialize(deserializer)?;
+                let flags: ::std::result::Result<Self, D::Error> = $from_bits;
+
+                flags
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{serialized::Context, to_bytes, Type, LE};
+
+    bitflags::bitflags! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        struct Strict: u16 {
+            const A = 0b001;
+            const B = 0b010;
+        }
+    }
+    bitflags_type!(Strict);
+
+    bitflags::bitflags! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        struct Truncated: u64 {
+            const A = 0b001;
+        }
+    }
+    bitflags_type!(Truncated, unknown_bits = truncate);
+
+    #[test]
+    fn bitflags() {
+        assert_eq!(Strict::SIGNATURE, "q");
+        assert_eq!(Truncated::SIGNATURE, "t");
+
+        let ctxt = Context::new_dbus(LE, 0);
+        let encoded = to_bytes(ctxt, &(Strict::A | Strict::B)).unwrap();
+        assert_eq!(encoded.bytes(), [0b011, 0]);
+        assert_eq!(encoded.deserialize::<Strict>().unwrap().0, Strict::all());
+
+        let encoded = to_bytes(ctxt, &0b111u16).unwrap();
+        let err = encoded.deserialize::<Strict>().unwrap_err();
+        assert_eq!(err.to_string(), "unknown bits set for `Strict`: 0x4");
+
+        let encoded = to_bytes(ctxt, &0b111u64).unwrap();
+        assert_eq!(encoded.deserialize::<Truncated>().unwrap().0, Truncated::A);
+    }
+}
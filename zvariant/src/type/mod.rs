@@ -1,5 +1,7 @@
 mod dynamic;
 pub use dynamic::{DynamicDeserialize, DynamicType};
+#[cfg(feature = "bitflags")]
+mod bitflags;
 #[cfg(feature = "serde_bytes")]
 mod bytes;
 #[cfg(feature = "enumflags2")]
@@ -0,0 +1,263 @@
+use serde::{
+    de::{DeserializeSeed, Deserializer, Error as _},
+    ser::{Serialize, Serializer},
+    Deserialize,
+};
+use static_assertions::assert_impl_all;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    hash::{BuildHasher, Hash},
+    marker::PhantomData,
+};
+
+use crate::{value::ValueSeed, Basic, Dict, Error, Result, Signature, Type, Value};
+
+/// A [`Dict`] with statically known key and value types.
+///
+/// This is a thin view over a `Dict` of signature `a{KV}`, where `K` and `V` are the key and value
+/// types. The signature is checked once, on creation, so entries can be inserted, looked up and
+/// iterated over with typed keys and values, instead of having to convert from and to [`Value`] at
+/// every access. A dictionary with variant values (`a{sv}`) is a
+/// `TypedDict<'_, &str, Value<'_>>`.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use zvariant::{serialized::Context, to_bytes, TypedDict, Value, LE};
+///
+/// let mut dict = TypedDict::<'_, &str, u32>::new();
+/// dict.insert("one", 1).unwrap();
+/// dict.insert("two", 2).unwrap();
+/// assert_eq!(dict.get(&"two").unwrap(), Some(2));
+/// assert_eq!(dict.get(&"three").unwrap(), None);
+///
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let encoded = to_bytes(ctxt, &dict).unwrap();
+/// let decoded: TypedDict<'_, &str, u32> = encoded.deserialize().unwrap().0;
+/// assert_eq!(decoded, dict);
+///
+/// let map: HashMap<String, u32> = TypedDict::<'_, &str, u32>::try_from(
+///     Value::new(HashMap::from([("three", 3u32)])),
+/// )
+/// .unwrap()
+/// .try_into()
+/// .unwrap();
+/// assert_eq!(map["three"], 3);
+/// ```
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TypedDict<'a, K, V> {
+    dict: Dict<'a, 'a>,
+    phantom: PhantomData<fn() -> (K, V)>,
+}
+
+assert_impl_all!(TypedDict<'_, &str, Value<'_>>: Send, Sync, Unpin);
+
+impl<'a, K, V> TypedDict<'a, K, V>
+where
+    K: Type + Basic,
+    V: Type,
+{
+    /// Create a new empty `TypedDict`.
+    pub fn new() -> Self {
+        Self {
+            dict: Dict::new(K::SIGNATURE, V::SIGNATURE),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Insert an entry, replacing the value of an existing entry with the same key.
+    ///
+    /// # Errors
+    ///
+    /// If the signature of the key or the value, as [`Value`], doesn't match the signature of the
+    /// dictionary. This can only happen if `K` or `V` have a [`Type`] implementation that's
+    /// inconsistent with their conversion to `Value`.
+    pub fn insert(&mut self, key: K, value: V) -> Result<()>
+    where
+        K: Into<Value<'a>> + Ord,
+        V: Into<Value<'a>> + Type,
+    {
+        self.dict.add(key, value)
+    }
+
+    /// Get the value for the given key.
+    pub fn get<'d>(&'d self, key: &K) -> Result<Option<V>>
+    where
+        K: Into<Value<'d>> + Clone,
+        V: TryFrom<&'d Value<'d>>,
+        <V as TryFrom<&'d Value<'d>>>::Error: Into<Error>,
+    {
+        let key = key.clone().into();
+
+        self.dict
+            .get_value(&key)
+            .map(|v| v.downcast_ref())
+            .transpose()
+    }
+
+    /// Iterate over the entries, converting the keys to `K` and the values to `V`.
+    ///
+    /// The conversions don't copy any data if `K` and `V` are borrowed types, e.g `&str`.
+    pub fn iter<'d>(&'d self) -> impl Iterator<Item = Result<(K, V)>> + 'd
+    where
+        K: TryFrom<&'d Value<'d>> + 'd,
+        <K as TryFrom<&'d Value<'d>>>::Error: Into<Error>,
+        V: TryFrom<&'d Value<'d>> + 'd,
+        <V as TryFrom<&'d Value<'d>>>::Error: Into<Error>,
+    {
+        self.dict.iter_as()
+    }
+
+    /// Get the number of entries.
+    pub fn len(&self) -> usize {
+        self.dict.len()
+    }
+
+    /// Whether there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.dict.is_empty()
+    }
+
+    /// Get a reference to the underlying [`Dict`].
+    pub fn as_dict(&self) -> &Dict<'a, 'a> {
+        &self.dict
+    }
+
+    /// Convert into the underlying [`Dict`].
+    pub fn into_dict(self) -> Dict<'a, 'a> {
+        self.dict
+    }
+}
+
+impl<K, V> Default for TypedDict<'_, K, V>
+where
+    K: Type + Basic,
+    V: Type,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> Display for TypedDict<'_, K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.dict, f)
+    }
+}
+
+impl<'a, K, V> TryFrom<Dict<'a, 'a>> for TypedDict<'a, K, V>
+where
+    K: Type + Basic,
+    V: Type,
+{
+    type Error = Error;
+
+    fn try_from(dict: Dict<'a, 'a>) -> Result<Self> {
+        if dict.signature() != Self::SIGNATURE {
+            return Err(Error::SignatureMismatch(
+                dict.signature().clone(),
+                format!("`{}`", Self::SIGNATURE),
+            ));
+        }
+
+        Ok(Self {
+            dict,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<'a, K, V> TryFrom<Value<'a>> for TypedDict<'a, K, V>
+where
+    K: Type + Basic,
+    V: Type,
+{
+    type Error = Error;
+
+    fn try_from(value: Value<'a>) -> Result<Self> {
+        Dict::try_from(value)?.try_into()
+    }
+}
+
+impl<'a, K, V> From<TypedDict<'a, K, V>> for Dict<'a, 'a> {
+    fn from(dict: TypedDict<'a, K, V>) -> Self {
+        dict.dict
+    }
+}
+
+impl<'a, K, V> From<TypedDict<'a, K, V>> for Value<'a> {
+    fn from(dict: TypedDict<'a, K, V>) -> Self {
+        Value::Dict(dict.dict)
+    }
+}
+
+impl<K, V> Type for TypedDict<'_, K, V>
+where
+    K: Type + Basic,
+    V: Type,
+{
+    const SIGNATURE: &'static Signature = &Signature::static_dict(K::SIGNATURE, V::SIGNATURE);
+}
+
+impl<K, V> Serialize for TypedDict<'_, K, V> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.dict.serialize(serializer)
+    }
+}
+
+impl<'de, K, V> Deserialize<'de> for TypedDict<'de, K, V>
+where
+    K: Type + Basic,
+    V: Type,
+{
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        ValueSeed::<Value<'_>>::new(Self::SIGNATURE)
+            .deserialize(deserializer)
+            .and_then(|value| Self::try_from(value).map_err(D::Error::custom))
+    }
+}
+
+// Conversion of TypedDict to and from Map types
+macro_rules! map_conversions {
+    ($ty:ident <K $(: $kbound1:ident $(+ $kbound2:ident)*)*, V $(, $typaram:ident)*>) => {
+        impl<'a, K, V $(, $typaram)*> From<$ty<K, V $(, $typaram)*>> for TypedDict<'a, K, V>
+        where
+            K: Type + Basic + Into<Value<'a>>,
+            V: Type + Into<Value<'a>>,
+            $($typaram: BuildHasher,)*
+        {
+            fn from(map: $ty<K, V $(, $typaram)*>) -> Self {
+                Self {
+                    dict: Dict::from(map),
+                    phantom: PhantomData,
+                }
+            }
+        }
+
+        impl<'a, K, V, MK, MV $(, $typaram)*> TryFrom<TypedDict<'a, K, V>>
+            for $ty<MK, MV $(, $typaram)*>
+        where
+            MK: Basic + TryFrom<Value<'a>> $(+ $kbound1 $(+ $kbound2)*)*,
+            MV: TryFrom<Value<'a>>,
+            MK::Error: Into<Error>,
+            MV::Error: Into<Error>,
+            $($typaram: BuildHasher + Default,)*
+        {
+            type Error = Error;
+
+            fn try_from(dict: TypedDict<'a, K, V>) -> Result<Self> {
+                dict.dict.try_into()
+            }
+        }
+    };
+}
+map_conversions!(HashMap<K: Eq + Hash, V, H>);
+map_conversions!(BTreeMap<K: Ord, V>);
@@ -50,6 +50,21 @@ pub(crate) fn padding_for_n_bytes(value: usize, align: usize) -> usize {
     len_rounded_up.wrapping_sub(value)
 }
 
+/// Whether `bytes` contain a nul byte.
+pub(crate) fn contains_nul(bytes: &[u8]) -> bool {
+    memchr::memchr(0, bytes).is_some()
+}
+
+/// Convert `bytes` to a string slice, validating them as UTF-8 with SIMD instructions when
+/// available.
+pub(crate) fn str_from_utf8(bytes: &[u8]) -> Result<&str> {
+    match simdutf8::basic::from_utf8(bytes) {
+        Ok(s) => Ok(s),
+        // The fast path doesn't tell where the invalid sequence is, so let std figure that out.
+        Err(_) => std::str::from_utf8(bytes).map_err(Error::Utf8),
+    }
+}
+
 pub(crate) fn usize_to_u32(value: usize) -> u32 {
     assert!(
         value <= (u32::MAX as usize),
@@ -0,0 +1,328 @@
+use serde::de::{
+    Deserialize, DeserializeSeed, Deserializer, Error, MapAccess, SeqAccess, Unexpected, Visitor,
+};
+
+use crate::{
+    serialized::{Context, Data},
+    DynamicType, ObjectPath, Result, Signature,
+};
+
+/// Validate serialized data against a signature, without deserializing it.
+///
+/// This checks the alignment and padding, the lengths, the UTF-8 validity of strings and the
+/// validity of object paths and signatures (including those of variants) of the given data, without
+/// constructing any Rust values from it. This is useful for brokers and monitors that forward data
+/// they don't need to interpret.
+///
+/// Since no file descriptors are passed along with the bytes, file descriptor indices are **not**
+/// checked. Use [`Data::validate`] if you need them to be checked as well.
+///
+/// # Examples
+///
+/// ```
+/// use zvariant::{serialized::Context, to_bytes, validate, LE};
+///
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let encoded = to_bytes(ctxt, &("hello", 42u32)).unwrap();
+/// assert_eq!(validate(ctxt, "(su)", &encoded).unwrap(), encoded.len());
+///
+/// // Not enough data for the `t` in the signature.
+/// assert!(validate(ctxt, "(sut)", &encoded).is_err());
+/// ```
+///
+/// # Return value
+///
+/// The number of bytes validated from `bytes`. Note that trailing bytes after the last value are
+/// not considered an error.
+pub fn validate<S>(ctxt: Context, signature: S, bytes: &[u8]) -> Result<usize>
+where
+    S: TryInto<Signature>,
+    S::Error: Into<crate::Error>,
+{
+    let signature = signature.try_into().map_err(Into::into)?;
+    let seed = ValidationSeed {
+        signature: &signature,
+        num_fds: None,
+    };
+
+    Data::new(bytes, ctxt)
+        .deserialize_with_seed(seed)
+        .map(|(_, len)| len)
+}
+
+/// A seed that walks over serialized data using the given signature, without producing any value.
+pub(crate) struct ValidationSeed<'sig> {
+    pub(crate) signature: &'sig Signature,
+    /// The number of file descriptors, if file descriptor indices are to be checked.
+    pub(crate) num_fds: Option<usize>,
+}
+
+impl ValidationSeed<'_> {
+    fn child<'c>(&self, signature: &'c Signature) -> ValidationSeed<'c> {
+        ValidationSeed {
+            signature,
+            num_fds: self.num_fds,
+        }
+    }
+
+    fn unexpected_signature<E>(&self, expected: &str) -> E
+    where
+        E: Error,
+    {
+        E::invalid_type(Unexpected::Str(&self.signature.to_string()), &expected)
+    }
+
+    fn visit_variant_as_seq<'de, V>(self, mut visitor: V) -> std::result::Result<(), V::Error>
+    where
+        V: SeqAccess<'de>,
+    {
+        let signature = visitor.next_element::<Signature>()?.ok_or_else(|| {
+            Error::invalid_value(Unexpected::Other("nothing"), &"a Value signature")
+        })?;
+
+        visitor
+            .next_element_seed(self.child(&signature))?
+            .ok_or_else(|| Error::invalid_value(Unexpected::Other("nothing"), &"a Value value"))
+    }
+
+    fn visit_variant_as_map<'de, V>(self, mut visitor: V) -> std::result::Result<(), V::Error>
+    where
+        V: MapAccess<'de>,
+    {
+        let (_, signature) = visitor.next_entry::<&str, Signature>()?.ok_or_else(|| {
+            Error::invalid_value(Unexpected::Other("nothing"), &"a Value signature")
+        })?;
+        let _ = visitor.next_key::<&str>()?;
+
+        visitor.next_value_seed(self.child(&signature))
+    }
+}
+
+impl DynamicType for ValidationSeed<'_> {
+    fn signature(&self) -> Signature {
+        self.signature.clone()
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for ValidationSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> std::result::Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match self.signature {
+            // File descriptors are only checked by index, so we don't need them to be around.
+            #[cfg(unix)]
+            Signature::Fd => {
+                let idx = u32::deserialize(deserializer)?;
+                match self.num_fds {
+                    Some(num_fds) if idx as usize >= num_fds => Err(D::Error::invalid_value(
+                        Unexpected::Unsigned(idx.into()),
+                        &format!("a file descriptor index < {num_fds}").as_str(),
+                    )),
+                    _ => Ok(()),
+                }
+            }
+            _ => deserializer.deserialize_any(self),
+        }
+    }
+}
+
+macro_rules! validation_seed_basic_method {
+    ($name:ident, $type:ty) => {
+        #[inline]
+        fn $name<E>(self, _value: $type) -> std::result::Result<(), E>
+        where
+            E: Error,
+        {
+            Ok(())
+        }
+    };
+}
+
+impl<'de> Visitor<'de> for ValidationSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "a valid value of signature `{}`", self.signature)
+    }
+
+    validation_seed_basic_method!(visit_bool, bool);
+    validation_seed_basic_method!(visit_i16, i16);
+    validation_seed_basic_method!(visit_i32, i32);
+    validation_seed_basic_method!(visit_i64, i64);
+    validation_seed_basic_method!(visit_u8, u8);
+    validation_seed_basic_method!(visit_u16, u16);
+    validation_seed_basic_method!(visit_u32, u32);
+    validation_seed_basic_method!(visit_u64, u64);
+    validation_seed_basic_method!(visit_f64, f64);
+
+    fn visit_unit<E>(self) -> std::result::Result<(), E>
+    where
+        E: Error,
+    {
+        Ok(())
+    }
+
+    fn visit_str<E>(self, value: &str) -> std::result::Result<(), E>
+    where
+        E: Error,
+    {
+        match self.signature {
+            Signature::Str => Ok(()),
+            Signature::Signature => crate::signature::validate(value.as_bytes()).map_err(E::custom),
+            Signature::ObjectPath => ObjectPath::try_from(value).map(drop).map_err(E::custom),
+            _ => Err(self.unexpected_signature("`s`, `g` or `o`")),
+        }
+    }
+
+    fn visit_seq<V>(self, mut visitor: V) -> std::result::Result<(), V::Error>
+    where
+        V: SeqAccess<'de>,
+    {
+        match self.signature {
+            Signature::Array(child) => {
+                while visitor.next_element_seed(self.child(child))?.is_some() {}
+
+                Ok(())
+            }
+            Signature::Structure(fields) => {
+                for field in fields.iter() {
+                    visitor
+                        .next_element_seed(self.child(field))?
+                        .ok_or_else(|| {
+                            Error::invalid_length(fields.len(), &"all fields of the structure")
+                        })?;
+                }
+
+                Ok(())
+            }
+            Signature::Variant => self.visit_variant_as_seq(visitor),
+            _ => Err(self.unexpected_signature("an array, structure or variant signature")),
+        }
+    }
+
+    fn visit_map<V>(self, mut visitor: V) -> std::result::Result<(), V::Error>
+    where
+        V: MapAccess<'de>,
+    {
+        match self.signature {
+            Signature::Dict { key, value } => {
+                while visitor
+                    .next_entry_seed(self.child(key), self.child(value))?
+                    .is_some()
+                {}
+
+                Ok(())
+            }
+            Signature::Variant => self.visit_variant_as_map(visitor),
+            _ => Err(self.unexpected_signature("a dict or variant signature")),
+        }
+    }
+
+    #[cfg(feature = "gvariant")]
+    fn visit_some<D>(self, deserializer: D) -> std::result::Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match self.signature {
+            Signature::Maybe(child) => self.child(child).deserialize(deserializer),
+            _ => Err(self.unexpected_signature("a maybe signature")),
+        }
+    }
+
+    #[cfg(feature = "gvariant")]
+    fn visit_none<E>(self) -> std::result::Result<(), E>
+    where
+        E: Error,
+    {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{serialized::Context, to_bytes, validate, ObjectPath, Value, LE};
+
+    #[test]
+    fn valid() {
+        let ctxt = Context::new_dbus(LE, 0);
+        let mut dict = HashMap::new();
+        dict.insert("path", Value::from(ObjectPath::try_from("/a/b").unwrap()));
+        dict.insert(
+            "nested",
+            Value::Value(Box::new(Value::from((42u8, "hi", vec![1.0f64])))),
+        );
+        let value = (dict, vec!["hello", "world"], true);
+        let encoded = to_bytes(ctxt, &value).unwrap();
+
+        assert_eq!(
+            validate(ctxt, "(a{sv}asb)", &encoded).unwrap(),
+            encoded.len()
+        );
+        assert_eq!(encoded.validate("(a{sv}asb)").unwrap(), encoded.len());
+
+        #[cfg(feature = "gvariant")]
+        {
+            let ctxt = Context::new_gvariant(LE, 0);
+            let encoded = to_bytes(ctxt, &value).unwrap();
+            assert_eq!(encoded.validate("(a{sv}asb)").unwrap(), encoded.len());
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        let ctxt = Context::new_dbus(LE, 0);
+
+        // Invalid UTF-8.
+        let encoded = to_bytes(ctxt, &b"h\xffllo"[..]).unwrap();
+        assert!(validate(ctxt, "ay", &encoded).is_ok());
+        let mut bytes = encoded.to_vec();
+        bytes.push(0);
+        assert!(validate(ctxt, "s", &bytes).is_err());
+
+        // Invalid signature in a variant.
+        let encoded = to_bytes(ctxt, &Value::from(42u32)).unwrap();
+        let mut bytes = encoded.to_vec();
+        assert_eq!(bytes[1], b'u');
+        bytes[1] = b'z';
+        assert!(validate(ctxt, "v", &bytes).is_err());
+
+        // Invalid boolean value.
+        let encoded = to_bytes(ctxt, &2u32).unwrap();
+        assert!(validate(ctxt, "b", &encoded).is_err());
+
+        // Non-zero padding.
+        let encoded = to_bytes(ctxt, &(1u8, 1u32)).unwrap();
+        let mut bytes = encoded.to_vec();
+        bytes[1] = 1;
+        assert!(validate(ctxt, "(yu)", &bytes).is_err());
+
+        // Truncated data.
+        let encoded = to_bytes(ctxt, &vec![1u64, 2, 3]).unwrap();
+        assert!(validate(ctxt, "at", &encoded[..encoded.len() - 1]).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn fds() {
+        use crate::Fd;
+        use std::os::fd::AsFd;
+
+        let ctxt = Context::new_dbus(LE, 0);
+        let stdout = std::io::stdout();
+        let encoded = to_bytes(ctxt, &Fd::from(stdout.as_fd())).unwrap();
+        assert_eq!(encoded.validate("h").unwrap(), 4);
+
+        // Without the fds, the indices can't be checked.
+        assert_eq!(validate(ctxt, "h", &encoded).unwrap(), 4);
+        let mut bytes = encoded.to_vec();
+        bytes[0] = 1;
+        assert_eq!(validate(ctxt, "h", &bytes).unwrap(), 4);
+        let data = crate::serialized::Data::new(bytes, ctxt);
+        assert!(data.validate("h").is_err());
+    }
+}
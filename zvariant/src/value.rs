@@ -75,8 +75,46 @@ use crate::Fd;
 /// );
 /// ```
 ///
+/// # Ordering and hashing
+///
+/// `Value` implements [`Eq`], [`Ord`] and [`Hash`], so it can be used as a key in maps and as an
+/// element in sorted sets. Values of different types are ordered by their type, following the
+/// order of the variants of this enum (`U8` < `Bool` < `I16` < ... < `Structure` < `Maybe` <
+/// `Fd`), regardless of the contained values. Values of the same type are ordered by their
+/// contents, with a few specifics:
+///
+/// * `F64` values follow the IEEE 754 total order (see [`f64::total_cmp`]), except that `+0.0` and
+///   `-0.0` are considered equal. In particular, a NaN is equal to itself.
+/// * Containers compare their signatures first, and then their elements, lexicographically.
+/// * File descriptors are compared by their raw numeric value.
+///
+/// ```
+/// use std::collections::BTreeSet;
+/// use zvariant::Value;
+///
+/// let set: BTreeSet<_> = [
+///     Value::from("hello"),
+///     Value::from(f64::NAN),
+///     Value::from(42u32),
+///     Value::from(f64::NAN),
+///     Value::from(true),
+/// ]
+/// .into_iter()
+/// .collect();
+///
+/// assert_eq!(
+///     set.into_iter().collect::<Vec<_>>(),
+///     [
+///         Value::from(true),
+///         Value::from(42u32),
+///         Value::from(f64::NAN),
+///         Value::from("hello"),
+///     ],
+/// );
+/// ```
+///
 /// [D-Bus specification]: https://dbus.freedesktop.org/doc/dbus-specification.html#container-types
-#[derive(Debug, PartialEq, PartialOrd)]
+#[derive(Debug)]
 pub enum Value<'a> {
     // Simple types
     U8(u8),
@@ -135,19 +173,48 @@ impl Hash for Value<'_> {
     }
 }
 
+impl PartialEq for Value<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
 impl Eq for Value<'_> {}
 
+impl PartialOrd for Value<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Ord for Value<'_> {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other)
-            .unwrap_or_else(|| match (self, other) {
-                (Self::F64(lhs), Self::F64(rhs)) => lhs.total_cmp(rhs),
-                // `partial_cmp` returns `Some(_)` if either the discriminants are different
-                // or if both the left hand side and right hand side is `Self::F64(_)`. We can only
-                // reach this arm, if only one of the sides is `Self::F64(_)`. So we can just
-                // pretend the ordering is equal.
-                _ => Ordering::Equal,
-            })
+        match (self, other) {
+            (Self::U8(lhs), Self::U8(rhs)) => lhs.cmp(rhs),
+            (Self::Bool(lhs), Self::Bool(rhs)) => lhs.cmp(rhs),
+            (Self::I16(lhs), Self::I16(rhs)) => lhs.cmp(rhs),
+            (Self::U16(lhs), Self::U16(rhs)) => lhs.cmp(rhs),
+            (Self::I32(lhs), Self::I32(rhs)) => lhs.cmp(rhs),
+            (Self::U32(lhs), Self::U32(rhs)) => lhs.cmp(rhs),
+            (Self::I64(lhs), Self::I64(rhs)) => lhs.cmp(rhs),
+            (Self::U64(lhs), Self::U64(rhs)) => lhs.cmp(rhs),
+            // +0.0 and -0.0 are equal but otherwise, use the IEEE 754 total order so that NaNs are
+            // ordered (and equal to themselves) as well.
+            (Self::F64(lhs), Self::F64(rhs)) if *lhs == 0. && *rhs == 0. => Ordering::Equal,
+            (Self::F64(lhs), Self::F64(rhs)) => lhs.total_cmp(rhs),
+            (Self::Str(lhs), Self::Str(rhs)) => lhs.cmp(rhs),
+            (Self::Signature(lhs), Self::Signature(rhs)) => lhs.cmp(rhs),
+            (Self::ObjectPath(lhs), Self::ObjectPath(rhs)) => lhs.cmp(rhs),
+            (Self::Value(lhs), Self::Value(rhs)) => lhs.cmp(rhs),
+            (Self::Array(lhs), Self::Array(rhs)) => lhs.cmp(rhs),
+            (Self::Dict(lhs), Self::Dict(rhs)) => lhs.cmp(rhs),
+            (Self::Structure(lhs), Self::Structure(rhs)) => lhs.cmp(rhs),
+            #[cfg(feature = "gvariant")]
+            (Self::Maybe(lhs), Self::Maybe(rhs)) => lhs.cmp(rhs),
+            #[cfg(unix)]
+            (Self::Fd(lhs), Self::Fd(rhs)) => lhs.cmp(rhs),
+            _ => self.type_rank().cmp(&other.type_rank()),
+        }
     }
 }
 
@@ -184,6 +251,32 @@ macro_rules! serialize_value {
 }
 
 impl<'a> Value<'a> {
+    /// The position of the variant in the declaration, used to order values of different types.
+    fn type_rank(&self) -> u8 {
+        match self {
+            Self::U8(_) => 0,
+            Self::Bool(_) => 1,
+            Self::I16(_) => 2,
+            Self::U16(_) => 3,
+            Self::I32(_) => 4,
+            Self::U32(_) => 5,
+            Self::I64(_) => 6,
+            Self::U64(_) => 7,
+            Self::F64(_) => 8,
+            Self::Str(_) => 9,
+            Self::Signature(_) => 10,
+            Self::ObjectPath(_) => 11,
+            Self::Value(_) => 12,
+            Self::Array(_) => 13,
+            Self::Dict(_) => 14,
+            Self::Structure(_) => 15,
+            #[cfg(feature = "gvariant")]
+            Self::Maybe(_) => 16,
+            #[cfg(unix)]
+            Self::Fd(_) => 17,
+        }
+    }
+
     /// Make a [`Value`] for a given value.
     ///
     /// In general, you can use [`Into`] trait on basic types, except
@@ -359,6 +452,14 @@ impl<'a> Value<'a> {
         serialize_value!(self serializer.serialize_value)
     }
 
+    /// Serialize the contained value directly, rather than as a variant.
+    pub(crate) fn serialize_value_as_newtype<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_value!(self serializer.serialize_newtype_struct "Value")
+    }
+
     #[cfg(feature = "gvariant")]
     pub(crate) fn serialize_value_as_some<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -426,8 +527,30 @@ impl<'a> Value<'a> {
     /// Same as [`downcast`] except it doesn't consume `self` and hence requires
     /// `T: TryFrom<&Value<_>>`.
     ///
+    /// All the types a `Value` can hold can be borrowed this way, without any cloning: `&u8`,
+    /// `&bool`, `&i16`, `&u16`, `&i32`, `&u32`, `&i64`, `&u64`, `&f64`, `&str`, `&Str`,
+    /// `&Signature`, `&ObjectPath`, `&Array` (or the `&[Value]` of its elements), `&Dict`,
+    /// `&Structure`, `&Maybe` and `&Fd`. Basic types can also be copied out directly, e.g. as
+    /// `u32`.
+    ///
     /// # Examples
     ///
+    /// Peek into a variant:
+    ///
+    /// ```
+    /// use zvariant::{Array, Value};
+    ///
+    /// let v = Value::new(Value::new("hello"));
+    /// assert_eq!(v.downcast_ref::<&str>().unwrap(), "hello");
+    /// assert!(v.downcast_ref::<u32>().is_err());
+    ///
+    /// let v = Value::new(Array::from(vec![1u32, 2]));
+    /// let elements: &[Value] = v.downcast_ref().unwrap();
+    /// assert_eq!(elements[1].downcast_ref::<u32>().unwrap(), 2);
+    /// ```
+    ///
+    /// In generic code:
+    ///
     /// ```
     /// use zvariant::{Error, Result, Value};
     ///
@@ -717,11 +840,27 @@ impl<'sig, T> From<ValueSeed<'sig, T>> for SignatureSeed<'sig> {
     }
 }
 
-struct ValueSeed<'sig, T> {
+pub(crate) struct ValueSeed<'sig, T> {
     signature: &'sig Signature,
     phantom: PhantomData<T>,
 }
 
+impl<'sig, T> ValueSeed<'sig, T> {
+    /// A seed for deserializing a [`Value`] of the given signature.
+    pub(crate) fn new(signature: &'sig Signature) -> Self {
+        Self {
+            signature,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> DynamicType for ValueSeed<'_, T> {
+    fn signature(&self) -> Signature {
+        self.signature.clone()
+    }
+}
+
 impl<'de, T> ValueSeed<'_, T>
 where
     T: Deserialize<'de>,
@@ -0,0 +1,225 @@
+/// Construct a [`Value`] from a literal-like syntax.
+///
+/// This is similar to `serde_json::json!` and is handy for building nested values without long
+/// chains of [`Value::from`] and [`Into::into`]:
+///
+/// * `[a, b, c]` creates an array. All elements must have the same signature. An empty array has
+///   the element signature `v`.
+/// * `{ key1 => value1, key2 => value2 }` creates a dictionary. The values are wrapped in variants,
+///   so a dictionary with string keys has the signature `a{sv}`, which is the most common
+///   dictionary type on D-Bus. All keys must have the same signature. An empty dictionary has the
+///   signature `a{sv}`.
+/// * `(a, b, c)` creates a structure. Since structures can't be empty, `()` is rejected at compile
+///   time:
+///
+///   ```compile_fail
+///   let v = zvariant::value!(());
+///   ```
+/// * Anything else is an expression, which is converted using [`Value::from`]. Since a [`Value`] is
+///   converted into itself, `Value::new(value!(...))` can be used to wrap a value in a variant.
+///
+/// The syntax can be nested arbitrarily.
+///
+/// # Panics
+///
+/// If the elements of an array, or the keys of a dictionary, don't all have the same signature.
+///
+/// # Examples
+///
+/// ```
+/// use zvariant::{value, Value};
+///
+/// let v = value!({
+///     "name" => "zbus",
+///     "version" => (5u8, 1u8),
+///     "features" => ["tokio", "url"],
+///     "nested" => { "empty" => [] },
+/// });
+/// assert_eq!(v.value_signature(), "a{sv}");
+///
+/// let v = value!([
+///     (1u32, Value::new(value!("one"))),
+///     (2u32, Value::new(value!([2.0, 3.0]))),
+/// ]);
+/// assert_eq!(v.value_signature(), "a(uv)");
+/// ```
+///
+/// [`Value`]: enum.Value.html
+/// [`Value::from`]: enum.Value.html
+#[macro_export]
+macro_rules! value {
+    // Arrays: munch the elements one by one.
+    (@array [$($elems:expr,)*]) => {{
+        let elements: ::std::vec::Vec<$crate::Value<'_>> = ::std::vec![$($elems,)*];
+        let signature = elements
+            .first()
+            .map(|element| element.value_signature().clone())
+            .unwrap_or($crate::Signature::Variant);
+        let mut array = $crate::Array::new(&signature);
+        for element in elements {
+            array
+                .append(element)
+                .expect("all elements of an array must have the same signature");
+        }
+
+        $crate::Value::Array(array)
+    }};
+    (@array [$($elems:expr,)*] , $($rest:tt)*) => {
+        $crate::value!(@array [$($elems,)*] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
+        $crate::value!(@array [$($elems,)* $crate::value!([$($array)*]),] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] {$($dict:tt)*} $($rest:tt)*) => {
+        $crate::value!(@array [$($elems,)* $crate::value!({$($dict)*}),] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] ($($fields:tt)*) $($rest:tt)*) => {
+        $crate::value!(@array [$($elems,)* $crate::value!(($($fields)*)),] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::value!(@array [$($elems,)* $crate::value!($next),] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] $last:expr) => {
+        $crate::value!(@array [$($elems,)* $crate::value!($last),])
+    };
+
+    // Structures: same as arrays but the fields are allowed to differ in signature.
+    (@struct []) => {
+        ::std::compile_error!("structures must have at least one field")
+    };
+    (@struct [$($fields:expr,)*]) => {
+        $crate::Value::Structure(
+            $crate::StructureBuilder::new()
+                $(.append_field($fields))*
+                .build()
+                .expect("structures must have at least one field"),
+        )
+    };
+    (@struct [$($fields:expr,)*] , $($rest:tt)*) => {
+        $crate::value!(@struct [$($fields,)*] $($rest)*)
+    };
+    (@struct [$($fields:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
+        $crate::value!(@struct [$($fields,)* $crate::value!([$($array)*]),] $($rest)*)
+    };
+    (@struct [$($fields:expr,)*] {$($dict:tt)*} $($rest:tt)*) => {
+        $crate::value!(@struct [$($fields,)* $crate::value!({$($dict)*}),] $($rest)*)
+    };
+    (@struct [$($fields:expr,)*] ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::value!(@struct [$($fields,)* $crate::value!(($($inner)*)),] $($rest)*)
+    };
+    (@struct [$($fields:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::value!(@struct [$($fields,)* $crate::value!($next),] $($rest)*)
+    };
+    (@struct [$($fields:expr,)*] $last:expr) => {
+        $crate::value!(@struct [$($fields,)* $crate::value!($last),])
+    };
+
+    // Dictionaries: munch `key => value` entries one by one.
+    (@dict [$(($keys:expr, $values:expr))*]) => {{
+        let entries: ::std::vec::Vec<($crate::Value<'_>, $crate::Value<'_>)> = ::std::vec![
+            $(
+                (
+                    $crate::Value::from($keys),
+                    $crate::Value::Value(::std::boxed::Box::new($values)),
+                ),
+            )*
+        ];
+        let key_signature = entries
+            .first()
+            .map(|(key, _)| key.value_signature().clone())
+            .unwrap_or($crate::Signature::Str);
+        let mut dict = $crate::Dict::new(&key_signature, &$crate::Signature::Variant);
+        for (key, value) in entries {
+            dict.append(key, value)
+                .expect("all keys of a dictionary must have the same signature");
+        }
+
+        $crate::Value::Dict(dict)
+    }};
+    (@dict [$($entries:tt)*] , $($rest:tt)*) => {
+        $crate::value!(@dict [$($entries)*] $($rest)*)
+    };
+    (@dict [$($entries:tt)*] $key:expr => [$($array:tt)*] $($rest:tt)*) => {
+        $crate::value!(@dict [$($entries)* ($key, $crate::value!([$($array)*]))] $($rest)*)
+    };
+    (@dict [$($entries:tt)*] $key:expr => {$($dict:tt)*} $($rest:tt)*) => {
+        $crate::value!(@dict [$($entries)* ($key, $crate::value!({$($dict)*}))] $($rest)*)
+    };
+    (@dict [$($entries:tt)*] $key:expr => ($($fields:tt)*) $($rest:tt)*) => {
+        $crate::value!(@dict [$($entries)* ($key, $crate::value!(($($fields)*)))] $($rest)*)
+    };
+    (@dict [$($entries:tt)*] $key:expr => $value:expr, $($rest:tt)*) => {
+        $crate::value!(@dict [$($entries)* ($key, $crate::value!($value))] $($rest)*)
+    };
+    (@dict [$($entries:tt)*] $key:expr => $value:expr) => {
+        $crate::value!(@dict [$($entries)* ($key, $crate::value!($value))])
+    };
+
+    // Entry points.
+    ([$($tt:tt)*]) => {
+        $crate::value!(@array [] $($tt)*)
+    };
+    ({$($tt:tt)*}) => {
+        $crate::value!(@dict [] $($tt)*)
+    };
+    (($($tt:tt)*)) => {
+        $crate::value!(@struct [] $($tt)*)
+    };
+    ($other:expr) => {
+        $crate::Value::from($other)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{Array, OwnedValue, StructureBuilder, Value};
+
+    #[test]
+    fn basic() {
+        assert_eq!(value!(42u32), Value::U32(42));
+        assert_eq!(value!("hello"), Value::from("hello"));
+        let s = String::from("hi");
+        assert_eq!(value!(s.as_str()), Value::from("hi"));
+        assert_eq!(
+            value!(Value::new(value!(1u8))),
+            Value::Value(Box::new(Value::U8(1)))
+        );
+    }
+
+    #[test]
+    fn containers() {
+        assert_eq!(value!([1u8, 2u8, 3u8]), Value::from(vec![1u8, 2, 3]));
+        assert_eq!(value!([1u8, 2u8,]), Value::from(vec![1u8, 2]));
+        assert_eq!(value!([]).value_signature(), "av");
+        assert_eq!(value!([[1i32], [2i32, 3i32]]).value_signature(), "aai");
+
+        let expected = StructureBuilder::new()
+            .add_field(1u32)
+            .add_field("one")
+            .append_field(Value::Array(Array::from(vec![true])))
+            .build()
+            .unwrap();
+        assert_eq!(value!((1u32, "one", [true])), Value::Structure(expected));
+
+        let v = value!({
+            "a" => 1u32,
+            "b" => ["x", "y"],
+            "c" => { 1u8 => (2.0, false) },
+        });
+        assert_eq!(v.value_signature(), "a{sv}");
+        let dict = HashMap::<String, OwnedValue>::try_from(v).unwrap();
+        assert_eq!(dict["a"], OwnedValue::from(1u32));
+        let c = HashMap::<u8, OwnedValue>::try_from(Value::from(dict["c"].try_clone().unwrap()))
+            .unwrap();
+        assert_eq!(c[&1].value_signature(), "(db)");
+        assert_eq!(value!({}).value_signature(), "a{sv}");
+    }
+
+    #[test]
+    #[should_panic(expected = "same signature")]
+    fn mismatched_array() {
+        let _ = value!([1u8, "two"]);
+    }
+}
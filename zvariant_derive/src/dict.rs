@@ -1,36 +1,10 @@
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote, ToTokens};
-use syn::{punctuated::Punctuated, spanned::Spanned, Data, DeriveInput, Error, Field};
-use zvariant_utils::{case, macros};
+use syn::{punctuated::Punctuated, spanned::Spanned, Data, DeriveInput, Error};
+use zvariant_utils::macros;
 
 use crate::utils::*;
 
-fn dict_name_for_field(
-    f: &Field,
-    rename_attr: Option<String>,
-    rename_all_attr: Option<&str>,
-) -> Result<String, Error> {
-    if let Some(name) = rename_attr {
-        Ok(name)
-    } else {
-        let ident = f.ident.as_ref().unwrap().to_string();
-
-        match rename_all_attr {
-            Some("lowercase") => Ok(ident.to_ascii_lowercase()),
-            Some("UPPERCASE") => Ok(ident.to_ascii_uppercase()),
-            Some("PascalCase") => Ok(case::pascal_or_camel_case(&ident, true)),
-            Some("camelCase") => Ok(case::pascal_or_camel_case(&ident, false)),
-            Some("snake_case") => Ok(case::snake_or_kebab_case(&ident, true)),
-            Some("kebab-case") => Ok(case::snake_or_kebab_case(&ident, false)),
-            None => Ok(ident),
-            Some(other) => Err(Error::new(
-                f.span(),
-                format!("invalid `rename_all` attribute value {other}"),
-            )),
-        }
-    }
-}
-
 pub fn expand_serialize_derive(input: DeriveInput) -> Result<TokenStream, Error> {
     let (name, data) = match input.data {
         Data::Struct(data) => (input.ident, data),
@@ -42,35 +16,71 @@ pub fn expand_serialize_derive(input: DeriveInput) -> Result<TokenStream, Error>
     let zv = zvariant_path();
     let mut entries = quote! {};
     let mut num_entries: usize = 0;
+    let mut dict_names = Vec::new();
+    let mut flattened = None;
 
     for f in &data.fields {
-        let FieldAttributes { rename } = FieldAttributes::parse(&f.attrs)?;
+        let FieldAttributes {
+            rename, flatten, ..
+        } = FieldAttributes::parse(&f.attrs)?;
 
         let name = &f.ident;
+        let cfgs = cfg_attrs(f);
+        if flatten {
+            if flattened.replace(name).is_some() {
+                return Err(Error::new(f.span(), "only one field can be flattened"));
+            }
+
+            continue;
+        }
         let dict_name = dict_name_for_field(f, rename, rename_all.as_deref())?;
 
         let is_option = macros::ty_is_option(&f.ty);
 
         let e = if is_option {
             quote! {
+                #cfgs
                 if self.#name.is_some() {
                     map.serialize_entry(#dict_name, &#zv::SerializeValue(self.#name.as_ref().unwrap()))?;
                 }
             }
         } else {
             quote! {
+                #cfgs
                 map.serialize_entry(#dict_name, &#zv::SerializeValue(&self.#name))?;
             }
         };
 
         entries.extend(e);
+        dict_names.push(dict_name);
         num_entries += 1;
     }
 
+    let num_entries = match flattened {
+        Some(name) => {
+            // The flattened map is expected to hold values that serialize as variants already.
+            entries.extend(quote! {
+                for (key, value) in &self.#name {
+                    let key: &str = ::std::convert::AsRef::as_ref(key);
+                    if [#(#dict_names),*].contains(&key) {
+                        return ::std::result::Result::Err(
+                            <S::Error as #zv::export::serde::ser::Error>::custom(
+                                ::std::format!("flattened key `{}` collides with a field", key),
+                            ),
+                        );
+                    }
+                    map.serialize_entry(key, value)?;
+                }
+            });
+
+            quote! { #num_entries + self.#name.len() }
+        }
+        None => num_entries.to_token_stream(),
+    };
+
     let generics = input.generics;
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    let num_entries = num_entries.to_token_stream();
     Ok(quote! {
         #[allow(deprecated)]
         impl #impl_generics #zv::export::serde::ser::Serialize for #name #ty_generics
@@ -100,6 +110,7 @@ pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Erro
     let StructAttributes {
         rename_all,
         deny_unknown_fields,
+        deny_invalid_values,
         ..
     } = StructAttributes::parse(&input.attrs)?;
 
@@ -107,29 +118,63 @@ pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Erro
     let zv = zvariant_path();
     let mut fields = Vec::new();
     let mut req_fields = Vec::new();
+    let mut default_fields = Vec::new();
     let mut dict_names = Vec::new();
     let mut entries = Vec::new();
+    let mut flattened = None;
 
     for f in &data.fields {
-        let FieldAttributes { rename } = FieldAttributes::parse(&f.attrs)?;
+        let FieldAttributes {
+            rename,
+            default,
+            flatten,
+            ..
+        } = FieldAttributes::parse(&f.attrs)?;
 
         let name = &f.ident;
+        let cfgs = cfg_attrs(f);
+        if flatten {
+            if deny_unknown_fields {
+                return Err(Error::new(
+                    f.span(),
+                    "`flatten` can't be used together with `deny_unknown_fields`",
+                ));
+            }
+            if flattened.replace((name, &f.ty)).is_some() {
+                return Err(Error::new(f.span(), "only one field can be flattened"));
+            }
+
+            continue;
+        }
         let dict_name = dict_name_for_field(f, rename, rename_all.as_deref())?;
 
         let is_option = macros::ty_is_option(&f.ty);
 
+        let value = if deny_invalid_values {
+            quote! {
+                ::std::option::Option::Some(
+                    access.next_value::<#zv::DeserializeValue<_>>()?.0,
+                )
+            }
+        } else {
+            quote! {
+                access.next_value::<#zv::DeserializeValue<_>>().map(|v| v.0).ok()
+            }
+        };
         entries.push(quote! {
+            #cfgs
             #dict_name => {
-                // FIXME: add an option about strict parsing (instead of silently skipping the field)
-                #name = access.next_value::<#zv::DeserializeValue<_>>().map(|v| v.0).ok();
+                #name = #value;
             }
         });
 
         dict_names.push(dict_name);
-        fields.push(name);
+        fields.push((name, cfgs.clone()));
 
-        if !is_option {
-            req_fields.push(name);
+        if default {
+            default_fields.push((name, cfgs));
+        } else if !is_option {
+            req_fields.push((name, cfgs));
         }
     }
 
@@ -144,6 +189,15 @@ pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Erro
                 );
             }
         }
+    } else if let Some((name, _)) = flattened {
+        quote! {
+            field => {
+                #name.insert(
+                    ::std::convert::From::from(field),
+                    access.next_value()?,
+                );
+            }
+        }
     } else {
         quote! {
             unknown => {
@@ -152,6 +206,41 @@ pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Erro
         }
     };
     entries.push(fallback);
+    let (flattened_decl, flattened_field) = match flattened {
+        Some((name, ty)) => (
+            quote! { let mut #name: #ty = ::std::default::Default::default(); },
+            quote! { #name, },
+        ),
+        None => (quote! {}, quote! {}),
+    };
+
+    let field_decls = fields.iter().map(|(name, cfgs)| {
+        quote! {
+            #cfgs
+            let mut #name = ::std::default::Default::default();
+        }
+    });
+    let field_inits = fields.iter().map(|(name, cfgs)| quote! { #cfgs #name });
+    let default_field_lets = default_fields.iter().map(|(name, cfgs)| {
+        quote! {
+            #cfgs
+            let #name = #name.unwrap_or_default();
+        }
+    });
+    let req_field_lets = req_fields.iter().map(|(name, cfgs)| {
+        quote! {
+            #cfgs
+            let #name = if let ::std::option::Option::Some(val) = #name {
+                val
+            } else {
+                return ::std::result::Result::Err(
+                    <M::Error as #zv::export::serde::de::Error>::missing_field(
+                        ::std::stringify!(#name),
+                    ),
+                );
+            };
+        }
+    });
 
     let (_, ty_generics, _) = input.generics.split_for_impl();
     let mut generics = input.generics.clone();
@@ -193,7 +282,8 @@ pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Erro
                     where
                         M: #zv::export::serde::de::MapAccess<'de>,
                     {
-                        #( let mut #fields = ::std::default::Default::default(); )*
+                        #(#field_decls)*
+                        #flattened_decl
 
                         // does not check duplicated fields, since those shouldn't exist in stream
                         while let ::std::option::Option::Some(key) = access.next_key::<&str>()? {
@@ -202,17 +292,11 @@ pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Erro
                             }
                         }
 
-                        #(let #req_fields = if let ::std::option::Option::Some(val) = #req_fields {
-                            val
-                        } else {
-                            return ::std::result::Result::Err(
-                                <M::Error as #zv::export::serde::de::Error>::missing_field(
-                                    ::std::stringify!(#req_fields),
-                                ),
-                            );
-                        };)*
-
-                        ::std::result::Result::Ok(#name { #(#fields),* })
+                        #(#default_field_lets)*
+
+                        #(#req_field_lets)*
+
+                        ::std::result::Result::Ok(#name { #flattened_field #(#field_inits),* })
                     }
                 }
 
@@ -222,3 +306,13 @@ pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Erro
         }
     })
 }
+
+/// The `cfg` attributes of `field`, to be applied to all the code generated for it.
+fn cfg_attrs(field: &syn::Field) -> TokenStream {
+    let cfgs = field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"));
+
+    quote! { #(#cfgs)* }
+}
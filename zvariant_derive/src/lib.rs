@@ -155,6 +155,36 @@ mod value;
 /// assert_eq!(decoded, StrEnum::Variant2);
 /// ```
 ///
+/// The signature of individual fields of a structure can also be overridden through the same
+/// attribute on the field. This is useful if the field's type doesn't implement [`Type`] with the
+/// signature used on the wire, e.g when it's (de)serialized through `#[serde(with = "...")]`:
+///
+/// ```
+/// use zvariant::{serialized::Context, to_bytes, Type, LE};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Deserialize, Serialize, PartialEq, Debug)]
+/// enum Color {
+///     Red,
+///     Green,
+/// }
+///
+/// #[derive(Deserialize, Serialize, Type, PartialEq, Debug)]
+/// struct Pixel {
+///     x: u32,
+///     y: u32,
+///     #[zvariant(signature = "s")]
+///     color: Color,
+/// }
+///
+/// assert_eq!(Pixel::SIGNATURE, "(uus)");
+/// let pixel = Pixel { x: 1, y: 2, color: Color::Green };
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let encoded = to_bytes(ctxt, &pixel).unwrap();
+/// let decoded: Pixel = encoded.deserialize().unwrap().0;
+/// assert_eq!(decoded, pixel);
+/// ```
+///
 /// [`Type`]: https://docs.rs/zvariant/latest/zvariant/trait.Type.html
 /// [`Serialize`]: https://docs.serde.rs/serde/trait.Serialize.html
 /// [`Deserialize`]: https://docs.serde.rs/serde/de/trait.Deserialize.html
@@ -225,8 +255,21 @@ pub fn type_macro_derive(input: TokenStream) -> TokenStream {
 /// * `"camelCase"`
 /// * `"snake_case"`
 /// * `"kebab-case"`
+/// * `"SCREAMING_SNAKE_CASE"`
+/// * `"SCREAMING-KEBAB-CASE"`
+///
+/// # Optional and additional fields
+///
+/// Fields of type `Option<T>` are omitted from the dictionary when they're `None`.
+///
+/// A single `HashMap<String, OwnedValue>` (or any other map of strings to values that serialize
+/// as variants) field can be marked with `#[zvariant(flatten)]`. Its entries are serialized as
+/// additional entries of the dictionary. Together with the same attribute on [`DeserializeDict`],
+/// this allows round-tripping of unknown keys.
+/// Serialization fails if any of its keys is also the key of another field.
 ///
 /// [`Serialize`]: https://docs.serde.rs/serde/trait.Serialize.html
+/// [`DeserializeDict`]: derive.DeserializeDict.html
 #[proc_macro_derive(SerializeDict, attributes(zbus, zvariant))]
 pub fn serialize_dict_macro_derive(input: TokenStream) -> TokenStream {
     let input: DeriveInput = syn::parse(input).unwrap();
@@ -294,8 +337,59 @@ pub fn serialize_dict_macro_derive(input: TokenStream) -> TokenStream {
 /// * `"camelCase"`
 /// * `"snake_case"`
 /// * `"kebab-case"`
+/// * `"SCREAMING_SNAKE_CASE"`
+/// * `"SCREAMING-KEBAB-CASE"`
+///
+/// # Missing and unknown fields
+///
+/// Fields of type `Option<T>` are set to `None` when missing from the dictionary, while missing
+/// fields of any other type result in an error, unless the field is marked with
+/// `#[zvariant(default)]`, in which case its [`Default`] value is used.
+///
+/// By default, unknown keys in the dictionary are ignored. There are two ways to change that:
+///
+/// * `#[zvariant(deny_unknown_fields)]` on the structure turns unknown keys into an error.
+/// * `#[zvariant(flatten)]` on a single `HashMap<String, OwnedValue>` field collects all unknown
+///   entries into that field. It can't be combined with `deny_unknown_fields`.
+///
+/// Errors deserializing the value of a known key are ignored and the field is left unset, even with
+/// `deny_unknown_fields`. Use `#[zvariant(deny_invalid_values)]` on the structure to return them
+/// instead.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use zvariant::{
+///     serialized::Context, to_bytes, DeserializeDict, OwnedValue, SerializeDict, Type, Value, LE,
+/// };
+///
+/// #[derive(DeserializeDict, SerializeDict, Type, Debug, PartialEq)]
+/// #[zvariant(signature = "dict")]
+/// struct Settings {
+///     name: String,
+///     #[zvariant(default)]
+///     volume: u32,
+///     #[zvariant(flatten)]
+///     extra: HashMap<String, OwnedValue>,
+/// }
+///
+/// let mut dict = HashMap::new();
+/// dict.insert("name", Value::from("speaker"));
+/// dict.insert("balance", Value::from(0.5));
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let encoded = to_bytes(ctxt, &dict).unwrap();
+/// let settings: Settings = encoded.deserialize().unwrap().0;
+/// assert_eq!(settings.name, "speaker");
+/// assert_eq!(settings.volume, 0);
+/// assert_eq!(settings.extra["balance"], OwnedValue::from(0.5));
+///
+/// // Unknown keys are preserved.
+/// let encoded = to_bytes(ctxt, &settings).unwrap();
+/// let decoded: Settings = encoded.deserialize().unwrap().0;
+/// assert_eq!(decoded, settings);
+/// ```
 ///
 /// [`Deserialize`]: https://docs.serde.rs/serde/de/trait.Deserialize.html
+/// [`OwnedValue`]: https://docs.rs/zvariant/latest/zvariant/struct.OwnedValue.html
 #[proc_macro_derive(DeserializeDict, attributes(zbus, zvariant))]
 pub fn deserialize_dict_macro_derive(input: TokenStream) -> TokenStream {
     let input: DeriveInput = syn::parse(input).unwrap();
@@ -402,15 +496,60 @@ pub fn deserialize_dict_macro_derive(input: TokenStream) -> TokenStream {
 /// assert_eq!(e, Enum::Variant2);
 /// ```
 ///
+/// Converting from an integer that doesn't match any variant fails, unless a variant holding the
+/// integer is marked with the `unknown` attribute. It's then used for all the other values, which
+/// is handy for enumerations that may be extended by newer versions of a D-Bus API:
+///
+/// ```
+/// # use zvariant::{Type, Value};
+/// #
+/// #[derive(Debug, PartialEq, Type, Value)]
+/// #[repr(u32)]
+/// enum Enum {
+///     Variant1 = 1,
+///     Variant2 = 2,
+///     #[zvariant(unknown)]
+///     Unknown(u32),
+/// }
+///
+/// assert_eq!(Enum::SIGNATURE, u32::SIGNATURE);
+/// let e = Enum::try_from(Value::from(3u32)).unwrap();
+/// assert_eq!(e, Enum::Unknown(3));
+/// assert_eq!(Value::from(e), Value::from(3u32));
+/// ```
+///
 /// # Dictionary encoding
 ///
 /// For treating your type as a dictionary, you can use the `signature = "dict"` attribute. See
 /// [`Type`] for more details and an example use. Please note that this macro can only handle
 /// `dict` or `a{sv}` values. All other values will be ignored.
 ///
+/// The keys of the dictionary can be customized through the `rename_all` and `rename` attributes,
+/// just like with [`SerializeDict`] and [`DeserializeDict`]:
+///
+/// ```
+/// use std::collections::HashMap;
+/// use zvariant::{OwnedValue, Type, Value};
+///
+/// #[derive(Clone, Type, Value, OwnedValue)]
+/// #[zvariant(signature = "dict", rename_all = "PascalCase")]
+/// struct Props {
+///     max_volume: u32,
+///     #[zvariant(rename = "is-muted")]
+///     muted: bool,
+/// }
+///
+/// let props = Props { max_volume: 11, muted: false };
+/// let dict = HashMap::<String, OwnedValue>::try_from(Value::from(props)).unwrap();
+/// assert!(dict.contains_key("MaxVolume"));
+/// assert!(dict.contains_key("is-muted"));
+/// ```
+///
 /// [`Value`]: https://docs.rs/zvariant/latest/zvariant/enum.Value.html
 /// [`Type`]: derive.Type.html#custom-types
-#[proc_macro_derive(Value)]
+/// [`SerializeDict`]: derive.SerializeDict.html
+/// [`DeserializeDict`]: derive.DeserializeDict.html
+#[proc_macro_derive(Value, attributes(zbus, zvariant))]
 pub fn value_macro_derive(input: TokenStream) -> TokenStream {
     let ast: DeriveInput = syn::parse(input).unwrap();
     value::expand_derive(ast, value::ValueType::Value)
@@ -425,7 +564,7 @@ pub fn value_macro_derive(input: TokenStream) -> TokenStream {
 /// See [`Value`] documentation for examples.
 ///
 /// [`OwnedValue`]: https://docs.rs/zvariant/latest/zvariant/struct.OwnedValue.html
-#[proc_macro_derive(OwnedValue)]
+#[proc_macro_derive(OwnedValue, attributes(zbus, zvariant))]
 pub fn owned_value_macro_derive(input: TokenStream) -> TokenStream {
     let ast: DeriveInput = syn::parse(input).unwrap();
     value::expand_derive(ast, value::ValueType::OwnedValue)
@@ -1,9 +1,9 @@
 use std::str::FromStr;
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::{quote, ToTokens};
 use syn::{
-    spanned::Spanned, Attribute, Data, DataEnum, DeriveInput, Error, Fields, Generics, Ident,
+    spanned::Spanned, Attribute, Data, DataEnum, DeriveInput, Error, Field, Fields, Generics, Ident,
 };
 use zvariant_utils::signature::Signature;
 
@@ -16,10 +16,7 @@ pub fn expand_derive(ast: DeriveInput) -> Result<TokenStream, Error> {
     if let Some(signature_str) = signature {
         // Signature already provided, easy then!
 
-        let signature = match signature_str.as_str() {
-            "dict" => Signature::dict(Signature::Str, Signature::Variant),
-            s => Signature::from_str(s).map_err(|e| Error::new(ast.span(), e))?,
-        };
+        let signature = parse_signature(&signature_str, ast.span())?;
         let signature_tokens = signature_to_tokens(&signature, &zv);
 
         let name = ast.ident;
@@ -55,6 +52,14 @@ pub fn expand_derive(ast: DeriveInput) -> Result<TokenStream, Error> {
     })
 }
 
+/// Parse a signature given in an attribute, supporting the `dict` alias for `a{sv}`.
+fn parse_signature(signature: &str, span: Span) -> Result<Signature, Error> {
+    match signature {
+        "dict" => Ok(Signature::dict(Signature::Str, Signature::Variant)),
+        s => Signature::from_str(s).map_err(|e| Error::new(span, e)),
+    }
+}
+
 fn impl_struct(
     name: Ident,
     generics: Generics,
@@ -62,7 +67,7 @@ fn impl_struct(
     zv: &TokenStream,
 ) -> Result<TokenStream, Error> {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-    let signature = signature_for_struct(&fields, zv, false);
+    let signature = signature_for_struct(&fields, zv, false)?;
 
     Ok(quote! {
         impl #impl_generics #zv::Type for #name #ty_generics #where_clause {
@@ -75,30 +80,32 @@ fn signature_for_struct(
     fields: &Fields,
     zv: &TokenStream,
     insert_enum_variant: bool,
-) -> TokenStream {
-    let field_types = fields.iter().map(|field| field.ty.to_token_stream());
+) -> Result<TokenStream, Error> {
+    let field_signatures = fields
+        .iter()
+        .map(|field| signature_for_field(field, zv))
+        .collect::<Result<Vec<_>, Error>>()?;
     let new_type = match fields {
         Fields::Named(_) => false,
-        Fields::Unnamed(_) if field_types.len() == 1 => true,
+        Fields::Unnamed(_) if field_signatures.len() == 1 => true,
         Fields::Unnamed(_) => false,
         Fields::Unit => panic!("signature_for_struct must not be called for unit fields"),
     };
-    let field_types_clone = field_types.clone();
     let signature = if new_type {
         quote! {#(
-            <#field_types_clone as #zv::Type>::SIGNATURE
+            #field_signatures
         )*}
     } else {
         quote! {
             &#zv::Signature::Structure(#zv::signature::Fields::Static {
                 fields: &[#(
-                    <#field_types_clone as #zv::Type>::SIGNATURE
+                    #field_signatures
                 ),*],
             })
         }
     };
 
-    if insert_enum_variant {
+    Ok(if insert_enum_variant {
         quote! {
             &#zv::Signature::Structure(#zv::signature::Fields::Static {
                 fields: &[
@@ -109,6 +116,27 @@ fn signature_for_struct(
         }
     } else {
         signature
+    })
+}
+
+/// The signature of a field, as a `&'static Signature` expression.
+///
+/// The field type's signature is used, unless it's overridden by the `signature` attribute.
+fn signature_for_field(field: &Field, zv: &TokenStream) -> Result<TokenStream, Error> {
+    let FieldAttributes { signature, .. } = FieldAttributes::parse(&field.attrs)?;
+
+    match signature {
+        Some(signature) => {
+            let signature = parse_signature(&signature, field.span())?;
+            let signature_tokens = signature_to_tokens(&signature, zv);
+
+            Ok(quote! { &#signature_tokens })
+        }
+        None => {
+            let ty = field.ty.to_token_stream();
+
+            Ok(quote! { <#ty as #zv::Type>::SIGNATURE })
+        }
     }
 }
 
@@ -177,18 +205,21 @@ fn signature_for_variant(
     attrs: &[Attribute],
     zv: &TokenStream,
 ) -> Result<TokenStream, Error> {
+    let VariantAttributes { unknown } = VariantAttributes::parse(&variant.attrs)?;
+    if unknown {
+        check_unknown_variant(variant)?;
+    }
     let repr = attrs.iter().find(|attr| attr.path().is_ident("repr"));
-    match &variant.fields {
-        Fields::Unit => {
-            let repr = match repr {
-                Some(repr_attr) => repr_attr.parse_args()?,
-                None => quote! { u32 },
-            };
-
-            Ok(quote! { <#repr as #zv::Type>::SIGNATURE })
-        }
-        Fields::Named(_) => Ok(signature_for_struct(&variant.fields, zv, true)),
-        Fields::Unnamed(_) => Ok(signature_for_struct(&variant.fields, zv, true)),
+    // The `unknown` variant holds the integer value so it's encoded like the unit variants.
+    if unknown || matches!(variant.fields, Fields::Unit) {
+        let repr = match repr {
+            Some(repr_attr) => repr_attr.parse_args()?,
+            None => quote! { u32 },
+        };
+
+        Ok(quote! { <#repr as #zv::Type>::SIGNATURE })
+    } else {
+        signature_for_struct(&variant.fields, zv, true)
     }
 }
 
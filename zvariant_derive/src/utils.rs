@@ -1,7 +1,8 @@
 use proc_macro2::TokenStream;
 use proc_macro_crate::{crate_name, FoundCrate};
 use quote::{format_ident, quote};
-use zvariant_utils::def_attrs;
+use syn::{spanned::Spanned, Error, Field, Fields, Variant};
+use zvariant_utils::{case, def_attrs};
 
 pub fn zvariant_path() -> TokenStream {
     if let Ok(FoundCrate::Name(name)) = crate_name("zvariant") {
@@ -19,7 +20,56 @@ def_attrs! {
     crate zbus, zvariant;
 
     /// Attributes defined on structures.
-    pub StructAttributes("struct") { signature str, rename_all str, deny_unknown_fields none };
+    pub StructAttributes("struct") { signature str, rename_all str, deny_unknown_fields none, deny_invalid_values none };
     /// Attributes defined on fields.
-    pub FieldAttributes("field") { rename str };
+    pub FieldAttributes("field") { rename str, signature str, default none, flatten none };
+    /// Attributes defined on enum variants.
+    pub VariantAttributes("variant") { unknown none };
+}
+
+/// The name of the dictionary key for the given field.
+///
+/// The `rename` attribute of the field takes precedence over the `rename_all` attribute of the
+/// structure.
+pub fn dict_name_for_field(
+    f: &Field,
+    rename_attr: Option<String>,
+    rename_all_attr: Option<&str>,
+) -> Result<String, Error> {
+    if let Some(name) = rename_attr {
+        Ok(name)
+    } else {
+        let ident = f.ident.as_ref().unwrap().to_string();
+
+        match rename_all_attr {
+            Some("lowercase") => Ok(ident.to_ascii_lowercase()),
+            Some("UPPERCASE") => Ok(ident.to_ascii_uppercase()),
+            Some("PascalCase") => Ok(case::pascal_or_camel_case(&ident, true)),
+            Some("camelCase") => Ok(case::pascal_or_camel_case(&ident, false)),
+            Some("snake_case") => Ok(case::snake_or_kebab_case(&ident, true)),
+            Some("kebab-case") => Ok(case::snake_or_kebab_case(&ident, false)),
+            Some("SCREAMING_SNAKE_CASE") => {
+                Ok(case::snake_or_kebab_case(&ident, true).to_ascii_uppercase())
+            }
+            Some("SCREAMING-KEBAB-CASE") => {
+                Ok(case::snake_or_kebab_case(&ident, false).to_ascii_uppercase())
+            }
+            None => Ok(ident),
+            Some(other) => Err(Error::new(
+                f.span(),
+                format!("invalid `rename_all` attribute value {other}"),
+            )),
+        }
+    }
+}
+
+/// Ensure the variant marked with the `unknown` attribute holds a single unnamed field.
+pub fn check_unknown_variant(variant: &Variant) -> Result<(), Error> {
+    match &variant.fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Ok(()),
+        _ => Err(Error::new(
+            variant.span(),
+            "`unknown` variant must have exactly one unnamed field",
+        )),
+    }
 }
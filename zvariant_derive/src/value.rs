@@ -18,7 +18,11 @@ pub fn expand_derive(ast: DeriveInput, value_type: ValueType) -> Result<TokenStr
     match &ast.data {
         Data::Struct(ds) => match &ds.fields {
             Fields::Named(_) | Fields::Unnamed(_) => {
-                let StructAttributes { signature, .. } = StructAttributes::parse(&ast.attrs)?;
+                let StructAttributes {
+                    signature,
+                    rename_all,
+                    ..
+                } = StructAttributes::parse(&ast.attrs)?;
                 let signature = signature.map(|signature| match signature.as_str() {
                     "dict" => "a{sv}".to_string(),
                     _ => signature,
@@ -30,6 +34,7 @@ pub fn expand_derive(ast: DeriveInput, value_type: ValueType) -> Result<TokenStr
                     ast.generics,
                     &ds.fields,
                     signature,
+                    rename_all,
                     &zv,
                 )
             }
@@ -49,6 +54,7 @@ fn impl_struct(
     generics: Generics,
     fields: &Fields,
     signature: Option<String>,
+    rename_all: Option<String>,
     zv: &TokenStream,
 ) -> Result<TokenStream, Error> {
     let statc_lifetime = LifetimeParam::new(Lifetime::new("'static", Span::call_site()));
@@ -123,32 +129,44 @@ fn impl_struct(
                 .map(|field| field.ident.to_token_stream())
                 .collect();
             let (from_value_impl, into_value_impl) = match signature {
-                Some(signature) if signature == "a{sv}" => (
-                    // User wants the type to be encoded as a dict.
-                    // FIXME: Not the most efficient implementation.
-                    quote! {
-                        let mut fields = <::std::collections::HashMap::<::std::string::String, #zv::Value>>::try_from(value)?;
+                Some(signature) if signature == "a{sv}" => {
+                    let dict_names = fields
+                        .iter()
+                        .map(|field| {
+                            let FieldAttributes { rename, .. } =
+                                FieldAttributes::parse(&field.attrs)?;
 
-                        ::std::result::Result::Ok(Self {
-                            #(
-                                #field_names:
-                                    fields
-                                        .remove(stringify!(#field_names))
-                                        .ok_or_else(|| #zv::Error::IncorrectType)?
-                                        .downcast()?
-                            ),*
+                            dict_name_for_field(field, rename, rename_all.as_deref())
                         })
-                    },
-                    quote! {
-                        let mut fields = ::std::collections::HashMap::new();
-                        #(
-                            fields.insert(stringify!(#field_names), #zv::Value::from(s.#field_names));
-                        )*
+                        .collect::<Result<Vec<_>, Error>>()?;
 
-                        <#value_type>::#into_value_method(#zv::Value::from(fields))
-                            #into_value_error_transform
-                    },
-                ),
+                    (
+                        // User wants the type to be encoded as a dict.
+                        // FIXME: Not the most efficient implementation.
+                        quote! {
+                            let mut fields = <::std::collections::HashMap::<::std::string::String, #zv::Value>>::try_from(value)?;
+
+                            ::std::result::Result::Ok(Self {
+                                #(
+                                    #field_names:
+                                        fields
+                                            .remove(#dict_names)
+                                            .ok_or_else(|| #zv::Error::IncorrectType)?
+                                            .downcast()?
+                                ),*
+                            })
+                        },
+                        quote! {
+                            let mut fields = ::std::collections::HashMap::new();
+                            #(
+                                fields.insert(#dict_names, #zv::Value::from(s.#field_names));
+                            )*
+
+                            <#value_type>::#into_value_method(#zv::Value::from(fields))
+                                #into_value_error_transform
+                        },
+                    )
+                }
                 Some(_) | None => (
                     quote! {
                         let mut fields = #zv::Structure::try_from(value)?.into_fields();
@@ -239,8 +257,22 @@ fn impl_enum(
 
     let mut variant_names = vec![];
     let mut variant_values = vec![];
+    let mut unknown_variant = None;
     for variant in &data.variants {
-        // Ensure all variants of the enum are unit type
+        if VariantAttributes::parse(&variant.attrs)?.unknown {
+            check_unknown_variant(variant)?;
+            if unknown_variant.is_some() {
+                return Err(Error::new(
+                    variant.span(),
+                    "only one variant can be marked as `unknown`",
+                ));
+            }
+            unknown_variant = Some(&variant.ident);
+
+            continue;
+        }
+
+        // Ensure all other variants of the enum are unit type
         match variant.fields {
             Fields::Unit => {
                 variant_names.push(&variant.ident);
@@ -264,6 +296,18 @@ fn impl_enum(
         }
     }
 
+    // Values not matching any variant are kept by the `unknown` variant, if any.
+    let (unknown_into, unknown_from) = match unknown_variant {
+        Some(unknown) => (
+            quote! { #name::#unknown(u) => u, },
+            quote! { v => #name::#unknown(v), },
+        ),
+        None => (
+            quote! {},
+            quote! { _ => return ::std::result::Result::Err(#zv::Error::IncorrectType), },
+        ),
+    };
+
     let (value_type, into_value) = match value_type {
         ValueType::Value => (
             quote! { #zv::Value<'_> },
@@ -273,8 +317,9 @@ fn impl_enum(
                     fn from(e: #name) -> Self {
                         let u: #repr = match e {
                             #(
-                                #name::#variant_names => #variant_values
-                            ),*
+                                #name::#variant_names => #variant_values,
+                            )*
+                            #unknown_into
                         };
 
                         <#zv::Value as ::std::convert::From<_>>::from(u).into()
@@ -292,8 +337,9 @@ fn impl_enum(
                     fn try_from(e: #name) -> #zv::Result<Self> {
                         let u: #repr = match e {
                             #(
-                                #name::#variant_names => #variant_values
-                            ),*
+                                #name::#variant_names => #variant_values,
+                            )*
+                            #unknown_into
                         };
 
                         <#zv::OwnedValue as ::std::convert::TryFrom<_>>::try_from(
@@ -315,9 +361,9 @@ fn impl_enum(
 
                 ::std::result::Result::Ok(match v {
                     #(
-                        #variant_values => #name::#variant_names
-                     ),*,
-                    _ => return ::std::result::Result::Err(#zv::Error::IncorrectType),
+                        #variant_values => #name::#variant_names,
+                    )*
+                    #unknown_from
                 })
             }
         }
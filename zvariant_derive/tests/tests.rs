@@ -39,6 +39,28 @@ fn derive_enum() {
     assert_eq!(RequestNameFlags::SIGNATURE, "u")
 }
 
+#[test]
+fn derive_enum_unknown_variant() {
+    #[repr(u16)]
+    #[derive(Debug, PartialEq, Type, Value, OwnedValue)]
+    enum State {
+        Idle = 0,
+        Busy = 1,
+        #[zvariant(unknown)]
+        Unknown(u16),
+    }
+
+    assert_eq!(State::SIGNATURE, "q");
+
+    let state = State::try_from(Value::from(1u16)).unwrap();
+    assert_eq!(state, State::Busy);
+    let state = State::try_from(Value::from(7u16)).unwrap();
+    assert_eq!(state, State::Unknown(7));
+    assert_eq!(Value::from(State::Unknown(7)), Value::from(7u16));
+    let value = OwnedValue::try_from(State::Idle).unwrap();
+    assert_eq!(State::try_from(value).unwrap(), State::Idle);
+}
+
 #[test]
 fn derive_dict() {
     #[derive(SerializeDict, DeserializeDict, Type)]
@@ -82,3 +104,162 @@ fn derive_dict() {
 
     assert_eq!(Test::SIGNATURE, "a{sv}")
 }
+
+#[test]
+fn derive_field_signature() {
+    #[derive(Type)]
+    struct Test {
+        id: u32,
+        #[zvariant(signature = "s")]
+        kind: Kind,
+        #[zvariant(signature = "dict")]
+        props: Vec<(String, OwnedValue)>,
+    }
+
+    struct Kind;
+
+    assert_eq!(Test::SIGNATURE, "(usa{sv})");
+
+    #[derive(Type)]
+    enum Enum {
+        Variant1(#[zvariant(signature = "s")] Kind),
+        Variant2(#[zvariant(signature = "s")] Kind),
+    }
+
+    assert_eq!(Enum::SIGNATURE, "(us)");
+}
+
+#[test]
+fn derive_value_dict_renames() {
+    #[derive(Clone, Type, Value, OwnedValue)]
+    #[zvariant(signature = "dict", rename_all = "SCREAMING_SNAKE_CASE")]
+    struct Test {
+        field_a: u32,
+        #[zvariant(rename = "field-b")]
+        field_b: String,
+    }
+
+    let test = Test {
+        field_a: 1,
+        field_b: "foo".to_string(),
+    };
+    let dict = HashMap::<String, OwnedValue>::try_from(Value::from(test.clone())).unwrap();
+    assert_eq!(dict["FIELD_A"], Value::from(1u32).try_into().unwrap());
+    assert_eq!(dict["field-b"], Value::from("foo").try_into().unwrap());
+
+    let value = OwnedValue::try_from(test).unwrap();
+    let test = Test::try_from(value).unwrap();
+    assert_eq!(test.field_a, 1);
+    assert_eq!(test.field_b, "foo");
+}
+
+#[test]
+fn derive_dict_defaults_and_flatten() {
+    #[derive(SerializeDict, DeserializeDict, Type, Debug, PartialEq)]
+    #[zvariant(signature = "dict")]
+    struct Test {
+        field_a: Option<u32>,
+        #[zvariant(default)]
+        field_b: String,
+        #[zvariant(flatten)]
+        extra: HashMap<String, OwnedValue>,
+    }
+
+    let ctxt = Context::new(Format::DBus, LE, 0);
+    let mut dict = HashMap::new();
+    dict.insert("unknown1", Value::from(42u8));
+    dict.insert("unknown2", Value::from("bar"));
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    let deserialized: Test = serialized.deserialize().unwrap().0;
+
+    assert_eq!(deserialized.field_a, None);
+    assert_eq!(deserialized.field_b, "");
+    assert_eq!(deserialized.extra.len(), 2);
+    assert_eq!(deserialized.extra["unknown1"], OwnedValue::from(42u8));
+
+    // `None` fields are omitted while unknown fields round-trip.
+    let serialized = zvariant::to_bytes(ctxt, &deserialized).unwrap();
+    let roundtripped: HashMap<String, OwnedValue> = serialized.deserialize().unwrap().0;
+    assert_eq!(roundtripped.len(), 3);
+    assert!(!roundtripped.contains_key("field_a"));
+    let roundtripped: Test = serialized.deserialize().unwrap().0;
+    assert_eq!(roundtripped, deserialized);
+
+    // Flattened keys can't collide with the other fields.
+    let mut colliding = roundtripped;
+    colliding
+        .extra
+        .insert("field_b".to_string(), OwnedValue::from(42u8));
+    zvariant::to_bytes(ctxt, &colliding).unwrap_err();
+}
+
+#[test]
+fn derive_dict_deny_unknown_fields() {
+    #[derive(SerializeDict, DeserializeDict, Type, Debug)]
+    #[zvariant(signature = "dict", deny_unknown_fields)]
+    struct Test {
+        field_a: Option<u32>,
+    }
+
+    let ctxt = Context::new(Format::DBus, LE, 0);
+    let mut dict = HashMap::new();
+    dict.insert("field_a", Value::from(1u32));
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    let deserialized: Test = serialized.deserialize().unwrap().0;
+    assert_eq!(deserialized.field_a, Some(1));
+
+    // Unknown field.
+    dict.insert("field_b", Value::from(1u32));
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    serialized.deserialize::<Test>().unwrap_err();
+}
+
+#[test]
+fn derive_dict_deny_invalid_values() {
+    #[derive(SerializeDict, DeserializeDict, Type, Debug)]
+    #[zvariant(signature = "dict", deny_invalid_values)]
+    struct Test {
+        field_a: Option<u32>,
+    }
+
+    let ctxt = Context::new(Format::DBus, LE, 0);
+    let mut dict = HashMap::new();
+    dict.insert("field_a", Value::from("foo"));
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    serialized.deserialize::<Test>().unwrap_err();
+
+    // Unknown fields are still ignored.
+    dict.insert("field_a", Value::from(1u32));
+    dict.insert("field_b", Value::from(1u32));
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    let deserialized: Test = serialized.deserialize().unwrap().0;
+    assert_eq!(deserialized.field_a, Some(1));
+}
+
+#[test]
+fn derive_dict_cfg_fields() {
+    #[derive(SerializeDict, DeserializeDict, Type, Debug, PartialEq)]
+    #[zvariant(signature = "dict")]
+    struct Test {
+        field_a: u32,
+        #[cfg(not(test))]
+        field_b: String,
+        #[cfg(test)]
+        field_c: Option<String>,
+    }
+
+    let ctxt = Context::new(Format::DBus, LE, 0);
+    let mut dict = HashMap::new();
+    dict.insert("field_a", Value::from(1u32));
+    dict.insert("field_b", Value::from("ignored"));
+    dict.insert("field_c", Value::from("foo"));
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    let deserialized: Test = serialized.deserialize().unwrap().0;
+    assert_eq!(deserialized.field_a, 1);
+    assert_eq!(deserialized.field_c.as_deref(), Some("foo"));
+
+    let serialized = zvariant::to_bytes(ctxt, &deserialized).unwrap();
+    let roundtripped: HashMap<String, OwnedValue> = serialized.deserialize().unwrap().0;
+    assert_eq!(roundtripped.len(), 2);
+    assert!(!roundtripped.contains_key("field_b"));
+}
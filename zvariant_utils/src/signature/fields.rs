@@ -75,6 +75,17 @@ impl<const N: usize> From<[Signature; N]> for Fields {
     }
 }
 
+impl FromIterator<Signature> for Fields {
+    fn from_iter<I>(fields: I) -> Self
+    where
+        I: IntoIterator<Item = Signature>,
+    {
+        Fields::Dynamic {
+            fields: fields.into_iter().collect(),
+        }
+    }
+}
+
 impl From<&'static [&'static Signature]> for Fields {
     fn from(fields: &'static [&'static Signature]) -> Self {
         Fields::Static { fields }
@@ -37,6 +37,39 @@ use crate::serialized::Format;
 /// assert_eq!(sig.to_string(), "(xa{bs}as)");
 /// ```
 ///
+/// Signatures can also be composed structurally, from the variants for the basic types and the
+/// container constructors, instead of concatenating strings:
+///
+/// ```
+/// use zvariant::{signature::Fields, Signature};
+///
+/// let sig = Signature::structure([
+///     Signature::I64,
+///     Signature::dict(Signature::Bool, Signature::Str),
+///     Signature::array(Signature::Str),
+/// ]);
+/// assert_eq!(sig, "(xa{bs}as)");
+///
+/// // Fields can also be collected from an iterator.
+/// let sig = Signature::structure((0..3).map(|_| Signature::U8).collect::<Fields>());
+/// assert_eq!(sig, "(yyy)");
+/// ```
+///
+/// The `static_*` constructors are `const`, so they can be used to define signature constants,
+/// including in terms of the signatures of other types:
+///
+/// ```
+/// use zvariant::{Signature, Type};
+///
+/// const PROPERTIES: &Signature = &Signature::static_dict(&Signature::Str, &Signature::Variant);
+/// const CHANGES: &Signature = &Signature::static_structure(&[
+///     <&str>::SIGNATURE,
+///     PROPERTIES,
+///     <Vec<&str>>::SIGNATURE,
+/// ]);
+/// assert_eq!(CHANGES, "(sa{sv}as)");
+/// ```
+///
 /// [`zvariant::Signature`]: https://docs.rs/zvariant/latest/zvariant/struct.Signature.html
 #[derive(Debug, Default, Clone)]
 pub enum Signature {
@@ -173,7 +206,7 @@ impl Signature {
 
     /// Parse signature from a byte slice.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
-        parse(bytes, false)
+        parse(bytes)
     }
 
     /// Create a `Signature::Structure` for a given set of field signatures.
@@ -237,6 +270,126 @@ impl Signature {
         Signature::Maybe(Child::Static { child })
     }
 
+    /// The number of complete types on the top-level of the signature.
+    ///
+    /// This is `0` for [`Signature::Unit`], the number of fields for a
+    /// [`Signature::Structure`] and `1` for all other signatures. Since a structure signature and
+    /// a list of its fields (e.g `(xs)` and `xs`) parse into the same value, this is the number
+    /// of arguments a message body of this signature consists of.
+    pub const fn arity(&self) -> usize {
+        match self {
+            Signature::Unit => 0,
+            Signature::Structure(fields) => fields.len(),
+            _ => 1,
+        }
+    }
+
+    /// An iterator over the complete types on the top-level of the signature.
+    ///
+    /// See [`Signature::arity`] for the semantics. The number of items yielded is always equal to
+    /// the arity of the signature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use zvariant::Signature;
+    ///
+    /// let sig = Signature::from_str("sa{sv}as").unwrap();
+    /// let fields: Vec<_> = sig.top_level_fields().map(|f| f.to_string()).collect();
+    /// assert_eq!(fields, ["s", "a{sv}", "as"]);
+    /// assert_eq!(sig.arity(), 3);
+    /// ```
+    pub fn top_level_fields(&self) -> impl Iterator<Item = &Signature> {
+        let (fields, single) = match self {
+            Signature::Unit => (None, None),
+            Signature::Structure(fields) => (Some(fields.iter()), None),
+            _ => (None, Some(self)),
+        };
+
+        fields.into_iter().flatten().chain(single)
+    }
+
+    /// The signature of the elements, if `self` is an [`Signature::Array`].
+    pub fn array_element(&self) -> Option<&Signature> {
+        match self {
+            Signature::Array(child) => Some(child.signature()),
+            _ => None,
+        }
+    }
+
+    /// The signatures of the key and the value, if `self` is a [`Signature::Dict`].
+    pub fn dict_entry(&self) -> Option<(&Signature, &Signature)> {
+        match self {
+            Signature::Dict { key, value } => Some((key.signature(), value.signature())),
+            _ => None,
+        }
+    }
+
+    /// The signature of the key, if `self` is a [`Signature::Dict`].
+    pub fn dict_key(&self) -> Option<&Signature> {
+        self.dict_entry().map(|(key, _)| key)
+    }
+
+    /// The signature of the value, if `self` is a [`Signature::Dict`].
+    pub fn dict_value(&self) -> Option<&Signature> {
+        self.dict_entry().map(|(_, value)| value)
+    }
+
+    /// The signatures of the fields, if `self` is a [`Signature::Structure`].
+    pub fn structure_fields(&self) -> Option<&Fields> {
+        match self {
+            Signature::Structure(fields) => Some(fields),
+            _ => None,
+        }
+    }
+
+    /// The signature of the child, if `self` is a [`Signature::Maybe`].
+    #[cfg(feature = "gvariant")]
+    pub fn maybe_child(&self) -> Option<&Signature> {
+        match self {
+            Signature::Maybe(child) => Some(child.signature()),
+            _ => None,
+        }
+    }
+
+    /// Whether the signature is of a basic type.
+    ///
+    /// Basic types are the ones that can be used as keys of a dictionary. [`Signature::Unit`]
+    /// is not considered a basic type.
+    pub const fn is_basic(&self) -> bool {
+        match self {
+            Signature::U8
+            | Signature::Bool
+            | Signature::I16
+            | Signature::U16
+            | Signature::I32
+            | Signature::U32
+            | Signature::I64
+            | Signature::U64
+            | Signature::F64
+            | Signature::Str
+            | Signature::Signature
+            | Signature::ObjectPath => true,
+            #[cfg(unix)]
+            Signature::Fd => true,
+            _ => false,
+        }
+    }
+
+    /// Whether the signature is of a container type.
+    pub const fn is_container(&self) -> bool {
+        match self {
+            Signature::Variant
+            | Signature::Array(_)
+            | Signature::Dict { .. }
+            | Signature::Structure(_) => true,
+            #[cfg(feature = "gvariant")]
+            Signature::Maybe(_) => true,
+            _ => false,
+        }
+    }
+
     /// The required padding alignment for the given format.
     pub fn alignment(&self, format: Format) -> usize {
         match format {
@@ -381,7 +534,7 @@ impl FromStr for Signature {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parse(s.as_bytes(), false)
+        parse(s.as_bytes())
     }
 }
 
@@ -397,20 +550,58 @@ impl TryFrom<&[u8]> for Signature {
     type Error = Error;
 
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
-        parse(value, false)
+        parse(value)
     }
 }
 
 /// Validate the given signature string.
 pub fn validate(bytes: &[u8]) -> Result<(), Error> {
-    parse(bytes, true).map(|_| ())
+    // This is checked for every signature we validate, so we go through the bytes in a single pass
+    // instead of using the parser.
+    let mut rest = bytes;
+    while !rest.is_empty() {
+        rest = validate_single(rest)?;
+    }
+
+    Ok(())
+}
+
+/// Validate the first complete type in `bytes` and return the bytes after it.
+fn validate_single(bytes: &[u8]) -> Result<&[u8], Error> {
+    let (&first, rest) = bytes.split_first().ok_or(Error::InvalidSignature)?;
+    match first {
+        b'y' | b'b' | b'n' | b'q' | b'i' | b'u' | b'x' | b't' | b'd' | b's' | b'g' | b'o'
+        | b'v' => Ok(rest),
+        #[cfg(unix)]
+        b'h' => Ok(rest),
+        b'a' => match rest.split_first() {
+            Some((b'{', rest)) => {
+                let rest = validate_single(validate_single(rest)?)?;
+
+                match rest.split_first() {
+                    Some((b'}', rest)) => Ok(rest),
+                    _ => Err(Error::InvalidSignature),
+                }
+            }
+            _ => validate_single(rest),
+        },
+        b'(' => {
+            let mut rest = validate_single(rest)?;
+            loop {
+                match rest.split_first() {
+                    Some((b')', rest)) => return Ok(rest),
+                    _ => rest = validate_single(rest)?,
+                }
+            }
+        }
+        #[cfg(feature = "gvariant")]
+        b'm' => validate_single(rest),
+        _ => Err(Error::InvalidSignature),
+    }
 }
 
 /// Parse a signature string into a `Signature`.
-///
-/// When `check_only` is true, the function will not allocate memory for the dynamic types.
-/// Instead it will return dummy values in the parsed Signature.
-fn parse(bytes: &[u8], check_only: bool) -> Result<Signature, Error> {
+fn parse(bytes: &[u8]) -> Result<Signature, Error> {
     use winnow::{
         combinator::{alt, delimited, empty, eof, fail, repeat},
         dispatch,
@@ -420,16 +611,8 @@ fn parse(bytes: &[u8], check_only: bool) -> Result<Signature, Error> {
 
     let unit = eof.map(|_| Signature::Unit);
 
-    // `many1` allocates so we only want to use it when `check_only == false`
     type ManyError = winnow::error::ErrMode<()>;
-    fn many(bytes: &mut &[u8], check_only: bool, top_level: bool) -> Result<Signature, ManyError> {
-        let parser = |s: &mut _| parse_signature(s, check_only);
-        if check_only {
-            return repeat(1.., parser)
-                .map(|_: ()| Signature::Unit)
-                .parse_next(bytes);
-        }
-
+    fn many(bytes: &mut &[u8], top_level: bool) -> Result<Signature, ManyError> {
         // Avoid the allocation of `Vec<Signature>` in case of a single signature on the top-level.
         // This is a a very common case, especially in variants, where the signature needs to be
         // parsed at runtime.
@@ -439,7 +622,7 @@ fn parse(bytes: &[u8], check_only: bool) -> Result<Signature, Error> {
             Structure(Vec<Signature>),
         }
 
-        repeat(1.., parser)
+        repeat(1.., parse_signature)
             .fold(
                 || SignatureList::Unit,
                 |acc, signature| match acc {
@@ -462,9 +645,7 @@ fn parse(bytes: &[u8], check_only: bool) -> Result<Signature, Error> {
             .parse_next(bytes)
     }
 
-    fn parse_signature(bytes: &mut &[u8], check_only: bool) -> Result<Signature, ManyError> {
-        let parse_with_context = |bytes: &mut _| parse_signature(bytes, check_only);
-
+    fn parse_signature(bytes: &mut &[u8]) -> Result<Signature, ManyError> {
         let simple_type = dispatch! {any;
             b'y' => empty.value(Signature::U8),
             b'b' => empty.value(Signature::Bool),
@@ -484,40 +665,19 @@ fn parse(bytes: &[u8], check_only: bool) -> Result<Signature, Error> {
 
         let dict = (
             b'a',
-            delimited(b'{', (parse_with_context, parse_with_context), b'}'),
+            delimited(b'{', (parse_signature, parse_signature), b'}'),
         )
-            .map(|(_, (key, value))| {
-                if check_only {
-                    return Signature::Dict {
-                        key: Signature::Unit.into(),
-                        value: Signature::Unit.into(),
-                    };
-                }
-
-                Signature::Dict {
-                    key: key.into(),
-                    value: value.into(),
-                }
+            .map(|(_, (key, value))| Signature::Dict {
+                key: key.into(),
+                value: value.into(),
             });
 
-        let array = (b'a', parse_with_context).map(|(_, child)| {
-            if check_only {
-                return Signature::Array(Signature::Unit.into());
-            }
-
-            Signature::Array(child.into())
-        });
+        let array = (b'a', parse_signature).map(|(_, child)| Signature::Array(child.into()));
 
-        let structure = delimited(b'(', |s: &mut _| many(s, check_only, false), b')');
+        let structure = delimited(b'(', |s: &mut _| many(s, false), b')');
 
         #[cfg(feature = "gvariant")]
-        let maybe = (b'm', parse_with_context).map(|(_, child)| {
-            if check_only {
-                return Signature::Maybe(Signature::Unit.into());
-            }
-
-            Signature::Maybe(child.into())
-        });
+        let maybe = (b'm', parse_signature).map(|(_, child)| Signature::Maybe(child.into()));
 
         alt((
             simple_type,
@@ -534,7 +694,7 @@ fn parse(bytes: &[u8], check_only: bool) -> Result<Signature, Error> {
         .parse_next(bytes)
     }
 
-    let signature = alt((unit, |s: &mut _| many(s, check_only, true)))
+    let signature = alt((unit, |s: &mut _| many(s, true)))
         .parse(bytes)
         .map_err(|_| Error::InvalidSignature)?;
 
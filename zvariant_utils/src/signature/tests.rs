@@ -152,6 +152,53 @@ fn invalid_strings() {
     );
 }
 
+#[test]
+fn validate_like_parser() {
+    // `validate` doesn't use the parser, so make sure they agree.
+    for signature in [
+        "",
+        "y",
+        "ay",
+        "aay",
+        "a{sv}",
+        "a{a{yy}y}",
+        "a{(y)ay}",
+        "(y)",
+        "((y))",
+        "(ya{sv}(x))",
+        "a",
+        "aa",
+        "a{",
+        "a{y",
+        "a{yy",
+        "a{yyy}",
+        "a{y}",
+        "a{}",
+        "()",
+        "(",
+        ")",
+        "(y",
+        "y)",
+        "((y)",
+        "(y))",
+        "{yy}",
+        "a(y)a",
+        "mi",
+        "amy",
+        "h",
+        "ah",
+        "z",
+        "\0",
+        "y\0",
+    ] {
+        assert_eq!(
+            validate(signature.as_bytes()).is_ok(),
+            Signature::from_str(signature).is_ok(),
+            "`{signature}`"
+        );
+    }
+}
+
 #[test]
 fn hash() {
     // We need to test if all variants of Signature hold this invariant:
@@ -170,6 +217,25 @@ fn hash() {
     );
 }
 
+#[test]
+fn compose() {
+    const PROPERTIES: &Signature = &Signature::static_dict(&Signature::Str, &Signature::Variant);
+    const SIGNAL: &Signature =
+        &Signature::static_structure(&[&Signature::Str, PROPERTIES, &Signature::Str]);
+    assert_eq!(SIGNAL, "(sa{sv}s)");
+
+    let dynamic = Signature::structure(
+        [Signature::Str, PROPERTIES.clone(), Signature::Str]
+            .into_iter()
+            .collect::<Fields>(),
+    );
+    assert_eq!(&dynamic, SIGNAL);
+    assert_eq!(
+        Signature::array(Signature::structure(Vec::from([Signature::U8]))),
+        "a(y)"
+    );
+}
+
 fn test_hash(signature1: &Signature, signature2: &Signature) {
     assert_eq!(signature1, signature2);
 
@@ -183,3 +249,40 @@ fn test_hash(signature1: &Signature, signature2: &Signature) {
 
     assert_eq!(hash1, hash2);
 }
+
+#[test]
+fn inspect() {
+    let sig = Signature::from_str("").unwrap();
+    assert_eq!(sig.arity(), 0);
+    assert_eq!(sig.top_level_fields().count(), 0);
+
+    let sig = Signature::from_str("a{sv}").unwrap();
+    assert_eq!(sig.arity(), 1);
+    assert_eq!(sig.top_level_fields().collect::<Vec<_>>(), [&sig]);
+    assert_eq!(
+        sig.dict_entry(),
+        Some((&Signature::Str, &Signature::Variant))
+    );
+    assert_eq!(sig.dict_key(), Some(&Signature::Str));
+    assert_eq!(sig.dict_value(), Some(&Signature::Variant));
+    assert!(sig.array_element().is_none());
+    assert!(sig.is_container());
+    assert!(!sig.is_basic());
+
+    let sig = Signature::from_str("(ua(yv)s)").unwrap();
+    assert_eq!(sig.arity(), 3);
+    let fields = sig.structure_fields().unwrap();
+    assert_eq!(fields.len(), 3);
+    let array = sig.top_level_fields().nth(1).unwrap();
+    assert_eq!(array.array_element().unwrap(), "(yv)");
+    assert_eq!(
+        array
+            .array_element()
+            .and_then(Signature::structure_fields)
+            .map(|f| f.iter().collect::<Vec<_>>()),
+        Some(vec![&Signature::U8, &Signature::Variant])
+    );
+    assert!(Signature::Str.is_basic());
+    assert!(!Signature::Variant.is_basic());
+    assert!(Signature::Variant.is_container());
+}